@@ -0,0 +1,49 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use oasysdb::indices::{IndexBruteForce, IndexOps, SourceConfig};
+use oasysdb::types::distance::DistanceMetric;
+use oasysdb::types::record::{Record, RecordID, Vector};
+use std::collections::HashMap;
+
+/// The number of vector records in the index.
+const COUNT: usize = 10_000;
+
+/// The vector embedding dimension.
+const DIMENSION: usize = 128;
+
+fn build_test_index() -> IndexBruteForce {
+    let config = SourceConfig::new("bench", "id", "embedding");
+    let mut index = IndexBruteForce::new(config, DistanceMetric::Euclidean);
+
+    let records = (0..COUNT)
+        .map(|i| {
+            let vector = Vector::from(vec![i as f32; DIMENSION]);
+            (RecordID(i as u32), Record { vector, data: HashMap::new() })
+        })
+        .collect();
+
+    index.fit(records).unwrap();
+    index
+}
+
+fn bench_search_hashmap(criterion: &mut Criterion) {
+    let id = "brute force search (HashMap path, 10_000 vectors of dim 128)";
+    let index = build_test_index();
+    let query = Vector::random(DIMENSION);
+
+    criterion.bench_function(id, |b| {
+        b.iter(|| black_box(index.search(query.clone(), 10).unwrap()))
+    });
+}
+
+fn bench_search_arena(criterion: &mut Criterion) {
+    let id = "brute force search (arena path, 10_000 vectors of dim 128)";
+    let index = build_test_index();
+    let query = Vector::random(DIMENSION);
+
+    criterion.bench_function(id, |b| {
+        b.iter(|| black_box(index.search_arena(query.clone(), 10).unwrap()))
+    });
+}
+
+criterion_group!(bruteforce, bench_search_hashmap, bench_search_arena);
+criterion_main!(bruteforce);
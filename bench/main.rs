@@ -48,6 +48,28 @@ fn bench_true_search_collection(criterion: &mut Criterion) {
     criterion.bench_function(id, |b| b.iter(routine));
 }
 
+fn bench_search_collection_ef_sweep(criterion: &mut Criterion) {
+    // Create the collection.
+    let collection = build_test_collection(DIMENSION, COLLECTION_SIZE);
+
+    // Create a random vector to search for.
+    let vector = Vector::random(DIMENSION);
+
+    // Sweep `ef` independently of `config.ef_search` via `SearchParams`, to
+    // plot recall-versus-speed rather than being locked to one `ef`.
+    let mut group = criterion.benchmark_group("search collection by ef");
+    for ef in [10, 50, 100, 200] {
+        let params = SearchParams::default().with_ef(ef);
+        group.bench_with_input(BenchmarkId::from_parameter(ef), &ef, |b, _| {
+            b.iter(|| {
+                let result = collection.search_with_params(&vector, 10, params);
+                black_box(result.unwrap());
+            })
+        });
+    }
+    group.finish();
+}
+
 fn bench_insert_to_collection(criterion: &mut Criterion) {
     let id = "insert to collection";
 
@@ -67,6 +89,7 @@ criterion_group!(
     collection,
     bench_search_collection,
     bench_true_search_collection,
+    bench_search_collection_ef_sweep,
     bench_insert_to_collection
 );
 
@@ -0,0 +1,57 @@
+// Endpoints will be prefixed with /admin.
+
+use super::*;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use serde::Deserialize;
+
+/// A struct for the body of the create key endpoint.
+#[derive(Deserialize)]
+pub struct CreateKeyBody {
+    pub name: String,
+    pub scope: ApiKeyScope,
+}
+
+#[post("/keys", data = "<data>")]
+pub fn create_key(
+    db: &State<Database>,
+    metrics: &State<Metrics>,
+    data: Json<CreateKeyBody>,
+    _auth: AdminAuth,
+) -> (Status, Response) {
+    let data = data.into_inner();
+    let result = time(&metrics.keys_create, || {
+        db.create_key(data.name, data.scope)
+    });
+
+    match result {
+        Ok(key) => (Status::Created, Response::from(key)),
+        Err(err) => Response::from_error(&err),
+    }
+}
+
+#[get("/keys")]
+pub fn list_keys(
+    db: &State<Database>,
+    metrics: &State<Metrics>,
+    _auth: AdminAuth,
+) -> (Status, Response) {
+    match time(&metrics.keys_list, || db.list_keys()) {
+        Ok(keys) => (Status::Ok, Response::from(keys)),
+        Err(err) => Response::from_error(&err),
+    }
+}
+
+#[delete("/keys/<token>")]
+pub fn revoke_key(
+    db: &State<Database>,
+    metrics: &State<Metrics>,
+    token: &str,
+    _auth: AdminAuth,
+) -> (Status, Response) {
+    match time(&metrics.keys_revoke, || db.revoke_key(token)) {
+        Ok(_) => (Status::Ok, Response::empty()),
+        Err(err) => Response::from_error(&err),
+    }
+}
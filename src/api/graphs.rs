@@ -29,8 +29,9 @@ impl CreateGraphBody {
 #[post("/", data = "<data>")]
 pub fn create_graph(
     db: &State<Database>,
+    metrics: &State<Metrics>,
     data: Option<Json<CreateGraphBody>>,
-    _auth: Auth,
+    _auth: WriteAuth,
 ) -> (Status, Response) {
     let data = match data {
         Some(data) => data.into_inner(),
@@ -45,7 +46,7 @@ pub fn create_graph(
         GraphConfig { name, ef_construction, ef_search, filter }
     };
 
-    match db.create_graph(config) {
+    match time(&metrics.graphs_create, || db.create_graph(config)) {
         Ok(_) => (Status::Created, Response::empty()),
         Err(message) => (Status::BadRequest, Response::error(message)),
     }
@@ -54,10 +55,11 @@ pub fn create_graph(
 #[delete("/<name>")]
 pub fn delete_graph(
     db: &State<Database>,
+    metrics: &State<Metrics>,
     name: &str,
-    _auth: Auth,
+    _auth: WriteAuth,
 ) -> (Status, Response) {
-    match db.delete_graph(name) {
+    match time(&metrics.graphs_delete, || db.delete_graph(name)) {
         Ok(_) => (Status::Ok, Response::empty()),
         Err(message) => (Status::BadRequest, Response::error(message)),
     }
@@ -75,16 +77,21 @@ pub struct QueryGraphBody {
 #[post("/<name>/query", data = "<data>")]
 pub fn query_graph(
     db: &State<Database>,
+    metrics: &State<Metrics>,
     name: &str,
     data: Json<QueryGraphBody>,
-    _auth: Auth,
+    _auth: ReadAuth,
 ) -> (Status, Response) {
     let data = data.into_inner();
 
     // Default value for k is 10.
     let k = data.k.unwrap_or(10);
 
-    match db.query_graph(name, data.embedding, k) {
+    let result = time(&metrics.graphs_query, || {
+        db.query_graph(name, data.embedding, k)
+    });
+
+    match result {
         Ok(data) => (Status::Ok, Response::from(data)),
         Err(message) => (Status::BadRequest, Response::error(message)),
     }
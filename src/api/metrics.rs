@@ -0,0 +1,113 @@
+// Prometheus text-exposition metrics for the routes in this module.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Request counter paired with cumulative latency, rendered as a
+/// Prometheus `_count`/`_sum` pair. This is the minimal subset of a
+/// histogram needed to compute average latency without pulling in a
+/// dedicated metrics crate.
+#[derive(Debug, Default)]
+pub struct RequestTimer {
+    count: AtomicU64,
+    micros: AtomicU64,
+}
+
+impl RequestTimer {
+    fn observe(&self, elapsed: Duration) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.micros.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    fn seconds(&self) -> f64 {
+        self.micros.load(Ordering::Relaxed) as f64 / 1_000_000.0
+    }
+}
+
+/// Runs `handler`, recording its wall-clock time on `timer`, and returns
+/// its result unchanged. Wrap a route handler's body in this to time it
+/// without touching its error handling.
+pub fn time<T>(timer: &RequestTimer, handler: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = handler();
+    timer.observe(start.elapsed());
+    result
+}
+
+/// Rocket-managed metrics registry for the routes in this module, scraped
+/// by [`super::utils::get_metrics`].
+#[derive(Debug, Default)]
+pub struct Metrics {
+    pub values_get: RequestTimer,
+    pub values_set: RequestTimer,
+    pub values_delete: RequestTimer,
+    pub values_reset: RequestTimer,
+    pub graphs_create: RequestTimer,
+    pub graphs_delete: RequestTimer,
+    pub graphs_query: RequestTimer,
+    pub keys_create: RequestTimer,
+    pub keys_list: RequestTimer,
+    pub keys_revoke: RequestTimer,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Metrics::default()
+    }
+
+    /// Renders every counter/timer as Prometheus text exposition format,
+    /// plus `graphs_total`/`keys_total` gauges passed in by the caller
+    /// since they're read straight off [`super::Database`] rather than
+    /// tracked here.
+    pub fn render(&self, graphs_total: usize, keys_total: usize) -> String {
+        let mut body = String::new();
+
+        body.push_str("# HELP oasysdb_graphs_total Number of graphs.\n");
+        body.push_str("# TYPE oasysdb_graphs_total gauge\n");
+        body.push_str(&format!("oasysdb_graphs_total {graphs_total}\n"));
+
+        body.push_str("# HELP oasysdb_keys_total Number of API keys.\n");
+        body.push_str("# TYPE oasysdb_keys_total gauge\n");
+        body.push_str(&format!("oasysdb_keys_total {keys_total}\n"));
+
+        body.push_str("# HELP oasysdb_requests_total Requests handled.\n");
+        body.push_str("# TYPE oasysdb_requests_total counter\n");
+        body.push_str("# HELP oasysdb_request_duration_seconds_sum ");
+        body.push_str("Cumulative time spent handling requests.\n");
+        body.push_str("# TYPE oasysdb_request_duration_seconds_sum counter\n");
+
+        for (route, timer) in self.routes() {
+            let count = timer.count();
+            let seconds = timer.seconds();
+            body.push_str(&format!(
+                "oasysdb_requests_total{{route=\"{route}\"}} {count}\n"
+            ));
+
+            body.push_str(&format!(
+                "oasysdb_request_duration_seconds_sum{{route=\"{route}\"}} \
+                {seconds}\n"
+            ));
+        }
+
+        body
+    }
+
+    fn routes(&self) -> [(&'static str, &RequestTimer); 10] {
+        [
+            ("values_get", &self.values_get),
+            ("values_set", &self.values_set),
+            ("values_delete", &self.values_delete),
+            ("values_reset", &self.values_reset),
+            ("graphs_create", &self.graphs_create),
+            ("graphs_delete", &self.graphs_delete),
+            ("graphs_query", &self.graphs_query),
+            ("keys_create", &self.keys_create),
+            ("keys_list", &self.keys_list),
+            ("keys_revoke", &self.keys_revoke),
+        ]
+    }
+}
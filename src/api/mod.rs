@@ -1,5 +1,6 @@
 use crate::db::database::*;
 use crate::get_env;
+use crate::types::err::{Error, ErrorCode};
 use rocket::http::Status;
 use rocket::request::*;
 use rocket::Request;
@@ -7,12 +8,16 @@ use serde::Serialize;
 use std::collections::HashMap;
 
 // Initialize modules.
+mod admin;
 mod graphs;
+mod metrics;
 mod utils;
 mod values;
 
 // Export all of the endpoints from the modules.
+pub use admin::*;
 pub use graphs::*;
+pub use metrics::*;
 pub use utils::*;
 pub use values::*;
 
@@ -57,41 +62,168 @@ impl Response {
         let body = serde_json::to_string(&value).unwrap();
         Response(body)
     }
+
+    /// Creates a structured error response from a [`types::err::Error`],
+    /// paired with the HTTP status its [`ErrorCode`] maps to. Following
+    /// MeiliSearch's `ResponseError` design, the body carries a stable
+    /// `code` and broad `type` category alongside the human-readable
+    /// `message`, so API consumers can match on `code` programmatically
+    /// instead of parsing `message` or guessing a cause from the status
+    /// alone.
+    pub fn from_error(err: &Error) -> (Status, Response) {
+        let (code, error_type) = ApiError::describe(&err.code);
+        let body = ApiError {
+            code,
+            error_type,
+            message: err.message.to_owned(),
+            link: format!("https://docs.oasysdb.com/errors#{code}"),
+        };
+
+        (ApiError::status(&err.code), Response::from(body))
+    }
 }
 
-/// A custom data type that is used to authenticate requests.
-/// When handling routes that are private, we can add this type to
-/// the function parameters and Rocket will automatically check if
-/// the request has the correct token.
+/// Structured API error body: `{code, type, message, link}`.
+#[derive(Serialize)]
+struct ApiError {
+    /// Stable, machine-readable error code, e.g. `index_not_found`.
+    code: &'static str,
+    /// Broad error category: `invalid_request` or `internal`.
+    #[serde(rename = "type")]
+    error_type: &'static str,
+    /// Human-readable explanation of what went wrong.
+    message: String,
+    /// Link to the documentation page for this error code.
+    link: String,
+}
+
+impl ApiError {
+    /// Maps `code` to its stable API error code and broad category.
+    fn describe(code: &ErrorCode) -> (&'static str, &'static str) {
+        use ErrorCode::*;
+        match code {
+            InvalidSource => ("invalid_source", "invalid_request"),
+            MissingSource => ("missing_source", "invalid_request"),
+            InvalidID => ("invalid_id", "invalid_request"),
+            InvalidMetadata => ("invalid_metadata", "invalid_request"),
+            InvalidVector => ("invalid_vector", "invalid_request"),
+            InvalidParameter => ("invalid_parameter", "invalid_request"),
+            RequestError => ("bad_request", "invalid_request"),
+            NotFound => ("not_found", "invalid_request"),
+            InternalError => ("internal_error", "internal"),
+            ConcurrencyError => ("concurrency_error", "internal"),
+            FileError => ("file_error", "internal"),
+            SerializationError => ("serialization_error", "internal"),
+            SQLError => ("sql_error", "internal"),
+        }
+    }
+
+    /// Maps `code` to the HTTP status its API response should carry.
+    fn status(code: &ErrorCode) -> Status {
+        use ErrorCode::*;
+        match code {
+            NotFound => Status::NotFound,
+            InvalidSource | MissingSource | InvalidID | InvalidMetadata
+            | InvalidVector | InvalidParameter | RequestError => {
+                Status::BadRequest
+            }
+            InternalError | ConcurrencyError | FileError
+            | SerializationError | SQLError => Status::InternalServerError,
+        }
+    }
+}
+
+// Resolves the presented `x-oasysdb-token` header to its scope and checks
+// it covers `required`. The root `OASYSDB_TOKEN` env var is always a valid
+// `Admin` credential, so existing deployments keep working unchanged; any
+// other token is resolved against the database's persisted keys.
+async fn authenticate(
+    request: &Request<'_>,
+    required: ApiKeyScope,
+) -> Result<String, &'static str> {
+    let token = request
+        .headers()
+        .get_one("x-oasysdb-token")
+        .ok_or("Invalid x-oasysdb-token header.")?;
+
+    let scope = if token == get_env("OASYSDB_TOKEN") {
+        Some(ApiKeyScope::Admin)
+    } else {
+        let db = request.rocket().state::<Database>();
+        db.and_then(|db| db.resolve_token(token))
+    };
+
+    match scope {
+        Some(scope) if scope >= required => Ok(token.to_string()),
+        Some(_) => Err("This key's scope doesn't allow this operation."),
+        None => Err("Invalid x-oasysdb-token header."),
+    }
+}
+
+/// A custom data type that is used to authenticate requests requiring at
+/// least [`ApiKeyScope::Read`]. When handling routes that are private, we
+/// can add this type to the function parameters and Rocket will
+/// automatically check if the request has a key with sufficient scope.
 ///
 /// # Example
 ///
 /// ```rs
 /// #[get("/private")]
-/// pub fn private_route(_auth: Auth) {}
+/// pub fn private_route(_auth: ReadAuth) {}
 /// ```
-pub struct Auth {
+pub struct ReadAuth {
+    pub token: String,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ReadAuth {
+    type Error = &'static str;
+
+    async fn from_request(
+        request: &'r Request<'_>,
+    ) -> Outcome<Self, Self::Error> {
+        match authenticate(request, ApiKeyScope::Read).await {
+            Ok(token) => Outcome::Success(ReadAuth { token }),
+            Err(message) => Outcome::Error((Status::Unauthorized, message)),
+        }
+    }
+}
+
+/// Like [`ReadAuth`], but requires at least [`ApiKeyScope::Write`].
+pub struct WriteAuth {
     pub token: String,
 }
 
 #[rocket::async_trait]
-impl<'r> FromRequest<'r> for Auth {
+impl<'r> FromRequest<'r> for WriteAuth {
     type Error = &'static str;
 
     async fn from_request(
         request: &'r Request<'_>,
     ) -> Outcome<Self, Self::Error> {
-        let server_token = get_env("OASYSDB_TOKEN");
-        let token = request.headers().get_one("x-oasysdb-token");
-
-        if token.is_none() || token.unwrap() != server_token {
-            return Outcome::Error((
-                Status::Unauthorized,
-                "Invalid x-oasysdb-token header.",
-            ));
+        match authenticate(request, ApiKeyScope::Write).await {
+            Ok(token) => Outcome::Success(WriteAuth { token }),
+            Err(message) => Outcome::Error((Status::Unauthorized, message)),
         }
+    }
+}
 
-        let token = token.unwrap().to_string();
-        Outcome::Success(Auth { token })
+/// Like [`ReadAuth`], but requires [`ApiKeyScope::Admin`] — used by the key
+/// management endpoints in [`admin`](super::admin).
+pub struct AdminAuth {
+    pub token: String,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AdminAuth {
+    type Error = &'static str;
+
+    async fn from_request(
+        request: &'r Request<'_>,
+    ) -> Outcome<Self, Self::Error> {
+        match authenticate(request, ApiKeyScope::Admin).await {
+            Ok(token) => Outcome::Success(AdminAuth { token }),
+            Err(message) => Outcome::Error((Status::Unauthorized, message)),
+        }
     }
 }
@@ -1,4 +1,7 @@
+use super::Metrics;
+use crate::db::database::Database;
 use rocket::serde::json::Json;
+use rocket::State;
 use std::collections::HashMap;
 
 type StringMap = HashMap<&'static str, &'static str>;
@@ -14,3 +17,12 @@ pub fn get_version() -> Response {
     let version = env!("CARGO_PKG_VERSION");
     Json(HashMap::from([("version", version)]))
 }
+
+/// Prometheus text-exposition scrape of request counters/latencies for
+/// the routes in this module, plus `graphs_total`/`keys_total` gauges.
+#[get("/metrics")]
+pub fn get_metrics(db: &State<Database>, metrics: &State<Metrics>) -> String {
+    let graphs_total = db.state().map(|s| s.index_count()).unwrap_or(0);
+    let keys_total = db.list_keys().map(|keys| keys.len()).unwrap_or(0);
+    metrics.render(graphs_total, keys_total)
+}
@@ -8,10 +8,11 @@ use rocket::State;
 #[get("/<key>")]
 pub fn get_value(
     db: &State<Database>,
+    metrics: &State<Metrics>,
     key: &str,
-    _auth: Auth,
+    _auth: ReadAuth,
 ) -> (Status, Response) {
-    match db.get_value(key) {
+    match time(&metrics.values_get, || db.get_value(key)) {
         Ok(value) => (Status::Ok, Response::from(value)),
         Err(message) => (Status::BadRequest, Response::error(message)),
     }
@@ -20,11 +21,13 @@ pub fn get_value(
 #[post("/<key>", data = "<value>")]
 pub fn set_value(
     db: &State<Database>,
+    metrics: &State<Metrics>,
     key: &str,
     value: Json<Value>,
-    _auth: Auth,
+    _auth: WriteAuth,
 ) -> (Status, Response) {
-    match db.set_value(key, value.into_inner()) {
+    let value = value.into_inner();
+    match time(&metrics.values_set, || db.set_value(key, value)) {
         Ok(_) => (Status::Ok, Response::empty()),
         Err(message) => (Status::BadRequest, Response::error(message)),
     }
@@ -33,18 +36,23 @@ pub fn set_value(
 #[delete("/<key>")]
 pub fn delete_value(
     db: &State<Database>,
+    metrics: &State<Metrics>,
     key: &str,
-    _auth: Auth,
+    _auth: WriteAuth,
 ) -> (Status, Response) {
-    match db.delete_value(key) {
+    match time(&metrics.values_delete, || db.delete_value(key)) {
         Ok(_) => (Status::Ok, Response::empty()),
         Err(message) => (Status::BadRequest, Response::error(message)),
     }
 }
 
 #[delete("/")]
-pub fn reset_values(db: &State<Database>, _auth: Auth) -> (Status, Response) {
-    match db.reset_values() {
+pub fn reset_values(
+    db: &State<Database>,
+    metrics: &State<Metrics>,
+    _auth: WriteAuth,
+) -> (Status, Response) {
+    match time(&metrics.values_reset, || db.reset_values()) {
         Ok(_) => (Status::Ok, Response::empty()),
         Err(message) => (Status::BadRequest, Response::error(message)),
     }
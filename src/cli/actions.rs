@@ -1,12 +1,17 @@
 use super::*;
-use oasysdb::nodes::{CoordinatorNode, DataNode};
-use oasysdb::postgres::NodeParameters;
+use oasysdb::nodes::{CoordinatorNode, DataNode, PoolParameters};
+use oasysdb::postgres::{
+    CoordinatorSchema, DataSchema, Migratable, NodeParameters, NodeSchema,
+    PgEngine,
+};
 use oasysdb::protos::coordinator_node_client::CoordinatorNodeClient;
 use oasysdb::protos::coordinator_node_server::CoordinatorNodeServer;
 use oasysdb::protos::data_node_server::DataNodeServer;
 use oasysdb::protos::{NodeConnection, RegisterNodeRequest};
 use reqwest::get;
+use sqlx::{Connection, PgConnection};
 use std::env;
+use std::time::Duration;
 use std::future::Future;
 use tokio::runtime::Runtime;
 use tonic::transport::Server;
@@ -24,21 +29,41 @@ fn env_database_url() -> Url {
         .expect("Failed to parse the database URL")
 }
 
+/// Resolves how long to retry a transient Postgres connection failure
+/// before giving up: the `OASYSDB_CONNECT_RETRY_SECS` env var takes
+/// precedence over the `--connect-retry-secs` flag, since an operator
+/// usually sets it once per deployment rather than per invocation.
+fn connect_retry_max_elapsed(args: &ArgMatches) -> Duration {
+    let secs = env::var("OASYSDB_CONNECT_RETRY_SECS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| *args.get_one::<u64>("connect-retry-secs").unwrap());
+
+    Duration::from_secs(secs)
+}
+
 // Coordinator action handlers.
 
 pub fn coordinator_handler(args: &ArgMatches) {
     match args.subcommand() {
-        Some(("start", _)) => block_on(coordinator_start_handler()),
+        Some(("start", args)) => block_on(coordinator_start_handler(args)),
         Some(("config", args)) => block_on(coordinator_config_handler(args)),
+        Some(("migrate", _)) => block_on(coordinator_migrate_handler()),
         _ => unreachable!(),
     }
 }
 
-async fn coordinator_start_handler() {
+async fn coordinator_start_handler(args: &ArgMatches) {
     let database_url = env_database_url();
-    let node = CoordinatorNode::new(database_url).await;
+    let max_elapsed = connect_retry_max_elapsed(args);
+    let node = Arc::new(CoordinatorNode::new(database_url, max_elapsed).await);
+    node.spawn_health_monitor(Duration::from_secs(5));
+
+    if let Some(admin_port) = args.get_one::<u16>("admin-port") {
+        node.spawn_admin_server(*admin_port);
+    }
 
-    let server = CoordinatorNodeServer::new(Arc::new(node));
+    let server = CoordinatorNodeServer::new(node);
     let serve = "[::]:2505".parse::<SocketAddr>().unwrap();
     tracing::info!("coordinator server is running at port {}", serve.port());
 
@@ -57,6 +82,8 @@ async fn coordinator_config_handler(args: &ArgMatches) {
     let dimension = args.get_one::<usize>("dim").unwrap();
     let metric = args.get_one::<Metric>("metric").unwrap();
     let density = args.get_one::<usize>("density").unwrap();
+    let max_connections = args.get_one::<u32>("max-connections").unwrap();
+    let acquire_timeout = args.get_one::<u64>("acquire-timeout").unwrap();
 
     let params = NodeParameters {
         metric: *metric,
@@ -64,7 +91,32 @@ async fn coordinator_config_handler(args: &ArgMatches) {
         density: *density,
     };
 
-    CoordinatorNode::configure(database_url, params).await;
+    let pool_params = PoolParameters {
+        max_size: *max_connections,
+        acquire_timeout: Duration::from_secs(*acquire_timeout),
+    };
+
+    let max_elapsed = connect_retry_max_elapsed(args);
+    CoordinatorNode::configure(database_url, params, pool_params, max_elapsed)
+        .await;
+}
+
+async fn coordinator_migrate_handler() {
+    let database_url = env_database_url();
+
+    let mut connection = PgConnection::connect(database_url.as_ref())
+        .await
+        .expect("Failed to connect to Postgres database");
+
+    let schema = CoordinatorSchema::new();
+    if !schema.exists(&mut PgEngine(&mut connection)).await {
+        panic!(
+            "Coordinator hasn't been configured yet; run \
+            `coordinator config` first."
+        );
+    }
+
+    schema.migrate(&mut connection).await;
 }
 
 // Data action handlers.
@@ -72,10 +124,29 @@ async fn coordinator_config_handler(args: &ArgMatches) {
 pub fn data_handler(args: &ArgMatches) {
     match args.subcommand() {
         Some(("join", args)) => block_on(data_join_handler(args)),
+        Some(("migrate", args)) => block_on(data_migrate_handler(args)),
         _ => unreachable!(),
     }
 }
 
+async fn data_migrate_handler(args: &ArgMatches) {
+    let database_url = env_database_url();
+
+    // Unwrap is safe because the argument is validated by clap.
+    let name = args.get_one::<String>("name").unwrap().as_str();
+
+    let mut connection = PgConnection::connect(database_url.as_ref())
+        .await
+        .expect("Failed to connect to Postgres database");
+
+    let schema = DataSchema::new(name);
+    if !schema.exists(&mut PgEngine(&mut connection)).await {
+        panic!("Data node '{name}' hasn't started yet; run `data join` first.");
+    }
+
+    schema.migrate(&mut connection).await;
+}
+
 async fn data_join_handler(args: &ArgMatches) {
     let database_url = env_database_url();
 
@@ -86,6 +157,13 @@ async fn data_join_handler(args: &ArgMatches) {
         .get_one::<SocketAddr>("coordinator_addr")
         .expect("Please provide a valid coordinator address");
 
+    let max_connections = args.get_one::<u32>("max-connections").unwrap();
+    let acquire_timeout = args.get_one::<u64>("acquire-timeout").unwrap();
+    let pool_params = PoolParameters {
+        max_size: *max_connections,
+        acquire_timeout: Duration::from_secs(*acquire_timeout),
+    };
+
     let host = get("https://api.ipify.org")
         .await
         .expect("Failed to retrieve host address")
@@ -116,8 +194,23 @@ async fn data_join_handler(args: &ArgMatches) {
 
     params.trace();
 
-    let node = DataNode::new(name, params, database_url).await;
-    let server = DataNodeServer::new(Arc::new(node));
+    let max_elapsed = connect_retry_max_elapsed(args);
+    let node = DataNode::new_with_pool(
+        name,
+        params,
+        database_url,
+        pool_params,
+        max_elapsed,
+    )
+    .await;
+    let node = Arc::new(node);
+    node.spawn_heartbeat_loop(*coordinator_addr, Duration::from_secs(5));
+
+    if let Some(admin_port) = args.get_one::<u16>("admin-port") {
+        node.spawn_admin_server(*admin_port);
+    }
+
+    let server = DataNodeServer::new(node);
     let serve = format!("[::]:{port}").parse::<SocketAddr>().unwrap();
     tracing::info!("data node server is running at port {}", serve.port());
 
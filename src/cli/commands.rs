@@ -1,5 +1,5 @@
 use super::*;
-use clap::arg;
+use clap::{arg, Arg};
 
 // Coordinator subcommands section.
 
@@ -9,12 +9,33 @@ pub fn coordinator() -> Command {
         .subcommand_required(true)
         .subcommand(coordinator_start())
         .subcommand(coordinator_config())
+        .subcommand(coordinator_migrate())
+}
+
+/// Shared by every subcommand that establishes a Postgres connection: caps
+/// how long to retry a transient connection failure before giving up.
+fn arg_connect_retry() -> Arg {
+    arg!(
+        --"connect-retry-secs" <secs>
+        "Max time to retry a transient Postgres connection failure"
+    )
+    .default_value("30")
+    .value_parser(clap::value_parser!(u64))
+    .allow_negative_numbers(false)
 }
 
 fn coordinator_start() -> Command {
+    let arg_admin_port = arg!(
+        --"admin-port" <port> "Port to serve the read-only admin API on"
+    )
+    .value_parser(clap::value_parser!(u16))
+    .allow_negative_numbers(false);
+
     Command::new("start")
         .alias("run")
         .about("Start server as the coordinator node")
+        .arg(arg_admin_port)
+        .arg(arg_connect_retry())
 }
 
 fn coordinator_config() -> Command {
@@ -33,11 +54,34 @@ fn coordinator_config() -> Command {
         .value_parser(clap::value_parser!(usize))
         .allow_negative_numbers(false);
 
+    let arg_max_connections = arg!(
+        --"max-connections" <count> "Max size of the coordinator's pool"
+    )
+    .default_value("10")
+    .value_parser(clap::value_parser!(u32))
+    .allow_negative_numbers(false);
+
+    let arg_acquire_timeout = arg!(
+        --"acquire-timeout" <secs> "Seconds to wait for a pooled connection"
+    )
+    .default_value("30")
+    .value_parser(clap::value_parser!(u64))
+    .allow_negative_numbers(false);
+
     Command::new("config")
         .about("Configure the coordinator node parameters")
         .arg(arg_metric)
         .arg(arg_dimension)
         .arg(arg_density)
+        .arg(arg_max_connections)
+        .arg(arg_acquire_timeout)
+        .arg(arg_connect_retry())
+}
+
+fn coordinator_migrate() -> Command {
+    Command::new("migrate").about(
+        "Apply pending schema migrations for the coordinator out-of-band",
+    )
 }
 
 // Data subcommands section.
@@ -47,6 +91,15 @@ pub fn data() -> Command {
         .about("Interface to manage the data server")
         .subcommand_required(true)
         .subcommand(data_join())
+        .subcommand(data_migrate())
+}
+
+fn data_migrate() -> Command {
+    let arg_name = arg!(<name> "Name of the data node").required(true);
+
+    Command::new("migrate")
+        .about("Apply pending schema migrations for a data node out-of-band")
+        .arg(arg_name)
 }
 
 fn data_join() -> Command {
@@ -62,9 +115,33 @@ fn data_join() -> Command {
         .value_parser(clap::value_parser!(u16))
         .allow_negative_numbers(false);
 
+    let arg_admin_port = arg!(
+        --"admin-port" <port> "Port to serve the /metrics admin API on"
+    )
+    .value_parser(clap::value_parser!(u16))
+    .allow_negative_numbers(false);
+
+    let arg_max_connections = arg!(
+        --"max-connections" <count> "Max size of this node's Postgres pool"
+    )
+    .default_value("10")
+    .value_parser(clap::value_parser!(u32))
+    .allow_negative_numbers(false);
+
+    let arg_acquire_timeout = arg!(
+        --"acquire-timeout" <secs> "Seconds to wait for a pooled connection"
+    )
+    .default_value("30")
+    .value_parser(clap::value_parser!(u64))
+    .allow_negative_numbers(false);
+
     Command::new("join")
         .about("Start and join server as a data node in the cluster")
         .arg(arg_name)
         .arg(arg_coordinator_addr)
         .arg(arg_port)
+        .arg(arg_admin_port)
+        .arg(arg_max_connections)
+        .arg(arg_acquire_timeout)
+        .arg(arg_connect_retry())
 }
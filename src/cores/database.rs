@@ -1,6 +1,8 @@
 use super::*;
+use crc32c::crc32c;
 use protos::database_server::Database as DatabaseService;
-use std::io::{BufReader, BufWriter};
+use std::fmt;
+use tokio::sync::RwLock as TokioRwLock;
 use tonic::{Request, Response};
 
 const TMP_DIR: &str = "tmp";
@@ -8,17 +10,121 @@ const PARAMS_FILE: &str = "odb_params";
 const STORAGE_FILE: &str = "odb_storage";
 const INDEX_FILE: &str = "odb_index";
 
+// Snapshot file header: a fixed magic byte string identifying the file as
+// an oasysdb snapshot, a little-endian `u32` format version, a
+// little-endian `u32` CRC32C checksum of the payload that follows, and a
+// flags byte (currently only `FLAG_COMPRESSED`).
+const MAGIC: &[u8; 4] = b"ODB1";
+const HEADER_LEN: usize = MAGIC.len() + 4 + 4 + 1;
+
+// Payload is zstd-compressed; see `Parameters::compression_level`.
+const FLAG_COMPRESSED: u8 = 0b0000_0001;
+
+/// Why loading a snapshot file failed in a way worth telling apart from a
+/// generic I/O or deserialize error, so an operator knows whether to run
+/// `upgrade` or fall back to a previous snapshot instead of just retrying.
+#[derive(Debug)]
+enum SnapshotError {
+    VersionTooNew(u32),
+    ChecksumMismatch,
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::VersionTooNew(version) => write!(
+                f,
+                "Snapshot format version {version} is newer than this \
+                build of oasysdb supports; refusing to open"
+            ),
+            SnapshotError::ChecksumMismatch => {
+                write!(f, "Snapshot checksum mismatch, the file may be corrupt")
+            }
+        }
+    }
+}
+
+impl Error for SnapshotError {}
+
+fn encode_header(version: u32, checksum: u32, flags: u8) -> [u8; HEADER_LEN] {
+    let mut header = [0u8; HEADER_LEN];
+    header[..MAGIC.len()].copy_from_slice(MAGIC);
+    header[MAGIC.len()..MAGIC.len() + 4]
+        .copy_from_slice(&version.to_le_bytes());
+    header[MAGIC.len() + 4..MAGIC.len() + 8]
+        .copy_from_slice(&checksum.to_le_bytes());
+    header[MAGIC.len() + 8] = flags;
+    header
+}
+
+/// Splits `bytes` into its format version, checksum, flags, and payload.
+/// Snapshots written before the header format existed have no magic
+/// prefix; they're treated as implicit version 0 with no checksum to
+/// verify and no flags set, the version every [`Versioned::migrations`]
+/// chain starts from.
+fn decode_header(bytes: &[u8]) -> (u32, Option<u32>, u8, &[u8]) {
+    if bytes.len() >= HEADER_LEN && bytes[..MAGIC.len()] == *MAGIC {
+        let version_bytes = &bytes[MAGIC.len()..MAGIC.len() + 4];
+        let version = u32::from_le_bytes(version_bytes.try_into().unwrap());
+
+        let checksum_bytes = &bytes[MAGIC.len() + 4..MAGIC.len() + 8];
+        let checksum = u32::from_le_bytes(checksum_bytes.try_into().unwrap());
+
+        let flags = bytes[MAGIC.len() + 8];
+        (version, Some(checksum), flags, &bytes[HEADER_LEN..])
+    } else {
+        (0, None, 0, bytes)
+    }
+}
+
+type Migration<T> = fn(T) -> T;
+
+/// Associates a type persisted via [`Database::persist_as_binary`]/
+/// [`Database::load_binary`] with its current on-disk format version.
+///
+/// Bump [`Self::VERSION`] whenever the serialized layout changes, and
+/// register the transform needed to reach it in [`Self::migrations`], so
+/// [`Database::load_binary`] can upgrade an older snapshot in place and
+/// the `upgrade` command can rewrite it at rest.
+trait Versioned: Sized {
+    const VERSION: u32;
+
+    /// Migrations needed to walk a snapshot up to [`Self::VERSION`], keyed
+    /// by the version migrated *from* and applied in order. A version with
+    /// no registered step is assumed to already deserialize directly into
+    /// the current shape, which is true today: version 1 only adds the
+    /// header format itself, not a layout change.
+    fn migrations() -> &'static [(u32, Migration<Self>)] {
+        &[]
+    }
+}
+
+impl Versioned for Parameters {
+    const VERSION: u32 = 1;
+}
+
+impl Versioned for Index {
+    const VERSION: u32 = 1;
+}
+
+impl Versioned for Storage {
+    const VERSION: u32 = 1;
+}
+
 /// Database parameters.
 ///
 /// Fields:
 /// - dimension: Vector dimension.
 /// - metric: Metric to calculate distance.
 /// - density: Max number of records per IVF cluster.
+/// - compression_level: zstd level to compress snapshot files with, or 0
+///   to store them uncompressed.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Parameters {
     pub dimension: usize,
     pub metric: Metric,
     pub density: usize,
+    pub compression_level: i32,
 }
 
 /// Dynamic query-time parameters.
@@ -72,20 +178,24 @@ pub struct Database {
     dir: PathBuf,
     params: Parameters,
     index: RwLock<Index>,
-    storage: RwLock<Storage>,
+    storage: TokioRwLock<StorageEngine>,
 }
 
 impl Database {
-    pub fn configure(params: &Parameters) {
+    pub async fn configure(params: &Parameters) {
         let index = Index::new()
             .with_metric(params.metric)
             .with_density(params.density);
 
+        let storage = Self::new_storage_engine()
+            .await
+            .expect("Failed to open the storage backend");
+
         let db = Database {
             dir: Self::dir(),
             params: *params,
             index: RwLock::new(index),
-            storage: RwLock::new(Storage::new()),
+            storage: TokioRwLock::new(storage),
         };
 
         if db.dir.join(PARAMS_FILE).exists() {
@@ -105,14 +215,24 @@ impl Database {
             println!("The database has been reset successfully");
         }
 
-        db.setup_dir().expect("Failed to setup database directory");
+        db.setup_dir().await.expect("Failed to setup database directory");
     }
 
-    pub fn open() -> Result<Self, Box<dyn Error>> {
+    pub async fn open() -> Result<Self, Box<dyn Error>> {
         let dir = Self::dir();
-        let params = Self::load_binary(dir.join(PARAMS_FILE))?;
-        let index = Self::load_binary(dir.join(INDEX_FILE))?;
-        let storage: Storage = Self::load_binary(dir.join(STORAGE_FILE))?;
+        let params = Self::load_binary(dir.join(PARAMS_FILE)).await?;
+        let index = Self::load_binary(dir.join(INDEX_FILE)).await?;
+
+        let storage = match Self::storage_url() {
+            Some(url) => StorageEngine::Postgres(
+                PostgresStorage::connect(&url).await?,
+            ),
+            None => {
+                let storage: Storage =
+                    Self::load_binary(dir.join(STORAGE_FILE)).await?;
+                StorageEngine::Memory(storage)
+            }
+        };
 
         let count = storage.count();
         tracing::info!("Restored {count} record(s) from the disk");
@@ -121,7 +241,7 @@ impl Database {
             dir,
             params,
             index: RwLock::new(index),
-            storage: RwLock::new(storage),
+            storage: TokioRwLock::new(storage),
         })
     }
 
@@ -132,7 +252,26 @@ impl Database {
         }
     }
 
-    fn setup_dir(&self) -> Result<(), Box<dyn Error>> {
+    /// Connection string for the pluggable Postgres storage backend, or
+    /// `None` to use the default file-snapshotted in-memory [`Storage`].
+    fn storage_url() -> Option<String> {
+        env::var("ODB_STORAGE_URL").ok()
+    }
+
+    /// Builds a fresh (empty, unless `ODB_STORAGE_URL` already has data in
+    /// it) storage backend for a newly configured database, without
+    /// attempting to load an existing [`STORAGE_FILE`] snapshot.
+    async fn new_storage_engine() -> Result<StorageEngine, Box<dyn Error>> {
+        match Self::storage_url() {
+            Some(url) => {
+                let storage = PostgresStorage::connect(&url).await?;
+                Ok(StorageEngine::Postgres(storage))
+            }
+            None => Ok(StorageEngine::Memory(Storage::new())),
+        }
+    }
+
+    async fn setup_dir(&self) -> Result<(), Box<dyn Error>> {
         if self.dir.try_exists()? {
             return Ok(());
         }
@@ -140,49 +279,126 @@ impl Database {
         fs::create_dir_all(&self.dir)?;
         fs::create_dir_all(self.dir.join(TMP_DIR))?;
 
-        self.create_snapshot()?;
+        self.create_snapshot().await?;
         Ok(())
     }
 
-    fn load_binary<T: DeserializeOwned>(
-        path: impl AsRef<Path>,
-    ) -> Result<T, Box<dyn Error>> {
-        let file = OpenOptions::new().read(true).open(path)?;
-        let reader = BufReader::new(file);
-        Ok(bincode::deserialize_from(reader)?)
+    /// Deserializes `path` off the tokio blocking pool, so a large
+    /// `Index`/`Storage` snapshot doesn't stall the worker thread it's
+    /// loaded from. Transparently upgrades an older-versioned snapshot via
+    /// `T`'s registered [`Versioned::migrations`].
+    async fn load_binary<T>(path: impl AsRef<Path>) -> Result<T, Box<dyn Error>>
+    where
+        T: DeserializeOwned + Versioned + Send + 'static,
+    {
+        let path = path.as_ref().to_path_buf();
+        let result = tokio::task::spawn_blocking(move || {
+            let bytes = fs::read(path)?;
+            let (version, checksum, flags, payload) = decode_header(&bytes);
+
+            if version > T::VERSION {
+                return Err(SnapshotError::VersionTooNew(version).into());
+            }
+
+            if let Some(checksum) = checksum {
+                if crc32c(payload) != checksum {
+                    return Err(SnapshotError::ChecksumMismatch.into());
+                }
+            }
+
+            let decompressed;
+            let payload = if flags & FLAG_COMPRESSED != 0 {
+                decompressed = zstd::decode_all(payload)?;
+                decompressed.as_slice()
+            } else {
+                payload
+            };
+
+            let mut value: T = bincode::deserialize(payload)?;
+            for from in version..T::VERSION {
+                if let Some((_, migrate)) =
+                    T::migrations().iter().find(|(v, _)| *v == from)
+                {
+                    value = migrate(value);
+                }
+            }
+
+            Ok::<T, Box<dyn Error + Send + Sync>>(value)
+        })
+        .await?;
+
+        Ok(result?)
     }
 
-    fn persist_as_binary<T: Serialize>(
+    /// Serializes `data` to `path` at its current [`Versioned::VERSION`],
+    /// with a CRC32C checksum of the payload in its header, off the tokio
+    /// blocking pool, by writing a temp file and renaming it over `path`,
+    /// so a large `Index`/`Storage` snapshot doesn't stall the worker
+    /// thread it's written from.
+    async fn persist_as_binary<T>(
         &self,
         path: impl AsRef<Path>,
         data: T,
-    ) -> Result<(), Box<dyn Error>> {
-        let file_name = path.as_ref().file_name().unwrap();
+    ) -> Result<(), Box<dyn Error>>
+    where
+        T: Serialize + Versioned + Send + 'static,
+    {
+        let path = path.as_ref().to_path_buf();
+        let file_name = path.file_name().unwrap();
         let tmp_file = self.dir.join(TMP_DIR).join(file_name);
-        let file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(&tmp_file)?;
-
-        let writer = BufWriter::new(file);
-        bincode::serialize_into(writer, &data)?;
-        fs::rename(&tmp_file, &path)?;
-        Ok(())
-    }
+        let compression_level = self.params.compression_level;
+
+        let result = tokio::task::spawn_blocking(move || {
+            let mut payload = Vec::new();
+            bincode::serialize_into(&mut payload, &data)?;
+
+            let (payload, flags) = if compression_level > 0 {
+                let compressed =
+                    zstd::encode_all(&payload[..], compression_level)?;
+                (compressed, FLAG_COMPRESSED)
+            } else {
+                (payload, 0)
+            };
 
-    pub fn create_snapshot(&self) -> Result<SnapshotStats, Box<dyn Error>> {
-        self.persist_as_binary(self.dir.join(PARAMS_FILE), self.params)?;
+            let checksum = crc32c(&payload);
+            let mut bytes = encode_header(T::VERSION, checksum, flags).to_vec();
+            bytes.extend_from_slice(&payload);
 
-        let index = self.index.read().unwrap();
-        self.persist_as_binary(self.dir.join(INDEX_FILE), &*index)?;
+            fs::write(&tmp_file, &bytes)?;
+            fs::rename(&tmp_file, &path)?;
+            Ok::<(), Box<dyn Error + Send + Sync>>(())
+        })
+        .await?;
+
+        Ok(result?)
+    }
+
+    /// Writes a snapshot of the current params/index/storage to disk.
+    ///
+    /// The locked data is cloned and released quickly so the `RwLock`/
+    /// `TokioRwLock` read guards on `index`/`storage` aren't held for the
+    /// whole write; the actual serialization happens afterwards, off the
+    /// lock. When the storage backend is [`StorageEngine::Postgres`],
+    /// records are already durable per-mutation, so no [`STORAGE_FILE`]
+    /// is written for them.
+    pub async fn create_snapshot(
+        &self,
+    ) -> Result<SnapshotStats, Box<dyn Error>> {
+        self.persist_as_binary(self.dir.join(PARAMS_FILE), self.params)
+            .await?;
 
-        let storage = self.storage.read().unwrap();
-        self.persist_as_binary(self.dir.join(STORAGE_FILE), &*storage)?;
+        let index = self.index.read().unwrap().clone();
+        self.persist_as_binary(self.dir.join(INDEX_FILE), index).await?;
 
+        let storage = self.storage.read().await.clone();
         let count = storage.count();
-        tracing::info!("Created a snapshot with {count} record(s)");
 
+        if let StorageEngine::Memory(storage) = storage {
+            self.persist_as_binary(self.dir.join(STORAGE_FILE), storage)
+                .await?;
+        }
+
+        tracing::info!("Created a snapshot with {count} record(s)");
         Ok(SnapshotStats { count })
     }
 
@@ -216,7 +432,7 @@ impl DatabaseService for Arc<Database> {
         &self,
         _request: Request<()>,
     ) -> Result<Response<protos::SnapshotResponse>, Status> {
-        let stats = self.create_snapshot().map_err(|e| {
+        let stats = self.create_snapshot().await.map_err(|e| {
             let message = format!("Failed to create a snapshot: {e}");
             Status::internal(message)
         })?;
@@ -243,8 +459,8 @@ impl DatabaseService for Arc<Database> {
         // Insert the record into the storage.
         // This operation must be done before updating the index. Otherwise,
         // the index won't have access to the record data.
-        let mut storage = self.storage.write().unwrap();
-        storage.insert(&id, &record)?;
+        let mut storage = self.storage.write().await;
+        storage.insert(&id, &record).await?;
 
         let mut index = self.index.write().unwrap();
         index.insert(&id, &record, storage.records())?;
@@ -260,8 +476,8 @@ impl DatabaseService for Arc<Database> {
         let request = request.into_inner();
         let id = request.id.parse::<RecordID>()?;
 
-        let storage = self.storage.read().unwrap();
-        let record = storage.get(&id)?.to_owned();
+        let storage = self.storage.read().await;
+        let record = storage.get(&id).await?;
 
         let response = protos::GetResponse { record: Some(record.into()) };
         Ok(Response::new(response))
@@ -277,8 +493,8 @@ impl DatabaseService for Arc<Database> {
         let mut index = self.index.write().unwrap();
         index.delete(&id)?;
 
-        let mut storage = self.storage.write().unwrap();
-        storage.delete(&id)?;
+        let mut storage = self.storage.write().await;
+        storage.delete(&id).await?;
 
         tracing::info!("Deleted a record with ID: {id}");
         Ok(Response::new(()))
@@ -296,8 +512,8 @@ impl DatabaseService for Arc<Database> {
             metadata.insert(key, value.try_into()?);
         }
 
-        let mut storage = self.storage.write().unwrap();
-        storage.update(&id, &metadata)?;
+        let mut storage = self.storage.write().await;
+        storage.update(&id, &metadata).await?;
 
         tracing::info!("Updated metadata for a record: {id}");
         Ok(Response::new(()))
@@ -331,7 +547,7 @@ impl DatabaseService for Arc<Database> {
             None => QueryParameters::default(),
         };
 
-        let storage = self.storage.read().unwrap();
+        let storage = self.storage.read().await;
         let records = storage.records();
 
         let index = self.index.read().unwrap();
@@ -350,15 +566,15 @@ mod tests {
     use super::*;
     use uuid::Uuid;
 
-    #[test]
-    fn test_open() {
-        let db = setup_db();
+    #[tokio::test]
+    async fn test_open() {
+        let db = setup_db().await;
         assert_eq!(db.params, Parameters::default());
     }
 
     #[tokio::test]
     async fn test_heartbeat() {
-        let db = setup_db();
+        let db = setup_db().await;
         let request = Request::new(());
         let response = db.heartbeat(request).await.unwrap();
         assert_eq!(response.get_ref().version, env!("CARGO_PKG_VERSION"));
@@ -367,7 +583,7 @@ mod tests {
     #[tokio::test]
     async fn test_insert() {
         let params = Parameters::default();
-        let db = setup_db();
+        let db = setup_db().await;
 
         let vector = Vector::random(params.dimension);
         let request = Request::new(protos::InsertRequest {
@@ -379,17 +595,56 @@ mod tests {
 
         let response = db.insert(request).await.unwrap();
         assert!(response.get_ref().id.parse::<Uuid>().is_ok());
-        assert_eq!(db.storage.read().unwrap().records().len(), 1);
+        assert_eq!(db.storage.read().await.records().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_open_legacy_snapshot_without_header() {
+        let db = setup_db().await;
+
+        // Overwrite the params file with a headerless raw bincode dump, as
+        // every snapshot looked like before the version header existed.
+        let path = db.dir.join(PARAMS_FILE);
+        let bytes = bincode::serialize(&db.params).unwrap();
+        fs::write(&path, bytes).unwrap();
+
+        let params: Parameters = Database::load_binary(path).await.unwrap();
+        assert_eq!(params, db.params);
+    }
+
+    #[tokio::test]
+    async fn test_open_rejects_corrupt_snapshot() {
+        let db = setup_db().await;
+
+        let path = db.dir.join(PARAMS_FILE);
+        let mut bytes = fs::read(&path).unwrap();
+        *bytes.last_mut().unwrap() ^= 0xff;
+        fs::write(&path, bytes).unwrap();
+
+        let error =
+            Database::load_binary::<Parameters>(path).await.unwrap_err();
+        assert!(error.to_string().contains("checksum"));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_roundtrips_compressed() {
+        let mut db = setup_db().await;
+        Arc::get_mut(&mut db).unwrap().params.compression_level = 19;
+
+        db.create_snapshot().await.unwrap();
+        let params: Parameters =
+            Database::load_binary(db.dir.join(PARAMS_FILE)).await.unwrap();
+        assert_eq!(params, db.params);
     }
 
-    fn setup_db() -> Arc<Database> {
+    async fn setup_db() -> Arc<Database> {
         if Database::dir().exists() {
             fs::remove_dir_all(Database::dir()).unwrap();
         }
 
         let params = Parameters::default();
-        Database::configure(&params);
-        Arc::new(Database::open().unwrap())
+        Database::configure(&params).await;
+        Arc::new(Database::open().await.unwrap())
     }
 
     impl Default for Parameters {
@@ -398,6 +653,7 @@ mod tests {
                 dimension: 128,
                 metric: Metric::Euclidean,
                 density: 64,
+                compression_level: 0,
             }
         }
     }
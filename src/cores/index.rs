@@ -1,6 +1,6 @@
 use super::*;
 use std::cmp::{min, Ordering};
-use std::collections::BinaryHeap;
+use std::collections::{BinaryHeap, HashSet};
 use std::rc::Rc;
 
 type ClusterIndex = usize;
@@ -61,7 +61,7 @@ impl From<QueryResult> for protos::QueryResult {
 /// implementation allows OasysDB to maintain a balanced index structure
 /// allowing the clusters to grow to accommodate data growth.
 #[repr(C)]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Index {
     centroids: Vec<Vector>,
     clusters: Vec<Vec<RecordID>>,
@@ -177,38 +177,54 @@ impl Index {
         let probes = min(probes, self.centroids.len());
 
         let nearest_clusters = self.sort_nearest_centroids(vector);
-        let mut results = BinaryHeap::new();
-
-        for cluster_id in nearest_clusters.iter().take(probes) {
-            for record_id in &self.clusters[*cluster_id] {
-                let record = match records.get(record_id) {
-                    Some(record) => record,
-                    None => continue,
-                };
-
-                let distance = self.metric.distance(&record.vector, vector);
-                let distance = match distance {
-                    Some(distance) => distance as f32,
-                    None => continue,
-                };
-
-                // Check if the record is within the search radius and
-                // the record's metadata passes the filters.
-                if distance > radius || !filters.apply(&record.metadata) {
-                    continue;
-                }
 
-                results.push(QueryResult {
-                    id: *record_id,
-                    metadata: record.metadata.clone(),
-                    distance,
-                });
+        // Scan the probed clusters in parallel. Each task keeps its own
+        // bounded top-k heap so the clusters don't contend over one shared
+        // heap, then the per-task heaps are reduced into a single top-k.
+        let results = nearest_clusters[..probes]
+            .par_iter()
+            .fold(BinaryHeap::new, |mut heap, cluster_id| {
+                for record_id in &self.clusters[*cluster_id] {
+                    let record = match records.get(record_id) {
+                        Some(record) => record,
+                        None => continue,
+                    };
+
+                    let distance = self.metric.distance(&record.vector, vector);
+                    let distance = match distance {
+                        Some(distance) => distance as f32,
+                        None => continue,
+                    };
+
+                    // Check if the record is within the search radius and
+                    // the record's metadata passes the filters.
+                    if distance > radius || !filters.apply(&record.metadata) {
+                        continue;
+                    }
+
+                    heap.push(QueryResult {
+                        id: *record_id,
+                        metadata: record.metadata.clone(),
+                        distance,
+                    });
+
+                    if heap.len() > k {
+                        heap.pop();
+                    }
+                }
 
-                if results.len() > k {
-                    results.pop();
+                heap
+            })
+            .reduce(BinaryHeap::new, |mut a, b| {
+                for result in b {
+                    a.push(result);
+                    if a.len() > k {
+                        a.pop();
+                    }
                 }
-            }
-        }
+
+                a
+            });
 
         Ok(results.into_sorted_vec())
     }
@@ -290,7 +306,8 @@ impl Index {
             .map(|id| &records.get(id).unwrap().vector)
             .collect::<Vec<&Vector>>();
 
-        let mut kmeans = KMeans::new(2).with_metric(self.metric);
+        let mut kmeans =
+            KMeans::new(2).with_metric(self.metric).with_balanced(true);
         kmeans.fit(Rc::from(vectors)).unwrap();
 
         let centroids = kmeans.centroids();
@@ -306,6 +323,365 @@ impl Index {
         self.clusters[*cluster_id] = clusters[0].to_vec();
         self.clusters.push(clusters[1].to_vec());
     }
+
+    /// Refine the index with an Enhanced LBG (ELBG) utility-driven pass.
+    ///
+    /// Unlike `split_cluster`, which only reacts to a single overfull
+    /// cluster, this rebalances the whole index: it repeatedly merges a
+    /// low-utility cluster into its nearest neighbor and reinvests the
+    /// freed centroid into splitting a high-distortion cluster, keeping the
+    /// move only when it strictly reduces total distortion. Call this
+    /// periodically, e.g. after every N inserts, instead of rebuilding the
+    /// index from scratch.
+    pub fn refine(&mut self, records: &HashMap<RecordID, Record>) {
+        const MAX_ITERATIONS: usize = 32;
+
+        for _ in 0..MAX_ITERATIONS {
+            if self.clusters.len() < 3 {
+                return;
+            }
+
+            let distortions = self.cluster_distortions(records);
+            let mean = distortions.iter().sum::<f64>() / distortions.len() as f64;
+            if mean <= 0.0 {
+                return;
+            }
+
+            // Lowest-utility cell is the best merge candidate.
+            let low_utility_cell = distortions
+                .iter()
+                .enumerate()
+                .filter(|(_, d)| *d / mean < 1.0)
+                .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap());
+
+            let p = match low_utility_cell {
+                Some((index, _)) => index,
+                None => return,
+            };
+
+            let q = match self.sample_high_distortion_cell(&distortions, p) {
+                Some(index) => index,
+                None => return,
+            };
+
+            if !self.try_shift_split_merge(p, q, &distortions, records) {
+                return;
+            }
+        }
+    }
+
+    /// Compute the distortion `D_i` of every cluster: the sum of squared
+    /// distances from its members to `centroids[i]`.
+    fn cluster_distortions(
+        &self,
+        records: &HashMap<RecordID, Record>,
+    ) -> Vec<f64> {
+        self.clusters
+            .par_iter()
+            .enumerate()
+            .map(|(i, cluster)| self.cluster_distortion(i, cluster, records))
+            .collect()
+    }
+
+    /// Compute the distortion of a single cluster given its member IDs.
+    fn cluster_distortion(
+        &self,
+        cluster_id: ClusterIndex,
+        members: &[RecordID],
+        records: &HashMap<RecordID, Record>,
+    ) -> f64 {
+        let centroid = &self.centroids[cluster_id];
+        members
+            .iter()
+            .filter_map(|id| records.get(id))
+            .map(|record| {
+                let distance = self.metric.distance(&record.vector, centroid);
+                distance.unwrap_or(0.0).powi(2)
+            })
+            .sum()
+    }
+
+    /// Pick a high-distortion cell with probability proportional to its
+    /// distortion, excluding the given low-utility cell.
+    fn sample_high_distortion_cell(
+        &self,
+        distortions: &[f64],
+        excluding: ClusterIndex,
+    ) -> Option<ClusterIndex> {
+        let total: f64 = distortions
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != excluding)
+            .map(|(_, d)| d)
+            .sum();
+
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut rng = rand::thread_rng();
+        let threshold = rng.gen::<f64>() * total;
+        let mut cumulative = 0.0;
+
+        for (i, distortion) in distortions.iter().enumerate() {
+            if i == excluding {
+                continue;
+            }
+
+            cumulative += distortion;
+            if cumulative >= threshold {
+                return Some(i);
+            }
+        }
+
+        None
+    }
+
+    /// Find the nearest neighboring cluster to a given cluster, excluding
+    /// itself and any other cluster to exclude from the search.
+    fn nearest_neighbor_cluster(
+        &self,
+        cluster_id: ClusterIndex,
+        excluding: ClusterIndex,
+    ) -> Option<ClusterIndex> {
+        let centroid = &self.centroids[cluster_id];
+        self.centroids
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != cluster_id && *i != excluding)
+            .filter_map(|(i, other)| {
+                self.metric.distance(other, centroid).map(|d| (i, d))
+            })
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .map(|(i, _)| i)
+    }
+
+    /// Attempt a shift-split-merge triple: merge the low-utility cell `p`
+    /// into its nearest neighbor `n`, split the high-distortion cell `q`
+    /// into two via local 2-means, and place the centroid freed by `p`
+    /// among `q`'s split. The move is only committed if it strictly
+    /// decreases the total distortion of the three affected clusters.
+    fn try_shift_split_merge(
+        &mut self,
+        p: ClusterIndex,
+        q: ClusterIndex,
+        distortions: &[f64],
+        records: &HashMap<RecordID, Record>,
+    ) -> bool {
+        let n = match self.nearest_neighbor_cluster(p, q) {
+            Some(n) => n,
+            None => return false,
+        };
+
+        // Need at least 2 members in q to split it with 2-means.
+        if self.clusters[q].len() < 2 {
+            return false;
+        }
+
+        let original_total = distortions[p] + distortions[q] + distortions[n];
+
+        // Merge p into n.
+        let merged_members: Vec<RecordID> =
+            self.clusters[n].iter().chain(self.clusters[p].iter()).copied().collect();
+        let merged_centroid = self.mean_vector(&merged_members, records);
+
+        // Split q with local 2-means.
+        let vectors = self.clusters[q]
+            .iter()
+            .map(|id| &records.get(id).unwrap().vector)
+            .collect::<Vec<&Vector>>();
+
+        let mut kmeans = KMeans::new(2).with_metric(self.metric);
+        if kmeans.fit(Rc::from(vectors)).is_err() {
+            return false;
+        }
+
+        let split_centroids = kmeans.centroids();
+        let mut split_members = [vec![], vec![]];
+        for (i, cluster_id) in kmeans.assignments().iter().enumerate() {
+            split_members[*cluster_id].push(self.clusters[q][i]);
+        }
+
+        // Back up the affected slots in case the move is rejected.
+        let backup = (
+            self.centroids[p].clone(),
+            self.clusters[p].clone(),
+            self.centroids[q].clone(),
+            self.clusters[q].clone(),
+            self.centroids[n].clone(),
+            self.clusters[n].clone(),
+        );
+
+        // Tentatively commit: n absorbs p's members, while p and q become
+        // the two halves of q's split, reusing p's freed centroid slot.
+        self.centroids[n] = merged_centroid;
+        self.clusters[n] = merged_members;
+        self.centroids[p] = split_centroids[0].to_owned();
+        self.clusters[p] = split_members[0].to_vec();
+        self.centroids[q] = split_centroids[1].to_owned();
+        self.clusters[q] = split_members[1].to_vec();
+
+        let new_total = self.cluster_distortion(p, &self.clusters[p], records)
+            + self.cluster_distortion(q, &self.clusters[q], records)
+            + self.cluster_distortion(n, &self.clusters[n], records);
+
+        if new_total < original_total {
+            return true;
+        }
+
+        // Revert: the move didn't pay for itself.
+        (
+            self.centroids[p],
+            self.clusters[p],
+            self.centroids[q],
+            self.clusters[q],
+            self.centroids[n],
+            self.clusters[n],
+        ) = backup;
+
+        false
+    }
+
+    /// Compute the mean vector of a set of records, used to recompute a
+    /// centroid after merging clusters.
+    fn mean_vector(
+        &self,
+        ids: &[RecordID],
+        records: &HashMap<RecordID, Record>,
+    ) -> Vector {
+        let dimension = records.values().next().map(|r| r.vector.len()).unwrap_or(0);
+        let mut sum = vec![0.0_f32; dimension];
+
+        for id in ids {
+            if let Some(record) = records.get(id) {
+                for (s, v) in sum.iter_mut().zip(record.vector.as_slice()) {
+                    *s += v;
+                }
+            }
+        }
+
+        let count = ids.len().max(1) as f32;
+        sum.iter().map(|x| x / count).collect::<Vec<f32>>().into()
+    }
+
+    /// Rebuild `centroids`/`clusters` using DBSCAN instead of the growing
+    /// IVF heuristic.
+    ///
+    /// This suits datasets with non-spherical or variable-density regions
+    /// that IVF's fixed `density` clusters represent poorly. Points that
+    /// don't belong to any dense region (noise) are folded into their
+    /// nearest discovered cluster afterwards so `query` still covers them.
+    /// - `eps`: Maximum distance for two points to be considered neighbors.
+    /// - `min_pts`: Minimum number of neighbors for a point to seed a
+    ///   cluster.
+    pub fn recluster_dbscan(
+        &mut self,
+        eps: f32,
+        min_pts: usize,
+        records: &HashMap<RecordID, Record>,
+    ) {
+        let ids: Vec<RecordID> = records.keys().copied().collect();
+        let mut visited: HashSet<RecordID> = HashSet::new();
+        let mut labels: HashMap<RecordID, Option<usize>> = HashMap::new();
+        let mut cluster_count = 0;
+
+        for &id in &ids {
+            if visited.contains(&id) {
+                continue;
+            }
+
+            visited.insert(id);
+            let mut neighbors = self.region_query(&id, eps, &ids, records);
+            if neighbors.len() < min_pts {
+                labels.insert(id, None);
+                continue;
+            }
+
+            let cluster_id = cluster_count;
+            cluster_count += 1;
+            labels.insert(id, Some(cluster_id));
+
+            // Transitively expand the cluster through every
+            // density-reachable point.
+            let mut i = 0;
+            while i < neighbors.len() {
+                let neighbor = neighbors[i];
+                i += 1;
+
+                if visited.insert(neighbor) {
+                    let neighbor_neighbors =
+                        self.region_query(&neighbor, eps, &ids, records);
+
+                    if neighbor_neighbors.len() >= min_pts {
+                        for candidate in neighbor_neighbors {
+                            if !neighbors.contains(&candidate) {
+                                neighbors.push(candidate);
+                            }
+                        }
+                    }
+                }
+
+                if labels.get(&neighbor).copied().flatten().is_none() {
+                    labels.insert(neighbor, Some(cluster_id));
+                }
+            }
+        }
+
+        // If every point turned out to be noise, keep the existing
+        // centroids/clusters rather than emptying the index.
+        if cluster_count == 0 {
+            return;
+        }
+
+        let mut clusters: Vec<Vec<RecordID>> = vec![vec![]; cluster_count];
+        let mut noise: Vec<RecordID> = vec![];
+
+        for &id in &ids {
+            match labels.get(&id).copied().flatten() {
+                Some(cluster_id) => clusters[cluster_id].push(id),
+                None => noise.push(id),
+            }
+        }
+
+        self.centroids = clusters
+            .iter()
+            .map(|members| self.mean_vector(members, records))
+            .collect();
+        self.clusters = clusters;
+
+        // Collect noise points into their nearest existing centroid so
+        // `query` still covers them.
+        for id in noise {
+            let vector = &records.get(&id).unwrap().vector;
+            if let Some(cluster_id) = self.find_nearest_centroid(vector) {
+                self.clusters[cluster_id].push(id);
+            }
+        }
+    }
+
+    /// Find all points within `eps` distance of a given point, excluding
+    /// the point itself.
+    fn region_query(
+        &self,
+        id: &RecordID,
+        eps: f32,
+        ids: &[RecordID],
+        records: &HashMap<RecordID, Record>,
+    ) -> Vec<RecordID> {
+        let point = &records.get(id).unwrap().vector;
+        ids.par_iter()
+            .filter(|&&other| {
+                if other == *id {
+                    return false;
+                }
+
+                let vector = &records.get(&other).unwrap().vector;
+                let distance = self.metric.distance(point, vector);
+                distance.unwrap_or(f64::INFINITY) as f32 <= eps
+            })
+            .copied()
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -487,6 +863,68 @@ mod tests {
         assert_eq!(index.centroids.len(), 2);
     }
 
+    #[test]
+    fn test_refine() {
+        let params = Parameters::default();
+        let mut index = setup_index(&params);
+
+        let mut records = HashMap::new();
+        for _ in 0..2000 {
+            let id = RecordID::new();
+            let record = Record::random(params.dimension);
+            records.insert(id, record);
+        }
+
+        for (id, record) in records.iter() {
+            index.insert(id, record, &records).unwrap();
+        }
+
+        let cluster_count_before = index.clusters.len();
+        index.refine(&records);
+
+        // Refine only reshuffles members between existing clusters; it
+        // must never change how many clusters the index has.
+        assert_eq!(index.clusters.len(), cluster_count_before);
+
+        let total_records: usize =
+            index.clusters.iter().map(|cluster| cluster.len()).sum();
+        assert_eq!(total_records, records.len());
+    }
+
+    #[test]
+    fn test_recluster_dbscan() {
+        let params = Parameters::default();
+        let mut index = setup_index(&params);
+
+        // Two well-separated dense blobs plus a handful of noise points.
+        let mut records = HashMap::new();
+        for _ in 0..50 {
+            let id = RecordID::new();
+            let vector = Vector::from(vec![0.0; params.dimension]);
+            records.insert(id, Record { vector, metadata: HashMap::new() });
+        }
+
+        for _ in 0..50 {
+            let id = RecordID::new();
+            let vector = Vector::from(vec![100.0; params.dimension]);
+            records.insert(id, Record { vector, metadata: HashMap::new() });
+        }
+
+        for i in 0..5 {
+            let id = RecordID::new();
+            let vector = Vector::from(vec![50.0 + i as f32; params.dimension]);
+            records.insert(id, Record { vector, metadata: HashMap::new() });
+        }
+
+        index.recluster_dbscan(1.0, 5, &records);
+
+        assert_eq!(index.centroids.len(), 2);
+
+        let total_records: usize =
+            index.clusters.iter().map(|cluster| cluster.len()).sum();
+        assert_eq!(total_records, records.len());
+    }
+
     #[test]
     fn test_sort_nearest_centroids() {
         let params = Parameters::default();
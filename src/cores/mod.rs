@@ -1,11 +1,13 @@
 // Initialize the modules without making them public.
 mod database;
 mod index;
+mod postgres_storage;
 mod storage;
 
 // Re-export types from the modules.
 pub use database::*;
 pub use index::*;
+pub use postgres_storage::*;
 pub use storage::*;
 
 // Import common dependencies below.
@@ -15,7 +17,6 @@ use rayon::prelude::*;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
-use std::fs::OpenOptions;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 use std::{env, fs};
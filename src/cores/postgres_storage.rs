@@ -0,0 +1,149 @@
+use super::*;
+use async_trait::async_trait;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::{PgPool, Row};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Record storage backed by Postgres.
+///
+/// Every mutation is written through to a `records` table (vector as
+/// `bytea`, metadata as `jsonb`) so records survive a crash without
+/// waiting for the next snapshot. Reads are served from an in-memory
+/// cache mirroring the table, so the index can keep querying the storage
+/// the same synchronous way it queries [`Storage`].
+#[derive(Debug, Clone)]
+pub struct PostgresStorage {
+    pool: PgPool,
+    cache: HashMap<RecordID, Record>,
+}
+
+impl PostgresStorage {
+    /// Connects to `database_url`, creating the `records` table if it
+    /// doesn't exist yet, then loads its contents into the cache.
+    pub async fn connect(database_url: &str) -> Result<Self, Box<dyn Error>> {
+        let pool = PgPoolOptions::new()
+            .max_connections(10)
+            .acquire_timeout(Duration::from_secs(5))
+            .test_before_acquire(true)
+            .connect(database_url)
+            .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS records (
+                id UUID PRIMARY KEY,
+                vector BYTEA NOT NULL,
+                metadata JSONB NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await?;
+
+        let rows = sqlx::query("SELECT id, vector, metadata FROM records")
+            .fetch_all(&pool)
+            .await?;
+
+        let mut cache = HashMap::new();
+        for row in rows {
+            let id: Uuid = row.try_get("id")?;
+            let vector: Vec<u8> = row.try_get("vector")?;
+            let metadata: serde_json::Value = row.try_get("metadata")?;
+
+            let id = id.to_string().parse::<RecordID>()?;
+            let vector = bincode::deserialize(&vector)?;
+            let metadata = serde_json::from_value(metadata)?;
+            cache.insert(id, Record { vector, metadata });
+        }
+
+        Ok(PostgresStorage { pool, cache })
+    }
+
+    fn pg_id(id: &RecordID) -> Result<Uuid, Status> {
+        Uuid::parse_str(&id.to_string())
+            .map_err(|_| Status::internal("Failed to encode the record ID"))
+    }
+}
+
+#[async_trait]
+impl StorageBackend for PostgresStorage {
+    async fn insert(
+        &mut self,
+        id: &RecordID,
+        record: &Record,
+    ) -> Result<(), Status> {
+        let pg_id = Self::pg_id(id)?;
+        let vector = bincode::serialize(&record.vector)
+            .map_err(|_| Status::internal("Failed to encode the vector"))?;
+        let metadata = serde_json::to_value(&record.metadata)
+            .map_err(|_| Status::internal("Failed to encode the metadata"))?;
+
+        sqlx::query(
+            "INSERT INTO records (id, vector, metadata) VALUES ($1, $2, $3)",
+        )
+        .bind(pg_id)
+        .bind(&vector)
+        .bind(&metadata)
+        .execute(&self.pool)
+        .await
+        .map_err(|_| Status::internal("Failed to insert the record"))?;
+
+        self.cache.insert(*id, record.to_owned());
+        Ok(())
+    }
+
+    async fn get(&self, id: &RecordID) -> Result<Record, Status> {
+        match self.cache.get(id) {
+            Some(record) => Ok(record.to_owned()),
+            None => {
+                let message = "The specified record is not found";
+                Err(Status::not_found(message))
+            }
+        }
+    }
+
+    async fn delete(&mut self, id: &RecordID) -> Result<(), Status> {
+        let pg_id = Self::pg_id(id)?;
+        sqlx::query("DELETE FROM records WHERE id = $1")
+            .bind(pg_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| Status::internal("Failed to delete the record"))?;
+
+        self.cache.remove(id);
+        Ok(())
+    }
+
+    async fn update(
+        &mut self,
+        id: &RecordID,
+        metadata: &HashMap<String, Value>,
+    ) -> Result<(), Status> {
+        if !self.cache.contains_key(id) {
+            let message = "The specified record is not found";
+            return Err(Status::not_found(message));
+        }
+
+        let pg_id = Self::pg_id(id)?;
+        let metadata_json = serde_json::to_value(metadata)
+            .map_err(|_| Status::internal("Failed to encode the metadata"))?;
+
+        sqlx::query("UPDATE records SET metadata = $1 WHERE id = $2")
+            .bind(&metadata_json)
+            .bind(pg_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|_| Status::internal("Failed to update the record"))?;
+
+        // Unwrap is safe, `contains_key` above already confirmed the entry.
+        self.cache.get_mut(id).unwrap().metadata = metadata.to_owned();
+        Ok(())
+    }
+
+    fn records(&self) -> &HashMap<RecordID, Record> {
+        &self.cache
+    }
+
+    fn count(&self) -> usize {
+        self.cache.len()
+    }
+}
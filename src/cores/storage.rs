@@ -1,12 +1,113 @@
 use super::*;
+use async_trait::async_trait;
 
-/// Record storage interface.
+/// Pluggable record storage interface.
+///
+/// [`Storage`] is the default, in-memory implementation, snapshotted to
+/// disk alongside the index (see [`Database::create_snapshot`]). A
+/// [`PostgresStorage`] implementation is also available, persisting every
+/// mutation to Postgres for crash durability instead of relying solely on
+/// periodic snapshots.
+///
+/// `insert`/`get`/`delete`/`update` are async so an implementation backed
+/// by a real database can await its I/O; `records`/`count` stay
+/// synchronous since every implementation keeps an in-memory view for the
+/// index to query.
+#[async_trait]
+pub trait StorageBackend: std::fmt::Debug + Send + Sync {
+    async fn insert(
+        &mut self,
+        id: &RecordID,
+        record: &Record,
+    ) -> Result<(), Status>;
+
+    async fn get(&self, id: &RecordID) -> Result<Record, Status>;
+    async fn delete(&mut self, id: &RecordID) -> Result<(), Status>;
+
+    async fn update(
+        &mut self,
+        id: &RecordID,
+        metadata: &HashMap<String, Value>,
+    ) -> Result<(), Status>;
+
+    /// Return a reference to the records in the storage.
+    fn records(&self) -> &HashMap<RecordID, Record>;
+
+    /// Return the number of records in the storage.
+    fn count(&self) -> usize;
+}
+
+/// Selects which [`StorageBackend`] a [`Database`] is running with, chosen
+/// once at `configure`/`open` time based on whether `ODB_STORAGE_URL` is
+/// set. Kept as an enum rather than `Box<dyn StorageBackend>` so
+/// [`Database::create_snapshot`] can tell, without downcasting, whether
+/// the active backend also needs a file snapshot of its records.
+#[derive(Debug, Clone)]
+pub enum StorageEngine {
+    Memory(Storage),
+    Postgres(PostgresStorage),
+}
+
+#[async_trait]
+impl StorageBackend for StorageEngine {
+    async fn insert(
+        &mut self,
+        id: &RecordID,
+        record: &Record,
+    ) -> Result<(), Status> {
+        match self {
+            StorageEngine::Memory(s) => s.insert(id, record).await,
+            StorageEngine::Postgres(s) => s.insert(id, record).await,
+        }
+    }
+
+    async fn get(&self, id: &RecordID) -> Result<Record, Status> {
+        match self {
+            StorageEngine::Memory(s) => s.get(id).await,
+            StorageEngine::Postgres(s) => s.get(id).await,
+        }
+    }
+
+    async fn delete(&mut self, id: &RecordID) -> Result<(), Status> {
+        match self {
+            StorageEngine::Memory(s) => s.delete(id).await,
+            StorageEngine::Postgres(s) => s.delete(id).await,
+        }
+    }
+
+    async fn update(
+        &mut self,
+        id: &RecordID,
+        metadata: &HashMap<String, Value>,
+    ) -> Result<(), Status> {
+        match self {
+            StorageEngine::Memory(s) => s.update(id, metadata).await,
+            StorageEngine::Postgres(s) => s.update(id, metadata).await,
+        }
+    }
+
+    fn records(&self) -> &HashMap<RecordID, Record> {
+        match self {
+            StorageEngine::Memory(s) => s.records(),
+            StorageEngine::Postgres(s) => s.records(),
+        }
+    }
+
+    fn count(&self) -> usize {
+        match self {
+            StorageEngine::Memory(s) => s.count(),
+            StorageEngine::Postgres(s) => s.count(),
+        }
+    }
+}
+
+/// In-memory record storage.
 ///
 /// This interface wraps around Hashbrown's HashMap implementation to store
 /// the records. In the future, if needed, we can modify the storage
 /// implementation without changing the rest of the code.
 #[repr(C)]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Storage {
     count: usize,
     records: HashMap<RecordID, Record>,
@@ -17,9 +118,12 @@ impl Storage {
     pub fn new() -> Self {
         Storage { count: 0, records: HashMap::new() }
     }
+}
 
+#[async_trait]
+impl StorageBackend for Storage {
     /// Insert a new record into the record storage.
-    pub fn insert(
+    async fn insert(
         &mut self,
         id: &RecordID,
         record: &Record,
@@ -29,8 +133,19 @@ impl Storage {
         Ok(())
     }
 
+    /// Return a copy of a record from the storage given its ID.
+    async fn get(&self, id: &RecordID) -> Result<Record, Status> {
+        match self.records.get(id) {
+            Some(record) => Ok(record.to_owned()),
+            None => {
+                let message = "The specified record is not found";
+                Err(Status::not_found(message))
+            }
+        }
+    }
+
     /// Delete a record from the storage given its ID.
-    pub fn delete(&mut self, id: &RecordID) -> Result<(), Status> {
+    async fn delete(&mut self, id: &RecordID) -> Result<(), Status> {
         self.records.remove(id);
         self.count -= 1;
         Ok(())
@@ -41,7 +156,7 @@ impl Storage {
     /// Vector data should be immutable as it is tightly coupled with the
     /// semantic meaning of the record. If the vector data changes, users
     /// should create a new record instead.
-    pub fn update(
+    async fn update(
         &mut self,
         id: &RecordID,
         metadata: &HashMap<String, Value>,
@@ -59,51 +174,56 @@ impl Storage {
     }
 
     /// Return a reference to the records in the storage.
-    pub fn records(&self) -> &HashMap<RecordID, Record> {
+    fn records(&self) -> &HashMap<RecordID, Record> {
         &self.records
     }
+
+    /// Return the number of records in the storage.
+    fn count(&self) -> usize {
+        self.count
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_insert() {
+    #[tokio::test]
+    async fn test_insert() {
         let mut storage = Storage::new();
 
         let record = Record::random(128);
         let id = RecordID::new();
-        storage.insert(&id, &record).unwrap();
+        storage.insert(&id, &record).await.unwrap();
 
         assert_eq!(storage.count, 1);
         assert_eq!(storage.count, storage.records.len());
     }
 
-    #[test]
-    fn test_delete() {
+    #[tokio::test]
+    async fn test_delete() {
         let mut storage = Storage::new();
 
         let record = Record::random(128);
         let id = RecordID::new();
-        storage.insert(&id, &record).unwrap();
+        storage.insert(&id, &record).await.unwrap();
 
-        storage.delete(&id).unwrap();
+        storage.delete(&id).await.unwrap();
         assert_eq!(storage.count, 0);
         assert_eq!(storage.count, storage.records.len());
     }
 
-    #[test]
-    fn test_update() {
+    #[tokio::test]
+    async fn test_update() {
         let mut storage = Storage::new();
 
         let record = Record::random(128);
         let id = RecordID::new();
-        storage.insert(&id, &record).unwrap();
+        storage.insert(&id, &record).await.unwrap();
 
         let mut metadata = HashMap::new();
         metadata.insert("key".to_string(), Value::random());
-        storage.update(&id, &metadata).unwrap();
+        storage.update(&id, &metadata).await.unwrap();
 
         let updated_record = storage.records.get(&id).unwrap();
         assert_eq!(updated_record.metadata, metadata);
@@ -1,24 +1,95 @@
 use super::*;
 use array::downcast_array;
-use arrow::compute::concat_batches;
+use arrow::buffer::Buffer;
+use arrow::compute::{concat_batches, take};
+use arrow::datatypes::Float32Type;
+use arrow::ipc::convert::fb_to_schema;
+use arrow::ipc::reader::{read_footer_length, FileDecoder};
+use arrow::ipc::{root_as_footer, Block};
+use roaring::RoaringBitmap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+#[cfg(feature = "py")]
+use arrow::pyarrow::PyArrowType;
+#[cfg(feature = "py")]
+use pyo3::exceptions::PyException;
+#[cfg(feature = "py")]
+use pyo3::prelude::*;
+
+// A dedicated Python exception for Arrow schema/array mismatches crossing
+// the C Data Interface, distinct from the `PyValueError` the in-memory
+// HNSW `Collection` raises, so callers can catch a malformed
+// `RecordBatch` separately from an ordinary bad-argument error.
+#[cfg(feature = "py")]
+pyo3::create_exception!(oasysdb, PyArrowException, PyException);
+
+#[cfg(feature = "py")]
+impl From<Error> for PyErr {
+    fn from(err: Error) -> Self {
+        PyArrowException::new_err(err.message)
+    }
+}
+
+/// One append-only entry in a collection's transaction log, written by a
+/// single mutating operation. Replaying these in version order against
+/// the empty starting state reconstructs `CollectionState` as of any
+/// committed version, the same way Delta Lake rebuilds a table snapshot
+/// from its commit history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitEntry {
+    pub version: u64,
+    pub files_added: Vec<PathBuf>,
+    pub files_removed: Vec<PathBuf>,
+    pub schema: Schema,
+    pub count: usize,
+    pub next_id: u32,
+    pub dimension: usize,
+    pub metric: String,
+}
+
+/// Distance metric used when no `set_metric` call has locked one in yet,
+/// matching `func::distance::Distance`'s own default.
+const DEFAULT_METRIC: &str = "euclidean";
+
+/// Arrow extension type name for the `vector` field, so schema-reading
+/// tooling can recognize a `FixedSizeList<Float32>` column as logically a
+/// searchable vector rather than just a generic list of floats.
+const VECTOR_EXTENSION_NAME: &str = "oasysdb.vector";
+
+/// Extension metadata serialized onto the `vector` field's
+/// `ARROW:extension:metadata` key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VectorExtension {
+    dimension: usize,
+    metric: String,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CollectionState {
     pub batch_size: usize,
     pub count: usize,
     pub dimension: usize,
+    /// Distance metric ("euclidean", "cosine", or "dot") this collection's
+    /// vectors are compared with, mirrored into the `vector` field's
+    /// Arrow extension metadata so it round-trips through IPC/export.
+    pub metric: String,
     pub schema: Schema,
     pub dir: Directory,
     /// Tracker of the next internal ID to assign to a record.
     next_id: u32,
+    /// Total rows physically stored across all data files, including rows
+    /// marked deleted in `dir.deletions` but not yet reclaimed by
+    /// `vacuum`. `count` tracks the logical (non-deleted) row count.
+    physical_count: usize,
+    /// Version of the last transaction log entry reflected in this state.
+    version: u64,
 }
 
 impl CollectionState {
     fn new(dir: PathBuf) -> Result<Self, Error> {
         let field_id = Field::new("internal_id", DataType::Int32, false);
-
-        let vector_type = MetadataType::Vector.into();
-        let field_vector = Field::new("vector", vector_type, false);
+        let field_vector = Self::vector_field(0, DEFAULT_METRIC);
 
         let mut state = Self {
             schema: Schema::new(vec![field_id, field_vector]),
@@ -26,13 +97,70 @@ impl CollectionState {
             batch_size: 1000,
             count: 0,
             dimension: 0,
+            metric: DEFAULT_METRIC.to_string(),
             next_id: 1,
+            physical_count: 0,
+            version: 0,
         };
 
         state.create_data_file()?;
         Ok(state)
     }
 
+    /// Empty in-memory state used as the starting point for replaying the
+    /// transaction log, without creating any files on disk. The version-1
+    /// log entry written by `initialize_state` adds the real initial data
+    /// file once replay reaches it.
+    fn empty(dir: PathBuf) -> Self {
+        Self {
+            schema: Schema::empty(),
+            dir: Directory::new(dir),
+            batch_size: 1000,
+            count: 0,
+            dimension: 0,
+            metric: DEFAULT_METRIC.to_string(),
+            next_id: 1,
+            physical_count: 0,
+            version: 0,
+        }
+    }
+
+    /// Builds the `vector` field as a `FixedSizeList` of `dimension` Float32
+    /// components, so the schema itself states the column's width instead
+    /// of the per-row offsets a variable-length list would need. Used with
+    /// `dimension: 0` before the collection's first insert, when the width
+    /// isn't known yet, and rebuilt with the real width once it is. Tagged
+    /// with the `oasysdb.vector` Arrow extension type so `dimension` and
+    /// `metric` travel with the schema itself rather than living outside
+    /// the data.
+    fn vector_field(dimension: usize, metric: &str) -> Field {
+        let item = Field::new("item", DataType::Float32, true);
+        let datatype =
+            DataType::FixedSizeList(Arc::new(item), dimension as i32);
+
+        Field::new("vector", datatype, false)
+            .with_metadata(Self::vector_extension_metadata(dimension, metric))
+    }
+
+    /// Builds the `ARROW:extension:name`/`ARROW:extension:metadata` pair
+    /// that marks the `vector` field as an `oasysdb.vector` extension
+    /// type, the same convention Arrow's own canonical extension types
+    /// (e.g. `arrow.json`) use to layer a logical type over a physical
+    /// one. Both keys are plain field metadata, so they round-trip
+    /// through IPC and bincode serialization for free.
+    fn vector_extension_metadata(
+        dimension: usize,
+        metric: &str,
+    ) -> HashMap<String, String> {
+        let extension = VectorExtension { dimension, metric: metric.into() };
+        let extension_metadata = serde_json::to_string(&extension).unwrap();
+
+        HashMap::from([
+            ("ARROW:extension:name".to_string(), VECTOR_EXTENSION_NAME.into()),
+            ("ARROW:extension:metadata".to_string(), extension_metadata),
+        ])
+    }
+
     fn create_data_file(&mut self) -> Result<PathBuf, Error> {
         // The filename would be something like: cdata0000001.
         let index = self.dir.data_files.len() + 1;
@@ -57,6 +185,7 @@ impl CollectionState {
         file_writer.finish()?;
 
         self.dir.data_files.push(data_file.clone());
+        self.dir.deletions.push(RoaringBitmap::new());
         Ok(data_file)
     }
 }
@@ -66,19 +195,47 @@ pub struct Directory {
     pub root: PathBuf,
     pub state_file: PathBuf,
     pub data_files: Vec<PathBuf>,
+    /// Deletion vector for each entry in `data_files`, at the same index,
+    /// marking which `internal_id`s in that file are soft-deleted. Modeled
+    /// on Delta Lake's deletion vectors so a delete doesn't require
+    /// rewriting the (potentially large) Arrow IPC file it targets.
+    pub deletions: Vec<RoaringBitmap>,
+    /// Append-only transaction log, one [`CommitEntry`] per mutating
+    /// operation, used to reconstruct historical versions of the state.
+    pub log_file: PathBuf,
+    /// Full state snapshot written by `Collection::checkpoint`, so
+    /// reconstructing a version doesn't always require replaying the log
+    /// from the very beginning of the collection's history.
+    pub checkpoint_file: PathBuf,
 }
 
 impl Directory {
     fn new(root: PathBuf) -> Self {
         let state_file = root.join("cstate");
-        Self { root, state_file, data_files: vec![] }
+        let log_file = root.join("clog");
+        let checkpoint_file = root.join("ccheckpoint");
+
+        Self {
+            root,
+            state_file,
+            data_files: vec![],
+            deletions: vec![],
+            log_file,
+            checkpoint_file,
+        }
     }
 }
 
+#[cfg_attr(feature = "py", pyclass(module = "oasysdb.db"))]
 pub struct Collection {
     state: Lock<CollectionState>,
 }
 
+// This exposes Collection methods to Python.
+// Any modifications to these methods should be reflected in:
+// - py/tests/test_collection.py
+// - py/oasysdb/collection.pyi
+#[cfg_attr(feature = "py", pymethods)]
 impl Collection {
     pub fn open(dir: PathBuf) -> Result<Self, Error> {
         if !dir.try_exists()? {
@@ -115,6 +272,7 @@ impl Collection {
 
         // Update the state and data.
         state.schema = new_schema;
+        self.record_commit(&mut state, vec![], vec![])?;
         *state = state.clone();
 
         drop(state);
@@ -163,6 +321,52 @@ impl Collection {
 
         // Update the state and data.
         state.schema = new_schema;
+        self.record_commit(&mut state, vec![], vec![])?;
+        *state = state.clone();
+
+        drop(state);
+        self.persist_state()?;
+        Ok(())
+    }
+
+    /// Declares the distance metric ("euclidean", "cosine", or "dot") this
+    /// collection's vectors are compared with, re-tagging the `vector`
+    /// field's Arrow extension metadata so it's recorded in the schema
+    /// itself instead of living outside the data. Like `add_fields`, this
+    /// only works before the collection's first insert, since `dimension`
+    /// (the other half of the extension metadata) isn't known yet either.
+    pub fn set_metric(&self, metric: &str) -> Result<(), Error> {
+        let mut state = self.state.write()?;
+
+        if state.count > 0 {
+            let code = ErrorCode::ClientError;
+            let message = "Unable to change the metric of a non-empty \
+                collection";
+            return Err(Error::new(&code, message));
+        }
+
+        if !["euclidean", "cosine", "dot"].contains(&metric) {
+            let code = ErrorCode::ClientError;
+            let message = "Unsupported distance metric";
+            return Err(Error::new(&code, message));
+        }
+
+        state.metric = metric.to_string();
+
+        let fields: Vec<Field> = state
+            .schema
+            .all_fields()
+            .into_iter()
+            .map(|field| match field.name().as_str() {
+                "vector" => {
+                    CollectionState::vector_field(state.dimension, metric)
+                }
+                _ => field.clone(),
+            })
+            .collect();
+        state.schema = Schema::new(fields);
+
+        self.record_commit(&mut state, vec![], vec![])?;
         *state = state.clone();
 
         drop(state);
@@ -189,9 +393,9 @@ impl Collection {
 
         let data_size = vector_array.len();
         let dimension = {
-            let array: ListArray = downcast_array(vector_array.as_ref());
-            let vector: Float32Array = downcast_array(array.value(0).as_ref());
-            vector.len()
+            let array: FixedSizeListArray =
+                downcast_array(vector_array.as_ref());
+            array.value_length() as usize
         };
 
         if dimension == 0 {
@@ -200,9 +404,30 @@ impl Collection {
             return Err(Error::new(&code, message));
         }
 
-        // If it's the first record, we need to update the dimension.
+        // If it's the first record, the collection's dimension is still
+        // unbound: lock it in and resize the schema's `vector` field to a
+        // `FixedSizeList` of this width, so every later insert is validated
+        // against it the same way a mismatched column count would be.
         if state.count == 0 && state.dimension == 0 {
             state.dimension = dimension;
+
+            let metric = state.metric.clone();
+            let fields: Vec<Field> = state
+                .schema
+                .all_fields()
+                .into_iter()
+                .map(|field| match field.name().as_str() {
+                    "vector" => {
+                        CollectionState::vector_field(dimension, &metric)
+                    }
+                    _ => field.clone(),
+                })
+                .collect();
+            state.schema = Schema::new(fields);
+        } else if dimension != state.dimension {
+            let code = ErrorCode::ClientError;
+            let message = "The vector dimension doesn't match the collection";
+            return Err(Error::new(&code, message));
         }
 
         // Ensure all vectors have the same dimension.
@@ -230,7 +455,9 @@ impl Collection {
                 MetadataType::Float => Float32Array::null_array(data_size),
                 MetadataType::String => StringArray::null_array(data_size),
                 MetadataType::Boolean => BooleanArray::null_array(data_size),
-                MetadataType::Vector => ListArray::null_array(data_size),
+                MetadataType::Vector => {
+                    FixedSizeListArray::null_array(data_size, dimension)
+                }
             };
 
             (field.name().to_string(), array as Arc<dyn Array>)
@@ -261,43 +488,49 @@ impl Collection {
         // OasysDB limits the number of record batches in a data file to 1.
         // Per record batch, there can be a maximum of 1000 records by default.
 
-        // The behavior is as follows:
-        // 1. If the last data file is empty, write the record batch to it.
-        // 2. If the last data file is not empty, combine the last record batch
-        //    with the new record batch and write the combined record batch to
-        //    the last data file until it reaches the batch size.
+        // Writes are copy-on-write: the combined record batch always lands
+        // in brand-new data files rather than overwriting the last one, so
+        // any earlier committed version that still references it keeps
+        // seeing unchanged data. The behavior is as follows:
+        // 1. If the last data file is empty (never part of a committed
+        //    version), its slot is simply replaced.
+        // 2. If the last data file holds committed rows, combine it with
+        //    the new record batch, write the result to new files, and
+        //    record the old file as removed in this version's commit.
 
-        let data_files = &mut state.dir.data_files;
         let file_ops = FileOps::default();
 
         // Also, we can unwrap here because the data files won't be None.
-        let last_data_file = data_files.last().unwrap();
-        let last_record_batch = file_ops.read_ipc_file(last_data_file)?;
+        let last_data_file = state.dir.data_files.last().unwrap().clone();
+        let last_record_batch = file_ops.read_ipc_file(&last_data_file)?;
+        let last_file_committed = last_record_batch.num_rows() != 0;
 
-        let record_batch = if last_record_batch.num_rows() != 0 {
+        let record_batch = if last_file_committed {
             let batches = vec![&last_record_batch, &record_batch];
             concat_batches(&schemaref, batches)?
         } else {
             record_batch
         };
 
-        let mut files_to_write = vec![last_data_file.clone()];
+        // Drop the stale slot for the last data file; `create_data_file`
+        // below pushes a fresh data file/deletion-vector pair in its place.
+        state.dir.data_files.pop();
+        state.dir.deletions.pop();
 
         // This determines the number of new files to create.
         // Let's say the batch size is 1000 and the combined record batch
-        // has 1500 records. This means we need to create 1 new file because
-        // the first 1000 records will be written to the last data file and
-        // the remaining 500 records will be written to the new file.
+        // has 1500 records. This means we need to create 2 new files: the
+        // first 1000 records go in one file and the remaining 500 in the
+        // other.
         let num_new_file = {
-            let size = record_batch.num_rows();
-            let remain = size.saturating_sub(state.batch_size) as f32;
-            let div = remain / state.batch_size as f32;
+            let size = record_batch.num_rows().max(1);
+            let div = size as f32 / state.batch_size as f32;
             div.ceil() as usize
         };
 
+        let mut files_to_write = Vec::with_capacity(num_new_file);
         for _ in 0..num_new_file {
-            let data_file = state.create_data_file()?;
-            files_to_write.push(data_file);
+            files_to_write.push(state.create_data_file()?);
         }
 
         FileOps::default().write_ipc_files(
@@ -306,9 +539,17 @@ impl Collection {
             state.batch_size,
         )?;
 
+        let files_removed = if last_file_committed {
+            vec![last_data_file]
+        } else {
+            vec![]
+        };
+
         // Update and persist the state.
         state.count += data_size;
+        state.physical_count += data_size;
         state.next_id += data_size as u32;
+        self.record_commit(&mut state, files_to_write, files_removed)?;
         *state = state.clone();
 
         // Drop the state lock before persisting the state.
@@ -318,14 +559,681 @@ impl Collection {
 
         Ok(())
     }
+
+    /// Writes this collection's current rows to a standalone Arrow IPC
+    /// (Feather) file at `path`, giving it a durable interchange format
+    /// alongside the bincode files `FileOps` writes for internal state.
+    /// Deleted rows that haven't been reclaimed by `vacuum` yet are still
+    /// present in the underlying data files and so are included as-is.
+    pub fn export_ipc(&self, path: impl Into<PathBuf>) -> Result<(), Error> {
+        let combined = self.read_all_batch()?;
+        let batch_size = combined.num_rows().max(1);
+        let file_ops = FileOps::default();
+        file_ops.write_ipc_files(&[path.into()], &combined, batch_size)
+    }
+
+    // Reads and concatenates every data file into a single record batch,
+    // the same rows `export_ipc` writes out, factored out so the PyArrow
+    // export path below doesn't need its own copy of this scan.
+    fn read_all_batch(&self) -> Result<RecordBatch, Error> {
+        let state = self.state.read()?.clone();
+        let file_ops = FileOps::default();
+        let schema_ref = Arc::new(state.schema.clone());
+
+        let mut batches = Vec::with_capacity(state.dir.data_files.len());
+        for data_file in &state.dir.data_files {
+            batches.push(file_ops.read_ipc_file(data_file)?);
+        }
+
+        Ok(concat_batches(&schema_ref, &batches)?)
+    }
+
+    /// Inserts a `pyarrow.RecordBatch` or `Table` into this collection,
+    /// crossing the C Data Interface via [`PyArrowType`] instead of
+    /// extracting each value one at a time the way `From<&PyAny> for
+    /// Metadata` does, so a batch insert from Python doesn't pay for a
+    /// GIL-bound round trip per record. Schema/array mismatches surface
+    /// as a catchable [`PyArrowException`] rather than the `panic!` that
+    /// `From<&PyAny>` falls back to on an unsupported type.
+    #[cfg(feature = "py")]
+    #[pyo3(name = "insert_record_batch")]
+    fn py_insert_record_batch(
+        &self,
+        batch: PyArrowType<RecordBatch>,
+    ) -> Result<(), Error> {
+        self.insert_batch(&batch.0)
+    }
+
+    /// Returns every row currently stored in the collection as a
+    /// zero-copy `pyarrow.Table`, the read-side counterpart of
+    /// `insert_record_batch`.
+    #[cfg(feature = "py")]
+    #[pyo3(name = "to_record_batch")]
+    fn py_to_record_batch(&self) -> Result<PyArrowType<RecordBatch>, Error> {
+        Ok(PyArrowType(self.read_all_batch()?))
+    }
+
+    /// Bulk-loads rows from a standalone Arrow IPC file written by
+    /// `export_ipc` (or any compatible writer) into this collection,
+    /// reusing `insert_records`'s validation and ID assignment.
+    pub fn import_ipc(&self, path: impl Into<PathBuf>) -> Result<(), Error> {
+        let batch = FileOps::default().read_ipc_file(&path.into())?;
+        self.insert_batch(&batch)
+    }
+
+    /// Bulk-loads rows from an Arrow IPC file directly out of a
+    /// memory-mapped buffer, so ingesting millions of vectors doesn't
+    /// require first buffering them through row-format records. `buffer`
+    /// is typically an `Arc<Mmap>`. Each referenced block is copied out of
+    /// it into an owned Arrow buffer; the arrays don't borrow the mapping.
+    pub fn import_ipc_mmap<B: Clone + AsRef<[u8]>>(
+        &self,
+        buffer: B,
+    ) -> Result<(), Error> {
+        for batch in Self::read_ipc_mmap(buffer)? {
+            self.insert_batch(&batch)?;
+        }
+
+        Ok(())
+    }
+
+    /// Decodes every record batch in a memory-mapped Arrow IPC file, one
+    /// block at a time, copying each block's bytes as it is decoded: the
+    /// footer and schema are parsed first, then dictionaries are mapped (unchecked, since the footer's
+    /// block offsets are trusted as-is) before the record batches that may
+    /// reference them, then each record batch is mapped against the
+    /// resolved schema in turn.
+    fn read_ipc_mmap<B: Clone + AsRef<[u8]>>(
+        buffer: B,
+    ) -> Result<Vec<RecordBatch>, Error> {
+        let code = ErrorCode::ClientError;
+        let bytes = buffer.as_ref();
+
+        let trailer_start = bytes.len() - 10;
+        let footer_len =
+            read_footer_length(bytes[trailer_start..].try_into().unwrap())?;
+
+        let footer_start = trailer_start - footer_len;
+        let footer = root_as_footer(&bytes[footer_start..trailer_start])
+            .map_err(|_| Error::new(&code, "Invalid Arrow IPC footer"))?;
+
+        let schema_fb = footer
+            .schema()
+            .ok_or_else(|| Error::new(&code, "IPC file is missing a schema"))?;
+        let schema = Arc::new(fb_to_schema(schema_fb));
+
+        let mut decoder = FileDecoder::new(schema, footer.version());
+
+        for block in footer.dictionaries().into_iter().flatten() {
+            let data = Self::mmap_block(bytes, block);
+            decoder.read_dictionary(block, &data)?;
+        }
+
+        let mut batches = Vec::new();
+        for block in footer.record_batches().into_iter().flatten() {
+            let data = Self::mmap_block(bytes, block);
+            if let Some(batch) = decoder.read_record_batch(block, &data)? {
+                batches.push(batch);
+            }
+        }
+
+        Ok(batches)
+    }
+
+    // Slices the mapped buffer at the offset and length recorded in an IPC
+    // footer block, copying those bytes into a new Arrow `Buffer`; the
+    // buffer doesn't borrow the mapped bytes.
+    fn mmap_block(bytes: &[u8], block: &Block) -> Buffer {
+        let offset = block.offset() as usize;
+        let length =
+            block.metaDataLength() as usize + block.bodyLength() as usize;
+        Buffer::from(&bytes[offset..offset + length])
+    }
+
+    // Inserts every row of `batch` as new records, reusing the field
+    // names/arrays path `insert_records` already validates against the
+    // collection's schema. `internal_id` is dropped since IDs are always
+    // assigned fresh on insert rather than imported.
+    fn insert_batch(&self, batch: &RecordBatch) -> Result<(), Error> {
+        let schema = batch.schema();
+
+        let field_names: Vec<String> = schema
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .filter(|name| name != "internal_id")
+            .collect();
+
+        let records: Vec<Arc<dyn Array>> = field_names
+            .iter()
+            .map(|name| batch.column_by_name(name).unwrap().clone())
+            .collect();
+
+        self.insert_records(&field_names, &records)
+    }
+
+    /// Streams a newline-delimited JSON file straight into this collection,
+    /// so bulk data doesn't need to be buffered through row structs first.
+    /// When `infer_schema` is set, the schema is inferred by scanning the
+    /// first `sample_size` lines before any row is inserted; otherwise the
+    /// collection's existing schema is used as-is. A row missing the
+    /// `vector` field is rejected the same way a missing vector is
+    /// rejected elsewhere in this collection.
+    pub fn import_ndjson(
+        &self,
+        path: impl Into<PathBuf>,
+        infer_schema: bool,
+        sample_size: usize,
+    ) -> Result<(), Error> {
+        let path = path.into();
+
+        if infer_schema {
+            let sample = BufReader::new(File::open(&path)?);
+            let inferred = Self::infer_ndjson_schema(sample, sample_size)?;
+            self.add_fields(inferred.fields)?;
+        }
+
+        let schema = self.state.read()?.schema.clone();
+        let batch_size = self.state.read()?.batch_size;
+
+        let mut rows: Vec<serde_json::Value> = Vec::with_capacity(batch_size);
+        for line in BufReader::new(File::open(&path)?).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let code = ErrorCode::ClientError;
+            let row: serde_json::Value = serde_json::from_str(&line)
+                .map_err(|_| Error::new(&code, "Malformed NDJSON line"))?;
+            rows.push(row);
+
+            if rows.len() == batch_size {
+                self.insert_json_rows(&schema, std::mem::take(&mut rows))?;
+            }
+        }
+
+        if !rows.is_empty() {
+            self.insert_json_rows(&schema, rows)?;
+        }
+
+        Ok(())
+    }
+
+    // Infers a schema from up to `sample_size` NDJSON lines: each key's
+    // type is coerced per record (integer, float, boolean, string, or a
+    // vector for JSON arrays), then unified across records, with integer
+    // widening to float when both appear and anything else falling back
+    // to string.
+    fn infer_ndjson_schema(
+        reader: impl BufRead,
+        sample_size: usize,
+    ) -> Result<Schema, Error> {
+        let code = ErrorCode::ClientError;
+        let mut order: Vec<String> = Vec::new();
+        let mut types: HashMap<String, MetadataType> = HashMap::new();
+
+        for line in reader.lines().take(sample_size) {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let row: serde_json::Value = serde_json::from_str(&line)
+                .map_err(|_| Error::new(&code, "Malformed NDJSON line"))?;
+            let object = row.as_object().ok_or_else(|| {
+                Error::new(&code, "Each NDJSON line must be a JSON object")
+            })?;
+
+            for (key, value) in object {
+                let inferred = Self::infer_json_type(value);
+                match types.get(key) {
+                    Some(existing) => {
+                        let widened = Self::widen_type(*existing, inferred);
+                        types.insert(key.clone(), widened);
+                    }
+                    None => {
+                        order.push(key.clone());
+                        types.insert(key.clone(), inferred);
+                    }
+                }
+            }
+        }
+
+        let fields = order
+            .into_iter()
+            .map(|key| {
+                let datatype: DataType = types[&key].into();
+                Field::new(&key, datatype, true)
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Schema::new(fields))
+    }
+
+    // Coerces a single JSON value into the field type it would occupy: a
+    // float or integer number, a boolean, a vector for a JSON array, and a
+    // string for anything else, including `null`.
+    fn infer_json_type(value: &serde_json::Value) -> MetadataType {
+        match value {
+            serde_json::Value::Bool(_) => MetadataType::Boolean,
+            serde_json::Value::Number(number) if number.is_i64() => {
+                MetadataType::Integer
+            }
+            serde_json::Value::Number(_) => MetadataType::Float,
+            serde_json::Value::Array(_) => MetadataType::Vector,
+            _ => MetadataType::String,
+        }
+    }
+
+    // Unifies two type observations for the same key across records:
+    // identical types pass through, an integer/float mix widens to float,
+    // and any other mismatch falls back to string.
+    fn widen_type(a: MetadataType, b: MetadataType) -> MetadataType {
+        match (a, b) {
+            (a, b) if a == b => a,
+            (MetadataType::Integer, MetadataType::Float)
+            | (MetadataType::Float, MetadataType::Integer) => {
+                MetadataType::Float
+            }
+            _ => MetadataType::String,
+        }
+    }
+
+    // Projects a batch of JSON rows into the schema's column arrays and
+    // inserts them the same way `insert_records` would. Missing or `null`
+    // keys become null slots rather than errors, except the `vector`
+    // field, which is required on every row.
+    fn insert_json_rows(
+        &self,
+        schema: &Schema,
+        rows: Vec<serde_json::Value>,
+    ) -> Result<(), Error> {
+        let fields: Vec<Field> = schema
+            .all_fields()
+            .into_iter()
+            .filter(|field| field.name() != "internal_id")
+            .cloned()
+            .collect();
+
+        let mut field_names = Vec::with_capacity(fields.len());
+        let mut arrays: Vec<Arc<dyn Array>> = Vec::with_capacity(fields.len());
+
+        for field in &fields {
+            let values: Vec<Option<&serde_json::Value>> = rows
+                .iter()
+                .map(|row| row.get(field.name()).filter(|v| !v.is_null()))
+                .collect();
+
+            if field.name() == "vector" && values.iter().any(Option::is_none) {
+                let code = ErrorCode::ClientError;
+                let message = "Every row must include the vector field";
+                return Err(Error::new(&code, message));
+            }
+
+            let datatype: MetadataType = field.data_type().clone().into();
+            let array = Self::build_json_column(datatype, &values)?;
+
+            field_names.push(field.name().clone());
+            arrays.push(array);
+        }
+
+        self.insert_records(&field_names, &arrays)
+    }
+
+    // Builds one Arrow array out of a column's JSON values, coercing each
+    // value to `datatype` and leaving a null slot where the row omitted
+    // the key (or set it to `null`).
+    fn build_json_column(
+        datatype: MetadataType,
+        values: &[Option<&serde_json::Value>],
+    ) -> Result<Arc<dyn Array>, Error> {
+        let code = ErrorCode::ClientError;
+
+        let array: Arc<dyn Array> = match datatype {
+            MetadataType::Integer => {
+                let column: Vec<Option<i32>> = values
+                    .iter()
+                    .map(|v| v.and_then(|v| v.as_i64()).map(|n| n as i32))
+                    .collect();
+                Arc::new(Int32Array::from(column))
+            }
+            MetadataType::Float => {
+                let column: Vec<Option<f32>> = values
+                    .iter()
+                    .map(|v| v.and_then(|v| v.as_f64()).map(|n| n as f32))
+                    .collect();
+                Arc::new(Float32Array::from(column))
+            }
+            MetadataType::Boolean => {
+                let column: Vec<Option<bool>> = values
+                    .iter()
+                    .map(|v| v.and_then(|v| v.as_bool()))
+                    .collect();
+                Arc::new(BooleanArray::from(column))
+            }
+            MetadataType::String => {
+                let column: Vec<Option<String>> = values
+                    .iter()
+                    .map(|v| match v {
+                        Some(serde_json::Value::String(text)) => {
+                            Some(text.clone())
+                        }
+                        Some(other) => Some(other.to_string()),
+                        None => None,
+                    })
+                    .collect();
+                Arc::new(StringArray::from(column))
+            }
+            MetadataType::Vector => {
+                let column: Vec<Vec<f32>> = values
+                    .iter()
+                    .map(|v| match v {
+                        Some(serde_json::Value::Array(items)) => items
+                            .iter()
+                            .filter_map(|item| item.as_f64())
+                            .map(|n| n as f32)
+                            .collect(),
+                        _ => vec![],
+                    })
+                    .collect();
+
+                let dimension = column.iter().map(Vec::len).find(|&n| n > 0);
+                let dimension = match dimension {
+                    Some(dimension) => dimension,
+                    None => {
+                        let message = "The vector column can't be empty";
+                        return Err(Error::new(&code, message));
+                    }
+                };
+
+                if column.iter().any(|vector| vector.len() != dimension) {
+                    let message =
+                        "Every row's vector must share the same dimension";
+                    return Err(Error::new(&code, message));
+                }
+
+                let values: Vec<f32> = column.into_iter().flatten().collect();
+                let values = Float32Array::from(values);
+                let list = FixedSizeListArray::try_new(
+                    Arc::new(Field::new("item", DataType::Float32, true)),
+                    dimension as i32,
+                    Arc::new(values),
+                    None,
+                )?;
+                Arc::new(list)
+            }
+        };
+
+        Ok(array)
+    }
+
+    /// Soft-deletes records by setting their `internal_id`s in the
+    /// deletion vector of whichever data file holds them. No data file is
+    /// rewritten; deleted rows are simply skipped by query and scan paths
+    /// until a later `vacuum` reclaims the space. IDs that don't exist or
+    /// are already deleted are silently skipped.
+    /// - `ids`: Internal IDs of the records to delete.
+    pub fn delete_records(&self, ids: &[u32]) -> Result<(), Error> {
+        let mut state = self.state.write()?;
+        let file_ops = FileOps::default();
+
+        let mut deleted = 0;
+        for &id in ids {
+            let Some((file_index, _row)) =
+                Self::locate_record(&state, &file_ops, id)?
+            else {
+                continue;
+            };
+
+            if state.dir.deletions[file_index].insert(id) {
+                deleted += 1;
+            }
+        }
+
+        state.count = state.count.saturating_sub(deleted);
+        self.record_commit(&mut state, vec![], vec![])?;
+        *state = state.clone();
+
+        drop(state);
+        self.persist_state()?;
+        Ok(())
+    }
+
+    /// Rewrites a data file to physically drop its deleted rows once its
+    /// deletion ratio reaches `threshold`, then clears that file's
+    /// deletion vector. Files under the threshold are left alone, so a
+    /// handful of stale rows doesn't force a rewrite.
+    /// - `threshold`: Fraction (0..1) of a file's rows that must be
+    ///   deleted before it's rewritten.
+    pub fn vacuum(&self, threshold: f32) -> Result<(), Error> {
+        let mut state = self.state.write()?;
+        let file_ops = FileOps::default();
+        let schema_ref = Arc::new(state.schema.clone());
+
+        for file_index in 0..state.dir.data_files.len() {
+            let bitmap = state.dir.deletions[file_index].clone();
+            if bitmap.is_empty() {
+                continue;
+            }
+
+            let data_file = state.dir.data_files[file_index].clone();
+            let batch = file_ops.read_ipc_file(&data_file)?;
+
+            let total_rows = batch.num_rows();
+            if total_rows == 0 {
+                continue;
+            }
+
+            let ratio = bitmap.len() as f32 / total_rows as f32;
+            if ratio < threshold {
+                continue;
+            }
+
+            let ids: Int32Array =
+                downcast_array(batch.column_by_name("internal_id").unwrap());
+
+            let keep_rows: Vec<u32> = (0..total_rows as u32)
+                .filter(|&row| {
+                    !bitmap.contains(ids.value(row as usize) as u32)
+                })
+                .collect();
+
+            let indices = UInt32Array::from(keep_rows);
+            let columns = batch
+                .columns()
+                .iter()
+                .map(|column| take(column, &indices, None))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let vacuumed = RecordBatch::try_new(schema_ref.clone(), columns)?;
+            let batch_size = state.batch_size;
+            file_ops.write_ipc_files(&[data_file], &vacuumed, batch_size)?;
+
+            state.physical_count -= bitmap.len() as usize;
+            state.dir.deletions[file_index] = RoaringBitmap::new();
+        }
+
+        *state = state.clone();
+        drop(state);
+        self.persist_state()?;
+        Ok(())
+    }
+
+    /// Finds the data file and row position holding `internal_id`.
+    /// `internal_id` is assigned monotonically and never reused, so a
+    /// given ID can only ever live in one file; this stops reading files
+    /// as soon as it's found rather than scanning every file up front.
+    fn locate_record(
+        state: &CollectionState,
+        file_ops: &FileOps,
+        internal_id: u32,
+    ) -> Result<Option<(usize, usize)>, Error> {
+        for (file_index, data_file) in state.dir.data_files.iter().enumerate() {
+            let batch = file_ops.read_ipc_file(data_file)?;
+            let ids: Int32Array =
+                downcast_array(batch.column_by_name("internal_id").unwrap());
+
+            let target = internal_id as i32;
+            let row = (0..ids.len()).find(|&row| ids.value(row) == target);
+            if let Some(row) = row {
+                return Ok(Some((file_index, row)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Appends a [`CommitEntry`] for the operation that's about to commit
+    /// and bumps `state.version`, recording the data files it added and
+    /// removed along with the schema/count/next_id as of the new version.
+    fn record_commit(
+        &self,
+        state: &mut CollectionState,
+        files_added: Vec<PathBuf>,
+        files_removed: Vec<PathBuf>,
+    ) -> Result<(), Error> {
+        state.version += 1;
+
+        let entry = CommitEntry {
+            version: state.version,
+            files_added,
+            files_removed,
+            schema: state.schema.clone(),
+            count: state.count,
+            next_id: state.next_id,
+            dimension: state.dimension,
+            metric: state.metric.clone(),
+        };
+
+        FileOps::default().append_binary_file(&state.dir.log_file, &entry)
+    }
+
+    /// Reconstructs the `CollectionState` as committed at `version`, by
+    /// replaying the transaction log from the last checkpoint at or before
+    /// `version` (or from the beginning of history if there isn't one).
+    pub fn state_at(
+        dir: impl Into<PathBuf>,
+        version: u64,
+    ) -> Result<CollectionState, Error> {
+        Self::replay_log(&dir.into(), Some(version))
+    }
+
+    /// Reconstructs the latest committed `CollectionState` by replaying
+    /// the transaction log, rather than trusting the `cstate` snapshot
+    /// `persist_state` writes after every operation. Useful for recovering
+    /// from a crash between a commit and its snapshot write.
+    pub fn read_state_latest(
+        dir: impl Into<PathBuf>,
+    ) -> Result<CollectionState, Error> {
+        Self::replay_log(&dir.into(), None)
+    }
+
+    /// Opens the collection as of a specific committed `version`, for
+    /// time-travel queries against a historical snapshot.
+    pub fn open_at(dir: PathBuf, version: u64) -> Result<Self, Error> {
+        let state = Self::state_at(dir, version)?;
+        let state = Lock::new(state);
+        Ok(Self { state })
+    }
+
+    /// Writes a full snapshot of the current state to the checkpoint file,
+    /// so a later `state_at`/`read_state_latest` only has to replay log
+    /// entries committed after it instead of the entire history.
+    pub fn checkpoint(&self) -> Result<(), Error> {
+        let state = self.state.read()?.clone();
+        FileOps::default().write_binary_file(&state.dir.checkpoint_file, &state)
+    }
+
+    /// Replays the transaction log to reconstruct a `CollectionState`,
+    /// starting from the most recent checkpoint at or before `version`
+    /// (or the beginning of history if there isn't one), and stopping
+    /// after `version` if one was given.
+    fn replay_log(
+        dir: &Path,
+        version: Option<u64>,
+    ) -> Result<CollectionState, Error> {
+        let file_ops = FileOps::default();
+        let directory = Directory::new(dir.to_path_buf());
+
+        let checkpoint: Option<CollectionState> = file_ops
+            .read_binary_file(&directory.checkpoint_file)
+            .ok()
+            .filter(|state: &CollectionState| match version {
+                Some(version) => state.version <= version,
+                None => true,
+            });
+
+        let mut state = checkpoint
+            .unwrap_or_else(|| CollectionState::empty(dir.to_path_buf()));
+
+        let entries: Vec<CommitEntry> =
+            file_ops.read_binary_log(&directory.log_file)?;
+
+        for entry in entries {
+            if entry.version <= state.version {
+                continue;
+            }
+            if version.is_some_and(|version| entry.version > version) {
+                break;
+            }
+
+            if !entry.files_removed.is_empty() {
+                let kept: Vec<(PathBuf, RoaringBitmap)> = state
+                    .dir
+                    .data_files
+                    .drain(..)
+                    .zip(state.dir.deletions.drain(..))
+                    .filter(|(file, _)| !entry.files_removed.contains(file))
+                    .collect();
+
+                for (file, bitmap) in kept {
+                    state.dir.data_files.push(file);
+                    state.dir.deletions.push(bitmap);
+                }
+            }
+
+            for file in &entry.files_added {
+                state.dir.data_files.push(file.clone());
+                state.dir.deletions.push(RoaringBitmap::new());
+            }
+
+            state.schema = entry.schema.clone();
+            state.count = entry.count;
+            state.next_id = entry.next_id;
+            state.dimension = entry.dimension;
+            state.metric = entry.metric.clone();
+            state.version = entry.version;
+        }
+
+        Ok(state)
+    }
 }
 
 impl StateMachine<CollectionState> for Collection {
     fn initialize_state(
         root: impl Into<PathBuf>,
     ) -> Result<CollectionState, Error> {
-        let state = CollectionState::new(root.into())?;
-        FileOps::default().write_binary_file(&state.dir.state_file, &state)?;
+        let mut state = CollectionState::new(root.into())?;
+        let file_ops = FileOps::default();
+
+        // Seed the transaction log with the initial, empty data file as
+        // version 1, so `state_at`/`read_state_latest` can account for it
+        // from the very start of the collection's history.
+        state.version = 1;
+        let entry = CommitEntry {
+            version: state.version,
+            files_added: state.dir.data_files.clone(),
+            files_removed: vec![],
+            schema: state.schema.clone(),
+            count: state.count,
+            next_id: state.next_id,
+            dimension: state.dimension,
+            metric: state.metric.clone(),
+        };
+        file_ops.append_binary_file(&state.dir.log_file, &entry)?;
+
+        file_ops.write_binary_file(&state.dir.state_file, &state)?;
         Ok(state)
     }
 
@@ -25,25 +25,22 @@ impl Collection {
     }
 
     /// Validates the vectors given a column array consisting of vectors.
-    /// This ensures that all vectors provided have the same dimension.
+    /// This ensures that all vectors provided have the same dimension and
+    /// that none of them are null, since a `FixedSizeListArray` only fixes
+    /// the width of each row, not whether the row is present at all.
     pub fn validate_vectors(
         &self,
         vectors: &Arc<dyn Array>,
         dimension: usize,
     ) -> Result<(), Error> {
-        let vector_array: ListArray = downcast_array(vectors.as_ref());
+        let vector_array: FixedSizeListArray =
+            downcast_array(vectors.as_ref());
 
-        let is_dimension_mismatch = |array: Arc<dyn Array>| {
-            let vector: Float32Array = downcast_array(array.as_ref());
-            vector.len() != dimension
-        };
+        let dimension_mismatch =
+            vector_array.value_length() as usize != dimension;
+        let has_nulls = vector_array.null_count() > 0;
 
-        let dimension_mismatch = vector_array.iter().any(|array| match array {
-            Some(array) => is_dimension_mismatch(array),
-            None => true,
-        });
-
-        if dimension_mismatch {
+        if dimension_mismatch || has_nulls {
             let code = ErrorCode::ClientError;
             let message = "Vectors must have the same dimension.";
             return Err(Error::new(&code, message));
@@ -1,18 +1,82 @@
 use super::*;
+use crate::types::filter::Operator;
+use crate::types::record::{Record, Value};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use futures::executor;
 use futures::stream::StreamExt;
-use sqlx::any::install_default_drivers;
-use sqlx::Acquire;
+use sqlx::any::{install_default_drivers, AnyArguments};
+use sqlx::query::Query;
+use sqlx::{Acquire, Any};
+use std::collections::HashSet;
+use std::fs::OpenOptions;
+use std::io::{Read, Write};
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tar::{Archive, Builder, Header};
+use tokio::sync::RwLock;
 use url::Url;
 use uuid::Uuid;
 
+/// [`DumpManifest`] format version. Bumped whenever the archive layout
+/// changes incompatibly; [`Database::async_restore`] refuses to read a
+/// dump whose version is newer than this.
+const DUMP_FORMAT_VERSION: u32 = 1;
+
+/// Binds `values` onto `query` in order, picking the `Any`-compatible bind
+/// method for each [`Value`] variant.
+fn bind_values<'q>(
+    mut query: Query<'q, Any, AnyArguments<'q>>,
+    values: &'q [Value],
+) -> Query<'q, Any, AnyArguments<'q>> {
+    for value in values {
+        query = match value {
+            Value::Text(text) => query.bind(text),
+            Value::Number(number) => query.bind(number),
+            Value::Int(int) => query.bind(int),
+            Value::Boolean(boolean) => query.bind(boolean),
+            Value::Null => query.bind(Option::<bool>::None),
+            Value::Timestamp(micros) => query.bind(micros),
+            Value::NumberArray(numbers) => {
+                let json = serde_json::to_string(numbers)
+                    .expect("NumberArray values should serialize to JSON");
+
+                query.bind(json)
+            }
+            Value::Array(_) => panic!("Array values can't be bound to a query"),
+        };
+    }
+
+    query
+}
+
 // Type aliases for better readability.
 type DatabaseURL = String;
 type IndexName = String;
 type IndexFile = PathBuf;
-type Index = Arc<Mutex<Box<dyn VectorIndex>>>;
-type IndicesPool = Mutex<HashMap<IndexName, Index>>;
+/// A pooled index. Guarded by an async [`RwLock`] rather than a blocking
+/// `Mutex`, so many concurrent searches can hold a read lock at once and
+/// only a write (refresh, create) excludes readers.
+type Index = Arc<RwLock<Box<dyn VectorIndex>>>;
+type IndicesPool = RwLock<HashMap<IndexName, PoolEntry>>;
+
+/// An [`Index`] resident in the pool, plus the bookkeeping
+/// [`Database::evict_lru_locked`] needs to pick an eviction candidate.
+///
+/// `last_access` is its own [`Mutex`] rather than living behind the pool's
+/// lock, so touching it on a cache hit only needs the pool's read lock,
+/// not a write lock that would serialize concurrent searches.
+struct PoolEntry {
+    index: Index,
+    last_access: Mutex<Instant>,
+}
+
+impl PoolEntry {
+    fn new(index: Index) -> Self {
+        Self { index, last_access: Mutex::new(Instant::now()) }
+    }
+}
 
 /// The vector database interface.
 ///
@@ -24,6 +88,12 @@ pub struct Database {
     root: PathBuf,
     state: Mutex<DatabaseState>,
     pool: IndicesPool,
+    /// Max number of resident indices before [`Self::async_get_index`]
+    /// evicts the least-recently-used one. `None` means unbounded.
+    pool_capacity: Mutex<Option<usize>>,
+    /// Index names exempted from LRU eviction regardless of access time.
+    pinned: Mutex<HashSet<IndexName>>,
+    keys: Mutex<HashMap<String, ApiKey>>,
 }
 
 impl Database {
@@ -83,8 +153,58 @@ impl Database {
 
         state.validate_connection()?;
         let state = Mutex::new(state);
-        let pool: IndicesPool = Mutex::new(HashMap::new());
-        Ok(Self { root: root_dir, state, pool })
+        let pool: IndicesPool = RwLock::new(HashMap::new());
+        let pool_capacity = Mutex::new(None);
+        let pinned = Mutex::new(HashSet::new());
+
+        let keys_file = root_dir.join("odbkeys");
+        let keys: HashMap<String, ApiKey> = if keys_file.try_exists()? {
+            file::read_binary_file(&keys_file)?
+        } else {
+            HashMap::new()
+        };
+        let keys = Mutex::new(keys);
+
+        Ok(Self {
+            root: root_dir,
+            state,
+            pool,
+            pool_capacity,
+            pinned,
+            keys,
+        })
+    }
+
+    /// Sets the max number of resident indices, evicting the
+    /// least-recently-used unpinned one whenever [`Self::async_get_index`]
+    /// would otherwise exceed it. Chainable at construction time, e.g.
+    /// `Database::open(root, source_url)?.with_pool_capacity(8)`.
+    pub fn with_pool_capacity(self, capacity: usize) -> Self {
+        self.set_pool_capacity(Some(capacity));
+        self
+    }
+
+    /// Sets or clears (`None`) the indices pool's max resident count.
+    /// See [`Self::with_pool_capacity`].
+    pub fn set_pool_capacity(&self, capacity: Option<usize>) {
+        if let Ok(mut current) = self.pool_capacity.lock() {
+            *current = capacity;
+        }
+    }
+
+    /// Exempts `name` from LRU eviction until [`Self::unpin_index`] is
+    /// called, even if it isn't currently resident in the pool.
+    pub fn pin_index(&self, name: impl Into<IndexName>) {
+        if let Ok(mut pinned) = self.pinned.lock() {
+            pinned.insert(name.into());
+        }
+    }
+
+    /// Makes `name` eligible for LRU eviction again.
+    pub fn unpin_index(&self, name: impl AsRef<str>) {
+        if let Ok(mut pinned) = self.pinned.lock() {
+            pinned.remove(name.as_ref());
+        }
     }
 
     /// Creates a new index in the database asynchronously.
@@ -97,18 +217,29 @@ impl Database {
         algorithm: IndexAlgorithm,
         config: SourceConfig,
     ) -> Result<(), Error> {
-        // Query the source database for records.
-        let query = config.to_query();
-        let mut conn = self.state()?.async_connect().await?;
-        let mut stream = sqlx::query(&query).fetch(conn.acquire().await?);
+        let state = self.state()?;
+        let source_type = state.source_type();
 
-        // Process the rows from the query as records.
-        let mut records = HashMap::new();
-        while let Some(row) = stream.next().await {
-            let row = row?;
-            let (id, record) = config.to_record(&row)?;
-            records.insert(id, record);
-        }
+        // File sources are read directly; SQL sources are queried.
+        let (records, file_offset) = if source_type.is_file() {
+            let path = state.source_file_path()?;
+            let (records, len) = config.to_file_records(&source_type, path)?;
+            (records, Some(len))
+        } else {
+            let (query, binds) = config.to_query(&source_type)?;
+            let mut conn = state.async_connect().await?;
+            let built = bind_values(sqlx::query(&query), &binds);
+            let mut stream = built.fetch(conn.acquire().await?);
+
+            let mut records = HashMap::new();
+            while let Some(row) = stream.next().await {
+                let row = row?;
+                let (id, record) = config.to_record(&row)?;
+                records.insert(id, record);
+            }
+
+            (records, None)
+        };
 
         let index_name: IndexName = name.into();
         let index_file = {
@@ -124,8 +255,10 @@ impl Database {
 
         // Insert the index into the pool for easy access.
         {
-            let mut pool = self.pool.lock()?;
-            pool.insert(index_name.clone(), Arc::new(Mutex::new(index)));
+            let mut pool = self.pool.write().await;
+            self.evict_lru_locked(&mut pool);
+            let index: Index = Arc::new(RwLock::new(index));
+            pool.insert(index_name.clone(), PoolEntry::new(index));
         }
 
         // Update db state with the new index.
@@ -133,7 +266,8 @@ impl Database {
         // before persisting the state to the file.
         {
             let mut state = self.state.lock()?;
-            let index_ref = IndexRef { algorithm, config, file: index_file };
+            let index_ref =
+                IndexRef { algorithm, config, file: index_file, file_offset };
             state.indices.insert(index_name, index_ref);
         }
 
@@ -154,6 +288,63 @@ impl Database {
         executor::block_on(self.async_create_index(name, algorithm, config))
     }
 
+    /// Inserts a batch of records into an index's data source
+    /// asynchronously.
+    /// - `name`: Name of the index whose data source to insert into.
+    /// - `records`: Records to insert, in order.
+    /// - `batch_size`: Number of rows bound per flushed `INSERT` statement.
+    ///
+    /// The whole batch is inserted in a single transaction, flushed in
+    /// chunks of `batch_size` rows bound as prepared statement parameters,
+    /// instead of building one large interpolated `INSERT` string. This
+    /// only writes to the data source; call [`Self::async_refresh_index`]
+    /// afterwards to pick up the new records into the index itself.
+    pub async fn async_bulk_insert(
+        &self,
+        name: impl AsRef<str>,
+        records: &[Record],
+        batch_size: usize,
+    ) -> Result<(), Error> {
+        let name = name.as_ref();
+        let index_ref = self.get_index_ref(name).ok_or_else(|| {
+            let code = ErrorCode::NotFound;
+            let message = format!("Index not found: {name}.");
+            Error::new(code, message)
+        })?;
+
+        let config = index_ref.config();
+        let state = self.state()?;
+        let source_type = state.source_type();
+
+        if source_type.is_file() {
+            let path = state.source_file_path()?;
+            return config.to_file_insert(&source_type, path, records);
+        }
+
+        let mut conn = state.async_connect().await?;
+        let mut tx = conn.acquire().await?.begin().await?;
+
+        for batch in records.chunks(batch_size.max(1)) {
+            let (query, binds) = config.to_insert_query(&source_type, batch)?;
+            let built = bind_values(sqlx::query(&query), &binds);
+            built.execute(&mut *tx).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Inserts a batch of records into an index's data source
+    /// synchronously. See [`Self::async_bulk_insert`].
+    pub fn bulk_insert(
+        &self,
+        name: impl AsRef<str>,
+        records: &[Record],
+        batch_size: usize,
+    ) -> Result<(), Error> {
+        executor::block_on(self.async_bulk_insert(name, records, batch_size))
+    }
+
     /// Returns an index reference.
     /// - `name`: Index name.
     ///
@@ -165,41 +356,71 @@ impl Database {
         Some(index_ref.to_owned())
     }
 
-    /// Retrieves an index and returns it as a trait object.
+    /// Retrieves an index and returns it as a trait object, asynchronously.
     /// - `name`: Index name.
     ///
-    /// This method will return the index from the pool if it exists.
-    /// Otherwise, it will load the index from the file and store it
-    /// in the pool for future access.
-    pub fn get_index(&self, name: impl AsRef<str>) -> Option<Index> {
+    /// Returns the index from the pool if it's already resident. Otherwise,
+    /// loads it from its file and inserts it into the pool for future
+    /// access. The common pool-hit path only takes a read lock, so many
+    /// concurrent lookups proceed without blocking each other; the pool is
+    /// write-locked only on the rarer cold-load path.
+    pub async fn async_get_index(
+        &self,
+        name: impl AsRef<str>,
+    ) -> Option<Index> {
         let name = name.as_ref();
         let IndexRef { algorithm, file, .. } = self.get_index_ref(name)?;
 
-        // If the index is already in the indices pool, return it.
-        let mut pool = self.pool.lock().ok()?;
-        if let Some(index) = pool.get(name).cloned() {
-            return Some(index);
+        {
+            let pool = self.pool.read().await;
+            if let Some(entry) = pool.get(name) {
+                if let Ok(mut last_access) = entry.last_access.lock() {
+                    *last_access = Instant::now();
+                }
+
+                return Some(entry.index.clone());
+            }
         }
 
+        // Replay any leftover refresh checkpoint before loading, so a
+        // crash mid-refresh doesn't surface a stale or incomplete index.
+        self.replay_staging(name, &algorithm, &file).ok()?;
+
         // Load the index from the file and store it in the pool.
         // Then, return the index as a trait object.
-        let index = algorithm.load_index(file).ok()?;
-        let index: Index = Arc::new(Mutex::new(index));
-        pool.insert(name.into(), index.clone());
+        let index = algorithm.load_index(&file).ok()?;
+        let index: Index = Arc::new(RwLock::new(index));
+
+        let mut pool = self.pool.write().await;
+        self.evict_lru_locked(&mut pool);
+        pool.insert(name.into(), PoolEntry::new(index.clone()));
         Some(index)
     }
 
-    /// Retrieves an index and returns it in a result.
+    /// Blocking variant of [`Self::async_get_index`].
+    pub fn get_index(&self, name: impl AsRef<str>) -> Option<Index> {
+        executor::block_on(self.async_get_index(name))
+    }
+
+    /// Retrieves an index and returns it in a result, asynchronously.
     /// - `name`: Index name.
-    pub fn try_get_index(&self, name: impl AsRef<str>) -> Result<Index, Error> {
+    pub async fn async_try_get_index(
+        &self,
+        name: impl AsRef<str>,
+    ) -> Result<Index, Error> {
         let name = name.as_ref();
-        self.get_index(name).ok_or_else(|| {
+        self.async_get_index(name).await.ok_or_else(|| {
             let code = ErrorCode::NotFound;
             let message = format!("Index not found in database: {name}.");
             Error::new(code, message)
         })
     }
 
+    /// Blocking variant of [`Self::async_try_get_index`].
+    pub fn try_get_index(&self, name: impl AsRef<str>) -> Result<Index, Error> {
+        executor::block_on(self.async_try_get_index(name))
+    }
+
     /// Updates the index with new records from the source asynchronously.
     /// - `name`: Index name.
     ///
@@ -218,37 +439,87 @@ impl Database {
         })?;
 
         // Cloning is necessary here to avoid borrowing issues.
-        let IndexRef { algorithm, file, config } = index_ref.to_owned();
+        let IndexRef { algorithm, file, config, file_offset } =
+            index_ref.to_owned();
 
         // It's safe to unwrap here because we validated that index exists by
         // calling get_index_ref method above.
-        let index: Index = self.get_index(name).unwrap();
-
-        let (query, config) = {
-            // We wrap the index lock in a closure to make sure it's dropped
-            // before async functionalities are called.
-            let index = index.lock()?;
-            let meta = index.metadata();
-            let checkpoint = meta.last_inserted.unwrap_or_default();
-            (config.to_query_after(&checkpoint), config)
-        };
+        let index: Index = self.async_get_index(name).await.unwrap();
 
-        let mut conn = self.state()?.async_connect().await?;
-        let mut stream = sqlx::query(&query).fetch(conn.acquire().await?);
+        let state = self.state()?;
+        let source_type = state.source_type();
 
-        // Process the rows from the database as records.
-        let mut records = HashMap::new();
-        while let Some(row) = stream.next().await {
-            let row = row?;
-            let (id, record) = config.to_record(&row)?;
-            records.insert(id, record);
-        }
+        // File sources checkpoint by byte offset; SQL sources checkpoint
+        // by the index's own last-inserted record ID.
+        let (records, new_file_offset) = if source_type.is_file() {
+            let path = state.source_file_path()?;
+            let offset = file_offset.unwrap_or(0);
+            let (records, len) =
+                config.to_file_records_after(&source_type, path, offset)?;
+
+            (records, Some(len))
+        } else {
+            let (query, binds, config) = {
+                // We wrap the index lock in a block to make sure it's
+                // dropped before async functionalities are called.
+                let index = index.read().await;
+                let meta = index.metadata();
+                let checkpoint = meta.last_inserted.unwrap_or_default();
+                let (query, binds) =
+                    config.to_query_after(&source_type, &checkpoint)?;
+                (query, binds, config)
+            };
+
+            let mut conn = state.async_connect().await?;
+            let built = bind_values(sqlx::query(&query), &binds);
+            let mut stream = built.fetch(conn.acquire().await?);
+
+            // Process the rows from the database as records.
+            let mut records = HashMap::new();
+            while let Some(row) = stream.next().await {
+                let row = row?;
+                let (id, record) = config.to_record(&row)?;
+                records.insert(id, record);
+            }
+
+            (records, None)
+        };
+
+        // Stage the batch before mutating the index, so a crash between
+        // here and the atomic persist below can be replayed on the next
+        // load without re-deriving the batch from the source. See
+        // `replay_staging` and `RefreshCheckpoint`.
+        let staging_file = staging_file(&file);
+        let checkpoint = RefreshCheckpoint {
+            records: records.clone(),
+            file_offset: new_file_offset,
+        };
+        file::write_binary_file(&staging_file, &checkpoint)?;
 
         // Update the index with new records and persist it.
         // We might want to persist the index after every fit operation.
-        let mut index = index.lock()?;
-        index.insert(records)?;
-        algorithm.persist_index(file, index.as_ref())?;
+        {
+            let mut index = index.write().await;
+            index.insert(records)?;
+            algorithm.persist_index(&file, index.as_ref())?;
+        }
+
+        // The refresh is now durable; the staging file has served its
+        // purpose.
+        fs::remove_file(&staging_file)?;
+
+        // Advance the file source checkpoint, if this was one.
+        if let Some(new_file_offset) = new_file_offset {
+            {
+                let mut state = self.state.lock()?;
+                if let Some(index_ref) = state.indices.get_mut(name) {
+                    index_ref.file_offset = Some(new_file_offset);
+                }
+            }
+
+            self.persist_state()?;
+        }
+
         Ok(())
     }
 
@@ -268,6 +539,17 @@ impl Database {
     /// algorithm used when creating the index. ANNS algorithms
     /// may not return the exact nearest neighbors but perform
     /// much faster than linear search.
+    pub async fn async_search_index(
+        &self,
+        name: impl AsRef<str>,
+        query: impl Into<Vector>,
+        k: usize,
+        filters: impl Into<Filters>,
+    ) -> Result<Vec<SearchResult>, Error> {
+        self.async_search_index_with_params(name, query, k, filters).await
+    }
+
+    /// Blocking variant of [`Self::async_search_index`].
     pub fn search_index(
         &self,
         name: impl AsRef<str>,
@@ -275,18 +557,152 @@ impl Database {
         k: usize,
         filters: impl Into<Filters>,
     ) -> Result<Vec<SearchResult>, Error> {
-        let index: Index = self.try_get_index(name)?;
-        let index = index.lock()?;
-        index.search(query.into(), k, filters.into())
+        self.search_index_with_params(name, query, k, filters)
+    }
+
+    /// Searches the index for nearest neighbors, with per-query overrides
+    /// (e.g. IVFPQ's `num_probes` or HNSW's `ef_search`) to trade recall
+    /// for latency without rebuilding the index, asynchronously.
+    /// - `name`: Index name.
+    /// - `query`: Query vector.
+    /// - `params`: Search parameters. A bare `k` converts via `SearchParams::new`.
+    /// - `filters`: SQL-like filters to apply.
+    ///
+    /// Only takes a read lock on the index, so it runs concurrently with
+    /// other searches; it's excluded only while [`Self::async_refresh_index`]
+    /// or [`Self::async_create_index`] hold the write lock.
+    pub async fn async_search_index_with_params(
+        &self,
+        name: impl AsRef<str>,
+        query: impl Into<Vector>,
+        params: impl Into<SearchParams>,
+        filters: impl Into<Filters>,
+    ) -> Result<Vec<SearchResult>, Error> {
+        let params = params.into();
+        params.validate()?;
+
+        let index: Index = self.async_try_get_index(name).await?;
+        let index = index.read().await;
+        index.search(query.into(), params, filters.into())
+    }
+
+    /// Blocking variant of [`Self::async_search_index_with_params`].
+    pub fn search_index_with_params(
+        &self,
+        name: impl AsRef<str>,
+        query: impl Into<Vector>,
+        params: impl Into<SearchParams>,
+        filters: impl Into<Filters>,
+    ) -> Result<Vec<SearchResult>, Error> {
+        executor::block_on(
+            self.async_search_index_with_params(name, query, params, filters),
+        )
+    }
+
+    /// Runs a hybrid keyword + vector search against an index, fusing the
+    /// two ranked lists with Reciprocal Rank Fusion (RRF).
+    /// - `name`: Index name.
+    /// - `query`: Query vector for the dense vector search.
+    /// - `text_query`: Keyword to match against `text_column`.
+    /// - `text_column`: Metadata column to run the keyword match against.
+    /// - `k`: Number of fused results to return.
+    ///
+    /// RRF scores each record by `score = Σ 1/(c + rank)` (`c = 60`) over
+    /// every list it appears in, using its 1-based rank in that list;
+    /// records missing from a list simply don't contribute that term. This
+    /// gives keyword recall for rare tokens dense vectors tend to miss,
+    /// combined with semantic recall, without needing to calibrate the two
+    /// sides onto the same score scale.
+    pub async fn async_search_hybrid(
+        &self,
+        name: impl AsRef<str>,
+        query: impl Into<Vector>,
+        text_query: impl AsRef<str>,
+        text_column: impl Into<ColumnName>,
+        k: usize,
+    ) -> Result<Vec<SearchResult>, Error> {
+        let name = name.as_ref();
+        let vector_ranked =
+            self.async_search_index(name, query, k, Filters::NONE).await?;
+
+        let IndexRef { config, .. } = self.get_index_ref(name).ok_or_else(|| {
+            let code = ErrorCode::NotFound;
+            let message = format!("Index not found: {name}.");
+            Error::new(code, message)
+        })?;
+
+        let pattern = format!("%{}%", text_query.as_ref());
+        let config = config.with_typed_filter(
+            text_column,
+            Operator::Contains,
+            Value::Text(pattern),
+        );
+
+        let state = self.state()?;
+        let (query, binds) = config.to_query(&state.source_type())?;
+        let query = format!("{query} LIMIT {k}");
+
+        let mut conn = state.async_connect().await?;
+        let built = bind_values(sqlx::query(&query), &binds);
+        let mut stream = built.fetch(conn.acquire().await?);
+
+        let mut keyword_ranked = Vec::new();
+        while let Some(row) = stream.next().await {
+            let (id, record) = config.to_record(&row?)?;
+            keyword_ranked.push((id, record.data));
+        }
+
+        const RRF_K: f64 = 60.0;
+        let mut scores: HashMap<RecordID, f64> = HashMap::new();
+        for (rank, result) in vector_ranked.iter().enumerate() {
+            *scores.entry(result.id).or_insert(0.0) += 1.0 / (RRF_K + (rank + 1) as f64);
+        }
+        for (rank, (id, _)) in keyword_ranked.iter().enumerate() {
+            *scores.entry(*id).or_insert(0.0) += 1.0 / (RRF_K + (rank + 1) as f64);
+        }
+
+        // Hydrate metadata from whichever list already fetched the record.
+        let mut metadata: HashMap<RecordID, HashMap<ColumnName, Option<DataValue>>> =
+            keyword_ranked.into_iter().collect();
+        metadata.extend(vector_ranked.into_iter().map(|result| (result.id, result.data)));
+
+        let mut fused: Vec<SearchResult> = scores
+            .into_iter()
+            .filter_map(|(id, score)| {
+                let data = metadata.remove(&id)?;
+                // Negate the fused score so lower still means "more
+                // relevant", matching every other index's distance.
+                Some(SearchResult { id, data, distance: -(score as f32) })
+            })
+            .collect();
+
+        fused.sort();
+        fused.truncate(k);
+        Ok(fused)
+    }
+
+    /// Blocking variant of [`Self::async_search_hybrid`].
+    pub fn search_hybrid(
+        &self,
+        name: impl AsRef<str>,
+        query: impl Into<Vector>,
+        text_query: impl AsRef<str>,
+        text_column: impl Into<ColumnName>,
+        k: usize,
+    ) -> Result<Vec<SearchResult>, Error> {
+        executor::block_on(self.async_search_hybrid(name, query, text_query, text_column, k))
     }
 
-    /// Deletes an index from the database.
+    /// Deletes an index from the database asynchronously.
     /// - `name`: Index name.
     ///
     /// This method will remove the index from the pool and delete
     /// the index file from the disk. Returns an error if the index
     /// doesn't exist in the database.
-    pub fn delete_index(&self, name: impl AsRef<str>) -> Result<(), Error> {
+    pub async fn async_delete_index(
+        &self,
+        name: impl AsRef<str>,
+    ) -> Result<(), Error> {
         let name = name.as_ref();
         let index_ref = {
             let mut state = self.state.lock()?;
@@ -297,14 +713,20 @@ impl Database {
             })?
         };
 
-        self.release_indices(vec![name])?;
+        self.async_release_indices(vec![name]).await?;
         fs::remove_file(index_ref.file())?;
         self.persist_state()
     }
 
-    /// Loads indices to the pool if they are not already loaded.
+    /// Blocking variant of [`Self::async_delete_index`].
+    pub fn delete_index(&self, name: impl AsRef<str>) -> Result<(), Error> {
+        executor::block_on(self.async_delete_index(name))
+    }
+
+    /// Loads indices to the pool if they are not already loaded,
+    /// asynchronously.
     /// - `names`: Names of the indices.
-    pub fn load_indices(
+    pub async fn async_load_indices(
         &self,
         names: Vec<impl AsRef<str>>,
     ) -> Result<(), Error> {
@@ -317,13 +739,21 @@ impl Database {
 
         // Using the get_index method to avoid code duplication.
         for name in names {
-            self.get_index(name);
+            self.async_get_index(name).await;
         }
 
         Ok(())
     }
 
-    /// Releases indices from the pool.
+    /// Blocking variant of [`Self::async_load_indices`].
+    pub fn load_indices(
+        &self,
+        names: Vec<impl AsRef<str>>,
+    ) -> Result<(), Error> {
+        executor::block_on(self.async_load_indices(names))
+    }
+
+    /// Releases indices from the pool asynchronously.
     /// - `names`: Names of the indices.
     ///
     /// This method can free up memory by removing indices from the pool.
@@ -332,11 +762,11 @@ impl Database {
     ///
     /// Loading indices from the file might take some time. Therefore,
     /// it's recommended to keep the frequently used indices in the pool.
-    pub fn release_indices(
+    pub async fn async_release_indices(
         &self,
         names: Vec<impl AsRef<str>>,
     ) -> Result<(), Error> {
-        let mut pool = self.pool.lock()?;
+        let mut pool = self.pool.write().await;
         for name in names {
             let name = name.as_ref();
             pool.remove(name);
@@ -345,6 +775,14 @@ impl Database {
         Ok(())
     }
 
+    /// Blocking variant of [`Self::async_release_indices`].
+    pub fn release_indices(
+        &self,
+        names: Vec<impl AsRef<str>>,
+    ) -> Result<(), Error> {
+        executor::block_on(self.async_release_indices(names))
+    }
+
     /// Returns the state object of the database.
     pub fn state(&self) -> Result<DatabaseState, Error> {
         let state = self.state.lock()?;
@@ -360,6 +798,211 @@ impl Database {
     pub fn persist_state(&self) -> Result<(), Error> {
         file::write_binary_file(self.state_file(), &self.state()?)
     }
+
+    /// Creates and persists a new scoped API key.
+    /// - `name`: Human-readable label for the key.
+    /// - `scope`: Permission level to grant the key.
+    pub fn create_key(
+        &self,
+        name: impl Into<String>,
+        scope: ApiKeyScope,
+    ) -> Result<ApiKey, Error> {
+        let token = Uuid::new_v4().to_string();
+        let key = ApiKey { name: name.into(), token: token.clone(), scope };
+
+        let mut keys = self.keys.lock()?;
+        keys.insert(token, key.clone());
+        file::write_binary_file(self.keys_file(), &*keys)?;
+        Ok(key)
+    }
+
+    /// Lists every persisted API key.
+    pub fn list_keys(&self) -> Result<Vec<ApiKey>, Error> {
+        let keys = self.keys.lock()?;
+        Ok(keys.values().cloned().collect())
+    }
+
+    /// Revokes the API key presenting `token`.
+    pub fn revoke_key(&self, token: impl AsRef<str>) -> Result<(), Error> {
+        let mut keys = self.keys.lock()?;
+        if keys.remove(token.as_ref()).is_none() {
+            let code = ErrorCode::NotFound;
+            let message = "API key not found.";
+            return Err(Error::new(code, message));
+        }
+
+        file::write_binary_file(self.keys_file(), &*keys)
+    }
+
+    /// Resolves a presented `x-oasysdb-token` to its scope. Callers should
+    /// also accept the `OASYSDB_TOKEN` env var as an always-valid `Admin`
+    /// credential, so existing deployments keep working with their single
+    /// token.
+    pub fn resolve_token(&self, token: impl AsRef<str>) -> Option<ApiKeyScope> {
+        let keys = self.keys.lock().ok()?;
+        keys.get(token.as_ref()).map(|key| key.scope)
+    }
+
+    /// Writes a point-in-time consistent backup of the database to `path`
+    /// asynchronously, as a single gzip-compressed tarball containing a
+    /// manifest and every index's on-disk file.
+    /// - `path`: Destination of the `.tar.gz` archive.
+    ///
+    /// The `state` lock is held for the whole snapshot, and every pooled
+    /// index is flushed to its on-disk file first, so concurrent inserts
+    /// or index creation can't make the archive reflect a mix of two
+    /// points in time.
+    pub async fn async_dump(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<(), Error> {
+        let state = self.state.lock()?;
+        self.flush_pool_locked(&state).await?;
+
+        let mut builder = Builder::new(Vec::new());
+        let mut indices = HashMap::new();
+
+        for (name, index_ref) in state.indices.iter() {
+            let file_name = format!("{}.index", Uuid::new_v4());
+            let archive_path = format!("indices/{file_name}");
+            builder.append_path_with_name(&index_ref.file, &archive_path)?;
+
+            let entry = DumpIndexEntry {
+                algorithm: index_ref.algorithm.to_owned(),
+                config: index_ref.config.to_owned(),
+                file_name,
+            };
+
+            indices.insert(name.to_owned(), entry);
+        }
+
+        let manifest = DumpManifest {
+            version: DUMP_FORMAT_VERSION,
+            source: state.source.to_owned(),
+            indices,
+        };
+
+        let manifest_bytes = bincode::serialize(&manifest)?;
+        append_bytes(&mut builder, "manifest", &manifest_bytes)?;
+
+        let tar_bytes = builder.into_inner()?;
+        drop(state);
+
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path.as_ref())?;
+
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        encoder.write_all(&tar_bytes)?;
+        encoder.finish()?;
+        Ok(())
+    }
+
+    /// Blocking variant of [`Self::async_dump`].
+    pub fn dump(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        executor::block_on(self.async_dump(path))
+    }
+
+    /// Re-materializes a database from a [`Self::dump`] archive into
+    /// `root` asynchronously.
+    /// - `root`: Root directory for the restored database. A fresh
+    ///   `indices/` directory and `odbstate` are created here.
+    /// - `archive`: Path to the `.tar.gz` produced by [`Self::dump`].
+    ///
+    /// Every index file is re-materialized under a freshly generated
+    /// UUID, since the name it had on the source host may already be
+    /// taken (or simply shouldn't be trusted) on the restoring one.
+    pub async fn async_restore(
+        root: impl Into<PathBuf>,
+        archive: impl AsRef<Path>,
+    ) -> Result<Database, Error> {
+        let root_dir: PathBuf = root.into();
+        let indices_dir = root_dir.join("indices");
+        fs::create_dir_all(&indices_dir)?;
+
+        let file = OpenOptions::new().read(true).open(archive.as_ref())?;
+        let mut tar = Archive::new(GzDecoder::new(file));
+
+        let mut manifest: Option<DumpManifest> = None;
+        let mut extracted: HashMap<String, PathBuf> = HashMap::new();
+
+        for entry in tar.entries()? {
+            let mut entry = entry?;
+            let entry_path = entry.path()?.into_owned();
+
+            if entry_path == Path::new("manifest") {
+                let mut bytes = Vec::new();
+                entry.read_to_end(&mut bytes)?;
+                manifest = Some(bincode::deserialize(&bytes)?);
+                continue;
+            }
+
+            if let Ok(file_name) = entry_path.strip_prefix("indices") {
+                let dest = indices_dir.join(file_name);
+                entry.unpack(&dest)?;
+                let key = file_name.to_string_lossy().into_owned();
+                extracted.insert(key, dest);
+            }
+        }
+
+        let manifest = manifest.ok_or_else(|| {
+            let code = ErrorCode::InvalidSource;
+            Error::new(code, "Dump archive is missing its manifest.")
+        })?;
+
+        if manifest.version > DUMP_FORMAT_VERSION {
+            let code = ErrorCode::InvalidSource;
+            let message = format!(
+                "Dump format v{} is newer than this build supports (v{}).",
+                manifest.version, DUMP_FORMAT_VERSION
+            );
+
+            return Err(Error::new(code, message));
+        }
+
+        let mut indices = HashMap::new();
+        for (name, entry) in manifest.indices {
+            let extracted_file =
+                extracted.remove(&entry.file_name).ok_or_else(|| {
+                    let code = ErrorCode::InvalidSource;
+                    let message = format!(
+                        "Dump archive is missing the index file for '{name}'."
+                    );
+
+                    Error::new(code, message)
+                })?;
+
+            let new_file = indices_dir.join(Uuid::new_v4().to_string());
+            fs::rename(&extracted_file, &new_file)?;
+
+            let index_ref = IndexRef {
+                algorithm: entry.algorithm,
+                config: entry.config,
+                file: new_file,
+                // Dumps predate file sources' incremental checkpointing;
+                // a restored file-backed index just re-reads from the
+                // start on its next refresh.
+                file_offset: None,
+            };
+
+            indices.insert(name, index_ref);
+        }
+
+        let state = DatabaseState { source: manifest.source, indices };
+        file::write_binary_file(root_dir.join("odbstate"), &state)?;
+
+        Database::open(root_dir, None::<DatabaseURL>)
+    }
+
+    /// Blocking variant of [`Self::async_restore`].
+    pub fn restore(
+        root: impl Into<PathBuf>,
+        archive: impl AsRef<Path>,
+    ) -> Result<Database, Error> {
+        executor::block_on(Self::async_restore(root, archive))
+    }
 }
 
 // Write internal database methods here.
@@ -369,10 +1012,188 @@ impl Database {
         self.root.join("odbstate")
     }
 
+    /// Returns the file path where the API keys are stored.
+    fn keys_file(&self) -> PathBuf {
+        self.root.join("odbkeys")
+    }
+
     /// Returns the directory where the indices are stored.
     fn indices_dir(&self) -> PathBuf {
         self.root.join("indices")
     }
+
+    /// Persists every index currently resident in the pool back to its
+    /// on-disk file, so a concurrent [`Self::dump`] sees up-to-date index
+    /// files instead of whatever was last written by
+    /// [`Self::async_refresh_index`].
+    /// - `state`: Already-locked database state, so the caller controls
+    ///   how long the lock is held across the flush.
+    async fn flush_pool_locked(
+        &self,
+        state: &DatabaseState,
+    ) -> Result<(), Error> {
+        let pool = self.pool.read().await;
+        for (name, entry) in pool.iter() {
+            let Some(index_ref) = state.indices.get(name) else { continue };
+            let index = entry.index.read().await;
+            index_ref.algorithm.persist_index(&index_ref.file, index.as_ref())?;
+        }
+
+        Ok(())
+    }
+
+    /// Evicts the least-recently-used unpinned index if the pool is at or
+    /// over capacity, making room for one more insertion.
+    ///
+    /// No-op if capacity is unbounded, the pool is under capacity, or
+    /// every resident index is pinned. Evicting persists nothing, since
+    /// index files are already durable on disk.
+    fn evict_lru_locked(&self, pool: &mut HashMap<IndexName, PoolEntry>) {
+        let Ok(capacity) = self.pool_capacity.lock() else { return };
+        let Some(capacity) = *capacity else { return };
+        if pool.len() < capacity {
+            return;
+        }
+
+        let Ok(pinned) = self.pinned.lock() else { return };
+        let lru = pool
+            .iter()
+            .filter(|(name, _)| !pinned.contains(name.as_str()))
+            .filter_map(|(name, entry)| {
+                let last_access = *entry.last_access.lock().ok()?;
+                Some((name.to_owned(), last_access))
+            })
+            .min_by_key(|(_, last_access)| *last_access)
+            .map(|(name, _)| name);
+
+        if let Some(name) = lru {
+            pool.remove(&name);
+        }
+    }
+
+    /// Replays a leftover [`RefreshCheckpoint`] staging file for `file`, if
+    /// a crash during [`Self::async_refresh_index`] left one behind.
+    ///
+    /// Re-applies the staged batch to the index and re-persists it, then
+    /// commits its file-source checkpoint and removes the staging file.
+    /// Re-inserting the same batch is safe since [`VectorIndex::insert`]
+    /// keys records by [`RecordID`], so a replay after a successful but
+    /// undeleted staging file is a harmless no-op. No-op entirely if no
+    /// staging file exists.
+    fn replay_staging(
+        &self,
+        name: &str,
+        algorithm: &IndexAlgorithm,
+        file: &Path,
+    ) -> Result<(), Error> {
+        let staging_file = staging_file(file);
+        if !staging_file.exists() {
+            return Ok(());
+        }
+
+        let checkpoint: RefreshCheckpoint =
+            file::read_binary_file(&staging_file)?;
+
+        let mut index = algorithm.load_index(file)?;
+        index.insert(checkpoint.records)?;
+        algorithm.persist_index(file, index.as_ref())?;
+
+        if let Some(file_offset) = checkpoint.file_offset {
+            {
+                let mut state = self.state.lock()?;
+                if let Some(index_ref) = state.indices.get_mut(name) {
+                    index_ref.file_offset = Some(file_offset);
+                }
+            }
+
+            self.persist_state()?;
+        }
+
+        fs::remove_file(&staging_file)?;
+        Ok(())
+    }
+}
+
+/// A staging record of an in-flight [`Database::async_refresh_index`]
+/// batch, flushed to disk before the index itself is mutated.
+///
+/// If the process crashes between fetching a refresh batch and finishing
+/// the index persist, this file survives on disk; [`Database::replay_staging`]
+/// replays it the next time the index is loaded, instead of forcing a full
+/// rebuild. Staging the batch also means replay doesn't depend on
+/// re-deriving it from the source, which may have changed or become
+/// unreachable by the time the crash is noticed.
+#[derive(Debug, Serialize, Deserialize)]
+struct RefreshCheckpoint {
+    records: HashMap<RecordID, Record>,
+    /// New file-source byte offset to commit once the replay succeeds.
+    /// `None` for SQL sources, which derive their checkpoint from the
+    /// index's own `metadata.last_inserted` instead.
+    file_offset: Option<u64>,
+}
+
+/// Returns the staging file path for an index file. See [`RefreshCheckpoint`].
+fn staging_file(file: &Path) -> PathBuf {
+    let mut staging = file.as_os_str().to_owned();
+    staging.push(".staging");
+    PathBuf::from(staging)
+}
+
+/// Appends an in-memory byte blob to `builder` as a file named `name`.
+fn append_bytes(
+    builder: &mut Builder<Vec<u8>>,
+    name: &str,
+    data: &[u8],
+) -> Result<(), Error> {
+    let mut header = Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, name, data)?;
+    Ok(())
+}
+
+/// On-disk manifest for a [`Database::dump`] archive, recording enough to
+/// validate compatibility and re-materialize indices on
+/// [`Database::async_restore`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DumpManifest {
+    version: u32,
+    source: DatabaseURL,
+    indices: HashMap<IndexName, DumpIndexEntry>,
+}
+
+/// A single index's entry in a [`DumpManifest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DumpIndexEntry {
+    algorithm: IndexAlgorithm,
+    config: SourceConfig,
+    /// Name of this index's file inside the archive's `indices/` entry,
+    /// not the original on-disk path, which won't exist on the
+    /// restoring host.
+    file_name: String,
+}
+
+/// Permission level granted to an [`ApiKey`], checked against the minimum
+/// scope an API route requires. Ordered so a higher scope implies every
+/// permission a lower one has: `Admin` > `Write` > `Read`.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyScope {
+    Read,
+    Write,
+    Admin,
+}
+
+/// A named, scoped credential presented via the `x-oasysdb-token` header,
+/// as a least-privilege alternative to the single `OASYSDB_TOKEN` env var.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub name: String,
+    pub token: String,
+    pub scope: ApiKeyScope,
 }
 
 /// The state of the vector database.
@@ -389,6 +1210,11 @@ impl DatabaseState {
         file::read_binary_file(path)
     }
 
+    /// Returns the number of indices currently tracked by the database.
+    pub fn index_count(&self) -> usize {
+        self.indices.len()
+    }
+
     /// Updates the source URL of the database state.
     /// - `source`: New source URL.
     pub fn with_source(
@@ -426,10 +1252,17 @@ impl DatabaseState {
 
     /// Validates the connection to the source database.
     ///
-    /// This method will try to connect to the source database and
-    /// disconnect immediately to validate the connection. If this method
-    /// is unable to connect, it will return an error.
+    /// File sources have no connection to validate, so this just checks
+    /// that the file exists. Otherwise, this method will try to connect
+    /// to the source database and disconnect immediately to validate the
+    /// connection. If this method is unable to connect, it will return an
+    /// error.
     pub fn validate_connection(&self) -> Result<(), Error> {
+        if self.source_type().is_file() {
+            self.source_file_path()?;
+            return Ok(());
+        }
+
         let conn = self.connect()?;
         DatabaseState::disconnect(conn)
     }
@@ -438,11 +1271,22 @@ impl DatabaseState {
     /// - sqlite
     /// - mysql
     /// - postgresql
+    /// - file, as CSV or JSONL, inferred from its extension
     pub fn source_type(&self) -> SourceType {
         // We can safely unwrap here because
         // we have already validated the source URL.
         let url = self.source.parse::<Url>().unwrap();
-        url.scheme().into()
+        SourceType::from_url(&url)
+    }
+
+    /// Resolves a `file://` source URL to its filesystem path.
+    pub fn source_file_path(&self) -> Result<PathBuf, Error> {
+        let url = self.source.parse::<Url>().unwrap();
+        url.to_file_path().map_err(|_| {
+            let code = ErrorCode::InvalidSource;
+            let message = "File source URL isn't a valid file path.";
+            Error::new(code, message)
+        })
     }
 
     /// Validates the data source URL.
@@ -451,16 +1295,17 @@ impl DatabaseState {
     /// - sqlite
     /// - mysql
     /// - postgresql
+    /// - file, pointing to a `.csv` or `.jsonl` file
     pub fn validate_source(url: impl Into<DatabaseURL>) -> Result<(), Error> {
         let url = url.into();
-        let url = url.parse::<Url>().map_err(|_| {
+        let parsed = url.parse::<Url>().map_err(|_| {
             let code = ErrorCode::InvalidSource;
             let message = "Invalid database source URL.";
             Error::new(code, message)
         })?;
 
-        let valid_schemes = ["sqlite", "mysql", "postgresql"];
-        if !valid_schemes.contains(&url.scheme()) {
+        let valid_schemes = ["sqlite", "mysql", "postgresql", "file"];
+        if !valid_schemes.contains(&parsed.scheme()) {
             let code = ErrorCode::InvalidSource;
             let message = format!(
                 "Unsupported database scheme. Choose between: {}.",
@@ -470,6 +1315,20 @@ impl DatabaseState {
             return Err(Error::new(code, message));
         }
 
+        if parsed.scheme() == "file" {
+            let extension = Path::new(parsed.path())
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or_default();
+
+            if !["csv", "jsonl"].contains(&extension) {
+                let code = ErrorCode::InvalidSource;
+                let message = "Unsupported file source extension. \
+                    Choose between: csv, jsonl.";
+                return Err(Error::new(code, message));
+            }
+        }
+
         Ok(())
     }
 }
@@ -480,6 +1339,10 @@ pub struct IndexRef {
     config: SourceConfig,
     algorithm: IndexAlgorithm,
     file: IndexFile,
+    /// Byte offset into a file-backed (CSV/JSONL) source up to which
+    /// records have already been ingested. `None` for SQL sources, which
+    /// checkpoint incrementally via `IndexMetadata::last_inserted` instead.
+    file_offset: Option<u64>,
 }
 
 impl IndexRef {
@@ -497,6 +1360,12 @@ impl IndexRef {
     pub fn file(&self) -> &IndexFile {
         &self.file
     }
+
+    /// Returns the file-backed source checkpoint. See [`Self::file_offset`]
+    /// field docs.
+    pub fn file_offset(&self) -> Option<u64> {
+        self.file_offset
+    }
 }
 
 #[cfg(test)]
@@ -504,7 +1373,7 @@ mod tests {
     use super::*;
     use crate::prelude::RecordID;
     use sqlx::{Executor, Row};
-    use std::sync::MutexGuard;
+    use tokio::sync::RwLockReadGuard;
 
     const TABLE: &str = "embeddings";
     const TEST_INDEX: &str = "test_index";
@@ -519,7 +1388,7 @@ mod tests {
         let db = create_test_database()?;
 
         let index: Index = db.try_get_index(TEST_INDEX)?;
-        let index = index.lock()?;
+        let index = executor::block_on(index.read());
         let metadata = index.metadata();
 
         assert_eq!(index.len(), 100);
@@ -536,7 +1405,7 @@ mod tests {
         db.refresh_index(TEST_INDEX).unwrap();
 
         let index: Index = db.try_get_index(TEST_INDEX)?;
-        let index = index.lock()?;
+        let index = executor::block_on(index.read());
         let metadata = index.metadata();
 
         assert_eq!(index.len(), 110);
@@ -582,13 +1451,13 @@ mod tests {
 
         {
             db.release_indices(vec![TEST_INDEX])?;
-            let pool = db.pool()?;
+            let pool = db.pool();
             assert!(!pool.contains_key(TEST_INDEX));
         }
 
         {
             db.load_indices(vec![TEST_INDEX])?;
-            let pool = db.pool()?;
+            let pool = db.pool();
             assert!(pool.contains_key(TEST_INDEX));
         }
 
@@ -675,8 +1544,8 @@ mod tests {
     }
 
     impl Database {
-        fn pool(&self) -> Result<MutexGuard<HashMap<IndexName, Index>>, Error> {
-            Ok(self.pool.lock()?)
+        fn pool(&self) -> RwLockReadGuard<HashMap<IndexName, PoolEntry>> {
+            executor::block_on(self.pool.read())
         }
 
         async fn async_execute_sql(
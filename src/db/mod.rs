@@ -10,6 +10,8 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 mod database;
+mod snapshot;
 
 // Re-export types for public API below.
 pub use database::Database;
+pub use snapshot::{SnapshotCommand, SnapshotWorker};
@@ -0,0 +1,78 @@
+use crate::db::server::{ApiKeyScope, Server};
+use crate::db::utils::request::{Request, RequestBody};
+use crate::db::utils::response as res;
+use serde::Deserialize;
+
+pub fn handler(server: &Server, request: &Request) -> res::Response<String> {
+    let route = request.route.clone();
+
+    if route == "/admin/keys" || route == "/admin/keys/" {
+        return match request.method.as_str() {
+            "get" => list_keys(server),
+            "post" => create_key(server, request.body.clone()),
+            _ => res::get_405_response(),
+        };
+    }
+
+    if route.starts_with("/admin/keys/") {
+        return match request.method.as_str() {
+            "delete" => revoke_key(server, route),
+            _ => res::get_405_response(),
+        };
+    }
+
+    if route == "/admin/stats" || route == "/admin/stats/" {
+        return match request.method.as_str() {
+            "get" => get_stats(server),
+            _ => res::get_405_response(),
+        };
+    }
+
+    res::get_404_response()
+}
+
+fn list_keys(server: &Server) -> res::Response<String> {
+    let body = serde_json::to_string(&server.list_keys()).unwrap();
+    res::create_response(200, Some(body))
+}
+
+fn get_stats(server: &Server) -> res::Response<String> {
+    let body = serde_json::to_string(&server.stats()).unwrap();
+    res::create_response(200, Some(body))
+}
+
+#[derive(Deserialize)]
+struct CreateKeyBody {
+    name: String,
+    scope: ApiKeyScope,
+}
+
+fn create_key(server: &Server, body: RequestBody) -> res::Response<String> {
+    let data: CreateKeyBody = match serde_json::from_value(body) {
+        Ok(data) => data,
+        Err(_) => {
+            let message = "name and scope (read/write/admin) are required.";
+            return res::get_error_response(400, message);
+        }
+    };
+
+    let key = server.create_key(data.name, data.scope);
+    let body = serde_json::to_string(&key).unwrap();
+    res::create_response(201, Some(body))
+}
+
+fn revoke_key(server: &Server, route: String) -> res::Response<String> {
+    // Get the token from the route.
+    let route_parts: Vec<&str> = route.split('/').collect();
+    let token = route_parts.last().unwrap().to_string();
+
+    if token.is_empty() || route_parts.len() < 4 {
+        let message = "The key token is required.";
+        return res::get_error_response(400, message);
+    }
+
+    match server.revoke_key(&token) {
+        Ok(_) => res::create_response(204, None),
+        Err(message) => res::get_error_response(404, message),
+    }
+}
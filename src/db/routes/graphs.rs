@@ -1,6 +1,7 @@
-use crate::db::server::{Data, Server};
+use crate::db::server::{Data, Predicate, Server, SimilarityStyle};
 use crate::db::utils::request::{Request, RequestBody};
 use crate::db::utils::response as res;
+use serde_json::Value;
 use std::collections::HashMap;
 
 pub fn handler(server: &Server, request: &Request) -> res::Response<String> {
@@ -56,6 +57,35 @@ fn post_graphs(server: &Server, body: RequestBody) -> res::Response<String> {
     res::create_response(200, Some(body))
 }
 
+// Parses the optional `filter` field: a list of `{key, op, value}`
+// metadata predicates evaluated against each candidate's `data` during
+// the graph traversal. `op` is one of `eq` (default), `ne`, or `in`;
+// `value` is a single string for `eq`/`ne` or an array of strings for
+// `in`. Malformed entries are skipped rather than rejecting the request.
+fn parse_filter(body: &RequestBody) -> Vec<Predicate> {
+    let Some(items) = body.get("filter").and_then(Value::as_array) else {
+        return vec![];
+    };
+
+    items
+        .iter()
+        .filter_map(|item| {
+            let key = item["key"].as_str()?.to_string();
+            let op = item["op"].as_str().unwrap_or("eq").into();
+            let value = match &item["value"] {
+                Value::Array(values) => values
+                    .iter()
+                    .filter_map(|v| v.as_str().map(str::to_string))
+                    .collect(),
+                Value::String(s) => vec![s.clone()],
+                _ => return None,
+            };
+
+            Some(Predicate { key, op, value })
+        })
+        .collect()
+}
+
 fn post_graphs_query(
     server: &Server,
     body: RequestBody,
@@ -88,8 +118,24 @@ fn post_graphs_query(
         None => 5,
     };
 
+    // Get the optional similarity style, defaulting to Euclidean.
+    let style: SimilarityStyle = body["style"].as_str().unwrap_or("").into();
+
+    // Get the optional similarity/distance threshold to filter results by.
+    let threshold = body["threshold"].as_f64().map(|v| v as f32);
+
+    // Get the optional metadata predicates to filter results by.
+    let filter = parse_filter(&body);
+
     // query the nearest neighbors.
-    let result = server.query(name.into(), embedding, count.into());
+    let result = server.query(
+        name.into(),
+        embedding,
+        count.into(),
+        style,
+        threshold,
+        &filter,
+    );
 
     // If result is Err, return 500 with error message.
     if result.is_err() {
@@ -1,10 +1,12 @@
 use super::utils::response as res;
 use super::utils::stream;
-use crate::db::server::Server;
+use crate::db::server::{ApiKeyScope, Server};
 use tokio::net::TcpStream;
 
+mod admin;
 mod graphs;
 mod root;
+mod search;
 mod values;
 mod version;
 
@@ -26,31 +28,35 @@ mod version;
 // Note: Avoid wildcard imports.
 
 pub async fn handle_request(server: &Server, stream: &mut TcpStream) {
-    // Read request from the client.
-    let _req = stream::read(stream).await;
-
-    // Handle disconnection or invalid request.
-    // Return invalid request response.
-    if _req.is_none() {
-        let response = res::get_error_response(400, "Invalid request.");
-        stream::write(stream, response).await;
-        return;
-    }
+    // Read request from the client. A malformed request gets a 400 instead
+    // of silently killing the connection; a clean disconnect (`Ok(None)`)
+    // just closes the connection since there's no one to respond to.
+    let request = match stream::read(stream).await {
+        Ok(Some(request)) => request,
+        Ok(None) => return,
+        Err(err) => {
+            let response = res::get_error_response(400, err.message());
+            stream::write(stream, response).await;
+            return;
+        }
+    };
 
-    // Unwrap the data.
-    let request = _req.as_ref().unwrap();
     let route = request.route.clone();
 
     // Check if the route is private.
     // Private routes require authentication.
-    let private_routes = ["/graphs", "/values"];
+    let private_routes = ["/graphs", "/values", "/search", "/admin"];
     if private_routes.iter().any(|r| route.starts_with(r)) {
-        // Get the token from the request headers.
-        let token = request.headers.get("x-oasysdb-token");
+        // Get the token from the request headers and resolve its scope.
+        let scope = request
+            .headers
+            .get("x-oasysdb-token")
+            .and_then(|token| server.resolve_token(token));
 
-        // Check if the token is valid.
+        // Check if the resolved scope covers what the route requires.
         // If not, return unauthorized response.
-        if token.is_none() || token.unwrap() != &server.config.token {
+        let required = required_scope(&route, &request.method);
+        if scope.is_none_or(|scope| scope < required) {
             let response = res::get_401_response();
             stream::write(stream, response).await;
             return;
@@ -59,13 +65,36 @@ pub async fn handle_request(server: &Server, stream: &mut TcpStream) {
 
     // Get response based on different routes and methods.
     let response = match route.as_str() {
-        "/" => root::handler(request),
-        "/version" => version::handler(request),
-        _ if route.starts_with("/graphs") => graphs::handler(server, request),
-        _ if route.starts_with("/values") => values::handler(server, request),
+        "/" => root::handler(&request),
+        "/version" => version::handler(&request),
+        _ if route.starts_with("/admin") => admin::handler(server, &request),
+        _ if route.starts_with("/graphs") => graphs::handler(server, &request),
+        _ if route.starts_with("/search") => search::handler(server, &request),
+        _ if route.starts_with("/values") => values::handler(server, &request),
         _ => res::get_404_response(),
     };
 
     // Write the data back to the client.
     stream::write(stream, response).await;
 }
+
+// The minimum `ApiKeyScope` a presented key must have to access `route`
+// via `method`. Key management always needs `Admin`; everything else only
+// needs `Write` for requests that mutate state, since some read-only
+// endpoints are POSTed (e.g. `/graphs/query`, `/search`) and still only
+// need `Read`.
+fn required_scope(route: &str, method: &str) -> ApiKeyScope {
+    if route.starts_with("/admin") {
+        return ApiKeyScope::Admin;
+    }
+
+    let is_read_only = route.ends_with("/query")
+        || route.starts_with("/search")
+        || (method == "get" && route.starts_with("/values"));
+
+    if is_read_only {
+        ApiKeyScope::Read
+    } else {
+        ApiKeyScope::Write
+    }
+}
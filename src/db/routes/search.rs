@@ -16,21 +16,28 @@ fn post(
     server: &mut db::Server,
     body: req::RequestBody,
 ) -> res::Response<String> {
-    // Validate that embedding is in the body.
-    if body.get("embedding").is_none() {
-        let message = "Embedding is required.";
+    // Get the optional keyword query for hybrid search. This also doubles
+    // as the text to auto-embed when no embedding is provided and the
+    // server has a configured embedder.
+    let query = body["query"].as_str().map(String::from);
+
+    // Validate that either embedding or query is in the body.
+    if body.get("embedding").is_none() && query.is_none() {
+        let message = "Either embedding or query is required.";
         return res::get_error_response(400, message);
     }
 
-    // Get the embedding from the request body.
-    let embedding: Vec<f32> =
-        match serde_json::from_value(body["embedding"].clone()) {
-            Ok(vec) => vec,
+    // Get the optional embedding from the request body.
+    let embedding: Option<Vec<f32>> = match body.get("embedding") {
+        Some(value) => match serde_json::from_value(value.clone()) {
+            Ok(vec) => Some(vec),
             Err(_) => {
                 let m = "Embedding must be an array of floats.";
                 return res::get_error_response(400, m);
             }
-        };
+        },
+        None => None,
+    };
 
     // Get optional count from the request body.
     let count: u16 = match body["count"].as_u64() {
@@ -38,8 +45,31 @@ fn post(
         None => 5,
     };
 
-    // Search the nearest neighbors.
-    let result = server.search(embedding, count.into());
+    // Get optional graph name.
+    let name = match body["name"].as_str() {
+        Some(name) => name,
+        None => "default",
+    };
+
+    // Get the optional semantic ratio used to weigh the vector search
+    // against the keyword search when fusing the two ranked lists.
+    let semantic_ratio = body["semantic_ratio"].as_f64().map(|v| v as f32);
+
+    // Get the optional Reciprocal Rank Fusion constant. Lower values weigh
+    // top ranks more heavily; defaults to 60 when omitted.
+    let rrf_k = body["rrf_k"].as_f64().map(|v| v as f32);
+
+    // Search the nearest neighbors. When `embedding` is missing, the
+    // configured embedder generates it from `query`; when `query` is also
+    // provided, its keyword matches are fused in via RRF.
+    let result = server.search(
+        name.into(),
+        embedding,
+        query,
+        count.into(),
+        semantic_ratio,
+        rrf_k,
+    );
 
     // If result is Err, return 500 with error message.
     if result.is_err() {
@@ -47,9 +77,10 @@ fn post(
         return res::get_error_response(400, message);
     }
 
-    // Serialize the result as a string for the response.
+    // Serialize the result, with each hit's score breakdown, as a string
+    // for the response.
     let body = {
-        let _val: Vec<db::Data> = result.unwrap();
+        let _val: Vec<db::SearchResult> = result.unwrap();
         serde_json::to_string(&_val).unwrap()
     };
 
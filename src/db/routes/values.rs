@@ -1,6 +1,7 @@
 use crate::db::server::{Server, Value};
-use crate::db::utils::request::{Request, RequestBody};
+use crate::db::utils::request::{Request, RequestBody, RequestQuery};
 use crate::db::utils::response as res;
+use serde::{Deserialize, Serialize};
 
 pub fn handler(
     server: &mut Server,
@@ -8,6 +9,21 @@ pub fn handler(
 ) -> res::Response<String> {
     let route = request.route.clone();
     let body = request.body.clone();
+
+    if route.starts_with("/values/batch") {
+        return match request.method.as_str() {
+            "post" => batch(server, body),
+            _ => res::get_405_response(),
+        };
+    }
+
+    // A bare `/values` GET is a range/prefix scan, not a single-key lookup;
+    // `get` below is reserved for `/values/<key>`.
+    let is_bare_values = route == "/values" || route == "/values/";
+    if request.method.as_str() == "get" && is_bare_values {
+        return scan(server, &request.query);
+    }
+
     match request.method.as_str() {
         "get" => get(server, route),
         "post" => post(server, body),
@@ -114,3 +130,165 @@ fn delete(server: &Server, route: String) -> res::Response<String> {
     // Return empty success response.
     res::create_response(204, None)
 }
+
+// Default page size for `GET /values` range/prefix scans when `limit` is
+// omitted or invalid.
+const DEFAULT_SCAN_LIMIT: usize = 100;
+
+fn scan(server: &Server, query: &RequestQuery) -> res::Response<String> {
+    let prefix = query.get("prefix").cloned();
+    let start = query.get("start").cloned();
+    let end = query.get("end").cloned();
+    let cursor = query.get("cursor").cloned();
+
+    let limit = query
+        .get("limit")
+        .and_then(|limit| limit.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_SCAN_LIMIT);
+
+    let reverse = query.get("reverse").is_some_and(|value| value == "true");
+    let keys_only =
+        query.get("keys_only").is_some_and(|value| value == "true");
+
+    let result =
+        server.scan(prefix, start, end, cursor, limit, reverse, keys_only);
+
+    match result {
+        Ok(result) => {
+            let body = serde_json::to_string(&result).unwrap();
+            res::create_response(200, Some(body))
+        }
+        Err(message) => res::get_error_response(400, message),
+    }
+}
+
+// One operation in a `POST /values/batch` request, tagged by `method` the
+// same way MongoDB's `bulk_write` tags each op by its write type.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "method", rename_all = "lowercase")]
+enum BatchOp {
+    Set { key: String, value: Value },
+    Get { key: String },
+    Delete { key: String },
+}
+
+// Result of a single `BatchOp`, carrying its own status code so one bad
+// item doesn't fail the whole batch.
+#[derive(Serialize, Debug)]
+struct BatchItemResult {
+    status: u16,
+    body: serde_json::Value,
+}
+
+// A single `insert` entry in the K2V-style batch body.
+#[derive(Deserialize, Debug, Clone)]
+struct InsertItem {
+    key: String,
+    value: Value,
+}
+
+// Reads a batch body's operations, accepting either shape:
+// - `{ "ops": [{"method": "set", ...}, ...] }`, the tagged list `batch`
+//   has always accepted, run in the order given; or
+// - `{ "insert": [...], "read": [...], "delete": [...] }`, the K2V-style
+//   shape keyed by operation type, run as inserts then reads then
+//   deletes.
+fn parse_ops(body: &RequestBody) -> Result<Vec<BatchOp>, &'static str> {
+    if let Some(ops) = body.get("ops") {
+        return serde_json::from_value(ops.clone())
+            .map_err(|_| "The ops array is invalid.");
+    }
+
+    let mut ops = Vec::new();
+
+    if let Some(inserts) = body.get("insert") {
+        let inserts: Vec<InsertItem> = serde_json::from_value(inserts.clone())
+            .map_err(|_| "The insert array is invalid.")?;
+
+        ops.extend(
+            inserts
+                .into_iter()
+                .map(|item| BatchOp::Set { key: item.key, value: item.value }),
+        );
+    }
+
+    if let Some(reads) = body.get("read") {
+        let reads: Vec<String> = serde_json::from_value(reads.clone())
+            .map_err(|_| "The read array is invalid.")?;
+
+        ops.extend(reads.into_iter().map(|key| BatchOp::Get { key }));
+    }
+
+    if let Some(deletes) = body.get("delete") {
+        let deletes: Vec<String> = serde_json::from_value(deletes.clone())
+            .map_err(|_| "The delete array is invalid.")?;
+
+        ops.extend(deletes.into_iter().map(|key| BatchOp::Delete { key }));
+    }
+
+    if ops.is_empty() {
+        let message = "The batch needs ops, or insert/read/delete.";
+        return Err(message);
+    }
+
+    Ok(ops)
+}
+
+fn batch(server: &mut Server, body: RequestBody) -> res::Response<String> {
+    let ops = match parse_ops(&body) {
+        Ok(ops) => ops,
+        Err(message) => return res::get_error_response(400, message),
+    };
+
+    // Stops at the first failing item unless the caller opts out, in
+    // which case the rest of the batch still runs.
+    let ordered = body["ordered"].as_bool().unwrap_or(true);
+
+    let mut results = Vec::with_capacity(ops.len());
+    for op in ops {
+        let result = execute_batch_op(server, op);
+        let failed = result.status >= 400;
+        results.push(result);
+
+        if failed && ordered {
+            break;
+        }
+    }
+
+    let body = serde_json::to_string(&results).unwrap();
+    res::create_response(200, Some(body))
+}
+
+fn execute_batch_op(server: &mut Server, op: BatchOp) -> BatchItemResult {
+    match op {
+        BatchOp::Set { key, value } => match server.set(key, value) {
+            Ok(value) => {
+                let body = serde_json::to_value(value).unwrap();
+                BatchItemResult { status: 201, body }
+            }
+            Err(message) => {
+                let body = serde_json::json!({ "error": message });
+                BatchItemResult { status: 400, body }
+            }
+        },
+        BatchOp::Get { key } => match server.get(key) {
+            Ok(value) => {
+                let body = serde_json::to_value(value).unwrap();
+                BatchItemResult { status: 200, body }
+            }
+            Err(message) => {
+                let body = serde_json::json!({ "error": message });
+                BatchItemResult { status: 404, body }
+            }
+        },
+        BatchOp::Delete { key } => match server.delete(key) {
+            Ok(_) => {
+                BatchItemResult { status: 204, body: serde_json::json!({}) }
+            }
+            Err(message) => {
+                let body = serde_json::json!({ "error": message });
+                BatchItemResult { status: 400, body }
+            }
+        },
+    }
+}
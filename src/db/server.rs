@@ -1,8 +1,10 @@
 use instant_distance::HnswMap as HNSW;
 use instant_distance::{Builder, Search};
+use reqwest::blocking::Client;
 use sled::Db as Database;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use uuid::Uuid;
 
 // Data type for the key-value store value's metadata.
 pub type Data = HashMap<String, String>;
@@ -11,8 +13,196 @@ pub type Data = HashMap<String, String>;
 // the key-value store as the value.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Value {
+    #[serde(default)]
     pub embedding: Vec<f32>,
     pub data: Data,
+    /// Text to auto-embed into `embedding` on insert when the latter is
+    /// omitted. Takes priority over `Config::embed_field`, so callers can
+    /// send `{"key": ..., "value": {"text": "...", "data": {...}}}` without
+    /// having to also designate a metadata field ahead of time.
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+/// Explains why a hit ranked where it did in a `Server::search` call.
+#[derive(Serialize, Debug, Clone)]
+pub struct ScoreDetails {
+    /// Raw distance between the query embedding and the result's
+    /// embedding, as measured by the graph's distance function. Smaller
+    /// is closer.
+    pub distance: f32,
+    /// `distance` mapped to `[0, 1]` via `1 / (1 + distance)`, so higher
+    /// always means closer regardless of the underlying distance scale.
+    /// A hybrid hit found only by the keyword pass has an infinite
+    /// `distance` and a `similarity` of 0.
+    pub similarity: f32,
+    /// The result's 1-based rank in the keyword-matching pass, present
+    /// only when a hybrid `query` was supplied and the result matched.
+    pub keyword_rank: Option<usize>,
+    /// The combined score used to order hybrid results, present only
+    /// when a hybrid `query` was supplied.
+    pub fused_score: Option<f32>,
+}
+
+impl ScoreDetails {
+    fn from_distance(distance: f32) -> Self {
+        ScoreDetails {
+            distance,
+            similarity: 1.0 / (1.0 + distance.max(0.0)),
+            keyword_rank: None,
+            fused_score: None,
+        }
+    }
+}
+
+/// A single search hit paired with the signals that produced its rank.
+#[derive(Serialize, Debug, Clone)]
+pub struct SearchResult {
+    pub data: Data,
+    pub score: ScoreDetails,
+}
+
+/// One key-value pair returned by [`Server::scan`]. `value` is omitted in
+/// `keys_only` scans, for cheap key enumeration over large stores.
+#[derive(Serialize, Debug)]
+pub struct ScanItem {
+    pub key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+}
+
+/// Result of a [`Server::scan`] range/prefix query: the page of matching
+/// items, plus a continuation token for the next page. `next` is `None`
+/// once the scan has reached the end of the matching range.
+#[derive(Serialize, Debug)]
+pub struct ScanResult {
+    pub items: Vec<ScanItem>,
+    pub next: Option<String>,
+}
+
+/// Distance/similarity formula [`Server::query`] re-scores graph candidates
+/// with, selectable per request instead of always trusting the graph's
+/// built-in (Euclidean) traversal order.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum SimilarityStyle {
+    #[default]
+    Euclidean,
+    Cosine,
+    DotProduct,
+}
+
+impl SimilarityStyle {
+    // Re-scores `query` against `embedding` under this style. For
+    // `Euclidean` the result is a distance (smaller is closer); for
+    // `Cosine`/`DotProduct` it's a similarity (larger is closer).
+    fn score(&self, query: &[f32], embedding: &[f32]) -> f32 {
+        match self {
+            SimilarityStyle::Euclidean => query
+                .iter()
+                .zip(embedding)
+                .map(|(a, b)| (a - b).powi(2))
+                .sum::<f32>()
+                .sqrt(),
+            SimilarityStyle::Cosine => {
+                let dot: f32 =
+                    query.iter().zip(embedding).map(|(a, b)| a * b).sum();
+                let a_norm: f32 =
+                    query.iter().map(|v| v * v).sum::<f32>().sqrt();
+                let b_norm: f32 =
+                    embedding.iter().map(|v| v * v).sum::<f32>().sqrt();
+
+                if a_norm == 0.0 || b_norm == 0.0 {
+                    0.0
+                } else {
+                    dot / (a_norm * b_norm)
+                }
+            }
+            SimilarityStyle::DotProduct => {
+                query.iter().zip(embedding).map(|(a, b)| a * b).sum()
+            }
+        }
+    }
+
+    // Whether a `score` from `Self::score` passes `threshold`: `Euclidean`
+    // keeps distances at or under it, the similarity styles keep scores at
+    // or above it.
+    fn passes(&self, score: f32, threshold: f32) -> bool {
+        match self {
+            SimilarityStyle::Euclidean => score <= threshold,
+            SimilarityStyle::Cosine | SimilarityStyle::DotProduct => {
+                score >= threshold
+            }
+        }
+    }
+}
+
+/// Comparison a [`Predicate`] applies between `Value.data[key]` and
+/// `Predicate::value`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PredicateOp {
+    /// `data[key] == value[0]`.
+    Eq,
+    /// `data[key] != value[0]`.
+    Ne,
+    /// `data[key]` is one of `value`.
+    In,
+}
+
+impl From<&str> for PredicateOp {
+    fn from(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "ne" | "neq" | "!=" => PredicateOp::Ne,
+            "in" => PredicateOp::In,
+            _ => PredicateOp::Eq,
+        }
+    }
+}
+
+/// A single metadata constraint over a candidate's `Value.data`,
+/// evaluated during the graph traversal in `Server::query` rather than
+/// against a fixed top-k afterward, so a selective filter doesn't
+/// collapse recall.
+#[derive(Debug, Clone)]
+pub struct Predicate {
+    pub key: String,
+    pub op: PredicateOp,
+    pub value: Vec<String>,
+}
+
+impl Predicate {
+    fn matches(&self, data: &Data) -> bool {
+        let actual = data.get(&self.key);
+        match self.op {
+            PredicateOp::Eq => actual == self.value.first(),
+            PredicateOp::Ne => actual != self.value.first(),
+            PredicateOp::In => {
+                actual.is_some_and(|v| self.value.contains(v))
+            }
+        }
+    }
+}
+
+// Whether `data` satisfies every predicate in `filter`.
+fn matches_all(filter: &[Predicate], data: &Data) -> bool {
+    filter.iter().all(|predicate| predicate.matches(data))
+}
+
+impl From<&str> for SimilarityStyle {
+    fn from(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "cosine" => SimilarityStyle::Cosine,
+            "dot" | "dot_product" => SimilarityStyle::DotProduct,
+            _ => SimilarityStyle::Euclidean,
+        }
+    }
+}
+
+// Running totals tracked per item while fusing the vector and keyword
+// ranked lists, before they're turned into a `ScoreDetails`.
+struct FusedCandidate {
+    distance: Option<f32>,
+    keyword_rank: Option<usize>,
+    fused_score: f32,
 }
 
 // Use Arc and Mutex to share the graphs across threads.
@@ -20,18 +210,154 @@ pub struct Value {
 type Graph = HNSW<Value, String>;
 type Graphs = Arc<Mutex<HashMap<String, Graph>>>;
 
+/// The `ef_search`/`ef_construction` a graph was built with, since the
+/// underlying `instant_distance` graph doesn't retain them after the
+/// build completes. Persisted alongside the graph so a restart doesn't
+/// lose the values `/admin/stats` reports.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+struct GraphStats {
+    ef_search: usize,
+    ef_construction: usize,
+}
+
+type GraphStatsMap = Arc<Mutex<HashMap<String, GraphStats>>>;
+
+/// Per-graph introspection payload returned by `/admin/stats`.
+#[derive(Serialize, Debug)]
+pub struct GraphInfo {
+    /// Indexing algorithm backing the graph. Always `"hnsw"` today, since
+    /// `instant_distance` is the only one wired into `Server`.
+    pub algorithm: &'static str,
+    /// Distance metric the graph was built and searched with. Always
+    /// `"euclidean"` today, matching `impl instant_distance::Point for
+    /// Value` below.
+    pub metric: &'static str,
+    pub dimension: usize,
+    pub record_count: usize,
+    pub ef_search: usize,
+    pub ef_construction: usize,
+}
+
+/// Produces vector embeddings from text, so callers can search and insert
+/// records without precomputing embeddings client-side.
+pub trait Embedder: Send + Sync {
+    /// Embeds a batch of texts, one embedding per input, in order.
+    fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String>;
+}
+
+/// Built-in embedder that POSTs `{"input": texts}` to a user-configured
+/// HTTP endpoint and expects a JSON array of float arrays back, so the
+/// server isn't tied to any single embedding provider. Gated behind the
+/// `http-embedder` feature since it pulls in a blocking HTTP client that
+/// not every deployment needs.
+#[cfg(feature = "http-embedder")]
+pub struct HttpEmbedder {
+    pub endpoint: String,
+}
+
+#[cfg(feature = "http-embedder")]
+impl HttpEmbedder {
+    /// Creates a new HTTP embedder pointing at the given endpoint.
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        HttpEmbedder { endpoint: endpoint.into() }
+    }
+}
+
+#[cfg(feature = "http-embedder")]
+impl Embedder for HttpEmbedder {
+    fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        let client = Client::new();
+        let body = serde_json::json!({ "input": texts });
+
+        let response = client
+            .post(&self.endpoint)
+            .json(&body)
+            .send()
+            .map_err(|e| e.to_string())?;
+
+        response.json::<Vec<Vec<f32>>>().map_err(|e| e.to_string())
+    }
+}
+
+/// Deterministic embedder with no external dependencies, for tests and
+/// local development. Derives each dimension from a rolling hash of the
+/// text so the same input always produces the same vector, without
+/// needing a real model or network access.
+pub struct MockEmbedder {
+    pub dimension: usize,
+}
+
+impl MockEmbedder {
+    pub fn new(dimension: usize) -> Self {
+        MockEmbedder { dimension }
+    }
+}
+
+impl Embedder for MockEmbedder {
+    fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        Ok(texts
+            .iter()
+            .map(|text| {
+                let mut state: u64 = 0xcbf29ce484222325;
+                (0..self.dimension)
+                    .map(|i| {
+                        for byte in text.bytes().chain(std::iter::once(i as u8)) {
+                            state ^= byte as u64;
+                            state = state.wrapping_mul(0x100000001b3);
+                        }
+
+                        (state % 1000) as f32 / 1000.0
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+/// Permission level granted to an [`ApiKey`], checked against the minimum
+/// scope a route requires. Ordered so a higher scope implies every
+/// permission a lower one has: `Admin` > `Write` > `Read`.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyScope {
+    Read,
+    Write,
+    Admin,
+}
+
+/// A named, scoped credential presented via the `x-oasysdb-token` header,
+/// as a least-privilege alternative to the single `Config::token`
+/// superuser credential.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiKey {
+    pub name: String,
+    pub token: String,
+    pub scope: ApiKeyScope,
+}
+
 // Configuration for the database server.
 pub struct Config {
     pub dimension: usize,
     pub token: String,
     pub path: String,
+    /// Optional embedder used to auto-generate vectors from text when a
+    /// request provides a query or record text instead of a raw embedding.
+    pub embedder: Option<Arc<dyn Embedder>>,
+    /// Metadata field read for auto-embedding on insert when a value is
+    /// set without an embedding.
+    pub embed_field: Option<String>,
 }
 
 pub struct Server {
     pub config: Config,
     graphs: Graphs,
+    graph_stats: GraphStatsMap,
     graph_db: Database,
+    stats_db: Database,
     value_db: Database,
+    key_db: Database,
 }
 
 impl Server {
@@ -61,7 +387,37 @@ impl Server {
             graphs.lock().unwrap().insert(name, graph);
         }
 
-        Server { config, graphs, graph_db, value_db }
+        // Load the ef_search/ef_construction each graph above was built
+        // with, so `/admin/stats` survives a restart.
+        let stats_db = {
+            let path = format!("{}/graph_stats", config.path.clone());
+            sled::open(path).unwrap()
+        };
+
+        let graph_stats: GraphStatsMap =
+            Arc::new(Mutex::new(HashMap::new()));
+        for item in stats_db.iter() {
+            let (name, stats) = item.unwrap();
+            let name = String::from_utf8_lossy(&name).to_string();
+            let stats: GraphStats = serde_json::from_slice(&stats).unwrap();
+            graph_stats.lock().unwrap().insert(name, stats);
+        }
+
+        // Load the scoped API keys.
+        let key_db = {
+            let path = format!("{}/keys", config.path.clone());
+            sled::open(path).unwrap()
+        };
+
+        Server {
+            config,
+            graphs,
+            graph_stats,
+            graph_db,
+            stats_db,
+            value_db,
+            key_db,
+        }
     }
 
     // Native functionality handler.
@@ -79,7 +435,13 @@ impl Server {
         Ok(serde_json::from_slice(&value).unwrap())
     }
 
-    pub fn set(&self, key: String, value: Value) -> Result<Value, &str> {
+    pub fn set(&self, key: String, mut value: Value) -> Result<Value, &str> {
+        // Auto-embed the value from its designated text field when it's
+        // inserted without a precomputed embedding.
+        if value.embedding.is_empty() {
+            self.auto_embed(&mut value)?;
+        }
+
         // Validate the dimension of the value.
         if value.embedding.len() != self.config.dimension {
             return Err("The embedding dimension is invalid.");
@@ -98,6 +460,39 @@ impl Server {
         Ok(value)
     }
 
+    // Fills in `value.embedding` from the configured embedder when the
+    // server has an embedder and the value carries text to embed, either
+    // directly via `value.text` or via the designated metadata field.
+    // Otherwise leaves the value unchanged.
+    fn auto_embed(&self, value: &mut Value) -> Result<(), &str> {
+        let embedder = match &self.config.embedder {
+            Some(embedder) => embedder,
+            None => return Ok(()),
+        };
+
+        let text = match value.text.clone() {
+            Some(text) => text,
+            None => match &self.config.embed_field {
+                Some(field) => match value.data.get(field) {
+                    Some(text) => text.clone(),
+                    None => return Ok(()),
+                },
+                None => return Ok(()),
+            },
+        };
+
+        let mut embeddings = embedder
+            .embed(&[text])
+            .map_err(|_| "Failed to generate the embedding for the value.")?;
+
+        value.embedding = match embeddings.pop() {
+            Some(embedding) => embedding,
+            None => return Err("The embedder returned no embedding."),
+        };
+
+        Ok(())
+    }
+
     pub fn delete(&self, key: String) -> Result<Value, &str> {
         // Check if the key exists.
         if !self.value_db.contains_key(key.clone()).unwrap() {
@@ -115,6 +510,117 @@ impl Server {
         }
     }
 
+    // Enumerates stored key-value pairs whose keys fall in a lexicographic
+    // range or share a prefix, modeled on K2V range queries. `prefix` takes
+    // precedence over `start`/`end` when both are given. `cursor` is a
+    // continuation token from a previous call's `ScanResult::next`, and
+    // excludes keys up to and including it (or from it, when `reverse`).
+    pub fn scan(
+        &self,
+        prefix: Option<String>,
+        start: Option<String>,
+        end: Option<String>,
+        cursor: Option<String>,
+        limit: usize,
+        reverse: bool,
+        keys_only: bool,
+    ) -> Result<ScanResult, &str> {
+        let mut keys: Vec<String> = Vec::new();
+        for item in self.value_db.iter() {
+            let (key, _) =
+                item.map_err(|_| "Error scanning the key-value store.")?;
+            let key = String::from_utf8_lossy(&key).to_string();
+
+            if let Some(prefix) = &prefix {
+                if !key.starts_with(prefix.as_str()) {
+                    continue;
+                }
+            } else {
+                if start.as_ref().is_some_and(|start| &key < start) {
+                    continue;
+                }
+                if end.as_ref().is_some_and(|end| &key >= end) {
+                    continue;
+                }
+            }
+
+            if let Some(cursor) = &cursor {
+                let past_cursor =
+                    if reverse { &key >= cursor } else { &key <= cursor };
+                if past_cursor {
+                    continue;
+                }
+            }
+
+            keys.push(key);
+        }
+
+        // `value_db.iter()` yields keys in ascending order since sled is a
+        // sorted store, so only reversed scans need an explicit flip.
+        if reverse {
+            keys.reverse();
+        }
+
+        let has_more = keys.len() > limit;
+        keys.truncate(limit);
+        let next = if has_more { keys.last().cloned() } else { None };
+
+        let mut items = Vec::with_capacity(keys.len());
+        for key in keys {
+            let value =
+                if keys_only { None } else { Some(self.get(key.clone())?) };
+            items.push(ScanItem { key, value });
+        }
+
+        Ok(ScanResult { items, next })
+    }
+
+    // API key functionality handlers.
+    // This handles issuing, listing, and revoking scoped API keys, and
+    // resolving a presented token's scope for the auth check in
+    // `routes::handle_request`.
+
+    /// Creates and persists a new scoped API key.
+    pub fn create_key(&self, name: String, scope: ApiKeyScope) -> ApiKey {
+        let token = Uuid::new_v4().to_string();
+        let key = ApiKey { name, token: token.clone(), scope };
+
+        let bytes = serde_json::to_vec(&key).unwrap();
+        self.key_db.insert(token, bytes).unwrap();
+        key
+    }
+
+    /// Lists every persisted API key.
+    pub fn list_keys(&self) -> Vec<ApiKey> {
+        self.key_db
+            .iter()
+            .filter_map(|item| item.ok())
+            .map(|(_, value)| serde_json::from_slice(&value).unwrap())
+            .collect()
+    }
+
+    /// Revokes the API key presenting `token`.
+    pub fn revoke_key(&self, token: &str) -> Result<(), &str> {
+        match self.key_db.remove(token) {
+            Ok(Some(_)) => Ok(()),
+            _ => Err("The key is not found."),
+        }
+    }
+
+    /// Resolves a presented `x-oasysdb-token` to its scope. The configured
+    /// root `Config::token` always resolves to `ApiKeyScope::Admin`, so
+    /// existing deployments keep working with their single token; any
+    /// other token is looked up among the persisted keys.
+    pub fn resolve_token(&self, token: &str) -> Option<ApiKeyScope> {
+        if token == self.config.token {
+            return Some(ApiKeyScope::Admin);
+        }
+
+        let bytes = self.key_db.get(token).ok()??;
+        let key: ApiKey = serde_json::from_slice(&bytes).ok()?;
+        Some(key.scope)
+    }
+
     // Graphs functionality handlers.
     // This handles building and querying the graphs.
 
@@ -146,6 +652,12 @@ impl Server {
         let graph_config = serde_json::to_vec(&new_graph).unwrap();
         self.graph_db.insert(name.clone(), graph_config).unwrap();
 
+        // Store the build parameters so `/admin/stats` can report them.
+        let stats = GraphStats { ef_search, ef_construction };
+        let stats_bytes = serde_json::to_vec(&stats).unwrap();
+        self.stats_db.insert(name.clone(), stats_bytes).unwrap();
+        self.graph_stats.lock().unwrap().insert(name.clone(), stats);
+
         // Store the graph to Server.graphs which exists in memory.
         let mut graphs = self.graphs.lock().unwrap();
         graphs.insert(name, new_graph);
@@ -153,12 +665,179 @@ impl Server {
         Ok("The graph is built successfully.")
     }
 
+    /// Returns an introspection snapshot of every built graph, for the
+    /// `/admin/stats` endpoint.
+    pub fn stats(&self) -> HashMap<String, GraphInfo> {
+        let graphs = self.graphs.lock().unwrap();
+        let graph_stats = self.graph_stats.lock().unwrap();
+
+        graphs
+            .iter()
+            .map(|(name, graph)| {
+                let built =
+                    graph_stats.get(name).copied().unwrap_or_default();
+                let info = GraphInfo {
+                    algorithm: "hnsw",
+                    metric: "euclidean",
+                    dimension: self.config.dimension,
+                    record_count: graph.values.len(),
+                    ef_search: built.ef_search,
+                    ef_construction: built.ef_construction,
+                };
+
+                (name.clone(), info)
+            })
+            .collect()
+    }
+
+    /// Query the named graph for its `count` nearest points to `embedding`.
+    ///
+    /// `style` picks which distance/similarity formula re-scores the
+    /// graph's approximate candidates, and an optional `threshold` drops
+    /// candidates outside the cutoff: for `Euclidean`, distances greater
+    /// than `threshold` are dropped; for `Cosine`/`DotProduct`, similarities
+    /// below it are.
+    ///
+    /// `filter` restricts hits to candidates whose `data` satisfies every
+    /// predicate, evaluated as the traversal goes rather than against a
+    /// fixed top-k afterward, so a selective filter still surfaces `count`
+    /// matches when the graph has them.
     pub fn query(
         &self,
         name: String, // Graph name.
         embedding: Vec<f32>,
         count: usize,
+        style: SimilarityStyle,
+        threshold: Option<f32>,
+        filter: &[Predicate],
     ) -> Result<Vec<Data>, &str> {
+        let ranked = self.rank_by_vector_styled(
+            &name, embedding, count, style, threshold, filter,
+        )?;
+
+        let mut data: Vec<Data> =
+            ranked.into_iter().map(|(data, _)| data).collect();
+        data.truncate(count);
+        Ok(data)
+    }
+
+    // Hybrid search functionality handler.
+    // This combines the vector search above with a keyword matching
+    // pass over the stored data, fusing the two ranked lists together.
+
+    /// Searches a graph by vector similarity and, when `query` is provided,
+    /// fuses the result with a keyword-matching pass over the stored data.
+    ///
+    /// When `embedding` is omitted, it's generated from `query` using the
+    /// configured embedder, so callers can search by text alone.
+    ///
+    /// Without `semantic_ratio`, the two ranked lists are combined with
+    /// Reciprocal Rank Fusion. With `semantic_ratio`, each list's raw scores
+    /// are min-max normalized to [0, 1] first and then blended by `ratio`
+    /// and `1.0 - ratio`.
+    ///
+    /// `rrf_k` overrides the Reciprocal Rank Fusion constant (defaults to
+    /// `60.0`, the value used in the original RRF paper) and is ignored
+    /// when `semantic_ratio` is supplied.
+    pub fn search(
+        &self,
+        name: String,
+        embedding: Option<Vec<f32>>,
+        query: Option<String>,
+        count: usize,
+        semantic_ratio: Option<f32>,
+        rrf_k: Option<f32>,
+    ) -> Result<Vec<SearchResult>, &str> {
+        // Lower values weigh top ranks more heavily.
+        let rrf_k = rrf_k.unwrap_or(60.0);
+
+        let embedding = match embedding {
+            Some(embedding) => embedding,
+            None => self.embed_query(query.as_deref())?,
+        };
+
+        // Pull a larger pool than requested from each ranked list so the
+        // fused order isn't starved by either side's truncation.
+        let pool = count.max(1) * 4;
+        let vector_ranked = self.rank_by_vector(&name, embedding, pool)?;
+
+        let query = match query {
+            Some(query) if !query.trim().is_empty() => query,
+            _ => {
+                let mut results: Vec<SearchResult> = vector_ranked
+                    .into_iter()
+                    .map(|(data, distance)| SearchResult {
+                        data,
+                        score: ScoreDetails::from_distance(distance),
+                    })
+                    .collect();
+                results.truncate(count);
+                return Ok(results);
+            }
+        };
+
+        let keyword_ranked = self.rank_by_keyword(&query, pool);
+        let mut fused = match semantic_ratio {
+            Some(ratio) => Self::fuse_weighted(vector_ranked, keyword_ranked, ratio),
+            None => Self::fuse_rrf(vector_ranked, keyword_ranked, rrf_k),
+        };
+
+        fused.truncate(count);
+        Ok(fused)
+    }
+
+    // Generates a query embedding from text using the configured embedder,
+    // used when a search request omits a precomputed embedding.
+    fn embed_query(&self, query: Option<&str>) -> Result<Vec<f32>, &str> {
+        let query = query.ok_or("Embedding or query is required.")?;
+        let embedder =
+            self.config.embedder.as_ref().ok_or("No embedder is configured.")?;
+
+        let mut embeddings = embedder
+            .embed(&[query.to_string()])
+            .map_err(|_| "Failed to generate the embedding for the query.")?;
+
+        embeddings.pop().ok_or("The embedder returned no embedding.")
+    }
+
+    // Rank the graph's points by vector similarity to the embedding.
+    // Returns the data alongside its raw distance, smallest first.
+    fn rank_by_vector(
+        &self,
+        name: &str,
+        embedding: Vec<f32>,
+        limit: usize,
+    ) -> Result<Vec<(Data, f32)>, &str> {
+        self.rank_by_vector_styled(
+            name,
+            embedding,
+            limit,
+            SimilarityStyle::Euclidean,
+            None,
+            &[],
+        )
+    }
+
+    // Like `rank_by_vector`, but re-scores the graph's approximate
+    // candidates under `style` instead of trusting its (always Euclidean)
+    // traversal distance, and drops candidates `style` doesn't consider a
+    // match for `threshold`. Returned scores follow `style`: a distance for
+    // `Euclidean` (smaller is closer) or a similarity for `Cosine`/
+    // `DotProduct` (larger is closer).
+    //
+    // When `filter` is non-empty, the traversal is allowed to scan beyond
+    // `limit` candidates (bounded by a fixed budget) so a selective filter
+    // doesn't starve the result count; with an empty filter this behaves
+    // exactly like before.
+    fn rank_by_vector_styled(
+        &self,
+        name: &str,
+        embedding: Vec<f32>,
+        limit: usize,
+        style: SimilarityStyle,
+        threshold: Option<f32>,
+        filter: &[Predicate],
+    ) -> Result<Vec<(Data, f32)>, &str> {
         // Validate the dimension of the embedding.
         if embedding.len() != self.config.dimension {
             return Err("The embedding dimension is invalid.");
@@ -167,30 +846,218 @@ impl Server {
         // Get the graph from the HashMap with the provided name.
         // Graph name = HashMap key.
         let graphs = self.graphs.lock().unwrap();
-        let graph: &Graph = match graphs.get(&name) {
+        let graph: &Graph = match graphs.get(name) {
             Some(graph) => graph,
             None => return Err("The graph is not found."),
         };
 
         // Create a decoy value with the provided embedding.
         // Data is not needed for the query process.
-        let point = Value { embedding, data: HashMap::new() };
+        let point = Value { embedding, data: HashMap::new(), text: None };
 
-        // Query the graph.
+        // Query the graph. Its internal traversal always ranks by Euclidean
+        // distance, so candidates are gathered first and re-scored under
+        // `style` below rather than trusted as the final order.
         let mut query = Search::default();
         let results = graph.search(&point, &mut query);
 
-        // Get the keys from the result.
-        let mut data: Vec<Data> = Vec::new();
-        for result in results {
+        // A plain (unfiltered) query only ever needs to look at the first
+        // `limit` candidates. A filtered one may have to skip past many
+        // non-matching candidates to find `limit` real hits, so it's
+        // allowed a wider, but still bounded, extra-candidate budget
+        // instead of scanning the whole graph.
+        let budget = if filter.is_empty() { limit } else { limit * 20 };
+
+        let mut ranked: Vec<(Data, f32)> = Vec::new();
+        for (scanned, result) in results.enumerate() {
+            if scanned >= budget || ranked.len() >= limit {
+                break;
+            }
+
             let value = result.point;
-            data.push(value.data.clone());
+            if !matches_all(filter, &value.data) {
+                continue;
+            }
+
+            let score = match style {
+                SimilarityStyle::Euclidean => result.distance,
+                _ => style.score(&point.embedding, &value.embedding),
+            };
+
+            if threshold.is_some_and(|t| !style.passes(score, t)) {
+                continue;
+            }
+
+            ranked.push((value.data.clone(), score));
         }
 
-        // Truncate the result to count.
-        data.truncate(count);
+        if !matches!(style, SimilarityStyle::Euclidean) {
+            ranked.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap());
+        }
 
-        Ok(data)
+        Ok(ranked)
+    }
+
+    // Rank the stored key-value pairs by how many of the query's terms
+    // appear, case-insensitively, across their string fields.
+    fn rank_by_keyword(&self, query: &str, limit: usize) -> Vec<(Data, f32)> {
+        let terms: Vec<String> =
+            query.split_whitespace().map(|term| term.to_lowercase()).collect();
+
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let mut ranked: Vec<(Data, f32)> = Vec::new();
+        for item in self.value_db.iter() {
+            let (_, value) = match item {
+                Ok(pair) => pair,
+                Err(_) => continue,
+            };
+
+            let value: Value = match serde_json::from_slice(&value) {
+                Ok(value) => value,
+                Err(_) => continue,
+            };
+
+            let haystack = value
+                .data
+                .values()
+                .cloned()
+                .collect::<Vec<String>>()
+                .join(" ")
+                .to_lowercase();
+
+            let score =
+                terms.iter().filter(|term| haystack.contains(term.as_str())).count();
+            if score > 0 {
+                ranked.push((value.data, score as f32));
+            }
+        }
+
+        ranked.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap());
+        ranked.truncate(limit);
+        ranked
+    }
+
+    // Combines two ranked lists using Reciprocal Rank Fusion: each item's
+    // score is the sum, across the lists it appears in, of 1 / (k + rank).
+    // Keeps the vector distance and keyword rank around so callers can see
+    // why an item ranked where it did.
+    fn fuse_rrf(
+        vector: Vec<(Data, f32)>,
+        keyword: Vec<(Data, f32)>,
+        k: f32,
+    ) -> Vec<SearchResult> {
+        let mut fused: Vec<(Data, FusedCandidate)> = Vec::new();
+
+        for (rank, (data, distance)) in vector.into_iter().enumerate() {
+            let score = 1.0 / (k + (rank + 1) as f32);
+            Self::merge_candidate(&mut fused, data, score, Some(distance), None);
+        }
+
+        for (rank, (data, _)) in keyword.into_iter().enumerate() {
+            let score = 1.0 / (k + (rank + 1) as f32);
+            Self::merge_candidate(&mut fused, data, score, None, Some(rank + 1));
+        }
+
+        Self::finalize_fused(fused)
+    }
+
+    // Combines two ranked lists by min-max normalizing each list's raw
+    // scores to [0, 1] and blending them by `ratio` and `1.0 - ratio`.
+    fn fuse_weighted(
+        vector: Vec<(Data, f32)>,
+        keyword: Vec<(Data, f32)>,
+        ratio: f32,
+    ) -> Vec<SearchResult> {
+        let distances = vector.clone();
+        let normalized_vector = Self::normalize_scores(vector, true);
+        let normalized_keyword = Self::normalize_scores(keyword, false);
+
+        let mut fused: Vec<(Data, FusedCandidate)> = Vec::new();
+
+        for (data, score) in normalized_vector {
+            let distance =
+                distances.iter().find(|(d, _)| d == &data).map(|(_, dist)| *dist);
+            Self::merge_candidate(&mut fused, data, score * ratio, distance, None);
+        }
+
+        for (rank, (data, score)) in normalized_keyword.into_iter().enumerate() {
+            let score = score * (1.0 - ratio);
+            Self::merge_candidate(&mut fused, data, score, None, Some(rank + 1));
+        }
+
+        Self::finalize_fused(fused)
+    }
+
+    // Adds `score` to the matching candidate's running total, creating one
+    // if this is the item's first appearance. `distance`/`keyword_rank` are
+    // only set when the caller has them for this pass.
+    fn merge_candidate(
+        fused: &mut Vec<(Data, FusedCandidate)>,
+        data: Data,
+        score: f32,
+        distance: Option<f32>,
+        keyword_rank: Option<usize>,
+    ) {
+        match fused.iter_mut().find(|(d, _)| d == &data) {
+            Some((_, candidate)) => {
+                candidate.fused_score += score;
+                candidate.distance = candidate.distance.or(distance);
+                candidate.keyword_rank = candidate.keyword_rank.or(keyword_rank);
+            }
+            None => {
+                let candidate = FusedCandidate { distance, keyword_rank, fused_score: score };
+                fused.push((data, candidate));
+            }
+        }
+    }
+
+    // Sorts the fused candidates by their combined score and turns each
+    // into a `SearchResult` carrying its score breakdown.
+    fn finalize_fused(fused: Vec<(Data, FusedCandidate)>) -> Vec<SearchResult> {
+        let mut fused = fused;
+        fused.sort_by(|(_, a), (_, b)| {
+            b.fused_score.partial_cmp(&a.fused_score).unwrap()
+        });
+
+        fused
+            .into_iter()
+            .map(|(data, candidate)| {
+                let distance = candidate.distance.unwrap_or(f32::INFINITY);
+                let mut score = ScoreDetails::from_distance(distance);
+                score.keyword_rank = candidate.keyword_rank;
+                score.fused_score = Some(candidate.fused_score);
+                SearchResult { data, score }
+            })
+            .collect()
+    }
+
+    // Min-max normalizes raw scores to [0, 1]. When `invert` is set (used
+    // for distance-based scores where smaller is better), the normalized
+    // value is flipped so that, like the keyword scores, higher always
+    // means closer.
+    fn normalize_scores(
+        list: Vec<(Data, f32)>,
+        invert: bool,
+    ) -> Vec<(Data, f32)> {
+        if list.is_empty() {
+            return list;
+        }
+
+        let min = list.iter().map(|(_, s)| *s).fold(f32::INFINITY, f32::min);
+        let max = list.iter().map(|(_, s)| *s).fold(f32::NEG_INFINITY, f32::max);
+        let range = max - min;
+
+        list.into_iter()
+            .map(|(data, score)| {
+                let normalized =
+                    if range > 0.0 { (score - min) / range } else { 1.0 };
+                let score = if invert { 1.0 - normalized } else { normalized };
+                (data, score)
+            })
+            .collect()
     }
 }
 
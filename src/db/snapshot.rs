@@ -0,0 +1,72 @@
+use super::*;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Commands a running [`SnapshotWorker`] accepts over its channel.
+pub enum SnapshotCommand {
+    /// Take a snapshot immediately instead of waiting for the next tick.
+    RunNow,
+    /// Stop the worker thread.
+    Stop,
+}
+
+/// Start/stop handle for a [`Database`]'s periodic [`Database::dump`]
+/// worker.
+///
+/// The worker thread wakes up every `interval`, or on demand via
+/// [`Self::trigger`], and overwrites the snapshot at `path`, keeping
+/// running until [`Self::stop`] is called. A failed snapshot is dropped
+/// rather than panicking the worker, since a transient failure (e.g. a
+/// full disk) shouldn't stop future attempts. Dropping the handle without
+/// calling [`Self::stop`] disconnects the channel, so the worker thread
+/// exits once it next checks it, but nothing waits for it to finish.
+pub struct SnapshotWorker {
+    sender: mpsc::Sender<SnapshotCommand>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SnapshotWorker {
+    /// Spawns a worker thread that calls [`Database::dump`] to `path`
+    /// every `interval`.
+    /// - `db`: Shared handle to the database to snapshot.
+    /// - `path`: Destination of each snapshot, overwritten every run.
+    /// - `interval`: How often to take a new snapshot.
+    pub fn start(
+        db: Arc<Database>,
+        path: impl Into<PathBuf>,
+        interval: Duration,
+    ) -> Self {
+        let path = path.into();
+        let (sender, receiver) = mpsc::channel();
+        let run_pass = move || {
+            let _ = db.dump(&path);
+        };
+
+        let thread = thread::spawn(move || loop {
+            match receiver.recv_timeout(interval) {
+                Ok(SnapshotCommand::Stop) => break,
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                Ok(SnapshotCommand::RunNow) => run_pass(),
+                Err(mpsc::RecvTimeoutError::Timeout) => run_pass(),
+            }
+        });
+
+        Self { sender, thread: Some(thread) }
+    }
+
+    /// Triggers an immediate snapshot instead of waiting for the next
+    /// tick.
+    pub fn trigger(&self) {
+        let _ = self.sender.send(SnapshotCommand::RunNow);
+    }
+
+    /// Stops the worker thread and waits for it to finish.
+    pub fn stop(mut self) {
+        let _ = self.sender.send(SnapshotCommand::Stop);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
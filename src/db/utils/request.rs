@@ -5,11 +5,16 @@ use std::collections::HashMap;
 // Example: "content-type": "application/json".
 pub type RequestHeaders = HashMap<String, String>;
 
+// Query string parameters parsed from the route, e.g. `?prefix=a&limit=10`
+// becomes `{"prefix": "a", "limit": "10"}`.
+pub type RequestQuery = HashMap<String, String>;
+
 // This is the data structure that will be parsed
 // from the stream and is passed to the route handlers.
 pub struct Request {
     pub method: String,
     pub route: String,
+    pub query: RequestQuery,
     pub headers: RequestHeaders,
     pub body: RequestBody,
 }
@@ -4,22 +4,94 @@ use std::collections::HashMap;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
 
-pub async fn read(stream: &mut TcpStream) -> Option<req::Request> {
-    // Prepare the request for parsing.
-    let mut _headers = [httparse::EMPTY_HEADER; 16];
-    let mut _req = httparse::Request::new(&mut _headers);
+/// Why a request failed to parse. Every variant represents bad input from
+/// the client, not a server bug, so the caller turns it into a
+/// `400 Bad Request` via [`Self::message`] instead of panicking or
+/// silently dropping the connection.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The connection failed, or closed, before a full request arrived.
+    IncompleteRequest,
+    /// `httparse` couldn't parse the request line or headers.
+    MalformedHeaders,
+    /// The request line was missing a method or path.
+    MissingMethodOrPath,
+    /// A chunked-encoded body had a malformed chunk-size line.
+    MalformedChunkedBody,
+    /// The body claimed to be UTF-8 but wasn't.
+    InvalidUtf8Body,
+    /// The body wasn't valid JSON.
+    InvalidJsonBody,
+}
+
+impl ParseError {
+    /// A client-facing message describing the parse failure.
+    pub fn message(&self) -> &'static str {
+        match self {
+            ParseError::IncompleteRequest => {
+                "The request ended before it was complete."
+            }
+            ParseError::MalformedHeaders => "Malformed HTTP request.",
+            ParseError::MissingMethodOrPath => {
+                "The request is missing a method or path."
+            }
+            ParseError::MalformedChunkedBody => {
+                "Malformed chunked request body."
+            }
+            ParseError::InvalidUtf8Body => "Request body is not valid UTF-8.",
+            ParseError::InvalidJsonBody => "Request body is not valid JSON.",
+        }
+    }
+}
 
-    // Read data from the stream.
-    let mut buf = vec![0; 1024];
-    let n = stream.read(&mut buf).await.unwrap();
+/// Reads and parses one HTTP request from `stream`. Returns `Ok(None)`
+/// when the client disconnects before sending any bytes at all, which
+/// isn't malformed, just nothing to respond to. Any other failure to
+/// parse is returned as a [`ParseError`] rather than panicking, so a
+/// hostile or buggy client can't take down the worker handling it.
+pub async fn read(
+    stream: &mut TcpStream,
+) -> Result<Option<req::Request>, ParseError> {
+    // Read until the full header block (ending in \r\n\r\n) has arrived,
+    // growing the buffer as needed instead of truncating long requests
+    // to whatever a single fixed-size read happens to return.
+    let mut buf: Vec<u8> = Vec::new();
+    let header_end = loop {
+        if let Some(pos) = find_subslice(&buf, b"\r\n\r\n") {
+            break pos + 4;
+        }
 
-    // Disconnection handler.
-    if n == 0 {
-        return None;
+        let mut chunk = [0; 4096];
+        let n = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|_| ParseError::IncompleteRequest)?;
+
+        // Disconnection handler.
+        if n == 0 {
+            if buf.is_empty() {
+                return Ok(None);
+            }
+            return Err(ParseError::IncompleteRequest);
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    // Prepare the request line and headers for parsing.
+    let mut _headers = [httparse::EMPTY_HEADER; 16];
+    let mut _req = httparse::Request::new(&mut _headers);
+    match _req.parse(&buf) {
+        Ok(httparse::Status::Complete(_)) => {}
+        Ok(httparse::Status::Partial) => {
+            return Err(ParseError::IncompleteRequest);
+        }
+        Err(_) => return Err(ParseError::MalformedHeaders),
     }
 
-    // Parse the request.
-    let _ = _req.parse(&buf).unwrap();
+    let method =
+        _req.method.ok_or(ParseError::MissingMethodOrPath)?.to_lowercase();
+    let path = _req.path.ok_or(ParseError::MissingMethodOrPath)?;
+    let (route, query) = parse_path(path);
 
     // Parse request headers.
     let headers: req::RequestHeaders = HashMap::from_iter(_req.headers.iter().map(|header| {
@@ -28,45 +100,150 @@ pub async fn read(stream: &mut TcpStream) -> Option<req::Request> {
         (key, val)
     }));
 
-    // If content length is present or more than 0, read the body.
-    let _content_len = headers
-        .get("content-length")
-        .unwrap_or(&"0".to_string())
-        .parse::<usize>()
-        .unwrap_or(0);
+    // Whatever body bytes already arrived alongside the header block.
+    let mut body_buf = buf.split_off(header_end);
+
+    let chunked = headers
+        .get("transfer-encoding")
+        .map(|value| value.to_lowercase().contains("chunked"))
+        .unwrap_or(false);
+
+    let raw_body = if chunked {
+        read_chunked_body(stream, body_buf).await?
+    } else {
+        // If content length is present or more than 0, keep reading until
+        // the full body has arrived. A single `stream.read` isn't
+        // guaranteed to return a large payload (e.g. a batch of
+        // high-dimension vectors) all at once.
+        let content_len = headers
+            .get("content-length")
+            .and_then(|value| value.parse::<usize>().ok())
+            .unwrap_or(0);
+
+        while body_buf.len() < content_len {
+            let mut chunk = [0; 4096];
+            let n = stream
+                .read(&mut chunk)
+                .await
+                .map_err(|_| ParseError::IncompleteRequest)?;
+            if n == 0 {
+                return Err(ParseError::IncompleteRequest);
+            }
+            body_buf.extend_from_slice(&chunk[..n]);
+        }
+
+        body_buf
+    };
 
     // Parse the request body.
     // By default, the body is an empty map, not None.
-    let _body = if _content_len > 0 {
-        let _buf = String::from_utf8_lossy(&buf);
-        let _parts = _buf.split_once("\r\n\r\n").unwrap();
-        _parts.1.replace("\0", "").clone()
-    } else {
-        // Create an empty body.
+    let body_str = if raw_body.is_empty() {
         "{}".to_string()
+    } else {
+        String::from_utf8(raw_body)
+            .map_err(|_| ParseError::InvalidUtf8Body)?
+            .replace('\0', "")
     };
 
-    // Try to parse the body. If fail, return None.
-    // This will guard against invalid JSON.
-    let body: Option<req::RequestBody> = match serde_json::from_str(&_body) {
-        Ok(body) => body,
-        Err(_) => None,
+    // This guards against invalid JSON instead of just dropping it.
+    let body: req::RequestBody = serde_json::from_str(&body_str)
+        .map_err(|_| ParseError::InvalidJsonBody)?;
+
+    Ok(Some(req::Request { method, route, query, headers, body }))
+}
+
+// Splits a request-line path into its route and query parameters, e.g.
+// `/values?prefix=a&limit=10` becomes `("/values", {"prefix": "a", "limit":
+// "10"})`. Percent-decoding isn't performed since no route currently needs
+// it in its query values.
+fn parse_path(path: &str) -> (String, req::RequestQuery) {
+    let (route, query_string) = match path.split_once('?') {
+        Some((route, query_string)) => (route, query_string),
+        None => (path, ""),
     };
 
-    // Returning None will cause the connection to close.
-    if body.is_none() {
-        return None;
-    }
+    let query = query_string
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, val)| (key.to_string(), val.to_string()))
+        .collect();
 
-    // Return request data.
-    let data = Some(req::Request {
-        method: _req.method.unwrap().to_lowercase(),
-        route: _req.path.unwrap().to_string(),
-        headers: headers,
-        body: body.unwrap(),
-    });
+    (route.to_string(), query)
+}
 
-    data
+// Finds the first occurrence of `needle` in `haystack`, used to locate
+// the end of the header block and the end of each chunk-size line.
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+// A declared chunk size above this is treated as malformed rather than
+// trusted, so a hostile client can't force an unbounded (or overflowing)
+// allocation/read just by writing a huge hex number on the size line.
+const MAX_CHUNK_SIZE: usize = 64 * 1024 * 1024;
+
+// Decodes an HTTP/1.1 chunked transfer-encoded body: each chunk is
+// prefixed by its size in hex followed by \r\n, and the stream ends with
+// a zero-length chunk. `buf` carries any bytes already read past the
+// header block.
+async fn read_chunked_body(
+    stream: &mut TcpStream,
+    mut buf: Vec<u8>,
+) -> Result<Vec<u8>, ParseError> {
+    let mut body = Vec::new();
+
+    loop {
+        while find_subslice(&buf, b"\r\n").is_none() {
+            let mut chunk = [0; 4096];
+            let n = stream
+                .read(&mut chunk)
+                .await
+                .map_err(|_| ParseError::IncompleteRequest)?;
+            if n == 0 {
+                return Err(ParseError::IncompleteRequest);
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        }
+
+        let line_end = find_subslice(&buf, b"\r\n").unwrap();
+        let size_line = String::from_utf8_lossy(&buf[..line_end]).to_string();
+        let size = usize::from_str_radix(size_line.trim(), 16)
+            .map_err(|_| ParseError::MalformedChunkedBody)?;
+
+        // Reject an oversized or overflow-inducing declared chunk size
+        // before it's ever used in arithmetic or a buffer index.
+        if size > MAX_CHUNK_SIZE {
+            return Err(ParseError::MalformedChunkedBody);
+        }
+        let chunk_end = size
+            .checked_add(2)
+            .ok_or(ParseError::MalformedChunkedBody)?;
+
+        buf.drain(..line_end + 2);
+
+        // The zero-length chunk marks the end of the body.
+        if size == 0 {
+            break;
+        }
+
+        while buf.len() < chunk_end {
+            let mut chunk = [0; 4096];
+            let n = stream
+                .read(&mut chunk)
+                .await
+                .map_err(|_| ParseError::IncompleteRequest)?;
+            if n == 0 {
+                return Err(ParseError::IncompleteRequest);
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        }
+
+        body.extend_from_slice(&buf[..size]);
+        buf.drain(..chunk_end);
+    }
+
+    Ok(body)
 }
 
 pub async fn write(stream: &mut TcpStream, response: Response<String>) {
@@ -76,11 +253,31 @@ pub async fn write(stream: &mut TcpStream, response: Response<String>) {
     let status = parts.status.as_str();
     let reason = parts.status.canonical_reason().unwrap();
 
-    // HTTP response tag and header.
+    // HTTP response tag.
     let tag = format!("HTTP/1.1 {} {}", status, reason);
-    let header = format!("content-length: {}", body.len());
+
+    // Stream large bodies with chunked transfer encoding instead of
+    // buffering the whole response behind a single `content-length`
+    // write, so a large search/batch response doesn't need to be held in
+    // full before the first byte goes out.
+    const CHUNK_THRESHOLD: usize = 8192;
+    if body.len() > CHUNK_THRESHOLD {
+        let preamble = format!("{}\r\ntransfer-encoding: chunked\r\n\r\n", tag);
+        stream.write_all(preamble.as_bytes()).await.unwrap();
+
+        for piece in body.as_bytes().chunks(CHUNK_THRESHOLD) {
+            let size_line = format!("{:x}\r\n", piece.len());
+            stream.write_all(size_line.as_bytes()).await.unwrap();
+            stream.write_all(piece).await.unwrap();
+            stream.write_all(b"\r\n").await.unwrap();
+        }
+
+        stream.write_all(b"0\r\n\r\n").await.unwrap();
+        return;
+    }
 
     // Format the response as a string.
+    let header = format!("content-length: {}", body.len());
     let data = format!("{}\r\n{}\r\n\r\n{}", tag, header, body);
 
     // Write the response to the stream.
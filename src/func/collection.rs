@@ -12,6 +12,49 @@ pub struct Config {
     pub ml: f32,
     /// Distance calculation function.
     pub distance: Distance,
+    /// Seed for the RNG driving layer assignment and tie-breaks during
+    /// construction. `None` falls back to entropy, same as before this
+    /// field existed. Set this to get a byte-identical graph across
+    /// repeated [`Collection::build`] calls with the same records and
+    /// config, which `search`/benchmarking/recall regression tests rely
+    /// on.
+    pub seed: Option<u64>,
+    /// Neighbor selection strategy used while linking a node during
+    /// construction. `None` keeps the default nearest-`M` cut; see
+    /// [`HeuristicConfig`] for the diversity-aware alternative.
+    pub heuristic: Option<HeuristicConfig>,
+}
+
+/// Opts index construction into the Malkov-Yashunin heuristic neighbor
+/// selection (Algorithm 4 in the HNSW paper) instead of the default plain
+/// nearest-`M` cut. The heuristic favors diverse directions over a tight
+/// cluster of near-duplicates, trading extra construction-time work for a
+/// better-connected graph and higher search recall.
+#[cfg_attr(feature = "py", pyclass(module = "oasysdb.collection", get_all))]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+pub struct HeuristicConfig {
+    /// Widen the candidate set with each candidate's own neighbors on the
+    /// layer being linked before running the selection pass.
+    pub extend_candidates: bool,
+    /// Backfill from the candidates the pass discarded, in distance
+    /// order, when fewer than `M` neighbors survive it.
+    pub keep_pruned: bool,
+}
+
+// Any modifications to this methods should be reflected in:
+// - py/tests/test_collection.py
+// - py/oasysdb/collection.pyi
+#[cfg(feature = "py")]
+#[pymethods]
+impl HeuristicConfig {
+    #[new]
+    fn py_new(extend_candidates: bool, keep_pruned: bool) -> Self {
+        Self { extend_candidates, keep_pruned }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
 }
 
 // Any modifications to this methods should be reflected in:
@@ -50,6 +93,16 @@ impl Config {
         self.set_distance(distance)
     }
 
+    #[setter(seed)]
+    fn py_set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+    }
+
+    #[setter(heuristic)]
+    fn py_set_heuristic(&mut self, heuristic: Option<HeuristicConfig>) {
+        self.heuristic = heuristic;
+    }
+
     #[staticmethod]
     fn create_default() -> Self {
         Self::default()
@@ -75,7 +128,15 @@ impl Config {
         distance: &str,
     ) -> Result<Self, Error> {
         let distance = Distance::from(distance)?;
-        Ok(Self { ef_construction, ef_search, ml, distance })
+        let config = Self {
+            ef_construction,
+            ef_search,
+            ml,
+            distance,
+            seed: None,
+            heuristic: None,
+        };
+        Ok(config)
     }
 
     /// Sets the distance calculation function.
@@ -92,35 +153,150 @@ impl Default for Config {
     /// * `ef_search`: 15
     /// * `ml`: 0.2885
     /// * `distance`: euclidean
+    /// * `seed`: None (entropy)
+    /// * `heuristic`: None (plain nearest-`M` selection)
     fn default() -> Self {
         Self {
             ef_construction: 40,
             ef_search: 15,
             ml: 0.2885,
             distance: Distance::Euclidean,
+            seed: None,
+            heuristic: None,
         }
     }
 }
 
+/// Query-time override for [`Collection::search_with_params`], independent
+/// of `config.ef_construction`/`config.ef_search`. This lets one index
+/// serve both a fast/low-recall caller and a slow/high-recall one, and
+/// lets a benchmark sweep `ef` to plot a recall-versus-speed curve without
+/// rebuilding the collection.
+#[cfg_attr(feature = "py", pyclass(module = "oasysdb.collection", get_all))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SearchParams {
+    /// Base-layer candidate-list size for this query. `None` falls back
+    /// to `config.ef_search`.
+    pub ef: Option<usize>,
+    /// Maximum number of distance evaluations this query may spend before
+    /// it stops expanding and returns the best candidates found so far.
+    /// `None` means no ceiling.
+    pub distance_budget: Option<usize>,
+}
+
+// Any modifications to this methods should be reflected in:
+// - py/tests/test_collection.py
+// - py/oasysdb/collection.pyi
+#[cfg(feature = "py")]
+#[pymethods]
+impl SearchParams {
+    #[new]
+    fn py_new(ef: Option<usize>, distance_budget: Option<usize>) -> Self {
+        Self { ef, distance_budget }
+    }
+
+    #[setter(ef)]
+    fn py_set_ef(&mut self, ef: Option<usize>) {
+        self.ef = ef;
+    }
+
+    #[setter(distance_budget)]
+    fn py_set_distance_budget(&mut self, distance_budget: Option<usize>) {
+        self.distance_budget = distance_budget;
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+impl SearchParams {
+    /// Overrides `ef` for this query only.
+    pub fn with_ef(mut self, ef: usize) -> Self {
+        self.ef = Some(ef);
+        self
+    }
+
+    /// Caps the number of distance evaluations this query may spend.
+    pub fn with_distance_budget(mut self, budget: usize) -> Self {
+        self.distance_budget = Some(budget);
+        self
+    }
+}
+
 /// The collection of vector records with HNSW indexing.
 #[cfg_attr(feature = "py", pyclass(module = "oasysdb.collection"))]
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Collection {
     /// The collection configuration object.
     pub config: Config,
     /// The min/max distance to consider a neighbor.
     pub relevancy: f32,
     // Private fields below.
-    data: HashMap<VectorID, Metadata>,
+    //
+    // `data` is an `IndexMap`, not a `HashMap`, so that a snapshot written
+    // by `ShardedCollection::persist` serializes its records' metadata in a
+    // deterministic, insertion-preserving order instead of `HashMap`'s
+    // per-process random iteration order. `vectors`, `hash_groups` and
+    // `id_hash` are still `HashMap`s, so two bincode dumps of the same
+    // records aren't byte-identical as a whole.
+    data: IndexMap<VectorID, Metadata>,
     vectors: HashMap<VectorID, Vector>,
     slots: Vec<VectorID>,
-    base_layer: Vec<BaseNode>,
-    upper_layers: Vec<Vec<UpperNode>>,
+    // Base layer nodes, kept behind individual locks for the lifetime of
+    // the collection so that inserting new points only locks the specific
+    // nodes it links, rather than rebuilding the whole layer on every
+    // call.
+    #[serde(with = "locked_nodes")]
+    base_layer: Vec<RwLock<BaseNode>>,
+    // Flat neighbor-ID buffer backing the upper layers (1..=top), mirroring
+    // `LayerID` via `upper_meta[i - 1]`. Upper layers are fixed once
+    // `build()` finishes and are never touched by online insert, so a
+    // single flat allocation indexed by computed ranges avoids the
+    // per-layer `Vec<UpperNode>` allocations a nested layout would
+    // scatter across.
+    upper_neighbors: Vec<VectorID>,
+    upper_meta: Vec<Meta>,
+    // Content-hash dedup bookkeeping for the online insert path (`insert`,
+    // `insert_many`, `update`): `hash_groups` maps a vector's content hash
+    // to every live `VectorID` sharing that exact content, in insertion
+    // order, so `group[0]` is the "canonical" ID whose base-layer row
+    // came from a real HNSW descent and the rest are cheap row copies of
+    // it. Its length doubles as the alias refcount, so a node's row is
+    // only ever left dangling once its last alias is deleted. `id_hash`
+    // is the reverse lookup `delete` needs to find an ID's group.
+    hash_groups: HashMap<VectorHash, Vec<VectorID>>,
+    id_hash: HashMap<VectorID, VectorHash>,
     // Utility fields.
     count: usize,
     dimension: usize,
 }
 
+impl Clone for Collection {
+    fn clone(&self) -> Self {
+        let base_layer = self
+            .base_layer
+            .iter()
+            .map(|node| RwLock::new(*node.read()))
+            .collect();
+
+        Self {
+            config: self.config.clone(),
+            relevancy: self.relevancy,
+            data: self.data.clone(),
+            vectors: self.vectors.clone(),
+            slots: self.slots.clone(),
+            base_layer,
+            upper_neighbors: self.upper_neighbors.clone(),
+            upper_meta: self.upper_meta.clone(),
+            hash_groups: self.hash_groups.clone(),
+            id_hash: self.id_hash.clone(),
+            count: self.count,
+            dimension: self.dimension,
+        }
+    }
+}
+
 impl Index<&VectorID> for Collection {
     type Output = Vector;
     fn index(&self, index: &VectorID) -> &Self::Output {
@@ -128,6 +304,14 @@ impl Index<&VectorID> for Collection {
     }
 }
 
+impl Collection {
+    /// Returns a read-only view of `layer`'s slice of the flat upper-layer
+    /// neighbor buffer, usable anywhere a `Layer` is expected.
+    fn upper_layer_view(&self, layer: LayerID) -> LayerSlice<'_> {
+        LayerSlice::new(&self.upper_neighbors, &self.upper_meta[layer.0 - 1])
+    }
+}
+
 // This exposes Collection methods to Python.
 // Any modifications to these methods should be reflected in:
 // - py/tests/test_collection.py
@@ -217,9 +401,12 @@ impl Collection {
 
         self.delete_from_layers(&[*id]);
 
-        // Update the collection data.
+        // Update the collection data. `shift_remove` rather than the
+        // faster `swap_remove`, so deleting a record doesn't reorder the
+        // rest and disturb the deterministic snapshot order `data` exists
+        // to preserve.
         self.vectors.remove(id);
-        self.data.remove(id);
+        self.data.shift_remove(id);
 
         // Make the slot invalid so it won't be used again.
         self.slots[id.0 as usize] = INVALID;
@@ -227,6 +414,8 @@ impl Collection {
         // Update the collection count.
         self.count -= 1;
 
+        self.drop_hash_alias(id);
+
         Ok(())
     }
 
@@ -278,6 +467,10 @@ impl Collection {
         // Remove the old vector from the index layers.
         self.delete_from_layers(&[*id]);
 
+        // Drop the old content's dedup alias; `insert_to_layers` below
+        // re-establishes one for the new content.
+        self.drop_hash_alias(id);
+
         // Insert the updated vector and data.
         self.vectors.insert(*id, record.vector.clone());
         self.data.insert(*id, record.data.clone());
@@ -293,8 +486,149 @@ impl Collection {
         &self,
         vector: &Vector,
         n: usize,
+    ) -> Result<Vec<SearchResult>, Error> {
+        let res = self.search_base(vector, self.config.ef_search, None)?;
+        let mut relevant = self.truncate_irrelevant_result(res);
+        relevant.truncate(n);
+        Ok(relevant)
+    }
+
+    /// Searches the collection for the nearest neighbors, overriding the
+    /// base-layer candidate-list size (`ef`) for this query only instead of
+    /// using `config.ef_search`. A larger `ef` explores more of the graph
+    /// before settling on a result, trading latency for recall, so one
+    /// index can serve both fast/low-recall and slow/high-recall callers.
+    /// * `vector`: Vector to search.
+    /// * `n`: Number of neighbors to return. Must be greater than zero.
+    /// * `ef`: Candidate-list size for the base-layer traversal. Must be
+    ///   greater than or equal to `n`.
+    pub fn search_with_ef(
+        &self,
+        vector: &Vector,
+        n: usize,
+        ef: usize,
+    ) -> Result<Vec<SearchResult>, Error> {
+        if n == 0 {
+            let message = "n must be greater than zero.";
+            return Err(Error::invalid_search_params(message));
+        }
+
+        if ef < n {
+            let message = "ef must be greater than or equal to n.";
+            return Err(Error::invalid_search_params(message));
+        }
+
+        let res = self.search_base(vector, ef, None)?;
+        let mut relevant = self.truncate_irrelevant_result(res);
+        relevant.truncate(n);
+        Ok(relevant)
+    }
+
+    /// Searches the collection for the nearest neighbors, with query-time
+    /// overrides from `params`. Generalizes [`Self::search_with_ef`] with
+    /// an optional distance-computation budget, so a latency-sensitive
+    /// caller can cap the work a query does, or a benchmark can sweep `ef`
+    /// independently of `config.ef_search`. See [`SearchParams`].
+    /// * `vector`: Vector to search.
+    /// * `n`: Number of neighbors to return. Must be greater than zero.
+    /// * `params`: Query-time `ef`/distance-budget overrides.
+    pub fn search_with_params(
+        &self,
+        vector: &Vector,
+        n: usize,
+        params: SearchParams,
+    ) -> Result<Vec<SearchResult>, Error> {
+        if n == 0 {
+            let message = "n must be greater than zero.";
+            return Err(Error::invalid_search_params(message));
+        }
+
+        let ef = params.ef.unwrap_or(self.config.ef_search);
+        if ef < n {
+            let message = "ef must be greater than or equal to n.";
+            return Err(Error::invalid_search_params(message));
+        }
+
+        let res = self.search_base(vector, ef, params.distance_budget)?;
+        let mut relevant = self.truncate_irrelevant_result(res);
+        relevant.truncate(n);
+        Ok(relevant)
+    }
+
+    /// Searches with several query vectors — e.g. a dense and a sparse
+    /// embedding of the same input — and fuses their ranked result lists
+    /// with reciprocal-rank fusion (RRF) instead of returning one query's
+    /// results. For each candidate, the fused score is
+    /// `sum_over_queries(weight / (RANK_CONSTANT + rank_in_query))`, where
+    /// `rank_in_query` starts at 1 and a candidate missing from a query's
+    /// results contributes no term for that query. Results are sorted by
+    /// descending fused score, which is stored in [`SearchResult::score`]
+    /// alongside every query's raw distance in
+    /// [`SearchResult::query_distances`], so callers can see why a result
+    /// ranked where it did.
+    /// * `queries`: Query vectors to search and fuse.
+    /// * `weights`: Per-query weight, matched positionally to `queries`.
+    /// * `n`: Number of fused results to return.
+    pub fn search_hybrid(
+        &self,
+        queries: Vec<Vector>,
+        weights: Vec<f32>,
+        n: usize,
+    ) -> Result<Vec<SearchResult>, Error> {
+        const RANK_CONSTANT: f32 = 60.0;
+
+        if queries.len() != weights.len() {
+            let message = "queries and weights must be the same length.";
+            return Err(Error::invalid_search_params(message));
+        }
+
+        // Search wide enough per query that candidates outside the top `n`
+        // of any single query can still contribute to the fused ranking.
+        let per_query_n = n.max(self.config.ef_search);
+
+        let mut fused: HashMap<u32, (f32, Vec<f32>, Metadata)> = HashMap::new();
+        for (query, weight) in queries.iter().zip(&weights) {
+            let results = self.search(query, per_query_n)?;
+            for (rank, result) in results.into_iter().enumerate() {
+                let entry = fused
+                    .entry(result.id)
+                    .or_insert_with(|| (0.0, Vec::new(), result.data.clone()));
+
+                entry.0 += weight / (RANK_CONSTANT + (rank + 1) as f32);
+                entry.1.push(result.distance);
+            }
+        }
+
+        let mut results: Vec<SearchResult> = fused
+            .into_iter()
+            .map(|(id, (score, query_distances, data))| SearchResult {
+                id,
+                distance: query_distances[0],
+                score,
+                query_distances,
+                data,
+            })
+            .collect();
+
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+        results.truncate(n);
+        Ok(results)
+    }
+
+    /// Walks the graph from the first valid slot and returns every
+    /// candidate the base layer settled on, sorted by distance but not yet
+    /// truncated to a caller-requested `n`. Shared by [`Self::search`],
+    /// [`Self::search_with_ef`], and [`Self::search_with_params`], which
+    /// only differ in what `ef`/`distance_budget` they use for the
+    /// base-layer traversal and how they validate `ef`.
+    fn search_base(
+        &self,
+        vector: &Vector,
+        ef: usize,
+        distance_budget: Option<usize>,
     ) -> Result<Vec<SearchResult>, Error> {
         let mut search = Search::new(0, self.config.distance);
+        search.distance_budget = distance_budget;
 
         // Early return if the collection is empty.
         if self.vectors.is_empty() {
@@ -318,37 +652,85 @@ impl Collection {
         search.visited.resize_capacity(self.vectors.len());
         search.push(vector_id, vector, &self.vectors);
 
-        for layer in LayerID(self.upper_layers.len()).descend() {
-            search.ef = if layer.is_zero() { self.config.ef_search } else { 5 };
+        let start = Instant::now();
+        let mut base_layer_elapsed = Duration::ZERO;
+        let mut upper_layers_elapsed = Duration::ZERO;
 
-            if layer.0 == 0 {
-                let layer = self.base_layer.as_slice();
-                search.search(layer, vector, &self.vectors, M * 2);
-            } else {
-                let layer = self.upper_layers[layer.0 - 1].as_slice();
-                search.search(layer, vector, &self.vectors, M);
-            }
+        for layer in LayerID(self.upper_meta.len()).descend() {
+            search.ef = if layer.is_zero() { ef } else { 5 };
 
-            if !layer.is_zero() {
+            let layer_start = Instant::now();
+            if layer.is_zero() {
+                let base = self.base_layer.as_slice();
+                search.search(base, vector, &self.vectors, M * 2);
+                base_layer_elapsed += layer_start.elapsed();
+            } else {
+                let view = self.upper_layer_view(layer);
+                search.search(view, vector, &self.vectors, M);
                 search.cull();
+                upper_layers_elapsed += layer_start.elapsed();
             }
         }
 
+        record_query(start.elapsed(), base_layer_elapsed, upper_layers_elapsed);
+
         let map_result = |candidate: Candidate| {
             let id = candidate.vector_id.0;
             let distance = candidate.distance.0;
             let data = self.data[&candidate.vector_id].clone();
-            SearchResult { id, distance, data }
+            let metric = self.config.distance;
+            SearchResult::from_distance(id, distance, data, metric)
         };
 
         // Sort the search results by distance.
         let res = search.iter().map(map_result).collect();
         let sorted = self.sort_by_distance(res);
+        Ok(sorted)
+    }
 
-        // Truncate the list based on the relevancy score.
-        let mut relevant = self.truncate_irrelevant_result(sorted);
-        relevant.truncate(n);
-        Ok(relevant)
+    /// Searches the collection for the nearest neighbors matching a
+    /// key/value equality filter over the metadata, or a predicate
+    /// callable: `collection.search_filtered(vector, n, {"genre": "jazz"})`
+    /// or `collection.search_filtered(vector, n, lambda data: ...)`.
+    #[cfg(feature = "py")]
+    #[pyo3(name = "search_filtered")]
+    fn py_search_filtered(
+        &self,
+        vector: Vec<f32>,
+        n: usize,
+        filter: &PyAny,
+    ) -> Result<Vec<SearchResult>, Error> {
+        let vector = Vector::from(vector);
+
+        if filter.is_callable() {
+            let predicate = |metadata: &Metadata| {
+                Python::with_gil(|py| {
+                    let argument = metadata.clone().into_py(py);
+                    filter
+                        .call1((argument,))
+                        .and_then(|result| result.extract::<bool>())
+                        .unwrap_or(false)
+                })
+            };
+
+            return self.search_filtered(&vector, n, predicate);
+        }
+
+        // Otherwise, treat the filter as a dict of key/value pairs that
+        // must all equal-match fields of an object-shaped metadata.
+        let equals: HashMap<String, Metadata> = filter
+            .extract::<HashMap<String, &PyAny>>()
+            .map(|dict| dict.into_iter().map(|(k, v)| (k, v.into())).collect())
+            .unwrap_or_default();
+
+        let predicate = move |metadata: &Metadata| match metadata {
+            Metadata::Object(fields) => {
+                equals.iter().all(|(key, value)| fields.get(key) == Some(value))
+            }
+            _ => false,
+        };
+
+        self.search_filtered(&vector, n, predicate)
     }
 
     /// Searches the collection for the true nearest neighbors.
@@ -359,22 +741,20 @@ impl Collection {
         vector: &Vector,
         n: usize,
     ) -> Result<Vec<SearchResult>, Error> {
-        let mut nearest = Vec::with_capacity(self.vectors.len());
-
         // Ensure the vector dimension matches the collection dimension.
         self.validate_dimension(vector)?;
 
-        // Calculate the distance between the query and each record.
-        // Then, create a search result for each record.
-        for (id, vec) in self.vectors.iter() {
+        // Calculate the distance between the query and every record, kept
+        // to the best `n` by `bounded_top_k` as they're produced rather
+        // than collecting and sorting every record in the collection.
+        let candidates = self.vectors.iter().map(|(id, vec)| {
             let distance = self.config.distance.calculate(vector, vec);
             let data = self.data[id].clone();
-            let res = SearchResult { id: id.0, distance, data };
-            nearest.push(res);
-        }
+            let metric = self.config.distance;
+            SearchResult::from_distance(id.0, distance, data, metric)
+        });
 
-        // Sort the results by distance depending on the metric.
-        let sorted = self.sort_by_distance(nearest);
+        let sorted = self.bounded_top_k(candidates, n);
 
         // Remove irrelevant results and truncate the list.
         let mut res = self.truncate_irrelevant_result(sorted);
@@ -382,6 +762,34 @@ impl Collection {
         Ok(res)
     }
 
+    /// Searches for records similar to a record already in the
+    /// collection, instead of a fresh query vector. Mirrors a
+    /// "more like this" flow, e.g. recommending items similar to a
+    /// product page, without re-embedding or re-uploading its vector.
+    /// * `id`: Vector ID of the record to use as the query.
+    /// * `n`: Number of neighbors to return.
+    pub fn recommend(
+        &self,
+        id: u32,
+        n: usize,
+    ) -> Result<Vec<SearchResult>, Error> {
+        let vector_id = VectorID::from(id);
+        if !self.contains(&vector_id) {
+            return Err(Error::record_not_found());
+        }
+
+        let vector = self.vectors[&vector_id].clone();
+
+        // Search for one extra neighbor since the record itself is its
+        // own nearest neighbor and gets filtered out below.
+        let results = self.search(&vector, n + 1)?;
+        let mut recommended: Vec<SearchResult> =
+            results.into_iter().filter(|r| r.id != id).collect();
+        recommended.truncate(n);
+
+        Ok(recommended)
+    }
+
     #[cfg(feature = "py")]
     #[getter(config)]
     fn py_config(&self) -> Config {
@@ -441,11 +849,14 @@ impl Collection {
             dimension: 0,
             relevancy: -1.0,
             config: config.clone(),
-            data: HashMap::new(),
+            data: IndexMap::new(),
             vectors: HashMap::new(),
             slots: vec![],
             base_layer: vec![],
-            upper_layers: vec![],
+            upper_neighbors: vec![],
+            upper_meta: vec![],
+            hash_groups: HashMap::new(),
+            id_hash: HashMap::new(),
         }
     }
 
@@ -510,10 +921,19 @@ impl Collection {
         // construction progresses, while preserving randomness in
         // each point's layer and insertion order.
 
-        let vectors = records
+        // Resolve the base seed up front so both the layer-assignment
+        // shuffle below and the per-node tie-breaks inside
+        // `IndexConstruction::insert` derive from the same value. Falling
+        // back to entropy here, rather than inside `IndexConstruction`,
+        // keeps a `None` seed just as random as before this field existed.
+        let seed = config.seed.unwrap_or_else(random);
+        let mut order: Vec<usize> = (0..records.len()).collect();
+        order.shuffle(&mut SmallRng::seed_from_u64(seed));
+
+        let vectors = order
             .par_iter()
             .enumerate()
-            .map(|(i, item)| (i.into(), item.vector.clone()))
+            .map(|(i, &original)| (i.into(), records[original].vector.clone()))
             .collect::<HashMap<VectorID, Vector>>();
 
         // Figure out how many nodes will go on each layer.
@@ -532,7 +952,12 @@ impl Collection {
         // Create index constructor.
 
         let search_pool = SearchPool::new(vectors.len(), config.distance);
-        let mut upper_layers = vec![vec![]; top_layer.0];
+        // Scratch upper-layer storage for construction only: a flat
+        // buffer plus per-layer `Meta`, appended to as each layer
+        // finishes so later (lower, larger) layers can read the layers
+        // above them via a computed range instead of a nested `Vec`.
+        let mut upper_neighbors: Vec<VectorID> = Vec::new();
+        let mut upper_meta = vec![Meta::default(); top_layer.0];
         let base_layer = vectors
             .par_iter()
             .map(|_| RwLock::new(BaseNode::default()))
@@ -544,6 +969,7 @@ impl Collection {
             top_layer,
             vectors: &vectors,
             config,
+            seed,
         };
 
         // Initialize data for layers.
@@ -552,41 +978,50 @@ impl Collection {
             let end = range.end;
 
             range.into_par_iter().for_each(|i: usize| {
-                state.insert(&i.into(), &layer, &upper_layers)
+                state.insert(&i.into(), &layer, &upper_neighbors, &upper_meta)
             });
 
             // Copy the base layer state to the upper layer.
             if !layer.is_zero() {
+                let mut nodes = Vec::new();
                 (&state.base_layer[..end])
                     .into_par_iter()
                     .map(|zero| UpperNode::from_zero(&zero.read()))
-                    .collect_into_vec(&mut upper_layers[layer.0 - 1]);
+                    .collect_into_vec(&mut nodes);
+
+                let offset = upper_neighbors.len();
+                upper_neighbors.extend(nodes.iter().flat_map(|node| node.0));
+                upper_meta[layer.0 - 1] = Meta::new(offset, end, M);
             }
         }
 
-        let data = records
+        let data = order
             .iter()
             .enumerate()
-            .map(|(i, item)| (i.into(), item.data.clone()))
+            .map(|(i, &original)| (i.into(), records[original].data.clone()))
             .collect();
 
-        // Unwrap the base nodes for the base layer.
-        let base_iter = base_layer.into_par_iter();
-        let base_layer = base_iter.map(|node| node.into_inner()).collect();
-
         // Add IDs to the slots.
         let slots = (0..vectors.len()).map(|i| i.into()).collect();
 
+        // `build` constructs every layer in one parallel sweep over the
+        // whole record set rather than the online path's one-ID-at-a-time
+        // `insert_to_layers`, so it doesn't dedup content hashes; hash
+        // bookkeeping starts empty and only tracks IDs inserted online
+        // afterward.
         Ok(Self {
             data,
             vectors,
             base_layer,
-            upper_layers,
+            upper_neighbors,
+            upper_meta,
             slots,
             dimension,
             config: config.clone(),
             count: records.len(),
             relevancy: -1.0,
+            hash_groups: HashMap::new(),
+            id_hash: HashMap::new(),
         })
     }
 
@@ -677,76 +1112,237 @@ impl Collection {
         }
     }
 
+    /// Removes `id` from its content-hash dedup group, if it's in one.
+    /// The canonical node's row is only ever left unreferenced once every
+    /// alias sharing its content, including the canonical itself, has
+    /// been removed.
+    fn drop_hash_alias(&mut self, id: &VectorID) {
+        if let Some(hash) = self.id_hash.remove(id) {
+            if let Some(group) = self.hash_groups.get_mut(&hash) {
+                group.retain(|x| x != id);
+                if group.is_empty() {
+                    self.hash_groups.remove(&hash);
+                }
+            }
+        }
+    }
+
     /// Inserts vector IDs into the index layers.
+    ///
+    /// Before running the HNSW descent, each ID is checked against
+    /// `hash_groups` for an exact-content duplicate among IDs indexed by
+    /// an earlier call: if found, its base-layer row is a cheap `Copy` of
+    /// the canonical ID's row instead, skipping the redundant distance
+    /// calculations and neighbor selection a full insert would repeat for
+    /// identical content. Two IDs in the same `ids` batch that duplicate
+    /// each other both still get a real insert, since the first's row
+    /// isn't computed yet when the second is considered.
     fn insert_to_layers(&mut self, ids: &[VectorID]) {
-        // Add new nodes to the base layer.
-        for _ in 0..ids.len() {
-            self.base_layer.push(BaseNode::default());
+        // Grow the base layer with fresh locked nodes for the new IDs.
+        // This is the only O(K) step here; the nodes already in the layer
+        // are left untouched, so concurrent `insert_many` batches never
+        // pay for a rebuild of the whole layer.
+        self.base_layer.resize_with(
+            self.base_layer.len() + ids.len(),
+            || RwLock::new(BaseNode::default()),
+        );
+
+        let mut fresh = Vec::with_capacity(ids.len());
+        for &id in ids {
+            let hash = hash_vector(&self.vectors[&id]);
+            self.id_hash.insert(id, hash);
+
+            let duplicate_of = self.hash_groups.get(&hash).and_then(|group| {
+                let canonical = *group.first()?;
+                if fresh.contains(&canonical) {
+                    return None;
+                }
+
+                let same = self.vectors[&canonical] == self.vectors[&id];
+                same.then_some(canonical)
+            });
+
+            match duplicate_of {
+                Some(canonical) => {
+                    let row = *self.base_layer[canonical.0 as usize].read();
+                    *self.base_layer[id.0 as usize].write() = row;
+                    self.hash_groups.get_mut(&hash).unwrap().push(id);
+                }
+                None => {
+                    self.hash_groups.entry(hash).or_default().push(id);
+                    fresh.push(id);
+                }
+            }
         }
 
-        let base_layer = self
-            .base_layer
-            .par_iter()
-            .map(|node| RwLock::new(*node))
-            .collect::<Vec<_>>();
+        if fresh.is_empty() {
+            return;
+        }
 
-        let top_layer = match self.upper_layers.is_empty() {
-            true => LayerID(0),
-            false => LayerID(self.upper_layers.len()),
+        let top_layer = match self.upper_meta.len() {
+            0 => LayerID(0),
+            n => LayerID(n),
         };
 
         // Create a new index construction state.
         let state = IndexConstruction {
             top_layer,
-            base_layer: base_layer.as_slice(),
+            base_layer: self.base_layer.as_slice(),
             vectors: &self.vectors,
             config: &self.config,
+            seed: self.config.seed.unwrap_or_else(random),
             search_pool: SearchPool::new(
                 self.vectors.len(),
                 self.config.distance,
             ),
         };
 
-        // Insert all vectors into the state.
-        for id in ids {
-            state.insert(id, &top_layer, &self.upper_layers);
-        }
-
-        // Update base layer using the new state.
-        let iter = state.base_layer.into_par_iter();
-        self.base_layer = iter.map(|node| *node.read()).collect();
+        // Link each new point in parallel: `IndexConstruction::insert`
+        // only locks the specific base-layer nodes it touches, so disjoint
+        // batches proceed without contending on the whole layer.
+        let upper_neighbors = &self.upper_neighbors;
+        let upper_meta = &self.upper_meta;
+        fresh.par_iter().for_each(|id| {
+            state.insert(id, &top_layer, upper_neighbors, upper_meta);
+        });
     }
 
     /// Removes vector IDs from all index layers.
     fn delete_from_layers(&mut self, ids: &[VectorID]) {
-        // Remove the vectors from the base layer.
+        // Base layer: clear `id`'s slot within its own row, under that
+        // node's individual lock.
         for id in ids {
-            let base_node = &mut self.base_layer[id.0 as usize];
-            let index = base_node.par_iter().position_first(|x| *x == *id);
+            let node = id.0 as usize;
+            if node >= self.base_layer.len() {
+                continue;
+            }
+
+            let mut row = self.base_layer[node].write();
+            let index = row.par_iter().position_first(|x| *x == *id);
             if let Some(index) = index {
-                base_node.set(index, &INVALID);
+                row.set(index, &INVALID);
             }
         }
 
-        // Remove the vector from the upper layers.
-        for layer in LayerID(self.upper_layers.len()).descend() {
-            let upper_layer = match layer.0 > 0 {
-                true => &mut self.upper_layers[layer.0 - 1],
-                false => break,
-            };
-
+        // Upper layers: same idea, but the flat buffer is plain data we
+        // have exclusive access to here, so no locking is needed.
+        for layer in self.upper_meta.iter() {
             for id in ids {
-                let node = &mut upper_layer[id.0 as usize];
-                let index = node.0.par_iter().position_first(|x| *x == *id);
+                let node = id.0 as usize;
+                if node >= layer.nodes {
+                    continue;
+                }
+
+                let row = &mut self.upper_neighbors[layer.range(node)];
+                let index = row.par_iter().position_first(|x| *x == *id);
                 if let Some(index) = index {
-                    node.set(index, &INVALID);
+                    row[index] = INVALID;
                 }
             }
         }
     }
 
+    /// Searches the collection for the nearest neighbors whose metadata
+    /// satisfies `predicate`.
+    ///
+    /// Unlike [`Self::search`], the filter is applied during the graph
+    /// traversal rather than after it: the base layer walk keeps expanding
+    /// through nodes whose metadata doesn't match so connectivity through
+    /// them is preserved, but only matching nodes are kept as results. The
+    /// walk keeps exploring until `ef_search` matches are found or the
+    /// frontier is exhausted, instead of over-fetching and filtering
+    /// client-side.
+    /// * `vector`: Vector to search.
+    /// * `n`: Number of neighbors to return.
+    /// * `predicate`: Returns true for metadata that should be kept.
+    pub fn search_filtered(
+        &self,
+        vector: &Vector,
+        n: usize,
+        predicate: impl Fn(&Metadata) -> bool,
+    ) -> Result<Vec<SearchResult>, Error> {
+        let mut search = Search::new(0, self.config.distance);
+
+        // Early return if the collection is empty.
+        if self.vectors.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // Ensure the vector dimension matches the collection dimension.
+        self.validate_dimension(vector)?;
+
+        // Find the first valid vector ID from the slots.
+        let slots_iter = self.slots.as_slice().into_par_iter();
+        let vector_id = match slots_iter.find_first(|id| id.is_valid()) {
+            Some(id) => id,
+            None => {
+                let kind = ErrorKind::CollectionError;
+                let message = "Unable to initiate search.";
+                return Err(Error::new(&kind, message));
+            }
+        };
+
+        search.visited.resize_capacity(self.vectors.len());
+        search.push(vector_id, vector, &self.vectors);
+
+        for layer in LayerID(self.upper_meta.len()).descend() {
+            if layer.is_zero() {
+                search.ef = self.config.ef_search;
+                search.search_filtered(
+                    self.base_layer.as_slice(),
+                    vector,
+                    &self.vectors,
+                    &self.data,
+                    M * 2,
+                    &predicate,
+                );
+            } else {
+                search.ef = 5;
+                let view = self.upper_layer_view(layer);
+                search.search(view, vector, &self.vectors, M);
+                search.cull();
+            }
+        }
+
+        let map_result = |candidate: Candidate| {
+            let id = candidate.vector_id.0;
+            let distance = candidate.distance.0;
+            let data = self.data[&candidate.vector_id].clone();
+            let metric = self.config.distance;
+            SearchResult::from_distance(id, distance, data, metric)
+        };
+
+        // Sort the search results by distance.
+        let res = search.iter().map(map_result).collect();
+        let sorted = self.sort_by_distance(res);
+
+        // Truncate the list based on the relevancy score.
+        let mut relevant = self.truncate_irrelevant_result(sorted);
+        relevant.truncate(n);
+        Ok(relevant)
+    }
+
+    /// Searches the collection for the nearest neighbors whose metadata
+    /// matches a [`Filters`] expression, e.g. `object.category = shoes AND
+    /// object.color != red`.
+    ///
+    /// This is a thin wrapper around [`Self::search_filtered`]: the filter
+    /// tree is evaluated during traversal, so filtered-out vectors never
+    /// consume one of the `n` result slots.
+    /// * `vector`: Vector to search.
+    /// * `n`: Number of neighbors to return.
+    /// * `filters`: Filter expression to match against metadata.
+    pub fn search_with_filters(
+        &self,
+        vector: &Vector,
+        n: usize,
+        filters: &Filters,
+    ) -> Result<Vec<SearchResult>, Error> {
+        self.search_filtered(vector, n, |data| filters.match_metadata(data))
+    }
+
     /// Truncates the search result based on the relevancy score.
-    fn truncate_irrelevant_result(
+    pub(crate) fn truncate_irrelevant_result(
         &self,
         result: Vec<SearchResult>,
     ) -> Vec<SearchResult> {
@@ -774,27 +1370,90 @@ impl Collection {
     }
 
     /// Sorts the search results by distance and distance metric.
-    fn sort_by_distance(&self, result: Vec<SearchResult>) -> Vec<SearchResult> {
+    pub(crate) fn sort_by_distance(
+        &self,
+        result: Vec<SearchResult>,
+    ) -> Vec<SearchResult> {
         let mut result = result;
 
-        // Sort the results by distance based on the metric.
-        // For Euclidean distance, sort in ascending order
-        // because the best distance is 0.0.
-        match self.config.distance {
-            Distance::Euclidean => {
-                result.sort_by(|a, b| {
-                    a.distance.partial_cmp(&b.distance).unwrap()
-                });
-            }
-            _ => {
-                result.sort_by(|a, b| {
-                    b.distance.partial_cmp(&a.distance).unwrap()
-                });
+        // Sort the results by distance, consulting the metric's own
+        // ordering flag rather than special-casing a specific variant.
+        let ascending = self.config.distance.ascending();
+        result.sort_by(|a, b| {
+            if ascending {
+                a.distance.partial_cmp(&b.distance).unwrap()
+            } else {
+                b.distance.partial_cmp(&a.distance).unwrap()
             }
-        };
+        });
 
         result
     }
+
+    /// Folds `candidates` into the best `k` by maintaining a bounded
+    /// max-heap instead of collecting every candidate and sorting the
+    /// whole set, so memory and comparisons scale with `k` rather than
+    /// the number of candidates scanned. Applies [`Self::sort_by_distance`]
+    /// to the kept `k` for the final output ordering.
+    fn bounded_top_k(
+        &self,
+        candidates: impl Iterator<Item = SearchResult>,
+        k: usize,
+    ) -> Vec<SearchResult> {
+        // The heap's natural (max-heap) order must put the worst kept
+        // result at the root so it's the one evicted when a better
+        // candidate arrives. For ascending metrics (smaller is better)
+        // that's already the largest distance; descending metrics negate
+        // the distance first so the same max-heap logic still applies.
+        let ascending = self.config.distance.ascending();
+        let mut heap: BinaryHeap<HeapEntry> = BinaryHeap::with_capacity(k);
+
+        for result in candidates {
+            let distance = result.distance;
+            let signed = if ascending { distance } else { -distance };
+            let key = OrderedFloat(signed);
+
+            if heap.len() < k {
+                heap.push(HeapEntry { key, result });
+            } else if let Some(worst) = heap.peek() {
+                if key < worst.key {
+                    heap.pop();
+                    heap.push(HeapEntry { key, result });
+                }
+            }
+        }
+
+        let kept = heap.into_iter().map(|entry| entry.result).collect();
+        self.sort_by_distance(kept)
+    }
+}
+
+/// Heap entry for [`Collection::bounded_top_k`], ordered purely by `key` —
+/// a distance reoriented so a max-heap's root is always the worst kept
+/// result regardless of the active metric's direction.
+struct HeapEntry {
+    key: OrderedFloat<f32>,
+    result: SearchResult,
+}
+
+impl PartialEq for HeapEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for HeapEntry {}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
 }
 
 /// A record containing a vector and its associated data.
@@ -878,13 +1537,92 @@ pub struct SearchResult {
     pub id: u32,
     /// Distance between the query to the collection vector.
     pub distance: f32,
+    /// Normalized 0..1 similarity score derived from `distance`, where 1
+    /// means a perfect match. For [`Collection::search_hybrid`] results,
+    /// this instead holds the reciprocal-rank-fusion score used to rank
+    /// candidates across queries, which isn't bounded to 0..1.
+    pub score: f32,
+    /// Raw distance to each query vector that produced this result.
+    /// Has one entry for a single-query search, and one entry per query
+    /// that matched this candidate for [`Collection::search_hybrid`] — so
+    /// users can see why a fused result ranked where it did.
+    pub query_distances: Vec<f32>,
     /// Data associated with the vector.
     pub data: Metadata,
 }
 
+impl SearchResult {
+    /// Builds a search result from a single-query distance, deriving its
+    /// normalized `score` from `metric`.
+    fn from_distance(
+        id: u32,
+        distance: f32,
+        data: Metadata,
+        metric: Distance,
+    ) -> Self {
+        let score = metric.score(distance);
+        Self { id, distance, score, query_distances: vec![distance], data }
+    }
+}
+
 #[cfg(feature = "py")]
 impl SearchResult {
     fn __repr__(&self) -> String {
         format!("{:?}", self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_hybrid_fuses_agreeing_queries_first() {
+        let config = Config::default();
+        let records = Record::many_random(8, 50);
+        let collection = Collection::build(&config, &records).unwrap();
+
+        // Both queries are the same vector, so every candidate's rank
+        // should agree across queries and the fused order should match a
+        // single-query search.
+        let query = records[0].vector.clone();
+        let single = collection.search(&query, 5).unwrap();
+        let hybrid = collection
+            .search_hybrid(vec![query.clone(), query], vec![1.0, 1.0], 5)
+            .unwrap();
+
+        assert_eq!(hybrid.len(), 5);
+        let single_ids: Vec<u32> = single.iter().map(|r| r.id).collect();
+        let hybrid_ids: Vec<u32> = hybrid.iter().map(|r| r.id).collect();
+        assert_eq!(single_ids, hybrid_ids);
+    }
+
+    #[test]
+    fn test_search_hybrid_sorts_by_descending_fused_score() {
+        let config = Config::default();
+        let records = Record::many_random(8, 50);
+        let collection = Collection::build(&config, &records).unwrap();
+
+        let query_a = records[0].vector.clone();
+        let query_b = records[1].vector.clone();
+        let results = collection
+            .search_hybrid(vec![query_a, query_b], vec![0.7, 0.3], 10)
+            .unwrap();
+
+        let scores: Vec<f32> = results.iter().map(|r| r.score).collect();
+        let mut sorted = scores.clone();
+        sorted.sort_by(|a, b| b.partial_cmp(a).unwrap());
+        assert_eq!(scores, sorted);
+    }
+
+    #[test]
+    fn test_search_hybrid_rejects_mismatched_weights() {
+        let config = Config::default();
+        let records = Record::many_random(8, 10);
+        let collection = Collection::build(&config, &records).unwrap();
+
+        let query = records[0].vector.clone();
+        let result = collection.search_hybrid(vec![query], vec![1.0, 1.0], 5);
+        assert!(result.is_err());
+    }
+}
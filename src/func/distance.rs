@@ -1,46 +1,169 @@
 use super::*;
 
+/// A metric space for comparing two vectors: how the distance between them
+/// is computed, and whether a smaller distance means a closer match.
+///
+/// Every metric below happens to sort ascending (smaller is more similar,
+/// `DotProduct` and `Cosine` are defined so that holds too), but the flag
+/// is still a method on the trait rather than assumed by callers like
+/// [`super::collection::Collection::sort_by_distance`], so a future metric
+/// that sorts the other way doesn't require touching the sorting logic.
+pub trait Metric {
+    /// Computes the distance between two vectors.
+    fn distance(&self, a: &Vector, b: &Vector) -> f32;
+
+    /// Returns true if results using this metric should be sorted in
+    /// ascending order of distance, i.e. smaller means more similar.
+    fn ascending(&self) -> bool {
+        true
+    }
+
+    /// Normalizes a raw distance into a 0..1 similarity score, where 1 is a
+    /// perfect match. Distances are generally unbounded, so this rescales
+    /// with `1 / (1 + distance)` rather than a fixed-range formula; the
+    /// distance is clamped to 0 first so metrics that go negative for very
+    /// close matches (like `DotProductMetric`) still saturate at 1 instead
+    /// of producing a score outside 0..1.
+    fn normalize(&self, distance: f32) -> f32 {
+        1.0 / (1.0 + distance.max(0.0))
+    }
+}
+
+/// Euclidean distance: straight-line distance between two points.
+struct EuclideanMetric;
+
+impl Metric for EuclideanMetric {
+    fn distance(&self, a: &Vector, b: &Vector) -> f32 {
+        let sq = f32::sqeuclidean(&a.0, &b.0).unwrap() as f32;
+        sq.sqrt()
+    }
+}
+
+/// Squared Euclidean distance: skips the square root, useful when only the
+/// relative ordering matters and the extra precision isn't worth the cost.
+struct SquaredEuclideanMetric;
+
+impl Metric for SquaredEuclideanMetric {
+    fn distance(&self, a: &Vector, b: &Vector) -> f32 {
+        f32::sqeuclidean(&a.0, &b.0).unwrap() as f32
+    }
+}
+
+/// Manhattan (taxicab) distance: sum of absolute component differences.
+struct ManhattanMetric;
+
+impl Metric for ManhattanMetric {
+    fn distance(&self, a: &Vector, b: &Vector) -> f32 {
+        a.0.iter().zip(&b.0).map(|(x, y)| (x - y).abs()).sum()
+    }
+}
+
+/// Chebyshev distance: largest absolute component difference.
+struct ChebyshevMetric;
+
+impl Metric for ChebyshevMetric {
+    fn distance(&self, a: &Vector, b: &Vector) -> f32 {
+        a.0.iter()
+            .zip(&b.0)
+            .map(|(x, y)| (x - y).abs())
+            .fold(0.0, f32::max)
+    }
+}
+
+/// Cosine distance (1 - cosine similarity).
+struct CosineMetric;
+
+impl Metric for CosineMetric {
+    fn distance(&self, a: &Vector, b: &Vector) -> f32 {
+        f32::cosine(&a.0, &b.0).unwrap() as f32
+    }
+}
+
+/// Negated dot product (inner product) similarity. Unlike Cosine, this
+/// doesn't normalize the vectors first, so it preserves the magnitude
+/// information that dot-product-tuned embedding models rely on for
+/// ranking. The result is negated so that, like the other metrics,
+/// smaller values mean "more relevant".
+struct DotProductMetric;
+
+impl Metric for DotProductMetric {
+    fn distance(&self, a: &Vector, b: &Vector) -> f32 {
+        -(f32::dot(&a.0, &b.0).unwrap() as f32)
+    }
+}
+
 /// The distance function used for similarity calculations.
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 #[derive(PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Distance {
     /// Euclidean distance function.
     Euclidean,
+    /// Squared Euclidean distance function. Skips the square root Euclidean
+    /// takes, so it's cheaper when only relative ordering matters.
+    SquaredEuclidean,
+    /// Manhattan (taxicab) distance function.
+    Manhattan,
+    /// Chebyshev distance function.
+    Chebyshev,
     /// Cosine distance function (1 - Cosine similarity).
     Cosine,
+    /// Negated dot product (inner product) function. Unlike Cosine, this
+    /// doesn't normalize the vectors first, so it preserves the magnitude
+    /// information that dot-product-tuned embedding models rely on for
+    /// ranking. The result is negated so that, like the other variants,
+    /// smaller values mean "more relevant".
+    DotProduct,
 }
 
 impl Distance {
     /// Creates a new distance function from a string.
     /// Available options:
     /// * `euclidean`: Euclidean distance function.
+    /// * `squared_euclidean`: Squared Euclidean distance function.
+    /// * `manhattan`: Manhattan distance function.
+    /// * `chebyshev`: Chebyshev distance function.
     /// * `cosine`: Cosine similarity function.
+    /// * `dot`/`inner_product`: Negated dot product function.
     pub fn from(distance: &str) -> Result<Self, Error> {
         match distance {
             "euclidean" => Ok(Distance::Euclidean),
+            "squared_euclidean" => Ok(Distance::SquaredEuclidean),
+            "manhattan" => Ok(Distance::Manhattan),
+            "chebyshev" => Ok(Distance::Chebyshev),
             "cosine" => Ok(Distance::Cosine),
+            "dot" | "inner_product" => Ok(Distance::DotProduct),
             _ => Err(Error::invalid_distance()),
         }
     }
 
-    /// Calculates the distance between two vectors.
-    pub fn calculate(&self, a: &Vector, b: &Vector) -> f32 {
-        assert_eq!(a.0.len(), b.0.len());
+    /// Returns the [`Metric`] implementation backing this variant.
+    fn metric(&self) -> &'static dyn Metric {
         match self {
-            Distance::Euclidean => Distance::euclidean(a, b),
-            Distance::Cosine => Distance::cosine(a, b),
+            Distance::Euclidean => &EuclideanMetric,
+            Distance::SquaredEuclidean => &SquaredEuclideanMetric,
+            Distance::Manhattan => &ManhattanMetric,
+            Distance::Chebyshev => &ChebyshevMetric,
+            Distance::Cosine => &CosineMetric,
+            Distance::DotProduct => &DotProductMetric,
         }
     }
 
-    // List additional distance functions below.
+    /// Calculates the distance between two vectors.
+    pub fn calculate(&self, a: &Vector, b: &Vector) -> f32 {
+        assert_eq!(a.0.len(), b.0.len());
+        self.metric().distance(a, b)
+    }
 
-    fn cosine(a: &Vector, b: &Vector) -> f32 {
-        f32::cosine(&a.0, &b.0).unwrap() as f32
+    /// Returns true if results using this metric should be sorted in
+    /// ascending order of distance, i.e. smaller means more similar.
+    pub fn ascending(&self) -> bool {
+        self.metric().ascending()
     }
 
-    fn euclidean(a: &Vector, b: &Vector) -> f32 {
-        let sq = f32::sqeuclidean(&a.0, &b.0).unwrap() as f32;
-        sq.sqrt()
+    /// Normalizes a raw distance into a 0..1 similarity score, where 1
+    /// means a perfect match.
+    pub fn score(&self, distance: f32) -> f32 {
+        self.metric().normalize(distance)
     }
 }
 
@@ -57,7 +180,11 @@ impl IntoPy<Py<PyAny>> for Distance {
     fn into_py(self, py: Python) -> Py<PyAny> {
         match self {
             Distance::Euclidean => "euclidean".into_py(py),
+            Distance::SquaredEuclidean => "squared_euclidean".into_py(py),
+            Distance::Manhattan => "manhattan".into_py(py),
+            Distance::Chebyshev => "chebyshev".into_py(py),
             Distance::Cosine => "cosine".into_py(py),
+            Distance::DotProduct => "dot".into_py(py),
         }
     }
 }
@@ -1,6 +1,7 @@
 // Other error types.
 use bincode::ErrorKind as BincodeError;
 use sled::Error as SledError;
+use sqlx::Error as SqlxError;
 use std::error::Error as StandardError;
 use std::fmt::{Display, Formatter, Result};
 use std::io::Error as IOError;
@@ -21,6 +22,14 @@ pub enum ErrorKind {
     CollectionError,
     DistanceError,
     SerializationError,
+    FilterError,
+    /// A database integrity constraint, e.g. a unique or foreign key, was
+    /// violated (Postgres SQLSTATE class 23).
+    ConstraintError,
+    /// A database failure that's likely to succeed on retry, such as a
+    /// serialization failure, deadlock, or connection drop (Postgres
+    /// SQLSTATE classes 08 and 40).
+    TransientError,
 }
 
 /// A custom error object with error type and message.
@@ -30,12 +39,15 @@ pub struct Error {
     pub kind: ErrorKind,
     /// Why the error occurred.
     pub message: String,
+    /// Raw 5-character Postgres SQLSTATE code, set when this error was
+    /// converted from a [`sqlx::Error`] carrying one.
+    pub code: Option<String>,
 }
 
 impl Error {
     /// Create a new error with the given message.
     pub fn new(kind: &ErrorKind, message: &str) -> Self {
-        Self { kind: *kind, message: message.to_string() }
+        Self { kind: *kind, message: message.to_string(), code: None }
     }
 
     /// Returns the error message.
@@ -43,6 +55,18 @@ impl Error {
         &self.message
     }
 
+    /// Returns the raw SQLSTATE code, if this error originated from a
+    /// database query that returned one.
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    /// Returns true if the operation that produced this error is likely to
+    /// succeed if retried, e.g. a serialization failure or deadlock.
+    pub fn is_retryable(&self) -> bool {
+        self.kind == ErrorKind::TransientError
+    }
+
     // Common errors.
 
     /// Creates error: The collection is not found.
@@ -86,6 +110,19 @@ impl Error {
         let kind = ErrorKind::DistanceError;
         Error::new(&kind, message)
     }
+
+    /// Creates error when a filter key, value, or operator is invalid.
+    pub fn invalid_filter(message: &str) -> Self {
+        let kind = ErrorKind::FilterError;
+        Error::new(&kind, message)
+    }
+
+    /// Creates error when a per-query search parameter, like `n` or `ef`,
+    /// violates its invariant, e.g. `ef` smaller than `n`.
+    pub fn invalid_search_params(message: &str) -> Self {
+        let kind = ErrorKind::CollectionError;
+        Error::new(&kind, message)
+    }
 }
 
 impl Display for Error {
@@ -114,6 +151,26 @@ impl From<SledError> for Error {
     }
 }
 
+impl From<SqlxError> for Error {
+    fn from(err: SqlxError) -> Self {
+        // Classify by the Postgres SQLSTATE class (the code's first two
+        // digits) when the database reported one, so callers can tell a
+        // constraint violation from a transient failure worth retrying.
+        let code = err
+            .as_database_error()
+            .and_then(|db_err| db_err.code())
+            .map(|code| code.into_owned());
+
+        let kind = match code.as_deref().map(|code| &code[..2]) {
+            Some("23") => ErrorKind::ConstraintError,
+            Some("08" | "40") => ErrorKind::TransientError,
+            _ => ErrorKind::DatabaseError,
+        };
+
+        Self { kind, message: err.to_string(), code }
+    }
+}
+
 impl From<IOError> for Error {
     fn from(err: IOError) -> Self {
         let kind = ErrorKind::IOError;
@@ -1,15 +1,25 @@
 use super::*;
 
-const AND: &str = " AND ";
-const OR: &str = " OR ";
+const AND: &str = "AND";
+const OR: &str = "OR";
+const NOT: &str = "NOT";
 
 /// The filters to apply to the collection metadata.
+///
+/// A small boolean-expression tree, so filters can nest arbitrarily and mix
+/// `AND`/`OR`/`NOT` freely, e.g. `object.category = books AND NOT
+/// (object.format = ebook OR object.status = draft)`. `NOT` binds tighter
+/// than `AND`, which in turn binds tighter than `OR`.
 #[derive(Debug, PartialEq)]
 pub enum Filters {
-    /// Results must match all filters.
-    AND(Vec<Filter>),
-    /// Results must match at least one filter.
-    OR(Vec<Filter>),
+    /// Results must match all nested filters.
+    AND(Vec<Filters>),
+    /// Results must match at least one nested filter.
+    OR(Vec<Filters>),
+    /// Results must not match the nested filter.
+    NOT(Box<Filters>),
+    /// A single filter predicate.
+    Leaf(Filter),
 }
 
 impl Filters {
@@ -22,35 +32,35 @@ impl Filters {
             Filters::OR(filters) => {
                 filters.iter().any(|f| f.match_metadata(metadata))
             }
+            Filters::NOT(filter) => !filter.match_metadata(metadata),
+            Filters::Leaf(filter) => filter.match_metadata(metadata),
         }
     }
 }
 
-impl From<&str> for Filters {
-    fn from(filters: &str) -> Self {
-        if filters.is_empty() {
-            return Filters::AND(vec![]);
+impl TryFrom<&str> for Filters {
+    type Error = Error;
+    fn try_from(filters: &str) -> Result<Self, Self::Error> {
+        if filters.trim().is_empty() {
+            return Ok(Filters::AND(vec![]));
         }
 
-        // Check which join operator is used.
-        let or_count = filters.matches(OR).count();
-        let and_count = filters.matches(AND).count();
-
-        let join = if or_count > 0 && and_count > 0 {
-            panic!("Mixing AND and OR join operators is not supported.");
-        } else if or_count > 0 {
-            OR
-        } else {
-            // If no join operator is found, use AND since it doesn't matter.
-            AND
-        };
+        let tokens = tokenize(filters);
+        let mut parser = FiltersParser::new(&tokens);
+        let tree = parser.parse_or()?;
 
-        // Split the filters.
-        let filters = filters.split(join).map(Into::into).collect();
-        match join {
-            OR => Filters::OR(filters),
-            _ => Filters::AND(filters),
+        if parser.peek().is_some() {
+            let message = "Unexpected trailing tokens in filter expression.";
+            return Err(Error::invalid_filter(message));
         }
+
+        Ok(tree)
+    }
+}
+
+impl From<&str> for Filters {
+    fn from(filters: &str) -> Self {
+        Filters::try_from(filters).unwrap_or_else(|err| panic!("{err}"))
     }
 }
 
@@ -60,6 +70,113 @@ impl From<String> for Filters {
     }
 }
 
+// Splits a filter expression into whitespace-delimited tokens, treating
+// parentheses as their own tokens so grouped sub-expressions can be told
+// apart from the key/operator/value tokens of a leaf filter.
+fn tokenize(value: &str) -> Vec<String> {
+    value
+        .replace('(', " ( ")
+        .replace(')', " ) ")
+        .split_whitespace()
+        .map(String::from)
+        .collect()
+}
+
+// Recursive-descent parser respecting `NOT` > `AND` > `OR` precedence
+// (`OR` binds loosest) and parenthesized groups. Leaf tokens are rejoined
+// back into a single string and handed to the existing `Filter::from(&str)`
+// parser.
+struct FiltersParser<'a> {
+    tokens: &'a [String],
+    position: usize,
+}
+
+impl<'a> FiltersParser<'a> {
+    fn new(tokens: &'a [String]) -> Self {
+        Self { tokens, position: 0 }
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.position).map(String::as_str)
+    }
+
+    fn advance(&mut self) -> Option<&str> {
+        let token = self.peek();
+        self.position += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Filters, Error> {
+        let mut nodes = vec![self.parse_and()?];
+        while self.peek() == Some(OR) {
+            self.advance();
+            nodes.push(self.parse_and()?);
+        }
+
+        Ok(match nodes.len() {
+            1 => nodes.pop().unwrap(),
+            _ => Filters::OR(nodes),
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Filters, Error> {
+        let mut nodes = vec![self.parse_not()?];
+        while self.peek() == Some(AND) {
+            self.advance();
+            nodes.push(self.parse_not()?);
+        }
+
+        Ok(match nodes.len() {
+            1 => nodes.pop().unwrap(),
+            _ => Filters::AND(nodes),
+        })
+    }
+
+    fn parse_not(&mut self) -> Result<Filters, Error> {
+        if self.peek() == Some(NOT) {
+            self.advance();
+            let inner = self.parse_not()?;
+            return Ok(Filters::NOT(Box::new(inner)));
+        }
+
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Filters, Error> {
+        if self.peek() == Some("(") {
+            self.advance();
+            let inner = self.parse_or()?;
+            return match self.advance() {
+                Some(")") => Ok(inner),
+                _ => {
+                    let message = "Unbalanced parentheses in filter expression.";
+                    Err(Error::invalid_filter(message))
+                }
+            };
+        }
+
+        // Collect every token up to the next AND/OR/NOT/")" at this level
+        // and rejoin them into the leaf's "key operator value" string.
+        let mut leaf_tokens = Vec::new();
+        while let Some(token) = self.peek() {
+            if token == AND || token == OR || token == NOT || token == ")" {
+                break;
+            }
+
+            leaf_tokens.push(token.to_string());
+            self.advance();
+        }
+
+        if leaf_tokens.is_empty() {
+            let message = "Expected a filter inside an empty group.";
+            return Err(Error::invalid_filter(message));
+        }
+
+        let leaf = Filter::try_from(leaf_tokens.join(" ").as_str())?;
+        Ok(Filters::Leaf(leaf))
+    }
+}
+
 /// The basic filter operator to use to compare with metadata.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, PartialEq)]
@@ -99,12 +216,25 @@ impl Filter {
     /// * `value`: Value to use for filtering.
     /// * `operator`: Filter operator.
     pub fn new(key: &str, value: &Metadata, operator: &FilterOperator) -> Self {
-        Self::validate_filter(key, value, operator);
-        Self {
+        Self::try_new(key, value, operator).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Creates a new filter instance, returning an error instead of
+    /// panicking when the key, value, or operator combination is invalid.
+    /// * `key`: Key to filter.
+    /// * `value`: Value to use for filtering.
+    /// * `operator`: Filter operator.
+    pub fn try_new(
+        key: &str,
+        value: &Metadata,
+        operator: &FilterOperator,
+    ) -> Result<Self, Error> {
+        Self::validate_filter(key, value, operator)?;
+        Ok(Self {
             key: key.to_string(),
             value: value.clone(),
             operator: operator.clone(),
-        }
+        })
     }
 
     /// Matches the collection metadata against the filter.
@@ -266,10 +396,15 @@ impl Filter {
     /// * `key`: Filter key.
     /// * `value`: Filter metadata value.
     /// * `operator`: Filter operator.
-    fn validate_filter(key: &str, value: &Metadata, operator: &FilterOperator) {
+    fn validate_filter(
+        key: &str,
+        value: &Metadata,
+        operator: &FilterOperator,
+    ) -> Result<(), Error> {
         // Check if the key is valid.
         if key.is_empty() {
-            panic!("Filter key cannot be empty.");
+            let message = "Filter key cannot be empty.";
+            return Err(Error::invalid_filter(message));
         }
 
         let key_parts: Vec<&str> = key.split('.').collect();
@@ -278,94 +413,89 @@ impl Filter {
         // Check if the key is valid.
         let valid_types = vec![TEXT, INTEGER, FLOAT, BOOLEAN, ARRAY, OBJECT];
         if !valid_types.contains(&key_type) {
-            panic!("Invalid filter key type: {key_type}");
+            let message = format!("Invalid filter key type: {key_type}");
+            return Err(Error::invalid_filter(&message));
         }
 
         // Check if the key has a sub-key for object type.
         if key_type == OBJECT {
             if key_parts.len() != 2 {
-                panic!("Object key must have exactly one sub-key.");
+                let message = "Object key must have exactly one sub-key.";
+                return Err(Error::invalid_filter(message));
             }
 
             if key_parts[1].is_empty() {
-                panic!("Object sub-key must be a non-empty string.");
+                let message = "Object sub-key must be a non-empty string.";
+                return Err(Error::invalid_filter(message));
             }
         }
 
         // Validate key for array type.
         if key_type == ARRAY {
             if operator != &FilterOperator::Contains && key_parts.len() != 2 {
-                panic!("Array filter must provide an index.");
+                let message = "Array filter must provide an index.";
+                return Err(Error::invalid_filter(message));
             }
 
             if key_parts.len() == 2 && key_parts[1].parse::<usize>().is_err() {
-                panic!("Array filter index must be a valid integer.");
+                let message = "Array filter index must be a valid integer.";
+                return Err(Error::invalid_filter(message));
             }
         }
 
-        Self::validate_value(key_type, value);
-        Self::validate_operator(key_type, operator);
+        Self::validate_value(key_type, value)?;
+        Self::validate_operator(key_type, operator)?;
+        Ok(())
     }
 
     // Validates the filter value based on the key type.
-    fn validate_value(key_type: &str, value: &Metadata) {
+    fn validate_value(key_type: &str, value: &Metadata) -> Result<(), Error> {
         // Prevent array and object types for value.
         // Because, we should handle it like this: object.key = value
-        match value {
-            Metadata::Array(_) | Metadata::Object(_) => {
-                panic!("Unsupported array or object type as value.")
-            }
-            // We handle the primitive types validation below.
-            _ => {}
+        if matches!(value, Metadata::Array(_) | Metadata::Object(_)) {
+            let message = "Unsupported array or object type as value.";
+            return Err(Error::invalid_filter(message));
         }
 
         // Array and object keys are always valid because we will validate
         // the value type when performing the filter.
         let always_valid_key_types = vec![ARRAY, OBJECT];
         if always_valid_key_types.contains(&key_type) {
-            return;
+            return Ok(());
         }
 
-        // Error message for invalid filter value type.
-        let panic =
-            || panic!("Invalid filter value of {value:?} for key: {key_type}");
-
         // For key types other than array and object,
         // we need to validate the value type.
-        match value {
-            Metadata::Text(_) => {
-                if key_type != TEXT {
-                    panic();
-                }
-            }
-            Metadata::Integer(_) => {
-                if key_type != INTEGER {
-                    panic();
-                }
-            }
-            Metadata::Float(_) => {
-                if key_type != FLOAT {
-                    panic();
-                }
-            }
-            Metadata::Boolean(_) => {
-                if key_type != BOOLEAN {
-                    panic();
-                }
-            }
-            // Array and object values has been handled above.
-            _ => {}
+        let matches_key_type = match value {
+            Metadata::Text(_) => key_type == TEXT,
+            Metadata::Integer(_) => key_type == INTEGER,
+            Metadata::Float(_) => key_type == FLOAT,
+            Metadata::Boolean(_) => key_type == BOOLEAN,
+            // Array and object values have been rejected above.
+            _ => true,
+        };
+
+        if !matches_key_type {
+            let message =
+                format!("Invalid filter value of {value:?} for key: {key_type}");
+            return Err(Error::invalid_filter(&message));
         }
+
+        Ok(())
     }
 
     /// Validates the filter operator based on the key type.
-    fn validate_operator(key_type: &str, operator: &FilterOperator) {
+    fn validate_operator(
+        key_type: &str,
+        operator: &FilterOperator,
+    ) -> Result<(), Error> {
         match operator {
             // Contains operator is only valid for text, array, and object types.
             FilterOperator::Contains => {
                 let valid_types = vec![TEXT, ARRAY, OBJECT];
                 if !valid_types.contains(&key_type) {
-                    panic!("Invalid CONTAINS operator for key: {key_type}");
+                    let message = format!("Invalid CONTAINS operator for key: {key_type}");
+                    return Err(Error::invalid_filter(&message));
                 }
             }
             // Numeric operators are not valid for text and boolean types.
@@ -375,24 +505,32 @@ impl Filter {
             | FilterOperator::LessThanOrEqual => {
                 let invalid_types = vec![TEXT, BOOLEAN];
                 if invalid_types.contains(&key_type) {
-                    panic!("Invalid numeric operator for key type: {key_type}");
+                    let message = format!("Invalid numeric operator for key type: {key_type}");
+                    return Err(Error::invalid_filter(&message));
                 }
             }
             // Equal and not equal are valid for all types.
             _ => {}
         }
+
+        Ok(())
     }
 }
 
-impl From<&str> for Filter {
-    fn from(filter: &str) -> Self {
+impl TryFrom<&str> for Filter {
+    type Error = Error;
+    fn try_from(filter: &str) -> Result<Self, Self::Error> {
         if filter.is_empty() {
-            panic!("Filter string cannot be empty.");
+            let message = "Filter string cannot be empty.";
+            return Err(Error::invalid_filter(message));
         }
 
         // Split the filter string into EXACTLY 3 parts.
-        let parts: Vec<&str> = filter.splitn(3, ' ').collect();
-        let parts: Vec<&str> = parts.into_iter().map(|p| p.trim()).collect();
+        let parts: Vec<&str> = filter.splitn(3, ' ').map(|p| p.trim()).collect();
+        if parts.len() != 3 {
+            let message = "Filter string must be \"key operator value\".";
+            return Err(Error::invalid_filter(message));
+        }
 
         // Get and validate the filter operator.
         let operator = match parts[1] {
@@ -403,12 +541,21 @@ impl From<&str> for Filter {
             "<" => FilterOperator::LessThan,
             "<=" => FilterOperator::LessThanOrEqual,
             "CONTAINS" => FilterOperator::Contains,
-            _ => panic!("Invalid filter operator: {}", parts[1]),
+            _ => {
+                let message = format!("Invalid filter operator: {}", parts[1]);
+                return Err(Error::invalid_filter(&message));
+            }
         };
 
         let key = parts[0].to_string();
         let value = Metadata::from(parts[2]);
-        Self::new(&key, &value, &operator)
+        Self::try_new(&key, &value, &operator)
+    }
+}
+
+impl From<&str> for Filter {
+    fn from(filter: &str) -> Self {
+        Filter::try_from(filter).unwrap_or_else(|err| panic!("{err}"))
     }
 }
 
@@ -3,6 +3,9 @@ use super::*;
 #[cfg(feature = "json")]
 use serde_json::{Map, Number, Value};
 
+#[cfg(feature = "py")]
+use pyo3::types::PyBytes;
+
 /// The metadata associated with a vector record.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum Metadata {
@@ -12,10 +15,19 @@ pub enum Metadata {
     Integer(usize),
     /// A float number to represent something like a score.
     Float(f32),
+    /// A flag such as a feature toggle.
+    Boolean(bool),
+    /// An explicit absence of a value, distinct from a missing key.
+    Null,
+    /// A raw byte blob, e.g. a quantized payload or image thumbnail.
+    Binary(Vec<u8>),
     /// An array containing any type of metadata.
     Array(Vec<Metadata>),
-    /// A map of string and metadata pairs. The most common type.
-    Object(HashMap<String, Metadata>),
+    /// A map of string and metadata pairs. The most common type. Backed by
+    /// an order-preserving map so a JSON document round-trips with its
+    /// fields in the order they were written, instead of HashMap's
+    /// unspecified iteration order.
+    Object(IndexMap<String, Metadata>),
 }
 
 impl From<usize> for Metadata {
@@ -24,6 +36,18 @@ impl From<usize> for Metadata {
     }
 }
 
+impl From<bool> for Metadata {
+    fn from(value: bool) -> Self {
+        Metadata::Boolean(value)
+    }
+}
+
+impl From<&[u8]> for Metadata {
+    fn from(value: &[u8]) -> Self {
+        Metadata::Binary(value.to_vec())
+    }
+}
+
 impl From<f32> for Metadata {
     fn from(value: f32) -> Self {
         Metadata::Float(value)
@@ -74,6 +98,17 @@ where
     }
 }
 
+impl<T> From<IndexMap<String, T>> for Metadata
+where
+    Metadata: From<T>,
+{
+    fn from(value: IndexMap<String, T>) -> Self {
+        let iter = value.into_iter();
+        let obj = iter.map(|(k, v)| (k, v.into())).collect();
+        Metadata::Object(obj)
+    }
+}
+
 // This implementation allows conversion from
 // JSON Value type to the Metadata enum.
 #[cfg(feature = "json")]
@@ -97,21 +132,24 @@ impl From<Value> for Metadata {
             Metadata::Array(vec)
         };
 
-        // Cast JSON object to Metadata object.
+        // Cast JSON object to Metadata object. Relies on serde_json's
+        // `preserve_order` feature so `object`'s iteration order already
+        // matches the order its fields appeared in the source document.
         let convert_object = |object: Map<String, Value>| {
             let map = object
                 .into_iter()
                 .map(|(k, v)| (k, v.into()))
-                .collect::<HashMap<String, Metadata>>();
+                .collect::<IndexMap<String, Metadata>>();
             Metadata::Object(map)
         };
 
         match value {
+            Value::Null => Metadata::Null,
+            Value::Bool(flag) => Metadata::Boolean(flag),
             Value::String(text) => Metadata::Text(text),
             Value::Number(number) => convert_number(number),
             Value::Array(array) => convert_array(array),
             Value::Object(object) => convert_object(object),
-            _ => panic!("Unsupported JSON type for the metadata."),
         }
     }
 }
@@ -133,14 +171,24 @@ impl From<Metadata> for Value {
             Value::Number(number)
         };
 
+        // Convert Metadata binary to a JSON array of byte values, since
+        // JSON has no native binary type to round-trip through.
+        let convert_binary = |bytes: Vec<u8>| {
+            let vec =
+                bytes.into_iter().map(|b| Value::Number(b.into())).collect();
+            Value::Array(vec)
+        };
+
         // Convert Metadata array to JSON array.
         let convert_array = |arr: Vec<Metadata>| {
             let vec = arr.into_iter().map(|v| v.into()).collect();
             Value::Array(vec)
         };
 
-        // Convert Metadata object to JSON object.
-        let convert_object = |obj: HashMap<String, Metadata>| {
+        // Convert Metadata object to JSON object. `Map` also relies on
+        // `preserve_order` so the re-serialized document keeps the same
+        // field order the `Metadata::Object` was built with.
+        let convert_object = |obj: IndexMap<String, Metadata>| {
             let map = obj
                 .into_iter()
                 .map(|(k, v)| (k, v.into()))
@@ -152,6 +200,9 @@ impl From<Metadata> for Value {
             Metadata::Text(text) => Value::String(text),
             Metadata::Integer(int) => convert_integer(int),
             Metadata::Float(float) => convert_float(float),
+            Metadata::Boolean(flag) => Value::Bool(flag),
+            Metadata::Null => Value::Null,
+            Metadata::Binary(bytes) => convert_binary(bytes),
             Metadata::Array(array) => convert_array(array),
             Metadata::Object(object) => convert_object(object),
         }
@@ -163,6 +214,18 @@ impl From<Metadata> for Value {
 #[cfg(feature = "py")]
 impl From<&PyAny> for Metadata {
     fn from(value: &PyAny) -> Self {
+        // Extract null.
+        if value.is_none() {
+            return Metadata::Null;
+        }
+
+        // Extract boolean. Checked before integer because Python's `bool`
+        // is a subclass of `int` and would otherwise be silently absorbed
+        // by the `usize` extraction below.
+        if let Ok(flag) = value.extract::<bool>() {
+            return Metadata::Boolean(flag);
+        }
+
         // Extract string.
         if let Ok(text) = value.extract::<String>() {
             return Metadata::Text(text);
@@ -178,14 +241,22 @@ impl From<&PyAny> for Metadata {
             return Metadata::Float(float);
         }
 
+        // Extract raw bytes, matched before list so a Python `bytes`
+        // object doesn't fall through to the generic list extraction.
+        if let Ok(bytes) = value.downcast::<PyBytes>() {
+            return Metadata::Binary(bytes.as_bytes().to_vec());
+        }
+
         // Extract list.
         if let Ok(list) = value.extract::<Vec<&PyAny>>() {
             let arr = list.into_iter().map(|v| v.into()).collect();
             return Metadata::Array(arr);
         }
 
-        // Extract dictionary.
-        if let Ok(dict) = value.extract::<HashMap<String, &PyAny>>() {
+        // Extract dictionary. Extracted into an `IndexMap` (the `indexmap`
+        // pyo3 feature extracts straight from the dict's own iteration
+        // order) so a Python dict's field order survives the round-trip.
+        if let Ok(dict) = value.extract::<IndexMap<String, &PyAny>>() {
             let obj = dict.into_iter().map(|(k, v)| (k, v.into())).collect();
             return Metadata::Object(obj);
         }
@@ -209,12 +280,13 @@ impl IntoPy<Py<PyAny>> for Metadata {
             list.into_py(py)
         };
 
-        // Convert HashMap of Metadata to Python dictionary.
-        let dict_converter = |map: HashMap<String, Metadata>| {
+        // Convert the Metadata object to a Python dictionary, keeping its
+        // field order via the `indexmap` pyo3 feature's `IntoPy` impl.
+        let dict_converter = |map: IndexMap<String, Metadata>| {
             let dict = map
                 .into_iter()
                 .map(|(key, value)| (key, value.into_py(py)))
-                .collect::<HashMap<String, Py<PyAny>>>();
+                .collect::<IndexMap<String, Py<PyAny>>>();
             dict.into_py(py)
         };
 
@@ -222,6 +294,9 @@ impl IntoPy<Py<PyAny>> for Metadata {
             Metadata::Text(text) => text.into_py(py),
             Metadata::Integer(int) => int.into_py(py),
             Metadata::Float(float) => float.into_py(py),
+            Metadata::Boolean(flag) => flag.into_py(py),
+            Metadata::Null => py.None(),
+            Metadata::Binary(bytes) => PyBytes::new(py, &bytes).into_py(py),
             Metadata::Array(arr) => list_converter(arr),
             Metadata::Object(obj) => dict_converter(obj),
         }
@@ -0,0 +1,192 @@
+// Prometheus text-exposition metrics for HNSW search and construction.
+//
+// Unlike `api::metrics`, there's no single long-lived instance to hang
+// these counters off of: `Search` is pooled and cloned per query by
+// `SearchPool`, and `Collection` is exposed directly to Python callers.
+// So this tracks process-wide totals instead, rendered by whichever
+// embedding surface (a coordinator/data node's admin API, a Python
+// caller, ...) wants to expose a `/metrics` endpoint.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+#[derive(Debug, Default)]
+struct SearchMetrics {
+    queries: AtomicU64,
+    query_micros: AtomicU64,
+    base_layer_micros: AtomicU64,
+    upper_layer_micros: AtomicU64,
+    distance_computations: AtomicU64,
+    visited_recycles: AtomicU64,
+    candidates_len_total: AtomicU64,
+    candidates_len_samples: AtomicU64,
+    inserts: AtomicU64,
+    insert_micros: AtomicU64,
+}
+
+static METRICS: SearchMetrics = SearchMetrics {
+    queries: AtomicU64::new(0),
+    query_micros: AtomicU64::new(0),
+    base_layer_micros: AtomicU64::new(0),
+    upper_layer_micros: AtomicU64::new(0),
+    distance_computations: AtomicU64::new(0),
+    visited_recycles: AtomicU64::new(0),
+    candidates_len_total: AtomicU64::new(0),
+    candidates_len_samples: AtomicU64::new(0),
+    inserts: AtomicU64::new(0),
+    insert_micros: AtomicU64::new(0),
+};
+
+/// Records one distance calculation, called once per [`Search::push`]/
+/// [`Search::push_filtered`] call that doesn't short-circuit on an
+/// already-visited node.
+pub(crate) fn record_distance_computation() {
+    METRICS.distance_computations.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Records a [`Visited`] running out of generations and falling back to a
+/// full buffer reset, the expensive branch of `Visited::clear`.
+pub(crate) fn record_visited_recycle() {
+    METRICS.visited_recycles.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Records the candidate heap's size right after a [`Search::push`]/
+/// [`Search::push_filtered`] call grows it, so [`render`] can report the
+/// average frontier width a search tends to carry.
+pub(crate) fn record_candidates_len(candidates_len: usize) {
+    METRICS
+        .candidates_len_total
+        .fetch_add(candidates_len as u64, Ordering::Relaxed);
+    METRICS.candidates_len_samples.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Records one `Collection::search_base` call: end-to-end latency and
+/// time spent in the base layer versus the upper layers.
+pub(crate) fn record_query(
+    elapsed: Duration,
+    base_layer: Duration,
+    upper_layers: Duration,
+) {
+    METRICS.queries.fetch_add(1, Ordering::Relaxed);
+    add_micros(&METRICS.query_micros, elapsed);
+    add_micros(&METRICS.base_layer_micros, base_layer);
+    add_micros(&METRICS.upper_layer_micros, upper_layers);
+}
+
+/// Records one `IndexConstruction::insert` call's wall-clock time.
+pub(crate) fn record_insert(elapsed: Duration) {
+    METRICS.inserts.fetch_add(1, Ordering::Relaxed);
+    add_micros(&METRICS.insert_micros, elapsed);
+}
+
+fn add_micros(counter: &AtomicU64, elapsed: Duration) {
+    counter.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+}
+
+fn seconds(counter: &AtomicU64) -> f64 {
+    counter.load(Ordering::Relaxed) as f64 / 1_000_000.0
+}
+
+/// Renders every counter as Prometheus text exposition format, meant to be
+/// embedded verbatim into a caller's own `/metrics` response body.
+pub fn render() -> String {
+    let mut body = String::new();
+
+    body.push_str("# HELP oasysdb_hnsw_queries_total Searches run.\n");
+    body.push_str("# TYPE oasysdb_hnsw_queries_total counter\n");
+    body.push_str(&format!(
+        "oasysdb_hnsw_queries_total {}\n",
+        METRICS.queries.load(Ordering::Relaxed)
+    ));
+
+    body.push_str(
+        "# HELP oasysdb_hnsw_query_duration_seconds_sum \
+        Cumulative end-to-end search time.\n",
+    );
+    body.push_str("# TYPE oasysdb_hnsw_query_duration_seconds_sum counter\n");
+    body.push_str(&format!(
+        "oasysdb_hnsw_query_duration_seconds_sum {}\n",
+        seconds(&METRICS.query_micros)
+    ));
+
+    body.push_str(
+        "# HELP oasysdb_hnsw_base_layer_duration_seconds_sum \
+        Cumulative time spent searching the base layer.\n",
+    );
+    body.push_str(
+        "# TYPE oasysdb_hnsw_base_layer_duration_seconds_sum counter\n",
+    );
+    body.push_str(&format!(
+        "oasysdb_hnsw_base_layer_duration_seconds_sum {}\n",
+        seconds(&METRICS.base_layer_micros)
+    ));
+
+    body.push_str(
+        "# HELP oasysdb_hnsw_upper_layer_duration_seconds_sum \
+        Cumulative time spent searching the upper layers.\n",
+    );
+    body.push_str(
+        "# TYPE oasysdb_hnsw_upper_layer_duration_seconds_sum counter\n",
+    );
+    body.push_str(&format!(
+        "oasysdb_hnsw_upper_layer_duration_seconds_sum {}\n",
+        seconds(&METRICS.upper_layer_micros)
+    ));
+
+    body.push_str(
+        "# HELP oasysdb_hnsw_distance_computations_total \
+        Distance calculations performed while searching or constructing.\n",
+    );
+    body.push_str(
+        "# TYPE oasysdb_hnsw_distance_computations_total counter\n",
+    );
+    body.push_str(&format!(
+        "oasysdb_hnsw_distance_computations_total {}\n",
+        METRICS.distance_computations.load(Ordering::Relaxed)
+    ));
+
+    body.push_str(
+        "# HELP oasysdb_hnsw_visited_recycles_total \
+        Times a Visited buffer ran out of generations and was reset.\n",
+    );
+    body.push_str("# TYPE oasysdb_hnsw_visited_recycles_total counter\n");
+    body.push_str(&format!(
+        "oasysdb_hnsw_visited_recycles_total {}\n",
+        METRICS.visited_recycles.load(Ordering::Relaxed)
+    ));
+
+    let samples = METRICS.candidates_len_samples.load(Ordering::Relaxed);
+    let total = METRICS.candidates_len_total.load(Ordering::Relaxed);
+    let average_candidates =
+        if samples == 0 { 0.0 } else { total as f64 / samples as f64 };
+
+    body.push_str(
+        "# HELP oasysdb_hnsw_search_candidates_average \
+        Average size of the search frontier after each push.\n",
+    );
+    body.push_str("# TYPE oasysdb_hnsw_search_candidates_average gauge\n");
+    body.push_str(&format!(
+        "oasysdb_hnsw_search_candidates_average {average_candidates}\n"
+    ));
+
+    body.push_str("# HELP oasysdb_hnsw_inserts_total Nodes inserted.\n");
+    body.push_str("# TYPE oasysdb_hnsw_inserts_total counter\n");
+    body.push_str(&format!(
+        "oasysdb_hnsw_inserts_total {}\n",
+        METRICS.inserts.load(Ordering::Relaxed)
+    ));
+
+    body.push_str(
+        "# HELP oasysdb_hnsw_insert_duration_seconds_sum \
+        Cumulative time spent inserting nodes during construction.\n",
+    );
+    body.push_str(
+        "# TYPE oasysdb_hnsw_insert_duration_seconds_sum counter\n",
+    );
+    body.push_str(&format!(
+        "oasysdb_hnsw_insert_duration_seconds_sum {}\n",
+        seconds(&METRICS.insert_micros)
+    ));
+
+    body
+}
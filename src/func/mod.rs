@@ -4,8 +4,14 @@ pub mod collection;
 pub mod distance;
 /// Error types for the database.
 pub mod err;
+/// A boolean-expression tree for filtering collection metadata.
+pub mod filter;
 /// Types for the metadata.
 pub mod metadata;
+/// Prometheus metrics for HNSW search and construction.
+pub mod metrics;
+/// A collection sharded across several independent HNSW partitions.
+pub mod sharded;
 /// Types for the vectors.
 pub mod vector;
 
@@ -15,21 +21,29 @@ mod utils;
 use collection::*;
 use distance::*;
 use err::*;
+use filter::*;
 use metadata::*;
+use metrics::*;
+use sharded::*;
 use utils::*;
 use vector::*;
 
 // External dependencies.
+use indexmap::IndexMap;
 use ordered_float::OrderedFloat;
 use parking_lot::*;
 use rand::random;
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_big_array::BigArray;
 use simsimd::SpatialSimilarity;
 use std::cmp::*;
-use std::collections::{BinaryHeap, HashMap};
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::ops::{Deref, Index};
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "py")]
 use pyo3::prelude::*;
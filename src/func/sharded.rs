@@ -0,0 +1,352 @@
+use super::*;
+
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+/// Number of virtual partitions every [`ShardedCollection`] divides its
+/// records into. Kept fixed and much larger than any realistic shard
+/// count, so adding a shard only needs to hand it a slice of the
+/// partitions, not rehash every existing record.
+const PARTITIONS: usize = 4096;
+
+/// Maps each virtual partition to the shard that owns new writes into it.
+/// `version` is bumped every time a shard is added, so callers can tell a
+/// cached copy of the table is stale.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct PartitionTable {
+    version: u32,
+    assignment: Vec<u16>,
+}
+
+impl PartitionTable {
+    fn new(shards: usize) -> Self {
+        let assignment = (0..PARTITIONS)
+            .map(|partition| (partition % shards) as u16)
+            .collect();
+
+        Self { version: 0, assignment }
+    }
+
+    /// The shard that owns `id`'s partition.
+    fn shard_of(&self, id: u32) -> usize {
+        self.assignment[id as usize % PARTITIONS] as usize
+    }
+
+    /// Hands the newest shard an even slice of the partition table,
+    /// leaving every other partition's assignment untouched.
+    fn grow(&mut self, shards: usize) {
+        let new_shard = (shards - 1) as u16;
+
+        for (partition, owner) in self.assignment.iter_mut().enumerate() {
+            if partition % shards == new_shard as usize {
+                *owner = new_shard;
+            }
+        }
+
+        self.version += 1;
+    }
+}
+
+/// A collection of vector records spread across several independent HNSW
+/// shards, so one logical collection is split over several smaller graphs.
+/// Records are addressed by collection-wide `u32` IDs, so the collection
+/// as a whole is still capped at `u32::MAX` records, like a single shard.
+///
+/// Each record is given a [`VectorID`] by the `ShardedCollection` itself,
+/// distinct from the local ID its owning shard's [`Collection`] assigns
+/// internally. `locations` records which shard (and which local ID) a
+/// given record lives under; once written, a record never moves shards,
+/// even after [`Self::add_shard`] reassigns part of the partition table.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShardedCollection {
+    config: Config,
+    relevancy: f32,
+    partitions: PartitionTable,
+    shards: Vec<Collection>,
+    locations: HashMap<VectorID, (usize, VectorID)>,
+    next_id: u32,
+}
+
+/// Snapshot of a [`ShardedCollection`]'s routing state, persisted
+/// alongside the shard files so they can be loaded back independently.
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    config: Config,
+    relevancy: f32,
+    partitions: PartitionTable,
+    locations: HashMap<VectorID, (usize, VectorID)>,
+    next_id: u32,
+    shards: usize,
+}
+
+impl ShardedCollection {
+    /// Creates an empty sharded collection with `shards` empty shards.
+    /// * `config`: Configuration shared by every shard.
+    /// * `shards`: Number of shards to start with. Must be at least 1.
+    pub fn new(config: &Config, shards: usize) -> Self {
+        let shards = shards.max(1);
+
+        Self {
+            config: config.clone(),
+            relevancy: -1.0,
+            partitions: PartitionTable::new(shards),
+            shards: (0..shards).map(|_| Collection::new(config)).collect(),
+            locations: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Adds a new, empty shard and reassigns a slice of the partition
+    /// table to it. Records already written to the other shards keep
+    /// their assignment; only future inserts are affected.
+    pub fn add_shard(&mut self) {
+        self.shards.push(Collection::new(&self.config));
+        self.partitions.grow(self.shards.len());
+    }
+
+    /// Inserts a vector record, routing it to the shard that owns its
+    /// assigned ID's partition.
+    /// * `record`: Vector record to insert.
+    pub fn insert(&mut self, record: &Record) -> Result<VectorID, Error> {
+        if self.next_id == u32::MAX {
+            return Err(Error::collection_limit());
+        }
+
+        let id = VectorID::from(self.next_id);
+        self.next_id += 1;
+
+        let shard = self.partitions.shard_of(id.to_u32());
+        let local_id = self.shards[shard].insert(record)?;
+        self.locations.insert(id, (shard, local_id));
+
+        Ok(id)
+    }
+
+    /// Inserts multiple vector records.
+    /// * `records`: List of vector records to insert.
+    pub fn insert_many(
+        &mut self,
+        records: &[Record],
+    ) -> Result<Vec<VectorID>, Error> {
+        records.iter().map(|record| self.insert(record)).collect()
+    }
+
+    /// Deletes a vector record from its owning shard.
+    /// * `id`: Vector ID to delete.
+    pub fn delete(&mut self, id: &VectorID) -> Result<(), Error> {
+        let (shard, local_id) = self.location(id)?;
+        self.shards[shard].delete(&local_id)?;
+        self.locations.remove(id);
+        Ok(())
+    }
+
+    /// Updates a vector record on its owning shard.
+    /// * `id`: Vector ID to update.
+    /// * `record`: New vector record.
+    pub fn update(
+        &mut self,
+        id: &VectorID,
+        record: &Record,
+    ) -> Result<(), Error> {
+        let (shard, local_id) = self.location(id)?;
+        self.shards[shard].update(&local_id, record)
+    }
+
+    /// Returns the vector record associated with the ID.
+    /// * `id`: Vector ID to retrieve.
+    pub fn get(&self, id: &VectorID) -> Result<Record, Error> {
+        let (shard, local_id) = self.location(id)?;
+        self.shards[shard].get(&local_id)
+    }
+
+    /// Searches every shard in parallel for the nearest neighbors, then
+    /// merges and re-sorts the shard-local results by distance.
+    /// * `vector`: Vector to search.
+    /// * `n`: Number of neighbors to return.
+    pub fn search(
+        &self,
+        vector: &Vector,
+        n: usize,
+    ) -> Result<Vec<SearchResult>, Error> {
+        // Each shard's own top-`local_n`, searched with `ef` = `local_n`, is
+        // a superset of its share of the global top `n`, so merging those
+        // is enough to recover the top `n` across every shard.
+        let local_n = n.max(self.config.ef_search);
+
+        let merged: Vec<SearchResult> = self
+            .shards
+            .par_iter()
+            .map(|shard| shard.search_with_ef(vector, local_n, local_n))
+            .collect::<Result<Vec<_>, Error>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+
+        let sorted = self.shards[0].sort_by_distance(merged);
+        let mut relevant = self.shards[0].truncate_irrelevant_result(sorted);
+        relevant.truncate(n);
+        Ok(relevant)
+    }
+
+    /// Sets the min/max relevancy for the search results on every shard.
+    /// * `relevancy`: Relevancy score.
+    pub fn set_relevancy(&mut self, relevancy: f32) {
+        self.relevancy = relevancy;
+        for shard in self.shards.iter_mut() {
+            shard.set_relevancy(relevancy);
+        }
+    }
+
+    /// Returns the total number of vector records across every shard.
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(Collection::len).sum()
+    }
+
+    /// Returns true if every shard is empty.
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(Collection::is_empty)
+    }
+
+    /// Returns the partition table's version, bumped every time
+    /// [`Self::add_shard`] reassigns part of it. Callers holding a cached
+    /// partition count can use this to tell it's gone stale.
+    pub fn partition_version(&self) -> u32 {
+        self.partitions.version
+    }
+
+    /// Persists the collection to `dir`: one bincode file per shard
+    /// (`shard-<i>.bin`), plus a `manifest.bin` holding the routing state.
+    /// Shards are written as independent files so they can be grown or
+    /// moved without touching the others.
+    /// * `dir`: Directory to write the collection's files to.
+    pub fn persist(&self, dir: impl AsRef<Path>) -> Result<(), Error> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        for (i, shard) in self.shards.iter().enumerate() {
+            let file = File::create(dir.join(format!("shard-{i}.bin")))?;
+            bincode::serialize_into(BufWriter::new(file), shard)?;
+        }
+
+        let manifest = Manifest {
+            config: self.config.clone(),
+            relevancy: self.relevancy,
+            partitions: self.partitions.clone(),
+            locations: self.locations.clone(),
+            next_id: self.next_id,
+            shards: self.shards.len(),
+        };
+
+        let file = File::create(dir.join("manifest.bin"))?;
+        bincode::serialize_into(BufWriter::new(file), &manifest)?;
+        Ok(())
+    }
+
+    /// Loads a collection previously written by [`Self::persist`].
+    /// * `dir`: Directory the collection's files were written to.
+    pub fn load(dir: impl AsRef<Path>) -> Result<Self, Error> {
+        let dir = dir.as_ref();
+
+        let file = File::open(dir.join("manifest.bin"))?;
+        let reader = BufReader::new(file);
+        let manifest: Manifest = bincode::deserialize_from(reader)?;
+
+        let mut shards = Vec::with_capacity(manifest.shards);
+        for i in 0..manifest.shards {
+            let file = File::open(dir.join(format!("shard-{i}.bin")))?;
+            shards.push(bincode::deserialize_from(BufReader::new(file))?);
+        }
+
+        Ok(Self {
+            config: manifest.config,
+            relevancy: manifest.relevancy,
+            partitions: manifest.partitions,
+            shards,
+            locations: manifest.locations,
+            next_id: manifest.next_id,
+        })
+    }
+
+    /// Looks up the shard and local ID a record lives under.
+    fn location(&self, id: &VectorID) -> Result<(usize, VectorID), Error> {
+        self.locations.get(id).copied().ok_or_else(Error::record_not_found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sharded_collection_insert_and_get_roundtrip() {
+        let config = Config::default();
+        let mut collection = ShardedCollection::new(&config, 4);
+
+        let records = Record::many_random(16, 50);
+        let ids = collection.insert_many(&records).unwrap();
+
+        assert_eq!(collection.len(), 50);
+        for (id, record) in ids.iter().zip(records.iter()) {
+            let fetched = collection.get(id).unwrap();
+            assert_eq!(fetched.vector.0, record.vector.0);
+        }
+    }
+
+    #[test]
+    fn test_sharded_collection_routes_across_every_shard() {
+        let config = Config::default();
+        let mut collection = ShardedCollection::new(&config, 4);
+
+        let records = Record::many_random(16, 200);
+        let ids = collection.insert_many(&records).unwrap();
+
+        // With 200 ids spread over a much larger partition table, every
+        // shard should end up owning at least one record instead of all
+        // of them landing on a single shard.
+        let shards_used: HashSet<usize> = ids
+            .iter()
+            .map(|id| collection.locations[id].0)
+            .collect();
+        assert_eq!(shards_used.len(), 4);
+    }
+
+    #[test]
+    fn test_sharded_collection_delete_removes_record() {
+        let config = Config::default();
+        let mut collection = ShardedCollection::new(&config, 2);
+
+        let record = Record::random(8);
+        let id = collection.insert(&record).unwrap();
+        collection.delete(&id).unwrap();
+
+        assert!(collection.get(&id).is_err());
+        assert!(collection.is_empty());
+    }
+
+    #[test]
+    fn test_sharded_collection_add_shard_bumps_version() {
+        let config = Config::default();
+        let mut collection = ShardedCollection::new(&config, 2);
+        assert_eq!(collection.partition_version(), 0);
+
+        collection.add_shard();
+        assert_eq!(collection.partition_version(), 1);
+        assert_eq!(collection.shards.len(), 3);
+    }
+
+    #[test]
+    fn test_sharded_collection_search_returns_nearest() {
+        let config = Config::default();
+        let mut collection = ShardedCollection::new(&config, 3);
+
+        let records = Record::many_random(8, 100);
+        collection.insert_many(&records).unwrap();
+
+        let query = records[0].vector.clone();
+        let results = collection.search(&query, 5).unwrap();
+
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].distance, 0.0);
+    }
+}
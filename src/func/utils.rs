@@ -1,6 +1,7 @@
 use self::distance::Distance;
 
 use super::*;
+use crate::utils::heuristic as heur;
 
 pub const INVALID: VectorID = VectorID(u32::MAX);
 
@@ -150,6 +151,36 @@ impl<'a> Layer for &'a [RwLock<BaseNode>] {
     }
 }
 
+/// Serde adapter for the permanently-locked base layer: a `RwLock` doesn't
+/// serialize on its own, so this (de)serializes the same shape as
+/// `Vec<BaseNode>` and wraps/unwraps the locks around it, the same way
+/// `#[serde(with = "BigArray")]` adapts `[VectorID; M * 2]` above.
+pub mod locked_nodes {
+    use super::*;
+
+    pub fn serialize<S>(
+        nodes: &[RwLock<BaseNode>],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let plain: Vec<BaseNode> =
+            nodes.iter().map(|node| *node.read()).collect();
+        plain.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(
+        deserializer: D,
+    ) -> Result<Vec<RwLock<BaseNode>>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let plain = Vec::<BaseNode>::deserialize(deserializer)?;
+        Ok(plain.into_iter().map(RwLock::new).collect())
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug)]
 pub struct UpperNode(#[serde(with = "BigArray")] pub [VectorID; M]);
 
@@ -172,6 +203,58 @@ impl<'a> Layer for &'a [UpperNode] {
     }
 }
 
+/// Describes one upper layer's slice of the flat `upper_neighbors` buffer:
+/// the layer has `nodes` rows of `degree` neighbor slots each, starting at
+/// `offset`. Replaces per-layer `Vec<UpperNode>` storage with computed
+/// ranges into a single backing array.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+pub struct Meta {
+    pub offset: usize,
+    pub nodes: usize,
+    pub degree: usize,
+}
+
+impl Meta {
+    pub fn new(offset: usize, nodes: usize, degree: usize) -> Self {
+        Self { offset, nodes, degree }
+    }
+
+    /// Total neighbor slots this layer occupies in the flat buffer.
+    pub fn size(&self) -> usize {
+        self.nodes * self.degree
+    }
+
+    /// The flat-buffer range holding `node`'s neighbor slots.
+    pub fn range(&self, node: usize) -> std::ops::Range<usize> {
+        let start = self.offset + node * self.degree;
+        start..start + self.degree
+    }
+}
+
+/// A read-only view into one upper layer's rows inside the flat
+/// `upper_neighbors` buffer, sliced down to just that layer's range so it
+/// can implement [`Layer`] the same way a nested `Vec<UpperNode>` did.
+#[derive(Clone, Copy)]
+pub struct LayerSlice<'a> {
+    buf: &'a [VectorID],
+    degree: usize,
+}
+
+impl<'a> LayerSlice<'a> {
+    pub fn new(buf: &'a [VectorID], meta: &Meta) -> Self {
+        let range = meta.offset..meta.offset + meta.size();
+        Self { buf: &buf[range], degree: meta.degree }
+    }
+}
+
+impl<'a> Layer for LayerSlice<'a> {
+    type Slice = &'a [VectorID];
+    fn nearest_iter(&self, vector_id: &VectorID) -> NearestIter<Self::Slice> {
+        let start = vector_id.0 as usize * self.degree;
+        NearestIter::new(&self.buf[start..start + self.degree])
+    }
+}
+
 #[derive(Clone)]
 pub struct Visited {
     store: Vec<u8>,
@@ -218,6 +301,7 @@ impl Visited {
             return;
         }
 
+        record_visited_recycle();
         self.store.clear();
         self.store.resize(self.store.len(), 0);
         self.generation = 1;
@@ -238,9 +322,16 @@ pub struct Search {
     pub visited: Visited,
     candidates: BinaryHeap<Reverse<Candidate>>,
     nearest: Vec<Candidate>,
-    working: Vec<Candidate>,
-    discarded: Vec<Candidate>,
+    working: Vec<heur::Candidate<VectorID>>,
+    discarded: Vec<heur::Candidate<VectorID>>,
     distance: Distance,
+    /// Ceiling on `distance_computations` for [`Self::search`]/
+    /// [`Self::search_filtered`] to stay under before giving up the
+    /// remaining descent and returning the best candidates found so far.
+    /// `None` means no ceiling. See [`crate::func::collection::SearchParams`].
+    pub distance_budget: Option<usize>,
+    /// Distance evaluations spent since the last [`Self::reset`].
+    distance_computations: usize,
 }
 
 impl Search {
@@ -258,6 +349,15 @@ impl Search {
         links: usize,
     ) {
         while let Some(Reverse(candidate)) = self.candidates.pop() {
+            // Give up the remaining descent once the distance-computation
+            // budget is spent, returning whatever `self.nearest` holds so
+            // far rather than pushing further candidates.
+            if let Some(budget) = self.distance_budget {
+                if self.distance_computations >= budget {
+                    break;
+                }
+            }
+
             // Skip candidates conditionally.
             // For Euclidean metrics, skip candidate with larger distances
             // because 0.0 is the smallest and best distance.
@@ -292,9 +392,17 @@ impl Search {
             return;
         }
 
+        if let Some(budget) = self.distance_budget {
+            if self.distance_computations >= budget {
+                return;
+            }
+        }
+
         // Create a new candidate.
         let other = &vectors[vector_id];
         let distance = self.distance.calculate(vector, other);
+        record_distance_computation();
+        self.distance_computations += 1;
         let distance = OrderedFloat(distance);
         let new = Candidate { distance, vector_id: *vector_id };
 
@@ -307,6 +415,102 @@ impl Search {
 
         self.nearest.insert(index, new);
         self.candidates.push(Reverse(new));
+        record_candidates_len(self.candidates.len());
+    }
+
+    /// Searches the nearest neighbors in the graph layer, keeping only the
+    /// candidates whose metadata satisfies `predicate` in the result set.
+    ///
+    /// Unlike [`Self::search`], every visited node still advances the
+    /// frontier through [`Self::push_filtered`] regardless of whether it
+    /// matches, so the walk doesn't lose connectivity through nodes that
+    /// don't match `predicate`. Only matching nodes are kept as results,
+    /// and the walk keeps going until `self.ef` matches have been found
+    /// or the frontier is exhausted.
+    pub fn search_filtered<L: Layer>(
+        &mut self,
+        layer: L,
+        vector: &Vector,
+        vectors: &HashMap<VectorID, Vector>,
+        data: &IndexMap<VectorID, Metadata>,
+        links: usize,
+        predicate: &impl Fn(&Metadata) -> bool,
+    ) {
+        while let Some(Reverse(candidate)) = self.candidates.pop() {
+            if let Some(budget) = self.distance_budget {
+                if self.distance_computations >= budget {
+                    break;
+                }
+            }
+
+            // Only stop on distance once `self.ef` matches are held, so the
+            // walk keeps going while the result set is still short.
+            if self.nearest.len() >= self.ef {
+                if let Some(furthest) = self.nearest.last() {
+                    if self.distance.ascending() {
+                        if candidate.distance > furthest.distance {
+                            break;
+                        }
+                    } else if candidate.distance < furthest.distance {
+                        break;
+                    }
+                }
+            }
+
+            let layer_iter = layer.nearest_iter(&candidate.vector_id);
+            for vector_id in layer_iter.take(links) {
+                self.push_filtered(&vector_id, vector, vectors, data, predicate);
+            }
+
+            self.nearest.truncate(self.ef);
+        }
+    }
+
+    /// Pushes a new neighbor candidate, only admitting it into the result
+    /// set (`self.nearest`) when its metadata satisfies `predicate`. The
+    /// candidate always advances the frontier (`self.candidates`) so
+    /// non-matching nodes still connect the walk to the rest of the graph.
+    pub fn push_filtered(
+        &mut self,
+        vector_id: &VectorID,
+        vector: &Vector,
+        vectors: &HashMap<VectorID, Vector>,
+        data: &IndexMap<VectorID, Metadata>,
+        predicate: &impl Fn(&Metadata) -> bool,
+    ) {
+        if !self.visited.insert(vector_id) {
+            return;
+        }
+
+        if let Some(budget) = self.distance_budget {
+            if self.distance_computations >= budget {
+                return;
+            }
+        }
+
+        let other = &vectors[vector_id];
+        let distance = self.distance.calculate(vector, other);
+        record_distance_computation();
+        self.distance_computations += 1;
+        let distance = OrderedFloat(distance);
+        let new = Candidate { distance, vector_id: *vector_id };
+
+        // Keep expanding the frontier through this node regardless of
+        // whether it matches, to preserve connectivity.
+        self.candidates.push(Reverse(new));
+        record_candidates_len(self.candidates.len());
+
+        let matches = data.get(vector_id).is_some_and(|metadata| predicate(metadata));
+        if !matches {
+            return;
+        }
+
+        let index = match self.nearest.binary_search(&new) {
+            Ok(index) | Err(index) => index,
+        };
+        if index < self.ef {
+            self.nearest.insert(index, new);
+        }
     }
 
     /// Lowers the search to the next lower layer.
@@ -329,6 +533,7 @@ impl Search {
         self.nearest.clear();
         self.working.clear();
         self.discarded.clear();
+        self.distance_computations = 0;
     }
 
     /// Selects the nearest neighbors.
@@ -336,6 +541,64 @@ impl Search {
         &self.nearest
     }
 
+    /// Selects up to `m` neighbors using the Malkov-Yashunin heuristic
+    /// (Algorithm 4 in the HNSW paper): a candidate is kept only if it's
+    /// closer to the query than to every neighbor already kept, which
+    /// favors diverse directions over a tight cluster and tends to give
+    /// better graph connectivity than [`Self::select_simple`]. The actual
+    /// pass is shared with every other HNSW-style index in this crate;
+    /// see [`heur::extend_candidates`] and [`heur::select_diverse`].
+    ///
+    /// When `heuristic.extend_candidates` is set, the candidate set is
+    /// first widened with each candidate's own neighbors on `layer`
+    /// before the pass runs, at the cost of extra distance computations.
+    /// When `heuristic.keep_pruned` is set and fewer than `m` candidates
+    /// survive the pass, the discarded candidates backfill the rest in
+    /// distance order rather than leaving the node under-connected.
+    pub fn select_heuristic<L: Layer>(
+        &mut self,
+        layer: L,
+        query: &Vector,
+        vectors: &HashMap<VectorID, Vector>,
+        m: usize,
+        heuristic: &HeuristicConfig,
+    ) -> &[Candidate] {
+        let distance = self.distance;
+
+        self.working.clear();
+        self.working.extend(self.nearest.drain(..).map(|c| heur::Candidate {
+            distance: c.distance,
+            id: c.vector_id,
+        }));
+
+        if heuristic.extend_candidates {
+            heur::extend_candidates(
+                &mut self.working,
+                |id| layer.nearest_iter(&id).collect(),
+                |id| vectors.get(&id).map(|v| distance.calculate(query, v)),
+            );
+        }
+
+        let accepted = heur::select_diverse(
+            &mut self.working,
+            &mut self.discarded,
+            m,
+            heuristic.keep_pruned,
+            |id| vectors.contains_key(&id),
+            |a, b| {
+                let a = vectors.get(&a)?;
+                let b = vectors.get(&b)?;
+                Some(distance.calculate(a, b))
+            },
+        );
+
+        self.nearest = accepted
+            .into_iter()
+            .map(|c| Candidate { distance: c.distance, vector_id: c.id })
+            .collect();
+        &self.nearest
+    }
+
     pub fn iter(&self) -> impl ExactSizeIterator<Item = Candidate> + '_ {
         self.nearest.iter().copied()
     }
@@ -351,6 +614,8 @@ impl Default for Search {
             discarded: Vec::new(),
             ef: 5,
             distance: Distance::Euclidean,
+            distance_budget: None,
+            distance_computations: 0,
         }
     }
 }
@@ -388,22 +653,37 @@ pub struct IndexConstruction<'a> {
     pub base_layer: &'a [RwLock<BaseNode>],
     pub vectors: &'a HashMap<VectorID, Vector>,
     pub config: &'a Config,
+    /// Base seed for this construction run. Combined with a node's own
+    /// `VectorID` to derive that node's [`SmallRng`] in [`Self::insert`],
+    /// so tie-breaks are reproducible regardless of Rayon's thread
+    /// scheduling: each node's RNG stream depends only on `seed` and its
+    /// own ID, never on the order threads happen to process nodes in.
+    pub seed: u64,
 }
 
 impl<'a> IndexConstruction<'a> {
     /// Inserts a vector ID into a layer.
     /// * `vector_id`: Vector ID to insert.
     /// * `layer`: Layer to insert into.
-    /// * `layers`: Upper layers.
+    /// * `upper_neighbors`: Flat neighbor buffer backing every upper layer.
+    /// * `upper_meta`: Per-upper-layer descriptor into `upper_neighbors`,
+    ///   indexed by `layer.0 - 1`.
     pub fn insert(
         &self,
         vector_id: &VectorID,
         layer: &LayerID,
-        layers: &[Vec<UpperNode>],
+        upper_neighbors: &[VectorID],
+        upper_meta: &[Meta],
     ) {
+        let start = Instant::now();
         let vector = &self.vectors[vector_id];
         let dist = self.config.distance;
 
+        // Seeded from `self.seed` and this node's own ID, so the tie-break
+        // below is reproducible no matter which thread or in what order
+        // Rayon happens to process nodes in.
+        let mut rng = SmallRng::seed_from_u64(self.seed ^ vector_id.0 as u64);
+
         let (mut search, mut insertion) = self.search_pool.pop();
         insertion.ef = self.config.ef_construction;
 
@@ -424,7 +704,8 @@ impl<'a> IndexConstruction<'a> {
 
             // Find the nearest neighbor candidates.
             if current_layer > *layer {
-                let layer = layers[current_layer.0 - 1].as_slice();
+                let meta = upper_meta[current_layer.0 - 1];
+                let layer = LayerSlice::new(upper_neighbors, &meta);
                 search.search(layer, vector, self.vectors, M);
                 search.cull();
             } else {
@@ -433,10 +714,22 @@ impl<'a> IndexConstruction<'a> {
             }
         }
 
-        // Select the neighbors.
-        let candidates = {
-            let candidates = search.select_simple();
-            &candidates[..Ord::min(candidates.len(), M)]
+        // Select the neighbors, either the plain nearest-`M` cut or, when
+        // `config.heuristic` is set, the diversity-aware heuristic
+        // selection (Algorithm 4 in the HNSW paper). Both the forward
+        // links set below and their back-links use this pruned set.
+        let candidates: Vec<Candidate> = match &self.config.heuristic {
+            Some(heuristic) => {
+                let layer = self.base_layer;
+                let vectors = self.vectors;
+                let selected = search
+                    .select_heuristic(layer, vector, vectors, M, heuristic);
+                selected.to_vec()
+            }
+            None => {
+                let candidates = search.select_simple();
+                candidates[..Ord::min(candidates.len(), M)].to_vec()
+            }
         };
 
         for (i, candidate) in candidates.iter().enumerate() {
@@ -444,13 +737,20 @@ impl<'a> IndexConstruction<'a> {
             let old = &self.vectors[&vid];
             let distance = candidate.distance;
 
-            // Function to sort the vectors by distance.
+            // Function to sort the vectors by distance. Ties (equal
+            // distances) are broken with `rng` rather than left to
+            // whatever order `binary_search_by` happens to probe them in,
+            // so the result is reproducible for a given seed.
             let ordering = |id: &VectorID| {
                 if !id.is_valid() {
                     Ordering::Greater
                 } else {
                     let other = &self.vectors[id];
-                    distance.cmp(&dist.calculate(old, other).into())
+                    match distance.cmp(&dist.calculate(old, other).into()) {
+                        Ordering::Equal if rng.gen() => Ordering::Less,
+                        Ordering::Equal => Ordering::Greater,
+                        ordering => ordering,
+                    }
                 }
             };
 
@@ -465,5 +765,6 @@ impl<'a> IndexConstruction<'a> {
         }
 
         self.search_pool.push(&(search, insertion));
+        record_insert(start.elapsed());
     }
 }
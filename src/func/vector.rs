@@ -1,4 +1,6 @@
 use super::*;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 
 /// The ID of a vector record.
 #[cfg_attr(feature = "py", pyclass(module = "oasysdb.vector"))]
@@ -143,3 +145,22 @@ impl From<Vector> for Vec<f32> {
         vector.0
     }
 }
+
+/// Content hash of a [`Vector`]'s raw f32 bytes, used by
+/// [`crate::func::collection::Collection`] to detect exact-content
+/// duplicates cheaply, without comparing every indexed vector on each
+/// insert. A collision only ever costs a missed dedup opportunity: the
+/// exact `Vector` equality check that gates every dedup decision still
+/// runs before two IDs are treated as aliases.
+pub type VectorHash = u64;
+
+/// Hashes `vector`'s raw f32 bytes with a fast non-cryptographic hash.
+/// Floats hash by their bit pattern, since `f32` has no `Hash` impl and
+/// NaN/signed-zero would otherwise be inconsistent with `PartialEq`.
+pub fn hash_vector(vector: &Vector) -> VectorHash {
+    let mut hasher = DefaultHasher::new();
+    for value in &vector.0 {
+        value.to_bits().hash(&mut hasher);
+    }
+    hasher.finish()
+}
@@ -62,9 +62,29 @@ impl VectorIndex for IndexFlat {
     fn search(
         &self,
         query: Vector,
-        k: usize,
+        params: SearchParams,
         filters: Filters,
     ) -> Result<Vec<SearchResult>, Error> {
+        params.validate()?;
+        let k = params.k;
+
+        if self.params.vp_tree {
+            let ids = self.data.keys().copied().collect();
+            let tree = VPNode::build(ids, &self.data, self.metric());
+
+            let mut results = BinaryHeap::new();
+            tree.search(
+                &query,
+                k,
+                &filters,
+                &self.data,
+                self.metric(),
+                &mut results,
+            );
+
+            return Ok(results.into_sorted_vec());
+        }
+
         let mut results = BinaryHeap::new();
         for (id, record) in &self.data {
             // Skip records that don't pass the filters.
@@ -98,6 +118,12 @@ impl VectorIndex for IndexFlat {
 pub struct ParamsFlat {
     /// Formula used to calculate the distance between vectors.
     pub metric: DistanceMetric,
+    /// Use a vantage-point tree for exact search instead of a brute-force
+    /// linear scan. The tree is rebuilt from every record on each search,
+    /// which alone computes distances from each vantage point to its whole
+    /// subtree, so a query is not sublinear in the dataset size; only the
+    /// traversal itself prunes subtrees.
+    pub vp_tree: bool,
 }
 
 impl IndexParams for ParamsFlat {
@@ -110,6 +136,127 @@ impl IndexParams for ParamsFlat {
     }
 }
 
+/// Vantage-point tree used for the opt-in exact-search mode.
+///
+/// Each internal node picks a vantage point and a split radius `mu`, the
+/// median distance from the vantage point to the rest of its subtree.
+/// Points within `mu` go to the inside branch, the rest go outside.
+#[derive(Debug)]
+enum VPNode {
+    Leaf,
+    Internal {
+        vantage: RecordID,
+        mu: f32,
+        inside: Box<VPNode>,
+        outside: Box<VPNode>,
+    },
+}
+
+impl VPNode {
+    /// Build the tree over a set of record IDs.
+    fn build(
+        mut ids: Vec<RecordID>,
+        data: &HashMap<RecordID, Record>,
+        metric: &DistanceMetric,
+    ) -> Self {
+        let vantage = match ids.pop() {
+            Some(vantage) => vantage,
+            None => return VPNode::Leaf,
+        };
+
+        if ids.is_empty() {
+            return VPNode::Internal {
+                vantage,
+                mu: 0.0,
+                inside: Box::new(VPNode::Leaf),
+                outside: Box::new(VPNode::Leaf),
+            };
+        }
+
+        let vantage_vector = &data[&vantage].vector;
+        let mut distances: Vec<(RecordID, f32)> = ids
+            .into_iter()
+            .map(|id| {
+                let distance = metric.distance(vantage_vector, &data[&id].vector);
+                (id, distance)
+            })
+            .collect();
+
+        distances.sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap());
+        let mu = distances[distances.len() / 2].1;
+
+        let (inside, outside): (Vec<(RecordID, f32)>, Vec<(RecordID, f32)>) =
+            distances.into_iter().partition(|(_, distance)| *distance <= mu);
+
+        let inside = inside.into_iter().map(|(id, _)| id).collect();
+        let outside = outside.into_iter().map(|(id, _)| id).collect();
+
+        VPNode::Internal {
+            vantage,
+            mu,
+            inside: Box::new(VPNode::build(inside, data, metric)),
+            outside: Box::new(VPNode::build(outside, data, metric)),
+        }
+    }
+
+    /// Descend the tree maintaining a bounded top-k heap, pruning the far
+    /// subtree whenever the triangle-inequality bound `|d(query,vp) - mu|`
+    /// shows it can't hold anything closer than the current k-th result.
+    /// For squared Euclidean the bound is taken on square roots; Cosine
+    /// and DotProduct don't obey the triangle inequality, so their far
+    /// subtree is always searched.
+    fn search(
+        &self,
+        query: &Vector,
+        k: usize,
+        filters: &Filters,
+        data: &HashMap<RecordID, Record>,
+        metric: &DistanceMetric,
+        results: &mut BinaryHeap<SearchResult>,
+    ) {
+        let (vantage, mu, inside, outside) = match self {
+            VPNode::Leaf => return,
+            VPNode::Internal { vantage, mu, inside, outside } => {
+                (vantage, *mu, inside, outside)
+            }
+        };
+
+        let record = &data[vantage];
+        let distance = metric.distance(&record.vector, query);
+
+        if filters.apply(&record.data) {
+            let data = record.data.clone();
+            results.push(SearchResult { id: *vantage, distance, data });
+
+            if results.len() > k {
+                results.pop();
+            }
+        }
+
+        let (near, far) = match distance <= mu {
+            true => (inside, outside),
+            false => (outside, inside),
+        };
+
+        near.search(query, k, filters, data, metric, results);
+
+        let worst = results.peek().map(|r| r.distance).unwrap_or(f32::INFINITY);
+        let reachable = match metric {
+            DistanceMetric::Euclidean => {
+                (distance.sqrt() - mu.sqrt()).powi(2) <= worst
+            }
+            DistanceMetric::Manhattan | DistanceMetric::Hamming => {
+                (distance - mu).abs() <= worst
+            }
+            DistanceMetric::Cosine | DistanceMetric::DotProduct => true,
+        };
+
+        if results.len() < k || reachable {
+            far.search(query, k, filters, data, metric, results);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,4 +270,14 @@ mod tests {
         index_tests::test_basic_search(&index);
         index_tests::test_advanced_search(&index);
     }
+
+    #[test]
+    fn test_flat_index_vp_tree() {
+        let params = ParamsFlat { vp_tree: true, ..Default::default() };
+        let mut index = IndexFlat::new(params).unwrap();
+
+        index_tests::populate_index(&mut index);
+        index_tests::test_basic_search(&index);
+        index_tests::test_advanced_search(&index);
+    }
 }
@@ -0,0 +1,539 @@
+use super::*;
+use rand::Rng;
+use std::cmp::{Ordering, Reverse};
+use std::collections::HashSet;
+
+/// Graph layer mapping a node to its neighbor list.
+type Layer = HashMap<RecordID, Vec<RecordID>>;
+
+/// Hierarchical Navigable Small World (HNSW) index.
+///
+/// This index builds a multi-layer proximity graph where higher layers hold
+/// exponentially fewer nodes, letting search descend quickly to the
+/// neighborhood of the query before doing a thorough search at layer 0. It
+/// trades a bit of memory for close-to-logarithmic search time, which makes
+/// it a great choice for large, high-recall workloads where IVFPQ's
+/// compression isn't worth the accuracy loss.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndexHNSW {
+    params: ParamsHNSW,
+    metadata: IndexMetadata,
+    data: HashMap<RecordID, Record>,
+
+    // HNSW specific data structures.
+    layers: Vec<Layer>,
+    levels: HashMap<RecordID, usize>,
+    entry_point: Option<RecordID>,
+
+    /// Deleted node ids, kept in the graph so neighbors stay reachable
+    /// instead of rebuilding links on every delete. Skipped when selecting
+    /// search results and when selecting neighbors for new insertions.
+    tombstones: HashSet<RecordID>,
+}
+
+impl IndexHNSW {
+    /// Assigns a random level to a new node.
+    ///
+    /// Levels follow an exponentially decaying distribution so that each
+    /// layer holds roughly `1/M` as many nodes as the layer below it.
+    fn random_level(&self) -> usize {
+        let ml = 1.0 / (self.params.m as f32).ln();
+        let mut rng = rand::thread_rng();
+        let unif: f32 = rng.gen_range(f32::EPSILON..1.0);
+        (-unif.ln() * ml).floor() as usize
+    }
+
+    /// Greedily walks a single layer from `from` towards the query,
+    /// returning the closest node found.
+    fn greedy_search(
+        &self,
+        layer: usize,
+        from: RecordID,
+        query: &Vector,
+    ) -> RecordID {
+        let mut current = from;
+        let mut current_distance = self.distance_to(&current, query);
+
+        loop {
+            let mut improved = false;
+            if let Some(neighbors) = self.layers[layer].get(&current) {
+                for &neighbor in neighbors {
+                    let distance = self.distance_to(&neighbor, query);
+                    if distance < current_distance {
+                        current = neighbor;
+                        current_distance = distance;
+                        improved = true;
+                    }
+                }
+            }
+
+            if !improved {
+                return current;
+            }
+        }
+    }
+
+    /// Performs an `ef`-bounded best-first search on a single layer starting
+    /// from a set of entry points, returning up to `ef` nearest candidates.
+    fn layer_search(
+        &self,
+        layer: usize,
+        entry_points: &[RecordID],
+        query: &Vector,
+        ef: usize,
+    ) -> Vec<Candidate> {
+        let mut visited: HashSet<RecordID> = entry_points.iter().copied().collect();
+        let mut candidates: BinaryHeap<Reverse<Candidate>> = BinaryHeap::new();
+        let mut found: BinaryHeap<Candidate> = BinaryHeap::new();
+
+        for &point in entry_points {
+            let distance = self.distance_to(&point, query);
+            candidates.push(Reverse(Candidate { id: point, distance }));
+            if !self.tombstones.contains(&point) {
+                found.push(Candidate { id: point, distance });
+            }
+        }
+
+        while let Some(Reverse(current)) = candidates.pop() {
+            let worst = found.peek().map(|c| c.distance).unwrap_or(f32::INFINITY);
+            if found.len() >= ef && current.distance > worst {
+                break;
+            }
+
+            let neighbors = match self.layers[layer].get(&current.id) {
+                Some(neighbors) => neighbors,
+                None => continue,
+            };
+
+            for &neighbor in neighbors {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+
+                let distance = self.distance_to(&neighbor, query);
+
+                // Tombstoned nodes still get expanded below so the graph
+                // stays connected through them, but they're never added to
+                // `found` so they can't come back as a search result or a
+                // neighbor selected for a new insertion.
+                if self.tombstones.contains(&neighbor) {
+                    candidates.push(Reverse(Candidate { id: neighbor, distance }));
+                    continue;
+                }
+
+                let worst = found.peek().map(|c| c.distance).unwrap_or(f32::INFINITY);
+                if found.len() < ef || distance < worst {
+                    candidates.push(Reverse(Candidate { id: neighbor, distance }));
+                    found.push(Candidate { id: neighbor, distance });
+
+                    if found.len() > ef {
+                        found.pop();
+                    }
+                }
+            }
+        }
+
+        found.into_sorted_vec()
+    }
+
+    /// Maximum neighbors a node may keep on `layer`. Layer 0 gets `2*M`
+    /// since it carries every node and benefits most from extra degree.
+    fn max_neighbors(&self, layer: usize) -> usize {
+        if layer == 0 { self.params.m * 2 } else { self.params.m }
+    }
+
+    /// Selects up to `m` of `candidates` (each already carrying its
+    /// distance to the node being connected, sorted nearest first), using
+    /// the standard HNSW heuristic: a candidate is kept only if it's closer
+    /// to the node than it is to every neighbor already selected, which
+    /// favors a spread of directions over a cluster of near-duplicates and
+    /// keeps the graph connected across regions. If the heuristic rejects
+    /// too many candidates to fill the quota, the nearest leftovers
+    /// backfill the rest so the node isn't left under-connected.
+    fn select_neighbors_heuristic(
+        &self,
+        candidates: &[Candidate],
+        m: usize,
+    ) -> Vec<RecordID> {
+        let mut selected: Vec<RecordID> = Vec::with_capacity(m);
+
+        for candidate in candidates {
+            if selected.len() >= m {
+                break;
+            }
+
+            let Some(candidate_vector) =
+                self.data.get(&candidate.id).map(|record| &record.vector)
+            else {
+                continue;
+            };
+
+            let dominated = selected.iter().any(|&neighbor| {
+                self.distance_to(&neighbor, candidate_vector) < candidate.distance
+            });
+
+            if !dominated {
+                selected.push(candidate.id);
+            }
+        }
+
+        if selected.len() < m {
+            for candidate in candidates {
+                if selected.len() >= m {
+                    break;
+                }
+
+                if !selected.contains(&candidate.id) {
+                    selected.push(candidate.id);
+                }
+            }
+        }
+
+        selected
+    }
+
+    /// Connects a node to its neighbors on a layer, pruning each side back
+    /// down to its degree cap via [`Self::select_neighbors_heuristic`].
+    fn connect(&mut self, layer: usize, node: RecordID, neighbors: &[RecordID]) {
+        let entry = self.layers[layer].entry(node).or_default();
+        entry.extend(neighbors.iter().copied());
+
+        for &neighbor in neighbors {
+            let reverse = self.layers[layer].entry(neighbor).or_default();
+            if !reverse.contains(&node) {
+                reverse.push(node);
+            }
+
+            self.trim_neighbors(layer, neighbor);
+        }
+
+        self.trim_neighbors(layer, node);
+    }
+
+    /// Re-applies the neighbor-selection heuristic to a node's adjacency
+    /// list on a layer once it exceeds its degree cap.
+    fn trim_neighbors(&mut self, layer: usize, node: RecordID) {
+        let max_neighbors = self.max_neighbors(layer);
+        let neighbors = match self.layers[layer].get(&node) {
+            Some(neighbors) if neighbors.len() > max_neighbors => {
+                neighbors.clone()
+            }
+            _ => return,
+        };
+
+        let vector = self.data.get(&node).map(|record| record.vector.clone());
+        let vector = match vector {
+            Some(vector) => vector,
+            None => return,
+        };
+
+        let mut ranked: Vec<Candidate> = neighbors
+            .into_iter()
+            .map(|id| Candidate { id, distance: self.distance_to(&id, &vector) })
+            .collect();
+
+        ranked.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap_or(Ordering::Equal));
+
+        let selected = self.select_neighbors_heuristic(&ranked, max_neighbors);
+        self.layers[layer].insert(node, selected);
+    }
+
+    fn distance_to(&self, id: &RecordID, query: &Vector) -> f32 {
+        match self.data.get(id) {
+            Some(record) => self.metric().distance(&record.vector, query),
+            None => f32::INFINITY,
+        }
+    }
+
+    /// Inserts a single record into the graph.
+    fn insert_one(&mut self, id: RecordID, record: Record) {
+        let level = self.random_level();
+        let query = record.vector.clone();
+
+        while self.layers.len() <= level {
+            self.layers.push(Layer::new());
+        }
+
+        let entry_point = match self.entry_point {
+            Some(entry_point) => entry_point,
+            None => {
+                self.entry_point = Some(id);
+                self.levels.insert(id, level);
+                self.data.insert(id, record);
+                return;
+            }
+        };
+
+        let entry_level = self.levels[&entry_point];
+        let mut nearest = entry_point;
+
+        // Descend greedily to find the closest entry point just above
+        // the node's own level.
+        for layer in (level + 1..=entry_level).rev() {
+            nearest = self.greedy_search(layer, nearest, &query);
+        }
+
+        // Connect the node at its own level and below with the
+        // `efConstruction`-bounded candidate list at each layer.
+        for layer in (0..=level.min(entry_level)).rev() {
+            let candidates =
+                self.layer_search(layer, &[nearest], &query, self.params.ef_construction);
+
+            let selected =
+                self.select_neighbors_heuristic(&candidates, self.max_neighbors(layer));
+
+            if let Some(&closest) = selected.first() {
+                nearest = closest;
+            }
+
+            self.connect(layer, id, &selected);
+        }
+
+        self.levels.insert(id, level);
+        self.data.insert(id, record);
+
+        if level > entry_level {
+            self.entry_point = Some(id);
+        }
+    }
+}
+
+impl IndexOps for IndexHNSW {
+    fn new(params: impl IndexParams) -> Result<Self, Error> {
+        let params = downcast_params::<ParamsHNSW>(params)?;
+        let index = IndexHNSW {
+            params,
+            metadata: IndexMetadata::default(),
+            data: HashMap::new(),
+
+            layers: vec![Layer::new()],
+            levels: HashMap::new(),
+            entry_point: None,
+            tombstones: HashSet::new(),
+        };
+
+        Ok(index)
+    }
+}
+
+impl VectorIndex for IndexHNSW {
+    fn metric(&self) -> &DistanceMetric {
+        &self.params.metric
+    }
+
+    fn metadata(&self) -> &IndexMetadata {
+        &self.metadata
+    }
+
+    fn build(
+        &mut self,
+        records: HashMap<RecordID, Record>,
+    ) -> Result<(), Error> {
+        self.metadata.built = true;
+        self.insert(records)
+    }
+
+    fn insert(
+        &mut self,
+        records: HashMap<RecordID, Record>,
+    ) -> Result<(), Error> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        self.metadata.last_inserted = records.keys().max().copied();
+        for (id, record) in records {
+            self.insert_one(id, record);
+        }
+
+        Ok(())
+    }
+
+    fn delete(&mut self, ids: Vec<RecordID>) -> Result<(), Error> {
+        // Tombstone rather than unlink: removing a node's links outright
+        // would require re-running the neighbor heuristic on everything
+        // that pointed to it to keep the graph connected. Marking it
+        // skipped is cheap and the usual approach for HNSW deletes.
+        for id in &ids {
+            self.tombstones.insert(*id);
+        }
+
+        if self.entry_point.as_ref().is_some_and(|id| self.tombstones.contains(id)) {
+            self.entry_point =
+                self.levels.keys().find(|id| !self.tombstones.contains(id)).copied();
+        }
+
+        Ok(())
+    }
+
+    fn search(
+        &self,
+        query: Vector,
+        params: SearchParams,
+        filters: Filters,
+    ) -> Result<Vec<SearchResult>, Error> {
+        params.validate()?;
+        let k = params.k;
+
+        let entry_point = match self.entry_point {
+            Some(entry_point) => entry_point,
+            None => return Ok(vec![]),
+        };
+
+        let top_level = self.levels[&entry_point];
+        let mut nearest = entry_point;
+        for layer in (1..=top_level).rev() {
+            nearest = self.greedy_search(layer, nearest, &query);
+        }
+
+        // `candidates` overrides the configured `ef_search` for this query
+        // only, letting callers trade recall for latency without mutating
+        // the index.
+        let ef = params.candidates.unwrap_or(self.params.ef_search).max(k);
+        let candidates = self.layer_search(0, &[nearest], &query, ef);
+
+        let mut results = BinaryHeap::new();
+        for candidate in candidates {
+            let record = match self.data.get(&candidate.id) {
+                Some(record) => record,
+                None => continue,
+            };
+
+            if !filters.apply(&record.data) {
+                continue;
+            }
+
+            let data = record.data.clone();
+            results.push(SearchResult {
+                id: candidate.id,
+                distance: candidate.distance,
+                data,
+            });
+
+            if results.len() > k {
+                results.pop();
+            }
+        }
+
+        Ok(results.into_sorted_vec())
+    }
+
+    fn len(&self) -> usize {
+        self.data.len().saturating_sub(self.tombstones.len())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Parameters for IndexHNSW.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParamsHNSW {
+    /// Maximum number of neighbors kept per node per layer.
+    pub m: usize,
+    /// Size of the dynamic candidate list used while inserting nodes.
+    pub ef_construction: usize,
+    /// Size of the dynamic candidate list used while searching. Unlike the
+    /// other parameters, this can be tuned after the index has been built
+    /// to trade recall for latency without rebuilding the graph.
+    pub ef_search: usize,
+    /// Metric used to compute the distance between vectors.
+    pub metric: DistanceMetric,
+}
+
+impl Default for ParamsHNSW {
+    fn default() -> Self {
+        Self {
+            m: 16,
+            ef_construction: 128,
+            ef_search: 64,
+            metric: DistanceMetric::Euclidean,
+        }
+    }
+}
+
+impl IndexParams for ParamsHNSW {
+    fn metric(&self) -> &DistanceMetric {
+        &self.metric
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Search candidate ranked by distance to the query, used to trim the
+/// best-first expansion buffers down to `ef`.
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    id: RecordID,
+    distance: f32,
+}
+
+impl Eq for Candidate {}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance.partial_cmp(&other.distance).unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hnsw_index() {
+        let params = ParamsHNSW { ef_construction: 64, ..Default::default() };
+        let mut index = IndexHNSW::new(params).unwrap();
+
+        index_tests::populate_index(&mut index);
+        index_tests::test_basic_search(&index);
+        index_tests::test_advanced_search(&index);
+    }
+
+    #[test]
+    fn test_hnsw_ef_search_is_tunable() {
+        let params = ParamsHNSW::default();
+        let mut index = IndexHNSW::new(params).unwrap();
+        index_tests::populate_index(&mut index);
+
+        index.params.ef_search = 1;
+        let query = Vector::from(vec![0.0; 128]);
+        let low_ef =
+            index.search(query.clone(), SearchParams::new(10), Filters::NONE).unwrap();
+
+        index.params.ef_search = 128;
+        let high_ef = index.search(query, SearchParams::new(10), Filters::NONE).unwrap();
+
+        assert_eq!(low_ef.len(), 10);
+        assert_eq!(high_ef.len(), 10);
+    }
+
+    #[test]
+    fn test_hnsw_candidates_override_with_low_ef_search() {
+        let params = ParamsHNSW { ef_construction: 64, ef_search: 1, ..Default::default() };
+        let mut index = IndexHNSW::new(params).unwrap();
+        index_tests::populate_index(&mut index);
+
+        let query = Vector::from(vec![0.0; 128]);
+        let overridden = index
+            .search(query, SearchParams::new(10).with_candidates(128), Filters::NONE)
+            .unwrap();
+
+        assert_eq!(overridden.len(), 10);
+    }
+}
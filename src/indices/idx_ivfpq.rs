@@ -1,7 +1,10 @@
 use super::*;
 use crate::utils::kmeans::{ClusterID, KMeans, Vectors};
+use memmap2::Mmap;
 use rand::seq::IteratorRandom;
 use std::cmp::Ordering;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Seek, SeekFrom, Write};
 use std::rc::Rc;
 
 /// Inverted File index with Product Quantization.
@@ -24,7 +27,13 @@ pub struct IndexIVFPQ {
 
 impl IndexIVFPQ {
     /// Creates the codebook for the Product Quantization.
-    /// - `vectors`: Dataset to create the codebook from.
+    /// - `vectors`: Dataset of residuals (vector minus its assigned IVF
+    ///   centroid) to create the codebook from.
+    ///
+    /// Training on residuals rather than full vectors keeps the codebook
+    /// focused on the variation within a cluster instead of the
+    /// variation between clusters, which the IVF centroids already
+    /// capture, improving recall for the same `sub_centroids` budget.
     ///
     /// The size of the dataset should be large enough to cover the
     /// entire vector space to ensure the codebook represents the
@@ -123,17 +132,92 @@ impl IndexIVFPQ {
             .into()
     }
 
-    /// Reconstructs a full-length vector from a PQ vector.
-    /// - `vector_pq`: PQ vector data.
-    fn dequantize_vector(&self, vector_pq: &VectorPQ) -> Vector {
-        vector_pq
+    /// Reconstructs a full-length vector from a PQ vector quantized
+    /// against `cluster`'s residual space.
+    /// - `vector_pq`: PQ vector data, encoding a residual.
+    /// - `cluster`: IVF cluster the record was assigned to, whose
+    ///   centroid must be added back to undo the residual subtraction
+    ///   done before quantization.
+    fn dequantize_vector(&self, vector_pq: &VectorPQ, cluster: ClusterID) -> Vector {
+        let residual: Vector = vector_pq
             .0
             .par_iter()
             .enumerate()
             .map(|(i, code_id)| self.codebook[i][*code_id as usize].to_vec())
             .flatten()
             .collect::<Vec<f32>>()
-            .into()
+            .into();
+
+        Self::add(&residual, &self.centroids[cluster.to_usize()])
+    }
+
+    /// Elementwise vector subtraction, `a - b`.
+    fn subtract(a: &Vector, b: &Vector) -> Vector {
+        a.0.iter().zip(b.0.iter()).map(|(x, y)| x - y).collect::<Vec<f32>>().into()
+    }
+
+    /// Elementwise vector addition, `a + b`.
+    fn add(a: &Vector, b: &Vector) -> Vector {
+        a.0.iter().zip(b.0.iter()).map(|(x, y)| x + y).collect::<Vec<f32>>().into()
+    }
+
+    /// Builds a per-subspace asymmetric distance computation (ADC) table
+    /// from `reference` to every codebook centroid in that subspace.
+    ///
+    /// For [`DistanceMetric::Euclidean`]/[`DistanceMetric::Cosine`],
+    /// `reference` must be the query residual relative to the *same*
+    /// cluster's centroid (`query - centroids[cid]`), since records are
+    /// quantized against their cluster's residual space (see
+    /// [`Self::create_codebook`]): squared Euclidean distance is
+    /// translation-invariant, so the cluster centroid cancels out of
+    /// `squared_euclidean(query - c, stored - c)` and the table comes out
+    /// directly comparable across every probed cluster.
+    ///
+    /// Dot product has no such invariance, so for
+    /// [`DistanceMetric::DotProduct`] `reference` must instead be the
+    /// *unmodified* query; [`Self::search`] adds the cluster centroid's
+    /// own contribution back in separately so results from different
+    /// probed clusters stay comparable — see the `offset` it computes.
+    ///
+    /// The approximate distance to any stored [`VectorPQ`] is then just
+    /// the sum of `table[i][code_i]` over subspaces (see
+    /// [`Self::adc_distance`]), instead of reconstructing the full vector
+    /// via [`Self::dequantize_vector`] and computing an exact distance.
+    fn build_distance_tables(&self, reference: &Vector) -> Vec<Vec<f32>> {
+        (0..self.params.sub_dimension as usize)
+            .into_par_iter()
+            .map(|i| {
+                let subvector = self.get_subvector(i, reference);
+                self.codebook[i]
+                    .iter()
+                    .map(|centroid| match self.metric() {
+                        DistanceMetric::DotProduct => {
+                            -Self::dot(centroid, &subvector)
+                        }
+                        _ => Self::squared_euclidean(centroid, &subvector),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Sums per-subspace table lookups built by
+    /// [`Self::build_distance_tables`] into an approximate distance
+    /// between the query used to build `tables` and a stored PQ code.
+    fn adc_distance(tables: &[Vec<f32>], code: &VectorPQ) -> f32 {
+        code.0
+            .iter()
+            .enumerate()
+            .map(|(i, &code_id)| tables[i][code_id as usize])
+            .sum()
+    }
+
+    fn squared_euclidean(a: &Vector, b: &Vector) -> f32 {
+        a.0.iter().zip(b.0.iter()).map(|(x, y)| (x - y).powi(2)).sum()
+    }
+
+    fn dot(a: &Vector, b: &Vector) -> f32 {
+        a.0.iter().zip(b.0.iter()).map(|(x, y)| x * y).sum()
     }
 
     /// Extracts a subvector from a full-length vector.
@@ -146,6 +230,32 @@ impl IndexIVFPQ {
         let subvector = vector.0[start..end].to_vec();
         Vector(subvector.into_boxed_slice())
     }
+
+    /// Normalizes `vector` to unit length when the configured metric is
+    /// [`DistanceMetric::Cosine`], leaving it untouched otherwise.
+    ///
+    /// Cosine similarity is magnitude-independent, but IVFPQ's centroid
+    /// maintenance and ADC tables all operate on squared Euclidean
+    /// distance internally, which only tracks angular distance when
+    /// every vector involved is unit length. Normalizing on the way in
+    /// (records, training samples, and queries alike) keeps assignment,
+    /// the codebook, and centroid averaging all consistent with cosine.
+    fn prepare_vector(&self, vector: &Vector) -> Vector {
+        if !matches!(self.metric(), DistanceMetric::Cosine) {
+            return vector.clone();
+        }
+
+        Self::normalize(vector)
+    }
+
+    fn normalize(vector: &Vector) -> Vector {
+        let norm = vector.0.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm == 0.0 {
+            return vector.clone();
+        }
+
+        vector.0.iter().map(|x| x / norm).collect::<Vec<f32>>().into()
+    }
 }
 
 impl IndexOps for IndexIVFPQ {
@@ -161,6 +271,20 @@ impl IndexOps for IndexIVFPQ {
             return Err(Error::new(code, message));
         }
 
+        // Manhattan and Hamming aren't supported: centroid maintenance
+        // and the ADC tables both operate on squared Euclidean distance
+        // internally (see `prepare_vector`/`build_distance_tables`),
+        // which is only a meaningful proxy for Euclidean and Cosine (once
+        // normalized) or DotProduct (which needs no normalization at
+        // all). Manhattan/Hamming assignment under a Euclidean-shaped
+        // index would silently rank candidates inconsistently with the
+        // configured metric.
+        if matches!(params.metric, DistanceMetric::Manhattan | DistanceMetric::Hamming) {
+            let code = ErrorCode::RequestError;
+            let message = "IndexIVFPQ only supports the Euclidean, Cosine, and DotProduct metrics.";
+            return Err(Error::new(code, message));
+        }
+
         let index = IndexIVFPQ {
             params,
             metadata: IndexMetadata::default(),
@@ -190,19 +314,26 @@ impl VectorIndex for IndexIVFPQ {
     ) -> Result<(), Error> {
         let mut rng = rand::thread_rng();
         let sample = (records.len() as f32 * self.params.sampling) as usize;
-        let vectors = records
+
+        // Normalized up front (a no-op unless the metric is Cosine) so
+        // the IVF centroids and codebook are trained in the same space
+        // `insert` will assign and quantize records in.
+        let sampled: Vec<Vector> = records
             .values()
             .choose_multiple(&mut rng, sample)
             .par_iter()
-            .map(|&record| &record.vector)
-            .collect::<Vec<&Vector>>();
+            .map(|&record| self.prepare_vector(&record.vector))
+            .collect();
 
         // We use RC to avoid cloning the entire vector data as it
         // can be very large and expensive to clone.
-        let vectors: Vectors = Rc::from(vectors.as_slice());
-        self.create_codebook(vectors.clone());
+        let vectors: Vectors = {
+            let refs = sampled.iter().collect::<Vec<&Vector>>();
+            Rc::from(refs.as_slice())
+        };
 
-        // Run KMeans to find the centroids for the IVF.
+        // Run KMeans to find the centroids for the IVF first, since the
+        // codebook is trained on residuals relative to these centroids.
         let centroids = {
             let mut kmeans = KMeans::new(
                 self.params.centroids,
@@ -215,6 +346,22 @@ impl VectorIndex for IndexIVFPQ {
         };
 
         self.centroids = centroids;
+
+        let residuals: Vec<Vector> = vectors
+            .iter()
+            .map(|vector| {
+                let cid = self.find_nearest_centroids(vector, 1)[0].to_usize();
+                Self::subtract(vector, &self.centroids[cid])
+            })
+            .collect();
+
+        let residuals: Vectors = {
+            let residuals = residuals.iter().collect::<Vec<&Vector>>();
+            Rc::from(residuals.as_slice())
+        };
+
+        self.create_codebook(residuals);
+
         self.metadata.built = true;
         self.insert(records)?;
         Ok(())
@@ -234,26 +381,45 @@ impl VectorIndex for IndexIVFPQ {
             return Err(Error::new(code, message));
         }
 
+        // Assigns each record to its nearest centroid and computes the
+        // residual against it, ahead of that centroid being moved by the
+        // online update below. The assignment is sequential since each
+        // record's cluster update can shift the centroid the next record
+        // in the same cluster is compared and quantized against.
+        let mut assignments: HashMap<RecordID, (ClusterID, Vector)> =
+            HashMap::with_capacity(records.len());
+
         for (id, record) in records.iter() {
-            let vector = &record.vector;
-            let cid = self.find_nearest_centroids(vector, 1)[0].to_usize();
+            let vector = self.prepare_vector(&record.vector);
+            let cid = self.find_nearest_centroids(&vector, 1)[0];
+            let cid_idx = cid.to_usize();
+            let residual = Self::subtract(&vector, &self.centroids[cid_idx]);
 
             // The number of records in the cluster.
-            let count = self.clusters[cid].len() as f32;
+            let count = self.clusters[cid_idx].len() as f32;
             let new_count = count + 1.0;
 
             // This updates the centroid of the cluster by taking the
             // weighted average of the existing centroid and the new
             // vector that is being inserted.
-            let centroid: Vec<f32> = self.centroids[cid]
+            let centroid: Vec<f32> = self.centroids[cid_idx]
                 .to_vec()
                 .par_iter()
                 .zip(vector.to_vec().par_iter())
                 .map(|(c, v)| ((c * count) + v) / new_count)
                 .collect();
 
-            self.centroids[cid] = centroid.into();
-            self.clusters[cid].push(id.to_owned());
+            let mut centroid: Vector = centroid.into();
+            if matches!(self.metric(), DistanceMetric::Cosine) {
+                // Keep the centroid a unit vector, consistent with every
+                // other vector in cosine space, instead of letting the
+                // plain weighted average drift off the unit sphere.
+                centroid = Self::normalize(&centroid);
+            }
+
+            self.centroids[cid_idx] = centroid;
+            self.clusters[cid_idx].push(id.to_owned());
+            assignments.insert(*id, (cid, residual));
         }
 
         self.metadata.last_inserted = records.keys().max().copied();
@@ -261,9 +427,10 @@ impl VectorIndex for IndexIVFPQ {
         let records: HashMap<RecordID, RecordPQ> = records
             .into_par_iter()
             .map(|(id, record)| {
-                let vector = self.quantize_vector(&record.vector);
+                let (cluster, residual) = assignments[&id].clone();
+                let vector = self.quantize_vector(&residual);
                 let data = record.data;
-                (id, RecordPQ { vector, data })
+                (id, RecordPQ { vector, cluster, data })
             })
             .collect();
 
@@ -283,26 +450,66 @@ impl VectorIndex for IndexIVFPQ {
     fn search(
         &self,
         query: Vector,
-        k: usize,
+        params: SearchParams,
         filters: Filters,
     ) -> Result<Vec<SearchResult>, Error> {
+        params.validate()?;
+        let k = params.k;
+
+        // Records were assigned and quantized in normalized space (for
+        // Cosine), so the query must land in that same space too.
+        let query = self.prepare_vector(&query);
+
         let nearest_centroids = {
-            let nprobes = self.params.num_probes as usize;
+            // `num_probes` overrides the configured exploration factor
+            // for this query only.
+            let nprobes = params.num_probes.unwrap_or(self.params.num_probes as usize);
             self.find_nearest_centroids(&query, nprobes)
         };
 
+        // `candidates` caps the total number of records examined across
+        // every probed cluster, bounding search cost independently of
+        // `num_probes`.
+        let mut examined = 0usize;
         let mut results = BinaryHeap::new();
         for centroid_id in nearest_centroids {
+            let cluster_centroid = &self.centroids[centroid_id.to_usize()];
+
+            // Records in this cluster are quantized against its
+            // centroid's residual space, so the ADC table must be built
+            // from the query's residual relative to this same centroid —
+            // except for DotProduct, which has no translation invariance
+            // to lean on, so the table is built from the raw query and
+            // the cluster centroid's own contribution is added back as a
+            // separate per-cluster offset. See `build_distance_tables`.
+            let (tables, offset) = match self.metric() {
+                DistanceMetric::DotProduct => {
+                    let tables = self.build_distance_tables(&query);
+                    (tables, -Self::dot(&query, cluster_centroid))
+                }
+                _ => {
+                    let residual = Self::subtract(&query, cluster_centroid);
+                    (self.build_distance_tables(&residual), 0.0)
+                }
+            };
+
             let cluster = &self.clusters[centroid_id.to_usize()];
             for &record_id in cluster {
+                if let Some(cap) = params.candidates {
+                    if examined >= cap {
+                        break;
+                    }
+                }
+                examined += 1;
+
                 let record = self.data.get(&record_id).unwrap();
                 let data = record.data.clone();
                 if !filters.apply(&data) {
                     continue;
                 }
 
-                let vector = self.dequantize_vector(&record.vector);
-                let distance = self.metric().distance(&vector, &query);
+                let distance =
+                    Self::adc_distance(&tables, &record.vector) + offset;
                 results.push(SearchResult { id: record_id, distance, data });
 
                 if results.len() > k {
@@ -323,6 +530,317 @@ impl VectorIndex for IndexIVFPQ {
     }
 }
 
+/// On-disk header for [`IndexIVFPQ::persist_mmap`]/[`IndexIVFPQMmap::load_mmap`].
+///
+/// Written last, after each section's raw bincode bytes back to back,
+/// with its length stored in the file's first 8 bytes. Recording every
+/// section's byte range lets
+/// [`IndexIVFPQMmap::load_mmap`] map the whole file once and only
+/// deserialize the centroids and codebook eagerly, leaving each cluster's
+/// inverted list to be deserialized on demand from the mapped bytes as
+/// [`IndexIVFPQMmap::search`] probes it.
+#[derive(Debug, Serialize, Deserialize)]
+struct MmapHeader {
+    params: ParamsIVFPQ,
+    metadata: IndexMetadata,
+    centroids_range: (u64, u64),
+    codebook_range: (u64, u64),
+    /// Byte range of each cluster's serialized `Vec<(RecordID, RecordPQ)>`,
+    /// indexed by cluster id.
+    cluster_ranges: Vec<(u64, u64)>,
+    total_records: usize,
+}
+
+impl IndexIVFPQ {
+    /// Persists this index in the lazily-loadable layout read by
+    /// [`IndexIVFPQMmap::load_mmap`].
+    ///
+    /// Unlike [`IndexOps::persist`], which bincode-serializes the whole
+    /// struct in one shot, this writes the header last so its offsets can
+    /// point at sections already written, and splits `data` out into one
+    /// section per cluster so a query only has to read the `nprobe`
+    /// clusters it actually probes back off disk.
+    pub fn persist_mmap(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let io_error = |error: std::io::Error| {
+            Error::new(ErrorCode::InternalError, format!("Failed to persist mmap index: {error}"))
+        };
+
+        let mut file = BufWriter::new(File::create(path.as_ref()).map_err(io_error)?);
+
+        // Reserve space for the header; its real length-prefixed form is
+        // written last, once every offset below it is known.
+        file.write_all(&[0u8; 8]).map_err(io_error)?;
+
+        let mut offset = 8u64;
+        let mut section = |file: &mut BufWriter<File>, bytes: Vec<u8>| -> Result<(u64, u64), Error> {
+            let range = (offset, bytes.len() as u64);
+            file.write_all(&bytes).map_err(io_error)?;
+            offset += bytes.len() as u64;
+            Ok(range)
+        };
+
+        let centroids_range = section(&mut file, bincode::serialize(&self.centroids).unwrap())?;
+        let codebook_range = section(&mut file, bincode::serialize(&self.codebook).unwrap())?;
+
+        let mut cluster_ranges = Vec::with_capacity(self.clusters.len());
+        for cluster in &self.clusters {
+            let records: Vec<(RecordID, &RecordPQ)> = cluster
+                .iter()
+                .map(|id| (*id, self.data.get(id).unwrap()))
+                .collect();
+
+            cluster_ranges.push(section(&mut file, bincode::serialize(&records).unwrap())?);
+        }
+
+        let header = MmapHeader {
+            params: self.params.clone(),
+            metadata: self.metadata.clone(),
+            centroids_range,
+            codebook_range,
+            cluster_ranges,
+            total_records: self.data.len(),
+        };
+
+        let header_bytes = bincode::serialize(&header).unwrap();
+        file.write_all(&header_bytes).map_err(io_error)?;
+        file.flush().map_err(io_error)?;
+
+        // Backfill the length prefix reserved at the start of the file.
+        let mut file = file.into_inner().map_err(|error| io_error(error.into_error()))?;
+        file.seek(SeekFrom::Start(0)).map_err(io_error)?;
+        file.write_all(&header_bytes.len().to_le_bytes()).map_err(io_error)?;
+
+        Ok(())
+    }
+}
+
+/// Memory-mapped, lazily-loaded counterpart to [`IndexIVFPQ`].
+///
+/// Built for IVFPQ indexes too large to comfortably deserialize and hold
+/// in memory in full. [`Self::load_mmap`] maps the index file and eagerly
+/// reads only its header, centroids, and codebook; each cluster's
+/// inverted list is deserialized straight from the mapped bytes each
+/// time [`Self::search`] probes it, so resident memory stays bounded by
+/// query traffic rather than total index size. Read-only: this is a
+/// search-serving view, not a mutable index, so build/insert/delete are
+/// not supported here — build with [`IndexIVFPQ`] and write a loadable
+/// copy with [`IndexIVFPQ::persist_mmap`].
+#[derive(Debug)]
+pub struct IndexIVFPQMmap {
+    params: ParamsIVFPQ,
+    metadata: IndexMetadata,
+    centroids: Vec<Vector>,
+    codebook: Vec<Vec<Vector>>,
+    cluster_ranges: Vec<(u64, u64)>,
+    total_records: usize,
+    mmap: Mmap,
+}
+
+impl IndexIVFPQMmap {
+    /// Maps `path` and eagerly loads its header, centroids, and codebook,
+    /// leaving every cluster's inverted list to be faulted in on demand.
+    pub fn load_mmap(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let io_error = |error: std::io::Error| {
+            Error::new(ErrorCode::InternalError, format!("Failed to load mmap index: {error}"))
+        };
+
+        let file = OpenOptions::new().read(true).open(path.as_ref()).map_err(io_error)?;
+        // Safety: the file is treated as immutable for the mapping's
+        // lifetime, matching the read-only contract the rest of this type
+        // upholds; nothing else in this process writes to it.
+        let mmap = unsafe { Mmap::map(&file).map_err(io_error)? };
+
+        let header_len = u64::from_le_bytes(mmap[0..8].try_into().unwrap()) as usize;
+        // The header follows every section, at the very end of the file.
+        let header_start = mmap.len() - header_len;
+        let header: MmapHeader = bincode::deserialize(&mmap[header_start..header_start + header_len])
+            .map_err(|error| {
+                Error::new(ErrorCode::InternalError, format!("Failed to parse mmap index header: {error}"))
+            })?;
+
+        let read_section = |range: (u64, u64)| -> &[u8] {
+            let start = range.0 as usize;
+            let end = start + range.1 as usize;
+            &mmap[start..end]
+        };
+
+        let centroids = bincode::deserialize(read_section(header.centroids_range)).map_err(|error| {
+            Error::new(ErrorCode::InternalError, format!("Failed to parse mmap centroids: {error}"))
+        })?;
+
+        let codebook = bincode::deserialize(read_section(header.codebook_range)).map_err(|error| {
+            Error::new(ErrorCode::InternalError, format!("Failed to parse mmap codebook: {error}"))
+        })?;
+
+        Ok(IndexIVFPQMmap {
+            params: header.params,
+            metadata: header.metadata,
+            centroids,
+            codebook,
+            cluster_ranges: header.cluster_ranges,
+            total_records: header.total_records,
+            mmap,
+        })
+    }
+
+    /// Deserializes a single cluster's inverted list straight from the
+    /// mapped bytes, only touching the pages that back its byte range.
+    fn load_cluster(&self, cluster_id: ClusterID) -> Vec<(RecordID, RecordPQ)> {
+        let (start, len) = self.cluster_ranges[cluster_id.to_usize()];
+        let (start, end) = (start as usize, start as usize + len as usize);
+        bincode::deserialize(&self.mmap[start..end]).unwrap_or_default()
+    }
+
+    fn find_nearest_centroids(&self, vector: &Vector, k: usize) -> Vec<ClusterID> {
+        let mut centroids = BinaryHeap::new();
+        for (i, center) in self.centroids.iter().enumerate() {
+            let id = ClusterID(i as u16);
+            let distance = self.metric().distance(center, vector);
+
+            let centroid = NearestCentroid { id, distance };
+            centroids.push(centroid);
+
+            if centroids.len() > k {
+                centroids.pop();
+            }
+        }
+
+        centroids.into_sorted_vec().into_iter().map(|centroid| centroid.id).collect()
+    }
+
+    /// See [`IndexIVFPQ::build_distance_tables`]. `reference` must already
+    /// be relative to the centroid of the cluster being probed, except
+    /// for [`DistanceMetric::DotProduct`], where it must be the raw query.
+    fn build_distance_tables(&self, reference: &Vector) -> Vec<Vec<f32>> {
+        (0..self.params.sub_dimension as usize)
+            .into_par_iter()
+            .map(|i| {
+                let dim = reference.len() / self.params.sub_dimension as usize;
+                let start = i * dim;
+                let end = (i + 1) * dim;
+                let subvector =
+                    Vector(reference.0[start..end].to_vec().into_boxed_slice());
+
+                self.codebook[i]
+                    .iter()
+                    .map(|centroid| match self.metric() {
+                        DistanceMetric::DotProduct => {
+                            -IndexIVFPQ::dot(centroid, &subvector)
+                        }
+                        _ => {
+                            IndexIVFPQ::squared_euclidean(centroid, &subvector)
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn subtract(a: &Vector, b: &Vector) -> Vector {
+        a.0.iter().zip(b.0.iter()).map(|(x, y)| x - y).collect::<Vec<f32>>().into()
+    }
+}
+
+impl VectorIndex for IndexIVFPQMmap {
+    fn metric(&self) -> &DistanceMetric {
+        &self.params.metric
+    }
+
+    fn metadata(&self) -> &IndexMetadata {
+        &self.metadata
+    }
+
+    fn build(&mut self, _records: HashMap<RecordID, Record>) -> Result<(), Error> {
+        let code = ErrorCode::RequestError;
+        let message = "IndexIVFPQMmap is read-only; build with IndexIVFPQ and call persist_mmap.";
+        Err(Error::new(code, message))
+    }
+
+    fn insert(&mut self, _records: HashMap<RecordID, Record>) -> Result<(), Error> {
+        let code = ErrorCode::RequestError;
+        let message = "IndexIVFPQMmap is read-only; build with IndexIVFPQ and call persist_mmap.";
+        Err(Error::new(code, message))
+    }
+
+    fn delete(&mut self, _ids: Vec<RecordID>) -> Result<(), Error> {
+        let code = ErrorCode::RequestError;
+        let message = "IndexIVFPQMmap is read-only; build with IndexIVFPQ and call persist_mmap.";
+        Err(Error::new(code, message))
+    }
+
+    /// Identical probing strategy to [`IndexIVFPQ::search`], except each
+    /// probed cluster's inverted list is deserialized from the mapped
+    /// file as it's visited instead of already residing in memory.
+    fn search(
+        &self,
+        query: Vector,
+        params: SearchParams,
+        filters: Filters,
+    ) -> Result<Vec<SearchResult>, Error> {
+        params.validate()?;
+        let k = params.k;
+
+        // See `IndexIVFPQ::prepare_vector`.
+        let query =
+            if matches!(self.metric(), DistanceMetric::Cosine) { IndexIVFPQ::normalize(&query) } else { query };
+
+        let nearest_centroids = {
+            let nprobes = params.num_probes.unwrap_or(self.params.num_probes as usize);
+            self.find_nearest_centroids(&query, nprobes)
+        };
+
+        let mut examined = 0usize;
+        let mut results = BinaryHeap::new();
+        for centroid_id in nearest_centroids {
+            let cluster_centroid = &self.centroids[centroid_id.to_usize()];
+
+            // See `IndexIVFPQ::search`.
+            let (tables, offset) = match self.metric() {
+                DistanceMetric::DotProduct => {
+                    let tables = self.build_distance_tables(&query);
+                    (tables, -IndexIVFPQ::dot(&query, cluster_centroid))
+                }
+                _ => {
+                    let residual = Self::subtract(&query, cluster_centroid);
+                    (self.build_distance_tables(&residual), 0.0)
+                }
+            };
+
+            for (record_id, record) in self.load_cluster(centroid_id) {
+                if let Some(cap) = params.candidates {
+                    if examined >= cap {
+                        break;
+                    }
+                }
+                examined += 1;
+
+                let data = record.data.clone();
+                if !filters.apply(&data) {
+                    continue;
+                }
+
+                let distance =
+                    IndexIVFPQ::adc_distance(&tables, &record.vector) + offset;
+                results.push(SearchResult { id: record_id, distance, data });
+
+                if results.len() > k {
+                    results.pop();
+                }
+            }
+        }
+
+        Ok(results.into_sorted_vec())
+    }
+
+    fn len(&self) -> usize {
+        self.total_records
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
 /// Parameters for IndexIVFPQ.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParamsIVFPQ {
@@ -419,13 +937,62 @@ mod tests {
         };
 
         let mut index = IndexIVFPQ::new(params).unwrap();
+        // A single zero centroid means the residual equals the vector
+        // itself, keeping this an exact round-trip check.
+        index.centroids = vec![vec![0.0; 4].into()];
         index.create_codebook(vectors);
 
         let encoded = index.quantize_vector(&data[0]);
-        let decoded = index.dequantize_vector(&encoded);
+        let decoded = index.dequantize_vector(&encoded, ClusterID(0));
         assert_eq!(decoded.to_vec(), data[0].to_vec());
     }
 
+    #[test]
+    fn test_adc_distance_matches_exact_reconstruction() {
+        let data: Vec<Vector> = vec![
+            vec![1.0, 2.0, 3.0, 4.0].into(),
+            vec![5.0, 6.0, 7.0, 8.0].into(),
+            vec![9.0, 10.0, 11.0, 12.0].into(),
+            vec![13.0, 14.0, 15.0, 16.0].into(),
+        ];
+
+        let vectors: Vectors = {
+            let data = data.iter().collect::<Vec<&Vector>>();
+            Rc::from(data.as_slice())
+        };
+
+        let params = ParamsIVFPQ {
+            max_iterations: 10,
+            sub_centroids: 8,
+            sub_dimension: 2,
+            sampling: 1.0,
+            ..Default::default()
+        };
+
+        let mut index = IndexIVFPQ::new(params).unwrap();
+        // A single zero centroid means the residual equals the vector
+        // itself, so the table built from the raw query is directly
+        // comparable to codes quantized against this cluster.
+        index.centroids = vec![vec![0.0; 4].into()];
+        index.create_codebook(vectors);
+
+        let query: Vector = vec![2.0, 3.0, 4.0, 5.0].into();
+        let tables = index.build_distance_tables(&query);
+
+        for vector in &data {
+            let encoded = index.quantize_vector(vector);
+            let adc = IndexIVFPQ::adc_distance(&tables, &encoded);
+
+            let reconstructed = index.dequantize_vector(&encoded, ClusterID(0));
+            let exact = DistanceMetric::Euclidean.distance(&reconstructed, &query);
+
+            // Both are squared Euclidean distance against the same
+            // reconstructed code, so they should match exactly modulo
+            // floating point rounding from summing in a different order.
+            assert!((adc - exact).abs() < 1e-3, "adc={adc} exact={exact}");
+        }
+    }
+
     #[test]
     fn test_ivfpq_index() {
         let params = ParamsIVFPQ {
@@ -440,4 +1007,155 @@ mod tests {
         index_tests::test_basic_search(&index);
         index_tests::test_advanced_search(&index);
     }
+
+    /// Builds a tiny 4-record, 4-dimension index under `metric`, letting
+    /// each metric-specific test decide how to tell its neighbors apart.
+    fn build_metric_index(metric: DistanceMetric) -> (IndexIVFPQ, Vec<RecordID>) {
+        let params = ParamsIVFPQ {
+            centroids: 2,
+            max_iterations: 10,
+            sub_centroids: 4,
+            sub_dimension: 2,
+            sampling: 1.0,
+            metric,
+            ..Default::default()
+        };
+
+        let mut index = IndexIVFPQ::new(params).unwrap();
+
+        let vectors: Vec<Vector> = vec![
+            vec![1.0, 0.0, 0.0, 0.0].into(),  // id 0: direction A, unit length
+            vec![0.0, 1.0, 0.0, 0.0].into(),  // id 1: direction B
+            vec![10.0, 0.0, 0.0, 0.0].into(), // id 2: direction A, 10x magnitude
+            vec![0.0, 0.0, 1.0, 0.0].into(),  // id 3: direction C
+        ];
+
+        let ids: Vec<RecordID> = (0..vectors.len() as u32).map(RecordID).collect();
+        let records = ids
+            .iter()
+            .zip(vectors)
+            .map(|(&id, vector)| (id, Record { vector, data: HashMap::new() }))
+            .collect();
+
+        index.build(records).unwrap();
+        (index, ids)
+    }
+
+    #[test]
+    fn test_ivfpq_cosine_metric() {
+        let (index, ids) = build_metric_index(DistanceMetric::Cosine);
+
+        // id 0 and id 2 point in the same direction, so cosine distance
+        // can't tell them apart, but both must rank strictly closer than
+        // the orthogonal id 1 and id 3.
+        let query: Vector = vec![1.0, 0.0, 0.0, 0.0].into();
+        let results = index.search(query, SearchParams::new(2), Filters::NONE).unwrap();
+
+        assert_eq!(results.len(), 2);
+        let top_ids: Vec<RecordID> = results.iter().map(|r| r.id).collect();
+        assert!(top_ids.contains(&ids[0]));
+        assert!(top_ids.contains(&ids[2]));
+    }
+
+    #[test]
+    fn test_ivfpq_dotproduct_metric() {
+        let (index, ids) = build_metric_index(DistanceMetric::DotProduct);
+
+        // Dot product (unlike cosine) is magnitude-sensitive, so the 10x
+        // scaled id 2 must outrank the unit-length id 0 despite both
+        // pointing in the same direction as the query.
+        let query: Vector = vec![1.0, 0.0, 0.0, 0.0].into();
+        let results = index.search(query, SearchParams::new(1), Filters::NONE).unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, ids[2]);
+    }
+
+    #[test]
+    fn test_ivfpq_rejects_unsupported_metrics() {
+        for metric in [DistanceMetric::Manhattan, DistanceMetric::Hamming] {
+            let params = ParamsIVFPQ { metric, ..Default::default() };
+            assert!(IndexIVFPQ::new(params).is_err());
+        }
+    }
+
+    #[test]
+    fn test_ivfpq_search_rejects_invalid_params() {
+        let params = ParamsIVFPQ {
+            centroids: 5,
+            max_iterations: 20,
+            sampling: 1.0,
+            ..Default::default()
+        };
+
+        let mut index = IndexIVFPQ::new(params).unwrap();
+        index_tests::populate_index(&mut index);
+        let query = Vector::from(vec![0.0; 128]);
+
+        let zero_k = index.search(query.clone(), SearchParams::new(0), Filters::NONE);
+        assert!(zero_k.is_err());
+
+        let zero_probes =
+            index.search(query, SearchParams::new(10).with_num_probes(0), Filters::NONE);
+        assert!(zero_probes.is_err());
+    }
+
+    #[test]
+    fn test_ivfpq_num_probes_override_affects_recall() {
+        let params = ParamsIVFPQ {
+            centroids: 20,
+            max_iterations: 20,
+            sampling: 1.0,
+            num_probes: 1,
+            ..Default::default()
+        };
+
+        let mut index = IndexIVFPQ::new(params).unwrap();
+        index_tests::populate_index(&mut index);
+
+        let query = Vector::from(vec![0.0; 128]);
+        let k = 10;
+
+        // Probing only the single configured cluster may miss some of the
+        // true nearest neighbors that landed in a different cluster.
+        let narrow =
+            index.search(query.clone(), SearchParams::new(k), Filters::NONE).unwrap();
+
+        // Overriding `num_probes` to explore every cluster for this query
+        // must recover at least as many of the true nearest neighbors.
+        let wide = index
+            .search(query, SearchParams::new(k).with_num_probes(20), Filters::NONE)
+            .unwrap();
+
+        let true_nearest: std::collections::HashSet<RecordID> =
+            (0..k as u32).map(RecordID).collect();
+        let narrow_hits =
+            narrow.iter().filter(|r| true_nearest.contains(&r.id)).count();
+        let wide_hits = wide.iter().filter(|r| true_nearest.contains(&r.id)).count();
+
+        assert!(wide_hits >= narrow_hits);
+    }
+
+    #[test]
+    fn test_ivfpq_mmap_roundtrip() {
+        let params = ParamsIVFPQ {
+            centroids: 5,
+            max_iterations: 20,
+            sampling: 1.0,
+            ..Default::default()
+        };
+
+        let mut index = IndexIVFPQ::new(params).unwrap();
+        index_tests::populate_index(&mut index);
+
+        let path = std::env::temp_dir().join("test_ivfpq_mmap_roundtrip.bin");
+        index.persist_mmap(&path).unwrap();
+
+        let mmap_index = IndexIVFPQMmap::load_mmap(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(mmap_index.len(), index.len());
+        index_tests::test_basic_search(&mmap_index);
+        index_tests::test_advanced_search(&mmap_index);
+    }
 }
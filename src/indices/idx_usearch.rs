@@ -0,0 +1,326 @@
+use super::*;
+use usearch::{Index as NativeIndex, IndexOptions, MetricKind, ScalarKind};
+
+/// Sidecar extension for the file holding everything usearch's own
+/// save/load doesn't know about: per-record metadata and the
+/// [`RecordID`] <-> native key mapping. Stored next to the file usearch
+/// itself manages.
+const METADATA_EXTENSION: &str = "usearch.meta";
+
+/// usearch-backed index implementation.
+///
+/// Wraps the [`usearch`](https://docs.rs/usearch) crate's native HNSW
+/// index, a battle-tested, SIMD-optimized ANN engine, for users who need
+/// lower query latency than the pure-Rust [`IndexHNSW`] provides. Purely
+/// additive: the pure-Rust indices remain the default, this is opt-in
+/// behind the `usearch` feature.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndexUsearch {
+    params: ParamsUsearch,
+    metadata: IndexMetadata,
+
+    // usearch only stores vectors against integer keys, so record metadata
+    // (for filter evaluation) and the RecordID <-> key mapping are tracked
+    // here instead, alongside the native index.
+    records: HashMap<RecordID, Record>,
+    keys: HashMap<RecordID, u64>,
+    next_key: u64,
+
+    #[serde(skip, default = "IndexUsearch::new_native")]
+    native: NativeIndex,
+}
+
+impl IndexUsearch {
+    fn new_native() -> NativeIndex {
+        let options = IndexOptions { metric: MetricKind::L2sq, ..Default::default() };
+        NativeIndex::new(&options).expect("Failed to initialize usearch index")
+    }
+
+    fn native_options(params: &ParamsUsearch) -> IndexOptions {
+        IndexOptions {
+            dimensions: params.dimensions,
+            metric: Self::metric_kind(&params.metric),
+            quantization: ScalarKind::F32,
+            connectivity: params.connectivity,
+            expansion_add: params.expansion_add,
+            expansion_search: params.expansion_search,
+            ..Default::default()
+        }
+    }
+
+    /// Maps our [`DistanceMetric`] onto usearch's own metric enum. usearch
+    /// has no squared-Euclidean/Manhattan/Hamming-on-floats distinction, so
+    /// the closest native equivalent is used for each.
+    fn metric_kind(metric: &DistanceMetric) -> MetricKind {
+        match metric {
+            DistanceMetric::Euclidean => MetricKind::L2sq,
+            DistanceMetric::Cosine => MetricKind::Cos,
+            DistanceMetric::DotProduct => MetricKind::IP,
+            DistanceMetric::Manhattan => MetricKind::L2sq,
+            DistanceMetric::Hamming => MetricKind::Hamming,
+        }
+    }
+
+    fn metadata_path(path: impl AsRef<Path>) -> PathBuf {
+        let mut path = path.as_ref().to_path_buf();
+        let extended = match path.extension() {
+            Some(extension) => {
+                format!("{}.{METADATA_EXTENSION}", extension.to_string_lossy())
+            }
+            None => METADATA_EXTENSION.to_string(),
+        };
+
+        path.set_extension(extended);
+        path
+    }
+
+    fn key_for(&mut self, id: RecordID) -> u64 {
+        if let Some(&key) = self.keys.get(&id) {
+            return key;
+        }
+
+        let key = self.next_key;
+        self.next_key += 1;
+        self.keys.insert(id, key);
+        key
+    }
+}
+
+/// Serializable half of [`IndexUsearch`] persisted to the metadata sidecar;
+/// the native `usearch` index is saved separately via its own format.
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexUsearchMetadata {
+    params: ParamsUsearch,
+    metadata: IndexMetadata,
+    records: HashMap<RecordID, Record>,
+    keys: HashMap<RecordID, u64>,
+    next_key: u64,
+}
+
+impl IndexOps for IndexUsearch {
+    fn new(params: impl IndexParams) -> Result<Self, Error> {
+        let params = downcast_params::<ParamsUsearch>(params)?;
+        let native = NativeIndex::new(&Self::native_options(&params)).map_err(|error| {
+            let code = ErrorCode::InternalError;
+            Error::new(code, format!("Failed to initialize usearch index: {error}"))
+        })?;
+
+        Ok(IndexUsearch {
+            params,
+            metadata: IndexMetadata::default(),
+            records: HashMap::new(),
+            keys: HashMap::new(),
+            next_key: 0,
+            native,
+        })
+    }
+
+    /// Loads the metadata sidecar with the usual binary format, then
+    /// restores the native usearch index from its own file via `load`.
+    fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let sidecar: IndexUsearchMetadata =
+            file::read_binary_file(Self::metadata_path(&path))?;
+
+        let native = NativeIndex::new(&Self::native_options(&sidecar.params))
+            .and_then(|index| index.load(path.as_ref().to_string_lossy().as_ref()).map(|_| index))
+            .map_err(|error| {
+                let code = ErrorCode::InternalError;
+                Error::new(code, format!("Failed to load usearch index: {error}"))
+            })?;
+
+        Ok(IndexUsearch {
+            params: sidecar.params,
+            metadata: sidecar.metadata,
+            records: sidecar.records,
+            keys: sidecar.keys,
+            next_key: sidecar.next_key,
+            native,
+        })
+    }
+
+    /// Persists the native usearch index via its own `save`, and writes
+    /// everything else (metadata, records, key mapping) to a sidecar file
+    /// using the usual binary format.
+    fn persist(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        self.native.save(path.as_ref().to_string_lossy().as_ref()).map_err(|error| {
+            let code = ErrorCode::InternalError;
+            Error::new(code, format!("Failed to save usearch index: {error}"))
+        })?;
+
+        let sidecar = IndexUsearchMetadata {
+            params: self.params.clone(),
+            metadata: self.metadata.clone(),
+            records: self.records.clone(),
+            keys: self.keys.clone(),
+            next_key: self.next_key,
+        };
+
+        file::write_binary_file(Self::metadata_path(path), &sidecar)
+    }
+}
+
+impl VectorIndex for IndexUsearch {
+    fn metric(&self) -> &DistanceMetric {
+        &self.params.metric
+    }
+
+    fn metadata(&self) -> &IndexMetadata {
+        &self.metadata
+    }
+
+    fn build(
+        &mut self,
+        records: HashMap<RecordID, Record>,
+    ) -> Result<(), Error> {
+        self.metadata.built = true;
+        self.insert(records)
+    }
+
+    fn insert(
+        &mut self,
+        records: HashMap<RecordID, Record>,
+    ) -> Result<(), Error> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        self.native
+            .reserve(self.native.size() + records.len())
+            .map_err(|error| {
+                let code = ErrorCode::InternalError;
+                Error::new(code, format!("Failed to reserve usearch capacity: {error}"))
+            })?;
+
+        self.metadata.last_inserted = records.keys().max().copied();
+        for (id, record) in records {
+            let key = self.key_for(id);
+            self.native.add(key, record.vector.as_slice()).map_err(|error| {
+                let code = ErrorCode::InternalError;
+                Error::new(code, format!("Failed to add record to usearch index: {error}"))
+            })?;
+
+            self.records.insert(id, record);
+        }
+
+        Ok(())
+    }
+
+    fn delete(&mut self, ids: Vec<RecordID>) -> Result<(), Error> {
+        for id in ids {
+            self.records.remove(&id);
+            if let Some(key) = self.keys.remove(&id) {
+                self.native.remove(key).map_err(|error| {
+                    let code = ErrorCode::InternalError;
+                    Error::new(code, format!("Failed to remove record from usearch index: {error}"))
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn search(
+        &self,
+        query: Vector,
+        params: SearchParams,
+        filters: Filters,
+    ) -> Result<Vec<SearchResult>, Error> {
+        params.validate()?;
+        let k = params.k;
+
+        // Over-fetch from the native index since some matches may be
+        // rejected by `filters`. Records outside that pool aren't scanned,
+        // so fewer than `k` results come back when too few of it pass.
+        // `candidates` overrides the over-fetch pool size for this query.
+        let pool = params.candidates.unwrap_or(k.max(1) * 4);
+        let matches = self.native.search(query.as_slice(), self.records.len().min(pool))
+            .map_err(|error| {
+                let code = ErrorCode::InternalError;
+                Error::new(code, format!("Failed to search usearch index: {error}"))
+            })?;
+
+        let key_to_id: HashMap<u64, RecordID> =
+            self.keys.iter().map(|(&id, &key)| (key, id)).collect();
+
+        let mut results = BinaryHeap::new();
+        for (key, distance) in matches.keys.into_iter().zip(matches.distances) {
+            let Some(id) = key_to_id.get(&key) else { continue };
+            let Some(record) = self.records.get(id) else { continue };
+            if !filters.apply(&record.data) {
+                continue;
+            }
+
+            let data = record.data.clone();
+            results.push(SearchResult { id: *id, distance, data });
+
+            if results.len() > k {
+                results.pop();
+            }
+        }
+
+        Ok(results.into_sorted_vec())
+    }
+
+    fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Parameters for IndexUsearch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParamsUsearch {
+    /// Number of dimensions of the vectors to be indexed. usearch
+    /// pre-allocates its native index based on this, unlike the pure-Rust
+    /// indices which infer it from the first inserted vector.
+    pub dimensions: usize,
+    /// Metric used to compute the distance between vectors.
+    pub metric: DistanceMetric,
+    /// Maximum number of connections per node in the native graph, akin to
+    /// `M` in [`ParamsHNSW`].
+    pub connectivity: usize,
+    /// Expansion factor (candidate list size) used while inserting.
+    pub expansion_add: usize,
+    /// Expansion factor (candidate list size) used while searching.
+    pub expansion_search: usize,
+}
+
+impl Default for ParamsUsearch {
+    fn default() -> Self {
+        Self {
+            dimensions: 128,
+            metric: DistanceMetric::Euclidean,
+            connectivity: 16,
+            expansion_add: 128,
+            expansion_search: 64,
+        }
+    }
+}
+
+impl IndexParams for ParamsUsearch {
+    fn metric(&self) -> &DistanceMetric {
+        &self.metric
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usearch_index() {
+        let params = ParamsUsearch { dimensions: 128, ..Default::default() };
+        let mut index = IndexUsearch::new(params).unwrap();
+
+        index_tests::populate_index(&mut index);
+        index_tests::test_basic_search(&index);
+        index_tests::test_advanced_search(&index);
+    }
+}
@@ -1,26 +1,45 @@
+use super::store::{IndexStore, MemoryStore, RedbStore};
 use super::*;
-use std::collections::BinaryHeap;
+use rand::Rng;
+use std::collections::{BinaryHeap, HashSet};
 
 /// Brute force index implementation.
 ///
-/// This index stores all records in memory and performs a linear search
-/// for the nearest neighbors. It is great for small datasets of less than
-/// 10,000 records due to perfect recall and precision.
+/// This index stores all records and performs a linear search for the
+/// nearest neighbors. It is great for small datasets of less than 10,000
+/// records due to perfect recall and precision.
+///
+/// Generic over its [`IndexStore`] backend: the default [`MemoryStore`]
+/// keeps every record in a `HashMap`, while [`RedbStore`] memory-maps
+/// them on disk via `redb` so the index isn't capped at available RAM.
 #[derive(Debug, Serialize, Deserialize)]
-pub struct IndexBruteForce {
+pub struct IndexBruteForce<S: IndexStore = MemoryStore> {
     config: SourceConfig,
     metric: DistanceMetric,
     metadata: IndexMetadata,
-    data: HashMap<RecordID, Record>,
+    store: S,
 }
 
-impl IndexOps for IndexBruteForce {
+impl IndexBruteForce<RedbStore> {
+    /// Creates an index whose records are memory-mapped on disk at
+    /// `path` via `redb`, instead of held fully in memory.
+    pub fn open(
+        config: SourceConfig,
+        metric: DistanceMetric,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, Error> {
+        let store = RedbStore::open(path)?;
+        Ok(Self { config, metric, metadata: IndexMetadata::default(), store })
+    }
+}
+
+impl IndexOps for IndexBruteForce<MemoryStore> {
     fn new(config: SourceConfig, metric: DistanceMetric) -> Self {
         Self {
             config,
             metric,
             metadata: IndexMetadata::default(),
-            data: HashMap::new(),
+            store: MemoryStore::default(),
         }
     }
 
@@ -37,7 +56,7 @@ impl IndexOps for IndexBruteForce {
     }
 }
 
-impl VectorIndex for IndexBruteForce {
+impl<S: IndexStore> VectorIndex for IndexBruteForce<S> {
     fn fit(&mut self, records: HashMap<RecordID, Record>) -> Result<(), Error> {
         if records.is_empty() {
             return Ok(());
@@ -45,9 +64,11 @@ impl VectorIndex for IndexBruteForce {
 
         self.metadata.last_inserted = records.keys().max().copied();
         self.metadata.count += records.len();
-        self.data.par_extend(records);
+        for (id, record) in records {
+            self.store.put_vector(id, record);
+        }
 
-        Ok(())
+        self.store.commit()
     }
 
     /// Refitting doesn't do anything for the brute force index
@@ -60,13 +81,16 @@ impl VectorIndex for IndexBruteForce {
     /// Removes records from the index data store.
     /// - `record_ids`: List of record IDs to remove from the index.
     fn hide(&mut self, record_ids: Vec<RecordID>) -> Result<(), Error> {
-        if self.data.len() < record_ids.len() {
-            return Ok(());
+        // Deduplicate up front so repeated IDs only cost one removal each,
+        // and so the count below reflects the store's actual state instead
+        // of assuming `record_ids` has no duplicates.
+        let record_ids: HashSet<RecordID> = record_ids.into_iter().collect();
+        for id in &record_ids {
+            self.store.remove_vector(id);
         }
 
-        self.data.retain(|id, _| !record_ids.contains(id));
-        self.metadata.count = self.data.len();
-        Ok(())
+        self.metadata.count = self.store.len();
+        self.store.commit()
     }
 
     fn search(
@@ -75,10 +99,10 @@ impl VectorIndex for IndexBruteForce {
         k: usize,
     ) -> Result<Vec<SearchResult>, Error> {
         let mut results = BinaryHeap::new();
-        for (id, record) in &self.data {
+        for (id, record) in self.store.iter_vectors() {
             let distance = self.metric.distance(&record.vector, &query);
             let data = record.data.clone();
-            results.push(SearchResult { id: *id, distance, data });
+            results.push(SearchResult { id, distance, data });
 
             if results.len() > k {
                 results.pop();
@@ -98,12 +122,16 @@ impl VectorIndex for IndexBruteForce {
             return self.search(query, k);
         }
 
+        // Compile the filter tree once instead of re-walking it and
+        // re-resolving each leaf's key for every record in the loop below.
+        let predicate = FilterPredicate::compile(&filters);
+
         let mut results = BinaryHeap::new();
-        for (id, record) in &self.data {
-            if filters.apply(&record.data) {
+        for (id, record) in self.store.iter_vectors() {
+            if predicate.test(&record.data) {
                 let distance = self.metric.distance(&record.vector, &query);
                 let data = record.data.clone();
-                results.push(SearchResult { id: *id, distance, data });
+                results.push(SearchResult { id, distance, data });
 
                 if results.len() > k {
                     results.pop();
@@ -114,11 +142,222 @@ impl VectorIndex for IndexBruteForce {
         Ok(results.into_sorted_vec())
     }
 
+    /// Returns every record within `radius` of `query`, sorted ascending
+    /// by distance, with no fixed cap on how many can match.
+    fn search_within(
+        &self,
+        query: Vector,
+        radius: f32,
+        filters: Filters,
+    ) -> Result<Vec<SearchResult>, Error> {
+        let mut results = Vec::new();
+        for (id, record) in self.store.iter_vectors() {
+            if !filters.apply(&record.data) {
+                continue;
+            }
+
+            let distance = self.metric.distance(&record.vector, &query);
+            if distance <= radius {
+                let data = record.data.clone();
+                results.push(SearchResult { id, distance, data });
+            }
+        }
+
+        results.sort();
+        Ok(results)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
 }
 
+/// Weights for [`IndexBruteForce::search_hybrid`]'s reciprocal-rank fusion.
+#[derive(Debug, Clone, Copy)]
+pub struct HybridWeights {
+    /// Smoothing constant added to each rank before taking its
+    /// reciprocal. Higher values flatten the contribution of top ranks.
+    pub c: f32,
+    /// Number of top entries considered from each ranking list (ranks 0
+    /// to `cutoff - 1`). Records ranked at or beyond this cutoff in a list
+    /// don't score from it.
+    pub cutoff: usize,
+}
+
+impl Default for HybridWeights {
+    fn default() -> Self {
+        Self { c: 60.0, cutoff: 100 }
+    }
+}
+
+impl<S: IndexStore> IndexBruteForce<S> {
+    /// Searches for the nearest neighbors using a flattened, row-major
+    /// arena of vector data instead of visiting the backing store's
+    /// records one at a time.
+    ///
+    /// Every vector is copied once into a single contiguous `Vec<f32>`
+    /// (`count * dimension` floats) alongside a parallel `Vec<RecordID>`,
+    /// then distances are computed over that arena in rayon-parallel,
+    /// cache-friendly chunks instead of following the store's scattered
+    /// per-record entries one distance call at a time. See
+    /// `bench/bruteforce.rs` for a comparison against [`Self::search`].
+    pub fn search_arena(
+        &self,
+        query: Vector,
+        k: usize,
+    ) -> Result<Vec<SearchResult>, Error> {
+        let records: Vec<(RecordID, Record)> = self.store.iter_vectors().collect();
+        let dimension = query.len();
+
+        let mut arena = Vec::with_capacity(records.len() * dimension);
+        for (_, record) in &records {
+            arena.extend_from_slice(record.vector.as_slice());
+        }
+
+        let metric = self.metric;
+        let distances: Vec<f32> = arena
+            .par_chunks(dimension)
+            .map(|chunk| metric.distance(&Vector::from(chunk.to_vec()), &query))
+            .collect();
+
+        let mut results = BinaryHeap::new();
+        for ((id, record), distance) in records.into_iter().zip(distances) {
+            let data = record.data.clone();
+            results.push(SearchResult { id, distance, data });
+
+            if results.len() > k {
+                results.pop();
+            }
+        }
+
+        Ok(results.into_sorted_vec())
+    }
+
+    /// Hybrid search combining vector distance and text relevance.
+    ///
+    /// Records are ranked twice, independently: once by vector distance
+    /// to `query`, and once by how many whitespace-separated terms of
+    /// `text_query` occur in the record's metadata. The two rankings are
+    /// then fused with reciprocal-rank fusion: a record's fused score is
+    /// the sum of `1 / (weights.c + rank)` over every list it appears
+    /// in, where `rank` is its 0-indexed position in that list; only the
+    /// first `weights.cutoff` entries of each list score. The top `k`
+    /// records by fused score are
+    /// returned, with `SearchResult::distance` set to the negated fused
+    /// score so that, consistent with every other search method, the
+    /// smallest distance is the most relevant record.
+    pub fn search_hybrid(
+        &self,
+        query: Vector,
+        text_query: &str,
+        k: usize,
+        weights: HybridWeights,
+    ) -> Result<Vec<SearchResult>, Error> {
+        let mut by_vector: Vec<(RecordID, f32)> = self
+            .store
+            .iter_vectors()
+            .map(|(id, record)| (id, self.metric.distance(&record.vector, &query)))
+            .collect();
+        by_vector.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal));
+
+        let terms: Vec<String> = text_query
+            .split_whitespace()
+            .map(|term| term.to_lowercase())
+            .collect();
+
+        let mut by_text: Vec<(RecordID, usize)> = self
+            .store
+            .iter_vectors()
+            .map(|(id, record)| (id, text_overlap_score(&record.data, &terms)))
+            .filter(|(_, score)| *score > 0)
+            .collect();
+        by_text.sort_by(|a, b| b.1.cmp(&a.1));
+
+        let mut fused: HashMap<RecordID, f32> = HashMap::new();
+        for (rank, (id, _)) in by_vector.iter().enumerate().take(weights.cutoff) {
+            *fused.entry(*id).or_insert(0.0) += 1.0 / (weights.c + rank as f32);
+        }
+        for (rank, (id, _)) in by_text.iter().enumerate().take(weights.cutoff) {
+            *fused.entry(*id).or_insert(0.0) += 1.0 / (weights.c + rank as f32);
+        }
+
+        let data_by_id: HashMap<RecordID, _> = self
+            .store
+            .iter_vectors()
+            .map(|(id, record)| (id, record.data.clone()))
+            .collect();
+
+        let mut results = BinaryHeap::new();
+        for (id, score) in fused {
+            let data = data_by_id.get(&id).cloned().unwrap_or_default();
+            results.push(SearchResult { id, distance: -score, data });
+            if results.len() > k {
+                results.pop();
+            }
+        }
+
+        Ok(results.into_sorted_vec())
+    }
+
+    /// Counts records matching `filters` under `epsilon`-differential
+    /// privacy, via the Laplace mechanism.
+    ///
+    /// Adding or removing a single record changes the true count by at
+    /// most 1, so noise drawn from a Laplace distribution with scale
+    /// `b = 1 / epsilon` satisfies `epsilon`-DP. `non_negative` clamps
+    /// the noisy result to 0, since a count can't be negative, at the
+    /// cost of a small bias for queries whose true count is near zero.
+    pub fn count_with_filters_dp(
+        &self,
+        filters: Filters,
+        epsilon: f64,
+        non_negative: bool,
+    ) -> Result<f64, Error> {
+        if epsilon <= 0.0 {
+            let message = "epsilon must be greater than 0 for differential privacy.";
+            let code = ErrorCode::InvalidParameter;
+            return Err(Error::new(code, message));
+        }
+
+        let true_count = if filters == Filters::NONE {
+            self.store.len()
+        } else {
+            let predicate = FilterPredicate::compile(&filters);
+            self.store
+                .iter_vectors()
+                .filter(|(_, record)| predicate.test(&record.data))
+                .count()
+        };
+
+        let noisy_count = true_count as f64 + laplace_noise(1.0 / epsilon);
+        let noisy_count =
+            if non_negative { noisy_count.max(0.0) } else { noisy_count };
+
+        Ok(noisy_count)
+    }
+}
+
+/// Samples noise from a zero-centered Laplace distribution with scale
+/// `b`, via inverse transform sampling from a uniform variable on
+/// `(-0.5, 0.5)`.
+fn laplace_noise(b: f64) -> f64 {
+    let u: f64 = rand::thread_rng().gen_range(-0.5..0.5);
+    -b * u.signum() * (1.0 - 2.0 * u.abs()).ln()
+}
+
+/// Counts how many `terms` occur in `data`'s stringified metadata values.
+///
+/// Metadata columns aren't tagged as text versus other types in this
+/// store, so overlap is scored against the whole record's metadata
+/// rendered as text rather than a single designated text column.
+fn text_overlap_score(
+    data: &HashMap<ColumnName, Option<DataValue>>,
+    terms: &[String],
+) -> usize {
+    let haystack = format!("{data:?}").to_lowercase();
+    terms.iter().filter(|term| haystack.contains(term.as_str())).count()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
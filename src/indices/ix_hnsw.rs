@@ -0,0 +1,947 @@
+use super::*;
+use crate::utils::heuristic as heur;
+use ordered_float::OrderedFloat;
+use rand::random;
+use serde_big_array::BigArray;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::ops::Deref;
+
+/// Sentinel value for a slot that has been deleted or never assigned.
+const INVALID: VectorID = VectorID(u32::MAX);
+
+/// Maximum number of neighbors kept per node on the base layer is `M * 2`;
+/// upper layers keep `M`, matching the Malkov-Yashunin HNSW paper.
+const M: usize = 32;
+
+/// Dense, sequential identifier for a vector's slot in the graph.
+///
+/// [`RecordID`] is a UUID and isn't suitable as a graph array index, so
+/// each inserted record is additionally assigned a `VectorID` used only
+/// internally by the layers below.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize)]
+struct VectorID(u32);
+
+impl VectorID {
+    fn is_valid(&self) -> bool {
+        self.0 != u32::MAX
+    }
+}
+
+/// A layer of the graph: something that can list a node's neighbors.
+trait Layer {
+    fn nearest_iter(&self, vector_id: &VectorID) -> NearestIter<'_>;
+}
+
+struct NearestIter<'a> {
+    node: &'a [VectorID],
+    current: usize,
+}
+
+impl<'a> NearestIter<'a> {
+    fn new(node: &'a [VectorID]) -> Self {
+        Self { node, current: 0 }
+    }
+}
+
+impl Iterator for NearestIter<'_> {
+    type Item = VectorID;
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = *self.node.get(self.current)?;
+        if !item.is_valid() {
+            self.current = self.node.len();
+            return None;
+        }
+
+        self.current += 1;
+        Some(item)
+    }
+}
+
+struct DescendingLayerIter {
+    next: Option<usize>,
+}
+
+impl Iterator for DescendingLayerIter {
+    type Item = LayerID;
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        self.next = current.checked_sub(1);
+        Some(LayerID(current))
+    }
+}
+
+/// Index of a graph layer, where `0` is the base layer holding every node.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct LayerID(usize);
+
+impl LayerID {
+    /// Iterates from this layer down to (and including) the base layer.
+    fn descend(&self) -> impl Iterator<Item = LayerID> {
+        DescendingLayerIter { next: Some(self.0) }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// A base layer node's neighbor list. Kept twice as wide as an
+/// [`UpperNode`] since the base layer carries the full graph and benefits
+/// from the extra redundancy.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct BaseNode(#[serde(with = "BigArray")] [VectorID; M * 2]);
+
+impl Default for BaseNode {
+    fn default() -> Self {
+        Self([INVALID; M * 2])
+    }
+}
+
+impl Deref for BaseNode {
+    type Target = [VectorID];
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Layer for [BaseNode] {
+    fn nearest_iter(&self, vector_id: &VectorID) -> NearestIter<'_> {
+        NearestIter::new(&self[vector_id.0 as usize])
+    }
+}
+
+/// An upper layer node's neighbor list, capped at `M` neighbors.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct UpperNode([VectorID; M]);
+
+impl Default for UpperNode {
+    fn default() -> Self {
+        Self([INVALID; M])
+    }
+}
+
+impl Deref for UpperNode {
+    type Target = [VectorID];
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Layer for [UpperNode] {
+    fn nearest_iter(&self, vector_id: &VectorID) -> NearestIter<'_> {
+        NearestIter::new(&self[vector_id.0 as usize].0)
+    }
+}
+
+/// Removes `target` from a node's neighbor array, shifting the remaining
+/// neighbors left so the array keeps its valid-prefix-then-invalid
+/// invariant, which [`NearestIter`] relies on to stop early.
+fn unlink(neighbors: &mut [VectorID], target: VectorID) {
+    if let Some(index) = neighbors.iter().position(|id| *id == target) {
+        let end = neighbors.len() - 1;
+        neighbors.copy_within(index + 1..neighbors.len(), index);
+        neighbors[end] = INVALID;
+    }
+}
+
+/// Tracks which vector IDs have already been expanded during a search so
+/// they aren't visited twice.
+#[derive(Clone, Debug, Default)]
+struct Visited(Vec<bool>);
+
+impl Visited {
+    fn resize_capacity(&mut self, capacity: usize) {
+        self.0.resize(capacity, false);
+    }
+
+    /// Marks `vector_id` as visited, returning `true` the first time.
+    fn insert(&mut self, vector_id: &VectorID) -> bool {
+        match self.0.get_mut(vector_id.0 as usize) {
+            Some(slot) if !*slot => {
+                *slot = true;
+                true
+            }
+            Some(_) => false,
+            None => false,
+        }
+    }
+
+    fn extend(&mut self, iter: impl Iterator<Item = VectorID>) {
+        for vector_id in iter {
+            self.insert(&vector_id);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.0.iter_mut().for_each(|slot| *slot = false);
+    }
+}
+
+/// A candidate neighbor, ordered by distance (smallest/nearest first)
+/// and then by vector ID to keep ties deterministic.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+struct Candidate {
+    distance: OrderedFloat<f32>,
+    vector_id: VectorID,
+}
+
+/// Best-first search state reused across layer descents.
+///
+/// Carries the same `working`/`discarded` scratch buffers as the
+/// heuristic neighbor selection used elsewhere so both can share a pool,
+/// even though the simple selection strategy below only needs `nearest`.
+#[derive(Clone, Debug, Default)]
+struct Search {
+    ef: usize,
+    visited: Visited,
+    candidates: BinaryHeap<Reverse<Candidate>>,
+    nearest: Vec<Candidate>,
+    working: Vec<heur::Candidate<VectorID>>,
+    discarded: Vec<heur::Candidate<VectorID>>,
+}
+
+impl Search {
+    /// Pushes a neighbor candidate, keeping `nearest` sorted. A candidate
+    /// that would land at position `ef` or beyond is rejected, but
+    /// `nearest` isn't truncated here, so it can grow past `ef` entries.
+    fn push(
+        &mut self,
+        vector_id: &VectorID,
+        vector: &Vector,
+        vectors: &HashMap<VectorID, Vector>,
+        metric: &DistanceMetric,
+    ) {
+        if !self.visited.insert(vector_id) {
+            return;
+        }
+
+        let other = match vectors.get(vector_id) {
+            Some(other) => other,
+            None => return,
+        };
+
+        let distance = OrderedFloat(metric.distance(other, vector));
+        let new = Candidate { distance, vector_id: *vector_id };
+
+        let index = match self.nearest.binary_search(&new) {
+            Ok(index) | Err(index) => index,
+        };
+        if index >= self.ef.max(1) {
+            return;
+        }
+
+        self.nearest.insert(index, new);
+        self.candidates.push(Reverse(new));
+    }
+
+    /// Expands the best-first frontier across a single layer.
+    ///
+    /// When `radius` is `Some`, the frontier keeps expanding as long as
+    /// the best pending candidate is within it, regardless of how many
+    /// neighbors have been kept so far, for a [`Self::select_within`]
+    /// threshold search. Otherwise this stops as soon as the best
+    /// pending candidate is further than the worst of the `ef` nearest
+    /// neighbors kept, since every metric in this index is "smaller is
+    /// nearer" and nothing left in the heap could improve the result.
+    fn search(
+        &mut self,
+        layer: &impl Layer,
+        vector: &Vector,
+        vectors: &HashMap<VectorID, Vector>,
+        metric: &DistanceMetric,
+        links: usize,
+        radius: Option<f32>,
+    ) {
+        while let Some(Reverse(candidate)) = self.candidates.pop() {
+            let stop = match radius {
+                Some(radius) => candidate.distance.0 > radius,
+                None => self
+                    .nearest
+                    .last()
+                    .is_some_and(|furthest| candidate.distance > furthest.distance),
+            };
+            if stop {
+                break;
+            }
+
+            for vector_id in layer.nearest_iter(&candidate.vector_id).take(links)
+            {
+                self.push(&vector_id, vector, vectors, metric);
+            }
+
+            if radius.is_none() {
+                self.nearest.truncate(self.ef.max(1));
+            }
+        }
+    }
+
+    /// Drops to the next lower layer, re-seeding the candidate heap from
+    /// the nearest neighbors found so far.
+    fn cull(&mut self) {
+        self.candidates.clear();
+        self.visited.clear();
+
+        for &candidate in &self.nearest {
+            self.candidates.push(Reverse(candidate));
+        }
+
+        let ids = self.nearest.iter().map(|c| c.vector_id);
+        self.visited.extend(ids);
+    }
+
+    fn reset(&mut self) {
+        self.visited.clear();
+        self.candidates.clear();
+        self.nearest.clear();
+        self.working.clear();
+        self.discarded.clear();
+    }
+
+    /// Returns the nearest candidates found, ordered by distance. This is
+    /// the "simple" neighbor selection strategy: plain nearest-`M`.
+    fn select_simple(&mut self) -> &[Candidate] {
+        &self.nearest
+    }
+
+    /// Slices `self.nearest` down to the prefix within `radius`, for a
+    /// threshold search where the caller wants every match rather than a
+    /// fixed top-`k`. `self.nearest` is sorted ascending by distance, so
+    /// everything past the first too-far candidate can be dropped.
+    fn select_within(&mut self, radius: f32) -> &[Candidate] {
+        let cutoff = self.nearest.partition_point(|c| c.distance.0 <= radius);
+        self.nearest.truncate(cutoff);
+        &self.nearest
+    }
+
+    /// Selects up to `m` neighbors using the Malkov-Yashunin heuristic
+    /// (Algorithm 4 in the HNSW paper): a candidate is only kept if it's
+    /// closer to the query than to every neighbor already kept, which
+    /// favors diverse directions over a tight cluster and gives much
+    /// better graph connectivity than [`Self::select_simple`]. The actual
+    /// pass is shared with every other HNSW-style index in this crate; see
+    /// [`heur::extend_candidates`] and [`heur::select_diverse`].
+    ///
+    /// When `heuristic.extend_candidates` is set, the working set is
+    /// first widened with each candidate's own neighbors on `layer`
+    /// before the pass runs, at the cost of extra distance computations.
+    /// When `heuristic.keep_pruned` is set and fewer than `m` candidates
+    /// survive the pass, the discarded candidates backfill the rest in
+    /// distance order rather than leaving the node under-connected.
+    fn select_heuristic(
+        &mut self,
+        layer: &impl Layer,
+        query: &Vector,
+        metric: &DistanceMetric,
+        vectors: &HashMap<VectorID, Vector>,
+        m: usize,
+        heuristic: &HeuristicConfig,
+    ) -> &[Candidate] {
+        self.working.clear();
+        self.working.extend(self.nearest.drain(..).map(|c| heur::Candidate {
+            distance: c.distance,
+            id: c.vector_id,
+        }));
+
+        if heuristic.extend_candidates {
+            heur::extend_candidates(
+                &mut self.working,
+                |id| layer.nearest_iter(&id).collect(),
+                |id| vectors.get(&id).map(|v| metric.distance(v, query)),
+            );
+        }
+
+        let accepted = heur::select_diverse(
+            &mut self.working,
+            &mut self.discarded,
+            m,
+            heuristic.keep_pruned,
+            |id| vectors.contains_key(&id),
+            |a, b| {
+                let a = vectors.get(&a)?;
+                let b = vectors.get(&b)?;
+                Some(metric.distance(a, b))
+            },
+        );
+
+        self.nearest = accepted
+            .into_iter()
+            .map(|c| Candidate { distance: c.distance, vector_id: c.id })
+            .collect();
+        &self.nearest
+    }
+}
+
+/// Reuses [`Search`] buffers across insertions to avoid reallocating the
+/// heap and scratch vectors for every node.
+#[derive(Debug, Default)]
+struct SearchPool(Vec<Search>);
+
+impl SearchPool {
+    fn pop(&mut self, capacity: usize) -> Search {
+        let mut search = self.0.pop().unwrap_or_default();
+        search.visited.resize_capacity(capacity);
+        search
+    }
+
+    fn push(&mut self, search: Search) {
+        self.0.push(search);
+    }
+
+    /// Builds a throwaway `Search` for read-only queries, which can't draw
+    /// from the pool since searching only takes `&self`.
+    fn scratch(&self, capacity: usize) -> Search {
+        let mut search = Search::default();
+        search.visited.resize_capacity(capacity);
+        search
+    }
+}
+
+/// Opts a build into Algorithm 4's heuristic neighbor selection instead of
+/// the default plain nearest-`M` cut, trading extra construction-time work
+/// for a better-connected graph.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct HeuristicConfig {
+    /// Widen the candidate set with each candidate's own neighbors on the
+    /// layer being connected before running the selection pass.
+    pub extend_candidates: bool,
+    /// Backfill from the candidates the pass discarded, in distance order,
+    /// when fewer than `M` neighbors survive it.
+    pub keep_pruned: bool,
+}
+
+/// Hierarchical Navigable Small World (HNSW) index.
+///
+/// Builds a multi-layer proximity graph: higher layers hold exponentially
+/// fewer nodes so a search can descend quickly to the query's
+/// neighborhood before doing a thorough, `ef`-bounded best-first search at
+/// the base layer. Nodes are inserted one at a time, each assigned a
+/// random top layer so the graph stays balanced without a global rebuild.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndexHnsw {
+    config: SourceConfig,
+    metric: DistanceMetric,
+    metadata: IndexMetadata,
+
+    ef_construction: usize,
+    ef_search: usize,
+    /// Layer multiplier controlling how many nodes reach each upper
+    /// layer; higher values send more nodes to the upper layers, producing
+    /// taller graphs.
+    ml: f32,
+    /// Neighbor selection strategy used while connecting nodes during
+    /// insertion. `None` keeps the default plain nearest-`M` selection.
+    heuristic: Option<HeuristicConfig>,
+
+    data: HashMap<RecordID, Record>,
+    slots: HashMap<RecordID, VectorID>,
+    ids: HashMap<VectorID, RecordID>,
+    vectors: HashMap<VectorID, Vector>,
+    next_slot: u32,
+
+    base_layer: Vec<BaseNode>,
+    upper_layers: Vec<Vec<UpperNode>>,
+    entry_point: Option<VectorID>,
+    top_level: usize,
+
+    #[serde(skip)]
+    search_pool: SearchPool,
+}
+
+impl IndexHnsw {
+    /// Enables Algorithm 4 heuristic neighbor selection for subsequent
+    /// insertions, instead of the default plain nearest-`M` cut.
+    pub fn with_heuristic(mut self, heuristic: HeuristicConfig) -> Self {
+        self.heuristic = Some(heuristic);
+        self
+    }
+
+    /// Assigns a random top layer to a new node, following the
+    /// exponentially decaying distribution from the HNSW paper.
+    fn random_level(ml: f32) -> usize {
+        let unif: f32 = random::<f32>().max(f32::EPSILON);
+        (-unif.ln() * ml).floor() as usize
+    }
+
+    fn distance_to(&self, vector_id: &VectorID, query: &Vector) -> f32 {
+        match self.vectors.get(vector_id) {
+            Some(vector) => self.metric.distance(vector, query),
+            None => f32::INFINITY,
+        }
+    }
+
+    /// Connects two base layer nodes to each other, pruning each side's
+    /// neighbor list back down to `M * 2` by distance.
+    fn connect_base(&mut self, a: VectorID, b: VectorID) {
+        self.link(a, b, M * 2, |index| &mut index.base_layer[a.0 as usize].0);
+        self.link(b, a, M * 2, |index| &mut index.base_layer[b.0 as usize].0);
+    }
+
+    /// Connects two upper layer nodes to each other, pruning each side's
+    /// neighbor list back down to `M`.
+    fn connect_upper(&mut self, layer: usize, a: VectorID, b: VectorID) {
+        self.link(a, b, M, |index| &mut index.upper_layers[layer][a.0 as usize].0);
+        self.link(b, a, M, |index| &mut index.upper_layers[layer][b.0 as usize].0);
+    }
+
+    fn link(
+        &mut self,
+        from: VectorID,
+        to: VectorID,
+        max_neighbors: usize,
+        neighbors: impl Fn(&mut Self) -> &mut [VectorID],
+    ) {
+        {
+            let slot = neighbors(self);
+            if slot.iter().any(|id| *id == to) {
+                return;
+            }
+
+            let index = slot.iter().position(|id| !id.is_valid());
+            if let Some(index) = index {
+                slot[index] = to;
+                return;
+            }
+        }
+
+        // The neighbor list is full. Keep the `max_neighbors` closest
+        // nodes to `from`, dropping the furthest one to make room.
+        let vector = match self.vectors.get(&from) {
+            Some(vector) => vector.clone(),
+            None => return,
+        };
+
+        let mut ranked: Vec<VectorID> = {
+            let slot = neighbors(self);
+            slot.iter().copied().filter(VectorID::is_valid).collect()
+        };
+        ranked.push(to);
+        ranked.sort_by_key(|id| OrderedFloat(self.distance_to(id, &vector)));
+        ranked.truncate(max_neighbors);
+        ranked.resize(max_neighbors, INVALID);
+
+        let slot = neighbors(self);
+        slot[..max_neighbors].copy_from_slice(&ranked);
+    }
+
+    /// Inserts a single record into the graph.
+    fn insert_one(&mut self, id: RecordID, record: Record) {
+        let level = Self::random_level(self.ml);
+        let vector = record.vector.clone();
+
+        let slot = VectorID(self.next_slot);
+        self.next_slot += 1;
+
+        self.slots.insert(id, slot);
+        self.ids.insert(slot, id);
+        self.vectors.insert(slot, vector.clone());
+        self.base_layer.push(BaseNode::default());
+        for layer in self.upper_layers.iter_mut() {
+            layer.push(UpperNode::default());
+        }
+        self.data.insert(id, record);
+
+        let entry = match self.entry_point {
+            Some(entry) => entry,
+            None => {
+                self.entry_point = Some(slot);
+                self.top_level = level;
+                return;
+            }
+        };
+
+        while self.upper_layers.len() < level {
+            let layer = vec![UpperNode::default(); self.base_layer.len()];
+            self.upper_layers.push(layer);
+        }
+
+        let mut nearest = entry;
+
+        // Greedily (ef = 1) descend from the current top layer down to
+        // just above the new node's own level, to find a good entry
+        // point for the more thorough search below.
+        for layer in (level + 1..=self.top_level).rev() {
+            let mut search = self.search_pool.pop(self.base_layer.len());
+            search.ef = 1;
+            search.push(&nearest, &vector, &self.vectors, &self.metric);
+            search.search(
+                self.upper_layers[layer - 1].as_slice(),
+                &vector,
+                &self.vectors,
+                &self.metric,
+                M,
+                None,
+            );
+
+            if let Some(closest) = search.select_simple().first() {
+                nearest = closest.vector_id;
+            }
+
+            self.search_pool.push(search);
+        }
+
+        // Connect the node at its own level and every level below it,
+        // gathering an `ef_construction`-bounded candidate list at each.
+        for layer in (0..=level.min(self.top_level)).rev() {
+            let mut search = self.search_pool.pop(self.base_layer.len());
+            search.ef = self.ef_construction;
+            search.push(&nearest, &vector, &self.vectors, &self.metric);
+
+            if layer == 0 {
+                search.search(
+                    self.base_layer.as_slice(),
+                    &vector,
+                    &self.vectors,
+                    &self.metric,
+                    M * 2,
+                    None,
+                );
+            } else {
+                search.search(
+                    self.upper_layers[layer - 1].as_slice(),
+                    &vector,
+                    &self.vectors,
+                    &self.metric,
+                    M,
+                    None,
+                );
+            }
+
+            let m = if layer == 0 { M * 2 } else { M };
+            let selected: Vec<VectorID> = match &self.heuristic {
+                Some(heuristic) if layer == 0 => search
+                    .select_heuristic(
+                        self.base_layer.as_slice(),
+                        &vector,
+                        &self.metric,
+                        &self.vectors,
+                        m,
+                        heuristic,
+                    )
+                    .iter()
+                    .map(|c| c.vector_id)
+                    .collect(),
+                Some(heuristic) => search
+                    .select_heuristic(
+                        self.upper_layers[layer - 1].as_slice(),
+                        &vector,
+                        &self.metric,
+                        &self.vectors,
+                        m,
+                        heuristic,
+                    )
+                    .iter()
+                    .map(|c| c.vector_id)
+                    .collect(),
+                // Plain nearest-M: `search.nearest` is already sorted
+                // ascending by distance and capped at `ef`, so the first
+                // `m` entries are exactly the closest `m` candidates.
+                None => search.select_simple().iter().take(m).map(|c| c.vector_id).collect(),
+            };
+
+            if let Some(&closest) = selected.first() {
+                nearest = closest;
+            }
+
+            if layer == 0 {
+                for neighbor in selected {
+                    self.connect_base(slot, neighbor);
+                }
+            } else {
+                for neighbor in selected {
+                    self.connect_upper(layer - 1, slot, neighbor);
+                }
+            }
+
+            self.search_pool.push(search);
+        }
+
+        if level > self.top_level {
+            self.entry_point = Some(slot);
+            self.top_level = level;
+        }
+    }
+
+    /// Removes a record's vector ID from the graph entirely: tombstones
+    /// its own neighbor lists and unlinks it from every node still
+    /// pointing at it.
+    fn remove_slot(&mut self, slot: VectorID) {
+        self.base_layer[slot.0 as usize] = BaseNode::default();
+        for layer in self.upper_layers.iter_mut() {
+            layer[slot.0 as usize] = UpperNode::default();
+        }
+
+        for node in self.base_layer.iter_mut() {
+            unlink(&mut node.0, slot);
+        }
+        for layer in self.upper_layers.iter_mut() {
+            for node in layer.iter_mut() {
+                unlink(&mut node.0, slot);
+            }
+        }
+
+        self.vectors.remove(&slot);
+        self.ids.remove(&slot);
+
+        if self.entry_point == Some(slot) {
+            self.entry_point = self.vectors.keys().next().copied();
+        }
+    }
+}
+
+impl IndexOps for IndexHnsw {
+    fn new(config: SourceConfig, metric: DistanceMetric) -> Self {
+        Self {
+            config,
+            metric,
+            metadata: IndexMetadata::default(),
+
+            ef_construction: 128,
+            ef_search: 64,
+            ml: 1.0 / (M as f32).ln(),
+            heuristic: None,
+
+            data: HashMap::new(),
+            slots: HashMap::new(),
+            ids: HashMap::new(),
+            vectors: HashMap::new(),
+            next_slot: 0,
+
+            base_layer: Vec::new(),
+            upper_layers: Vec::new(),
+            entry_point: None,
+            top_level: 0,
+
+            search_pool: SearchPool::default(),
+        }
+    }
+
+    fn config(&self) -> &SourceConfig {
+        &self.config
+    }
+
+    fn metric(&self) -> &DistanceMetric {
+        &self.metric
+    }
+
+    fn metadata(&self) -> &IndexMetadata {
+        &self.metadata
+    }
+}
+
+impl VectorIndex for IndexHnsw {
+    fn fit(&mut self, records: HashMap<RecordID, Record>) -> Result<(), Error> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        self.metadata.last_inserted = records.keys().max().copied();
+        self.metadata.count += records.len();
+
+        for (id, record) in records {
+            self.insert_one(id, record);
+        }
+
+        Ok(())
+    }
+
+    /// Refitting doesn't do anything extra for this index: `fit` and
+    /// `hide` keep the graph's edges consistent as they go, the same way
+    /// `IndexBruteForce` keeps its flat data store consistent.
+    fn refit(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn hide(&mut self, record_ids: Vec<RecordID>) -> Result<(), Error> {
+        for id in &record_ids {
+            if let Some(slot) = self.slots.remove(id) {
+                self.remove_slot(slot);
+            }
+            self.data.remove(id);
+        }
+
+        self.metadata.count = self.data.len();
+        Ok(())
+    }
+
+    fn search(
+        &self,
+        query: Vector,
+        k: usize,
+    ) -> Result<Vec<SearchResult>, Error> {
+        self.search_with_filters(query, k, Filters::NONE)
+    }
+
+    fn search_with_filters(
+        &self,
+        query: Vector,
+        k: usize,
+        filters: Filters,
+    ) -> Result<Vec<SearchResult>, Error> {
+        let entry = match self.entry_point {
+            Some(entry) => entry,
+            None => return Ok(vec![]),
+        };
+
+        let mut nearest = entry;
+        for layer in (1..=self.top_level).rev() {
+            let mut search = self.search_pool.scratch(self.base_layer.len());
+            search.ef = 1;
+            search.push(&nearest, &query, &self.vectors, &self.metric);
+            search.search(
+                self.upper_layers[layer - 1].as_slice(),
+                &query,
+                &self.vectors,
+                &self.metric,
+                M,
+                None,
+            );
+
+            if let Some(closest) = search.select_simple().first() {
+                nearest = closest.vector_id;
+            }
+        }
+
+        let mut search = self.search_pool.scratch(self.base_layer.len());
+        search.ef = self.ef_search.max(k);
+        search.push(&nearest, &query, &self.vectors, &self.metric);
+        search.search(
+            self.base_layer.as_slice(),
+            &query,
+            &self.vectors,
+            &self.metric,
+            M * 2,
+            None,
+        );
+
+        let mut results = BinaryHeap::new();
+        for candidate in search.select_simple() {
+            let id = match self.ids.get(&candidate.vector_id) {
+                Some(id) => *id,
+                None => continue,
+            };
+
+            let record = match self.data.get(&id) {
+                Some(record) => record,
+                None => continue,
+            };
+
+            if !filters.apply(&record.data) {
+                continue;
+            }
+
+            let data = record.data.clone();
+            let distance = candidate.distance.0;
+            results.push(SearchResult { id, distance, data });
+
+            if results.len() > k {
+                results.pop();
+            }
+        }
+
+        Ok(results.into_sorted_vec())
+    }
+
+    /// Returns every record within `radius` of `query`, sorted ascending
+    /// by distance, with no fixed cap on how many can match.
+    fn search_within(
+        &self,
+        query: Vector,
+        radius: f32,
+        filters: Filters,
+    ) -> Result<Vec<SearchResult>, Error> {
+        let entry = match self.entry_point {
+            Some(entry) => entry,
+            None => return Ok(vec![]),
+        };
+
+        let mut nearest = entry;
+        for layer in (1..=self.top_level).rev() {
+            let mut search = self.search_pool.scratch(self.base_layer.len());
+            search.ef = 1;
+            search.push(&nearest, &query, &self.vectors, &self.metric);
+            search.search(
+                self.upper_layers[layer - 1].as_slice(),
+                &query,
+                &self.vectors,
+                &self.metric,
+                M,
+                None,
+            );
+
+            if let Some(closest) = search.select_simple().first() {
+                nearest = closest.vector_id;
+            }
+        }
+
+        // Keep the candidate frontier wide open (`ef` = every vector) so
+        // `Search::push` never caps the result short before the radius
+        // cutoff below has a chance to look at it.
+        let mut search = self.search_pool.scratch(self.base_layer.len());
+        search.ef = self.base_layer.len().max(1);
+        search.push(&nearest, &query, &self.vectors, &self.metric);
+        search.search(
+            self.base_layer.as_slice(),
+            &query,
+            &self.vectors,
+            &self.metric,
+            M * 2,
+            Some(radius),
+        );
+
+        let mut results = Vec::new();
+        for candidate in search.select_within(radius) {
+            let id = match self.ids.get(&candidate.vector_id) {
+                Some(id) => *id,
+                None => continue,
+            };
+
+            let record = match self.data.get(&id) {
+                Some(record) => record,
+                None => continue,
+            };
+
+            if !filters.apply(&record.data) {
+                continue;
+            }
+
+            let data = record.data.clone();
+            let distance = candidate.distance.0;
+            results.push(SearchResult { id, distance, data });
+        }
+
+        Ok(results)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hnsw_index() {
+        let config = SourceConfig::default();
+        let metric = DistanceMetric::Euclidean;
+        let mut index = IndexHnsw::new(config, metric);
+        index_tests::populate_index(&mut index);
+        index_tests::test_search(&index);
+        index_tests::test_search_with_filters(&index);
+    }
+
+    #[test]
+    fn test_hnsw_heuristic_neighbor_selection() {
+        let config = SourceConfig::default();
+        let metric = DistanceMetric::Euclidean;
+        let heuristic = HeuristicConfig { extend_candidates: true, keep_pruned: true };
+        let mut index = IndexHnsw::new(config, metric).with_heuristic(heuristic);
+
+        index_tests::populate_index(&mut index);
+        index_tests::test_search(&index);
+        index_tests::test_search_with_filters(&index);
+    }
+}
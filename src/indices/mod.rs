@@ -12,28 +12,41 @@ use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap};
 use std::fmt::Debug;
 use std::path::Path;
+use url::Url;
 
 mod idx_flat;
+mod idx_hnsw;
 mod idx_ivfpq;
+#[cfg(feature = "usearch")]
+mod idx_usearch;
+mod source_file;
 
 // Re-export indices and their parameter types.
 pub use idx_flat::{IndexFlat, ParamsFlat};
-pub use idx_ivfpq::{IndexIVFPQ, ParamsIVFPQ};
+pub use idx_hnsw::{IndexHNSW, ParamsHNSW};
+pub use idx_ivfpq::{IndexIVFPQ, IndexIVFPQMmap, ParamsIVFPQ};
+#[cfg(feature = "usearch")]
+pub use idx_usearch::{IndexUsearch, ParamsUsearch};
 
 /// Name of the SQL table to use as a data source.
 pub type TableName = String;
 
-/// Type of SQL database used as a data source.
+/// Type of data source backing a [`SourceConfig`]: either a SQL database
+/// or a flat file, read directly instead of through a query engine.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SourceType {
     SQLITE,
     POSTGRES,
     MYSQL,
+    /// A `file://...csv` source. See [`SourceConfig::to_file_records`].
+    CSV,
+    /// A `file://...jsonl` source, one JSON object per line.
+    JSONL,
 }
 
 impl From<&str> for SourceType {
-    /// Converts source URL scheme to a source type.
+    /// Converts a SQL source URL scheme to a source type.
     fn from(scheme: &str) -> Self {
         match scheme {
             "sqlite" => SourceType::SQLITE,
@@ -44,6 +57,33 @@ impl From<&str> for SourceType {
     }
 }
 
+impl SourceType {
+    /// Returns true if this source is read directly from a file rather
+    /// than queried from a SQL database.
+    pub fn is_file(&self) -> bool {
+        matches!(self, SourceType::CSV | SourceType::JSONL)
+    }
+
+    /// Resolves a source URL to its [`SourceType`], inspecting the file
+    /// extension for `file://` URLs instead of just the scheme.
+    pub(crate) fn from_url(url: &Url) -> Self {
+        if url.scheme() != "file" {
+            return url.scheme().into();
+        }
+
+        let extension = Path::new(url.path())
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default();
+
+        match extension {
+            "csv" => SourceType::CSV,
+            "jsonl" => SourceType::JSONL,
+            _ => panic!("Unsupported file source extension: {extension}."),
+        }
+    }
+}
+
 /// Data source configuration for a vector index.
 ///
 /// The column data types used as the data source must be the following:
@@ -62,6 +102,9 @@ pub struct SourceConfig {
     pub metadata: Option<Vec<ColumnName>>,
     /// Filter to apply to the SQL query using WHERE clause.
     pub filter: Option<String>,
+    /// Structured filter conditions bound as placeholder values rather than
+    /// interpolated into the query. See [`Self::with_typed_filter`].
+    pub typed_filters: Vec<TypedFilter>,
 }
 
 #[cfg(test)]
@@ -73,6 +116,7 @@ impl Default for SourceConfig {
             vector: "vector".into(),
             metadata: None,
             filter: None,
+            typed_filters: Vec::new(),
         }
     }
 }
@@ -96,6 +140,7 @@ impl SourceConfig {
             vector: vector.into(),
             metadata: None,
             filter: None,
+            typed_filters: Vec::new(),
         }
     }
 
@@ -128,6 +173,32 @@ impl SourceConfig {
         self
     }
 
+    /// Adds a structured filter condition to the source configuration.
+    /// - `column`: Column name to filter on.
+    /// - `operator`: Comparison operator to apply.
+    /// - `value`: Value to compare the column against.
+    ///
+    /// Unlike [`Self::with_filter`]'s raw SQL string, the condition is bound
+    /// as a placeholder value when the query is built instead of being
+    /// interpolated into it, so it can't be used to smuggle SQL and values
+    /// containing quotes don't need escaping. Can be called multiple times;
+    /// conditions are joined with `AND`, alongside the raw filter if both
+    /// are set.
+    pub fn with_typed_filter(
+        mut self,
+        column: impl Into<ColumnName>,
+        operator: Operator,
+        value: Value,
+    ) -> Self {
+        self.typed_filters.push(TypedFilter {
+            column: column.into(),
+            operator,
+            value,
+        });
+
+        self
+    }
+
     /// Returns the list of columns in the following order:
     /// - Primary Key
     /// - Vector
@@ -141,44 +212,158 @@ impl SourceConfig {
         columns.into_iter().map(|s| s.to_owned()).collect()
     }
 
-    /// Generates a SQL query based on the source configuration.
+    /// Generates a SQL query and its ordered bind values based on the
+    /// source configuration.
+    /// - `source_type`: Database backend, which determines the placeholder
+    ///   style (`?` vs. `$1..$n`) used for the bound [`Self::typed_filters`].
     ///
     /// Example:
     /// ```sql
     /// SELECT id, vector, metadata
     /// FROM vectors
-    /// WHERE metadata > 2000
+    /// WHERE metadata > $1
     /// ```
-    pub(crate) fn to_query(&self) -> String {
+    pub(crate) fn to_query(
+        &self,
+        source_type: &SourceType,
+    ) -> Result<(String, Vec<Value>), Error> {
         let table = &self.table;
         let columns = self.columns().join(", ");
-        let filter = match &self.filter {
-            Some(filter) => format!("WHERE {}", filter),
-            None => String::new(),
-        };
+        let (clause, binds) = self.where_clause(source_type, &[], 1)?;
 
-        let query = format!("SELECT {columns} FROM {table} {filter}");
-        query.trim().to_string()
+        let query = format!("SELECT {columns} FROM {table} {clause}");
+        Ok((query.trim().to_string(), binds))
     }
 
-    /// Generates a SQL query string based on the configuration and a primary
-    /// key checkpoint. Instead of returning a query to fetch all records,
-    /// this method returns a query to fetch records from a specific RecordID.
+    /// Generates a SQL query and its ordered bind values based on the
+    /// configuration and a primary key checkpoint. Instead of returning a
+    /// query to fetch all records, this method returns a query to fetch
+    /// records from a specific RecordID.
+    /// - `source_type`: Database backend, which determines the placeholder
+    ///   style used for the bound checkpoint and [`Self::typed_filters`].
     /// - `checkpoint`: Record ID to start the query from.
-    pub(crate) fn to_query_after(&self, checkpoint: &RecordID) -> String {
+    pub(crate) fn to_query_after(
+        &self,
+        source_type: &SourceType,
+        checkpoint: &RecordID,
+    ) -> Result<(String, Vec<Value>), Error> {
         let table = &self.table;
         let pk = &self.primary_key;
         let columns = self.columns().join(", ");
 
-        // Prioritize the primary key filtering before
-        // joining with the optional filter.
-        let mut filter = format!("WHERE {pk} > {}", checkpoint.0);
-        if let Some(string) = &self.filter {
-            filter.push_str(&format!(" AND ({string})"));
+        // Prioritize the primary key filtering before joining with the
+        // optional filters. The checkpoint itself is bound, not
+        // interpolated, same as the typed filters.
+        let checkpoint = Value::Text(checkpoint.0.to_string());
+        let pk_clause = format!("{pk} > {}", self.placeholder(source_type, 1));
+        let (clause, binds) = self.where_clause(source_type, &[pk_clause], 2)?;
+
+        let mut all_binds = vec![checkpoint];
+        all_binds.extend(binds);
+
+        let query = format!("SELECT {columns} FROM {table} {clause}");
+        Ok((query.trim().to_string(), all_binds))
+    }
+
+    /// Builds the `WHERE` clause combining `leading` conditions (already
+    /// rendered with their own placeholders), the raw [`Self::filter`]
+    /// string, and the bound [`Self::typed_filters`], all joined by `AND`.
+    /// - `start_index`: 1-based placeholder index the typed filters should
+    ///   start counting from, accounting for any placeholders `leading`
+    ///   already used.
+    fn where_clause(
+        &self,
+        source_type: &SourceType,
+        leading: &[String],
+        start_index: usize,
+    ) -> Result<(String, Vec<Value>), Error> {
+        let mut conditions: Vec<String> = leading.to_vec();
+        if let Some(filter) = &self.filter {
+            conditions.push(format!("({filter})"));
+        }
+
+        let mut binds = Vec::with_capacity(self.typed_filters.len());
+        for (offset, typed_filter) in self.typed_filters.iter().enumerate() {
+            let placeholder = self.placeholder(source_type, start_index + offset);
+            conditions.push(typed_filter.to_sql(&placeholder)?);
+            binds.push(typed_filter.value.clone());
+        }
+
+        let clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        Ok((clause, binds))
+    }
+
+    /// Renders the `n`th (1-based) bind placeholder for `source_type`:
+    /// `?` for SQLite and MySQL, `$n` for Postgres.
+    fn placeholder(&self, source_type: &SourceType, n: usize) -> String {
+        match source_type {
+            SourceType::POSTGRES => format!("${n}"),
+            SourceType::SQLITE | SourceType::MYSQL => "?".to_string(),
         }
+    }
+
+    /// Generates a parameterized multi-row `INSERT` statement and its
+    /// ordered bind values for `records`, using `source_type`'s placeholder
+    /// style. Columns inserted are [`Self::vector`] followed by each
+    /// configured metadata column, in that order; the primary key is left
+    /// to the source table's auto-increment.
+    /// - `source_type`: Database backend, which determines the placeholder
+    ///   style used for the bound values.
+    /// - `records`: Batch of records to insert, in order.
+    pub(crate) fn to_insert_query(
+        &self,
+        source_type: &SourceType,
+        records: &[Record],
+    ) -> Result<(String, Vec<Value>), Error> {
+        let table = &self.table;
+        let metadata_columns = self.metadata.clone().unwrap_or_default();
+
+        let mut columns = vec![self.vector.clone()];
+        columns.extend(metadata_columns.iter().cloned());
+        let columns_sql = columns.join(", ");
+
+        let mut binds = Vec::with_capacity(records.len() * columns.len());
+        let mut groups = Vec::with_capacity(records.len());
+        let mut n = 1;
+
+        for record in records {
+            let mut placeholders = Vec::with_capacity(columns.len());
+
+            placeholders.push(self.placeholder(source_type, n));
+            n += 1;
+
+            let vector = serde_json::to_string(record.vector.as_slice())?;
+            binds.push(Value::Text(vector));
 
-        let query = format!("SELECT {columns} FROM {table} {filter}");
-        query.trim().to_string()
+            for column in &metadata_columns {
+                placeholders.push(self.placeholder(source_type, n));
+                n += 1;
+
+                let value = record.metadata.get(column).cloned();
+                let value = value.ok_or_else(|| {
+                    let code = ErrorCode::InvalidMetadata;
+                    let message =
+                        format!("Record is missing metadata column: {column}.");
+                    Error::new(code, message)
+                })?;
+
+                binds.push(value);
+            }
+
+            groups.push(format!("({})", placeholders.join(", ")));
+        }
+
+        let query = format!(
+            "INSERT INTO {table} ({columns_sql}) VALUES {}",
+            groups.join(", ")
+        );
+
+        Ok((query, binds))
     }
 
     /// Creates a tuple of record ID and record data from a row.
@@ -204,6 +389,49 @@ impl SourceConfig {
     }
 }
 
+/// A single structured filter condition added via
+/// [`SourceConfig::with_typed_filter`], rendered as a bound SQL comparison
+/// instead of an interpolated string.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypedFilter {
+    column: ColumnName,
+    operator: Operator,
+    value: Value,
+}
+
+impl TypedFilter {
+    /// Renders this condition's SQL, substituting `placeholder` for the
+    /// bound value.
+    fn to_sql(&self, placeholder: &str) -> Result<String, Error> {
+        let column = &self.column;
+        let operator = match self.operator {
+            Operator::Equal => "=",
+            Operator::NotEqual => "!=",
+            Operator::GreaterThan => ">",
+            Operator::GreaterThanOrEqual => ">=",
+            Operator::LessThan => "<",
+            Operator::LessThanOrEqual => "<=",
+            Operator::Contains => "LIKE",
+            // The remaining operators compare against a list of values
+            // rather than a single bound placeholder and aren't supported
+            // by the typed filter API yet; raw `with_filter` still covers
+            // them.
+            Operator::In | Operator::NotIn | Operator::Between => {
+                let code = ErrorCode::InvalidParameter;
+                let message = format!(
+                    "Column {column}: operator {:?} isn't supported by typed \
+                     filters yet.",
+                    self.operator
+                );
+
+                return Err(Error::new(code, message));
+            }
+        };
+
+        Ok(format!("{column} {operator} {placeholder}"))
+    }
+}
+
 /// Algorithm options used to index and search vectors.
 ///
 /// You might want to use a different algorithm based on the size
@@ -213,7 +441,10 @@ impl SourceConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum IndexAlgorithm {
     Flat(ParamsFlat),   // -> IndexFlat
+    HNSW(ParamsHNSW),   // -> IndexHNSW
     IVFPQ(ParamsIVFPQ), // -> IndexIVFPQ
+    #[cfg(feature = "usearch")]
+    Usearch(ParamsUsearch), // -> IndexUsearch
 }
 
 impl IndexAlgorithm {
@@ -221,7 +452,10 @@ impl IndexAlgorithm {
     pub fn name(&self) -> &str {
         match self {
             Self::Flat(_) => "FLAT",
+            Self::HNSW(_) => "HNSW",
             Self::IVFPQ(_) => "IVFPQ",
+            #[cfg(feature = "usearch")]
+            Self::Usearch(_) => "USEARCH",
         }
     }
 }
@@ -246,7 +480,10 @@ impl IndexAlgorithm {
 
         match self.to_owned() {
             Self::Flat(params) => initialize!(IndexFlat, params),
+            Self::HNSW(params) => initialize!(IndexHNSW, params),
             Self::IVFPQ(params) => initialize!(IndexIVFPQ, params),
+            #[cfg(feature = "usearch")]
+            Self::Usearch(params) => initialize!(IndexUsearch, params),
         }
     }
 
@@ -265,7 +502,10 @@ impl IndexAlgorithm {
 
         match self {
             Self::Flat(_) => load!(IndexFlat),
+            Self::HNSW(_) => load!(IndexHNSW),
             Self::IVFPQ(_) => load!(IndexIVFPQ),
+            #[cfg(feature = "usearch")]
+            Self::Usearch(_) => load!(IndexUsearch),
         }
     }
 
@@ -285,7 +525,10 @@ impl IndexAlgorithm {
 
         match self {
             Self::Flat(_) => persist!(IndexFlat),
+            Self::HNSW(_) => persist!(IndexHNSW),
             Self::IVFPQ(_) => persist!(IndexIVFPQ),
+            #[cfg(feature = "usearch")]
+            Self::Usearch(_) => persist!(IndexUsearch),
         }
     }
 
@@ -359,6 +602,74 @@ impl Ord for SearchResult {
     }
 }
 
+/// Per-query search parameters, letting callers trade recall for latency
+/// without rebuilding the index.
+///
+/// `num_probes` and `candidates` are optional overrides layered on top of
+/// `k`; an index with no notion of one of them (e.g. [`IndexFlat`]'s
+/// brute-force scan has no exploration factor to tune) simply ignores it.
+#[derive(Debug, Clone)]
+pub struct SearchParams {
+    /// Number of nearest neighbors to return. Must be greater than zero.
+    pub k: usize,
+    /// Overrides the number of clusters/partitions an index explores for
+    /// this query only (e.g. IVFPQ's `num_probes`), instead of whatever
+    /// it's configured with by default. Must be greater than zero when set.
+    pub num_probes: Option<usize>,
+    /// Caps the number of candidates an index examines before ranking,
+    /// bounding search cost independently of `num_probes` (e.g. HNSW's
+    /// `ef_search`, overridden for this query only).
+    pub candidates: Option<usize>,
+}
+
+impl SearchParams {
+    /// Creates search parameters requesting the `k` nearest neighbors,
+    /// with no overrides.
+    pub fn new(k: usize) -> Self {
+        SearchParams { k, num_probes: None, candidates: None }
+    }
+
+    /// Overrides the number of clusters/partitions explored for this query.
+    pub fn with_num_probes(mut self, num_probes: usize) -> Self {
+        self.num_probes = Some(num_probes);
+        self
+    }
+
+    /// Caps the number of candidates examined for this query.
+    pub fn with_candidates(mut self, candidates: usize) -> Self {
+        self.candidates = Some(candidates);
+        self
+    }
+
+    /// Rejects parameter combinations that would otherwise silently
+    /// return no results, such as `k == 0` or an explicit `num_probes`/
+    /// `candidates` of `0`.
+    pub fn validate(&self) -> Result<(), Error> {
+        let code = ErrorCode::RequestError;
+
+        if self.k == 0 {
+            return Err(Error::new(code, "k must be greater than zero."));
+        }
+
+        if self.num_probes == Some(0) {
+            return Err(Error::new(code, "num_probes must be greater than zero."));
+        }
+
+        if self.candidates == Some(0) {
+            return Err(Error::new(code, "candidates must be greater than zero."));
+        }
+
+        Ok(())
+    }
+}
+
+impl From<usize> for SearchParams {
+    /// Lets call sites that only care about `k` pass a bare number.
+    fn from(k: usize) -> Self {
+        SearchParams::new(k)
+    }
+}
+
 /// Trait for an index implementation.
 ///
 /// This trait defines the basic operations that an index should support.
@@ -416,7 +727,9 @@ pub trait VectorIndex: Debug + Send + Sync {
 
     /// Searches for the nearest neighbors of the query vector.
     /// - `query`: Query vector.
-    /// - `k`: Number of nearest neighbors to return.
+    /// - `params`: Per-query search parameters (`k` plus any overrides an
+    ///   index supports). Implementations must call
+    ///   [`SearchParams::validate`] before acting on it.
     /// - `filters`: Filters to apply to the search results.
     ///
     /// Returns search results sorted by their distance to the query.
@@ -426,7 +739,7 @@ pub trait VectorIndex: Debug + Send + Sync {
     fn search(
         &self,
         query: Vector,
-        k: usize,
+        params: SearchParams,
         filters: Filters,
     ) -> Result<Vec<SearchResult>, Error>;
 
@@ -477,8 +790,9 @@ mod tests {
     #[test]
     fn test_source_config_new() {
         let config = SourceConfig::new("table", "id", "embedding");
-        let query = config.to_query();
+        let (query, binds) = config.to_query(&SourceType::SQLITE).unwrap();
         assert_eq!(query, "SELECT id, embedding FROM table");
+        assert!(binds.is_empty());
     }
 
     #[test]
@@ -487,10 +801,67 @@ mod tests {
             .with_metadata(vec!["metadata"])
             .with_filter("id > 100");
 
-        let query = config.to_query();
+        let (query, binds) = config.to_query(&SourceType::SQLITE).unwrap();
         let expected =
-            "SELECT id, embedding, metadata FROM table WHERE id > 100";
+            "SELECT id, embedding, metadata FROM table WHERE (id > 100)";
         assert_eq!(query, expected);
+        assert!(binds.is_empty());
+    }
+
+    #[test]
+    fn test_source_config_typed_filter_postgres_placeholders() {
+        let config = SourceConfig::new("table", "id", "embedding").with_typed_filter(
+            "age",
+            Operator::GreaterThanOrEqual,
+            Value::Number(21.0),
+        );
+
+        let (query, binds) = config.to_query(&SourceType::POSTGRES).unwrap();
+        assert_eq!(query, "SELECT id, embedding FROM table WHERE age >= $1");
+        assert_eq!(binds, vec![Value::Number(21.0)]);
+    }
+
+    #[test]
+    fn test_source_config_typed_filter_rejects_in_operator() {
+        let config = SourceConfig::new("table", "id", "embedding")
+            .with_typed_filter("age", Operator::In, Value::Number(21.0));
+
+        assert!(config.to_query(&SourceType::SQLITE).is_err());
+    }
+
+    #[test]
+    fn test_source_config_to_query_after_binds_checkpoint() {
+        let config = SourceConfig::new("table", "id", "embedding");
+        let checkpoint = RecordID::new();
+
+        let (query, binds) =
+            config.to_query_after(&SourceType::SQLITE, &checkpoint).unwrap();
+
+        assert_eq!(query, "SELECT id, embedding FROM table WHERE id > ?");
+        assert_eq!(binds.len(), 1);
+    }
+
+    #[test]
+    fn test_search_params_rejects_zero_k() {
+        assert!(SearchParams::new(0).validate().is_err());
+    }
+
+    #[test]
+    fn test_search_params_rejects_zero_num_probes() {
+        let params = SearchParams::new(10).with_num_probes(0);
+        assert!(params.validate().is_err());
+    }
+
+    #[test]
+    fn test_search_params_rejects_zero_candidates() {
+        let params = SearchParams::new(10).with_candidates(0);
+        assert!(params.validate().is_err());
+    }
+
+    #[test]
+    fn test_search_params_accepts_valid_overrides() {
+        let params = SearchParams::new(10).with_num_probes(4).with_candidates(100);
+        assert!(params.validate().is_ok());
     }
 }
 
@@ -520,7 +891,7 @@ mod index_tests {
         let query = Vector::from(vec![0.0; 128]);
         let k = 10;
         let results: Vec<RecordID> = index
-            .search(query, k, Filters::NONE)
+            .search(query, SearchParams::new(k), Filters::NONE)
             .unwrap()
             .iter()
             .map(|result| result.id)
@@ -536,7 +907,7 @@ mod index_tests {
         let query = Vector::from(vec![0.0; 128]);
         let k = 10;
         let filters = Filters::from("number > 1010");
-        let results = index.search(query, k, filters).unwrap();
+        let results = index.search(query, SearchParams::new(k), filters).unwrap();
 
         assert_eq!(results.len(), k);
         assert_eq!(results[0].id, RecordID(11));
@@ -0,0 +1,367 @@
+use super::*;
+use base64::Engine;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+
+/// A single row read from a CSV or JSONL source, keyed by column name,
+/// before it's resolved into a [`RecordID`] and [`Record`].
+type FileRow = HashMap<ColumnName, Value>;
+
+impl SourceConfig {
+    /// Reads every record from the CSV or JSONL file at `path`.
+    /// - `source_type`: [`SourceType::CSV`] or [`SourceType::JSONL`].
+    /// - `path`: Path to the source file.
+    ///
+    /// Returns the parsed records alongside the file's total byte length,
+    /// to pass as `offset` to [`Self::to_file_records_after`] on the next
+    /// incremental refresh.
+    pub(crate) fn to_file_records(
+        &self,
+        source_type: &SourceType,
+        path: impl AsRef<Path>,
+    ) -> Result<(HashMap<RecordID, Record>, u64), Error> {
+        self.to_file_records_after(source_type, path, 0)
+    }
+
+    /// Reads records appended after byte `offset` in the CSV or JSONL file
+    /// at `path`.
+    /// - `source_type`: [`SourceType::CSV`] or [`SourceType::JSONL`].
+    /// - `path`: Path to the source file.
+    /// - `offset`: Byte offset to resume from, as previously returned by
+    ///   this method or [`Self::to_file_records`]. Must land on a line
+    ///   boundary.
+    ///
+    /// Returns the newly parsed records and the file's new total length,
+    /// to use as the next checkpoint.
+    pub(crate) fn to_file_records_after(
+        &self,
+        source_type: &SourceType,
+        path: impl AsRef<Path>,
+        offset: u64,
+    ) -> Result<(HashMap<RecordID, Record>, u64), Error> {
+        let path = path.as_ref();
+        let len = std::fs::metadata(path)?.len();
+
+        let rows = match source_type {
+            SourceType::JSONL => self.read_jsonl_rows(path, offset)?,
+            SourceType::CSV => self.read_csv_rows(path, offset)?,
+            _ => {
+                let code = ErrorCode::InvalidSource;
+                let message = "Source type isn't a file-backed source.";
+                return Err(Error::new(code, message));
+            }
+        };
+
+        let mut records = HashMap::new();
+        for row in rows {
+            let (id, record) = self.row_to_record(row)?;
+            records.insert(id, record);
+        }
+
+        Ok((records, len))
+    }
+
+    /// Reads every JSON object past `offset` in a JSONL file, one per line.
+    fn read_jsonl_rows(
+        &self,
+        path: &Path,
+        offset: u64,
+    ) -> Result<Vec<FileRow>, Error> {
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(offset))?;
+
+        let mut rows = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let json: HashMap<String, serde_json::Value> =
+                serde_json::from_str(&line)?;
+
+            let row = json
+                .into_iter()
+                .map(|(column, value)| (column, json_to_value(value)))
+                .collect();
+
+            rows.push(row);
+        }
+
+        Ok(rows)
+    }
+
+    /// Reads every CSV record past `offset`. The header row is always
+    /// re-read from the start of the file, since `offset` may point past
+    /// it and a resumed CSV reader still needs the column names.
+    fn read_csv_rows(
+        &self,
+        path: &Path,
+        offset: u64,
+    ) -> Result<Vec<FileRow>, Error> {
+        let headers = csv::Reader::from_reader(File::open(path)?)
+            .headers()?
+            .to_owned();
+
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(offset))?;
+
+        // At offset 0 the seeked reader still has the header row ahead of
+        // it, so let `csv::Reader` consume it normally. Past offset 0,
+        // we're already resuming mid-data, so headers must stay off.
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(offset == 0)
+            .from_reader(file);
+
+        let mut rows = Vec::new();
+        for record in reader.records() {
+            let record = record?;
+            let mut row = FileRow::new();
+            for (column, raw) in headers.iter().zip(record.iter()) {
+                row.insert(column.to_string(), infer_value(raw));
+            }
+
+            rows.push(row);
+        }
+
+        Ok(rows)
+    }
+
+    /// Resolves a parsed file `row` into a [`RecordID`] and [`Record`],
+    /// the file-source equivalent of [`Self::to_record`]'s SQL row
+    /// parsing.
+    fn row_to_record(
+        &self,
+        mut row: FileRow,
+    ) -> Result<(RecordID, Record), Error> {
+        let id = row.remove(&self.primary_key).ok_or_else(|| {
+            let code = ErrorCode::InvalidSource;
+            let message = format!(
+                "Row is missing the primary key column: {}.",
+                self.primary_key
+            );
+
+            Error::new(code, message)
+        })?;
+
+        let id = match id {
+            Value::Text(text) => text.parse::<RecordID>().map_err(|_| {
+                let code = ErrorCode::InvalidSource;
+                let message = "Primary key column must be a UUID string.";
+                Error::new(code, message)
+            })?,
+            _ => {
+                let code = ErrorCode::InvalidSource;
+                let message = "Primary key column must be a UUID string.";
+                return Err(Error::new(code, message));
+            }
+        };
+
+        let vector = row.remove(&self.vector).ok_or_else(|| {
+            let code = ErrorCode::InvalidSource;
+            let message =
+                format!("Row is missing the vector column: {}.", self.vector);
+            Error::new(code, message)
+        })?;
+
+        let vector = parse_vector(&vector)?;
+
+        let mut metadata = HashMap::new();
+        if let Some(metadata_columns) = &self.metadata {
+            for column in metadata_columns {
+                if let Some(value) = row.remove(column) {
+                    metadata.insert(column.to_owned(), value);
+                }
+            }
+        }
+
+        let record = Record { vector, metadata };
+        Ok((id, record))
+    }
+
+    /// Appends `records` to the CSV or JSONL file at `path`, generating a
+    /// fresh sortable [`RecordID`] for each one, since a file source has
+    /// no auto-increment primary key to leave this to the way
+    /// [`Self::to_insert_query`]'s SQL `INSERT` does.
+    /// - `source_type`: [`SourceType::CSV`] or [`SourceType::JSONL`].
+    /// - `path`: Path to the source file. Must already exist; for
+    ///   [`SourceType::CSV`], with a matching header.
+    pub(crate) fn to_file_insert(
+        &self,
+        source_type: &SourceType,
+        path: impl AsRef<Path>,
+        records: &[Record],
+    ) -> Result<(), Error> {
+        let metadata_columns = self.metadata.clone().unwrap_or_default();
+        let file =
+            std::fs::OpenOptions::new().append(true).open(path.as_ref())?;
+
+        match source_type {
+            SourceType::JSONL => {
+                let mut writer = std::io::BufWriter::new(file);
+                for record in records {
+                    let id = RecordID::new_sortable();
+                    let mut row = serde_json::Map::new();
+                    row.insert(
+                        self.primary_key.clone(),
+                        serde_json::Value::String(id.to_string()),
+                    );
+
+                    let vector = record.vector.as_slice().to_vec();
+                    let vector = serde_json::to_value(vector)?;
+                    row.insert(self.vector.clone(), vector);
+
+                    for column in &metadata_columns {
+                        if let Some(value) = record.metadata.get(column) {
+                            row.insert(column.clone(), value_to_json(value));
+                        }
+                    }
+
+                    let line = serde_json::to_string(&row)?;
+                    writeln!(writer, "{line}")?;
+                }
+
+                writer.flush()?;
+            }
+            SourceType::CSV => {
+                let mut writer = csv::WriterBuilder::new()
+                    .has_headers(false)
+                    .from_writer(file);
+
+                for record in records {
+                    let id = RecordID::new_sortable();
+                    let vector = record.vector.as_slice();
+                    let vector = serde_json::to_string(vector)?;
+                    let mut row = vec![id.to_string(), vector];
+
+                    for column in &metadata_columns {
+                        let value = record.metadata.get(column);
+                        row.push(format_csv_value(value));
+                    }
+
+                    writer.write_record(&row)?;
+                }
+
+                writer.flush()?;
+            }
+            _ => {
+                let code = ErrorCode::InvalidSource;
+                let message = "Source type isn't a file-backed source.";
+                return Err(Error::new(code, message));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders a metadata value as a CSV cell, the inverse of
+/// [`infer_value`]. A missing value is written as an empty cell.
+fn format_csv_value(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::Text(text)) => text.clone(),
+        Some(Value::Number(number)) => number.to_string(),
+        Some(Value::Int(int)) => int.to_string(),
+        Some(Value::Boolean(boolean)) => boolean.to_string(),
+        Some(Value::Timestamp(micros)) => micros.to_string(),
+        Some(Value::NumberArray(numbers)) => {
+            serde_json::to_string(numbers).unwrap_or_default()
+        }
+        Some(Value::Array(_)) => {
+            panic!("Array values can't be written as record metadata")
+        }
+    }
+}
+
+/// Renders a metadata value as JSON, the inverse of [`json_to_value`].
+fn value_to_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Text(text) => serde_json::Value::String(text.clone()),
+        Value::Number(number) => serde_json::json!(number),
+        Value::Int(int) => serde_json::json!(int),
+        Value::Boolean(boolean) => serde_json::json!(boolean),
+        Value::Null => serde_json::Value::Null,
+        Value::Timestamp(micros) => serde_json::json!(micros),
+        Value::NumberArray(numbers) => serde_json::json!(numbers),
+        Value::Array(_) => {
+            panic!("Array values can't be written as record metadata")
+        }
+    }
+}
+
+/// Parses a vector column's value, either a JSON array of floats (as
+/// written by [`SourceConfig::to_file_insert`]) or a
+/// base64-encoded little-endian `f32` byte string.
+fn parse_vector(value: &Value) -> Result<Vector, Error> {
+    let invalid = || {
+        let code = ErrorCode::InvalidSource;
+        let message = "Vector column must be a JSON float array \
+            or base64-encoded float bytes.";
+        Error::new(code, message)
+    };
+
+    match value {
+        Value::NumberArray(numbers) => {
+            Ok(numbers.iter().map(|n| *n as f32).collect::<Vec<_>>().into())
+        }
+        Value::Text(text) => {
+            if let Ok(numbers) = serde_json::from_str::<Vec<f32>>(text) {
+                return Ok(numbers.into());
+            }
+
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(text.trim())
+                .map_err(|_| invalid())?;
+
+            if bytes.len() % 4 != 0 {
+                return Err(invalid());
+            }
+
+            let numbers = bytes
+                .chunks_exact(4)
+                .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))
+                .collect::<Vec<_>>();
+
+            Ok(numbers.into())
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// Converts a parsed JSON value into our own [`Value`] representation.
+fn json_to_value(json: serde_json::Value) -> Value {
+    match json {
+        serde_json::Value::String(text) => Value::Text(text),
+        serde_json::Value::Bool(boolean) => Value::Boolean(boolean),
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Number(number) => match number.as_i64() {
+            Some(int) => Value::Int(int),
+            None => Value::Number(number.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::Array(array) => {
+            let numbers = array.iter().filter_map(|v| v.as_f64()).collect();
+            Value::NumberArray(numbers)
+        }
+        serde_json::Value::Object(_) => Value::Text(json.to_string()),
+    }
+}
+
+/// Infers a [`Value`] from a raw CSV cell, which `csv` always reads as a
+/// plain string: integer, then float, then boolean, falling back to text.
+fn infer_value(raw: &str) -> Value {
+    if let Ok(int) = raw.parse::<i64>() {
+        return Value::Int(int);
+    }
+
+    if let Ok(number) = raw.parse::<f64>() {
+        return Value::Number(number);
+    }
+
+    match raw {
+        "true" => Value::Boolean(true),
+        "false" => Value::Boolean(false),
+        "" => Value::Null,
+        _ => Value::Text(raw.to_string()),
+    }
+}
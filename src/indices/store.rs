@@ -0,0 +1,217 @@
+use super::*;
+
+/// Backing store for an index's records and graph nodes, behind a common
+/// interface so an index can be generic over where its data actually
+/// lives: fully in memory, or memory-mapped on disk via an embedded
+/// `redb` database for indexes that exceed available RAM.
+///
+/// Vectors/records are typed since every index needs to read them back
+/// as a [`Record`]; graph nodes are opaque bytes since each index
+/// implementation (e.g. HNSW's `BaseNode`/`UpperNode` layers) encodes
+/// its own node representation.
+pub(crate) trait IndexStore: Debug {
+    /// Reads a record by ID, if present.
+    fn get_vector(&self, id: &RecordID) -> Option<Record>;
+
+    /// Inserts or replaces a record.
+    fn put_vector(&mut self, id: RecordID, record: Record);
+
+    /// Removes a record, if present.
+    fn remove_vector(&mut self, id: &RecordID);
+
+    /// Iterates over every stored record, for a flat/exhaustive scan.
+    fn iter_vectors(&self) -> Box<dyn Iterator<Item = (RecordID, Record)> + '_>;
+
+    /// Number of records currently stored.
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Reads a graph node's encoded bytes by its slot ID, if present.
+    fn get_node(&self, id: u32) -> Option<Vec<u8>>;
+
+    /// Inserts or replaces a graph node's encoded bytes.
+    fn put_node(&mut self, id: u32, bytes: Vec<u8>);
+
+    /// Commits any buffered writes to durable storage. A no-op for
+    /// purely in-memory stores.
+    fn commit(&mut self) -> Result<(), Error>;
+}
+
+/// Default store: holds every record and node in memory. Simple and
+/// fast, but caps index size at available RAM and requires a full
+/// serialize/deserialize pass on persist/load.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct MemoryStore {
+    vectors: HashMap<RecordID, Record>,
+    nodes: HashMap<u32, Vec<u8>>,
+}
+
+impl IndexStore for MemoryStore {
+    fn get_vector(&self, id: &RecordID) -> Option<Record> {
+        self.vectors.get(id).cloned()
+    }
+
+    fn put_vector(&mut self, id: RecordID, record: Record) {
+        self.vectors.insert(id, record);
+    }
+
+    fn remove_vector(&mut self, id: &RecordID) {
+        self.vectors.remove(id);
+    }
+
+    fn iter_vectors(&self) -> Box<dyn Iterator<Item = (RecordID, Record)> + '_> {
+        Box::new(self.vectors.iter().map(|(id, record)| (*id, record.clone())))
+    }
+
+    fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    fn get_node(&self, id: u32) -> Option<Vec<u8>> {
+        self.nodes.get(&id).cloned()
+    }
+
+    fn put_node(&mut self, id: u32, bytes: Vec<u8>) {
+        self.nodes.insert(id, bytes);
+    }
+
+    fn commit(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Table holding each record, keyed by its primary key, as bincode-
+/// encoded bytes.
+const VECTORS_TABLE: redb::TableDefinition<u32, &[u8]> =
+    redb::TableDefinition::new("vectors");
+
+/// Table holding each graph node's encoded bytes, keyed by its internal
+/// slot ID.
+const NODES_TABLE: redb::TableDefinition<u32, &[u8]> =
+    redb::TableDefinition::new("nodes");
+
+/// Memory-mapped, crash-safe store backed by an embedded `redb`
+/// database, so an index's records and graph nodes don't need to fit in
+/// RAM and don't need a full serialize/deserialize pass to persist or
+/// reload. Writes are buffered in a transaction and only become durable
+/// once [`IndexStore::commit`] is called, so `insert`/`delete`/`build`
+/// stay atomic.
+#[derive(Debug)]
+pub(crate) struct RedbStore {
+    db: redb::Database,
+    pending: Option<redb::WriteTransaction>,
+}
+
+impl RedbStore {
+    /// Opens (or creates) the `redb` database file at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let db = redb::Database::create(path).map_err(Self::to_error)?;
+
+        // Create the tables up front so readers never have to handle a
+        // missing-table error before the first write.
+        let write = db.begin_write().map_err(Self::to_error)?;
+        write.open_table(VECTORS_TABLE).map_err(Self::to_error)?;
+        write.open_table(NODES_TABLE).map_err(Self::to_error)?;
+        write.commit().map_err(Self::to_error)?;
+
+        Ok(Self { db, pending: None })
+    }
+
+    /// Returns the in-progress write transaction, starting one if none
+    /// is open yet. Kept open across calls so a batch of `insert`/
+    /// `delete` operations only becomes durable on the next `commit`.
+    fn transaction(&mut self) -> Result<&redb::WriteTransaction, Error> {
+        if self.pending.is_none() {
+            let write = self.db.begin_write().map_err(Self::to_error)?;
+            self.pending = Some(write);
+        }
+
+        Ok(self.pending.as_ref().unwrap())
+    }
+
+    fn to_error(err: impl std::fmt::Display) -> Error {
+        Error::new(ErrorCode::InternalError, &err.to_string())
+    }
+}
+
+impl IndexStore for RedbStore {
+    fn get_vector(&self, id: &RecordID) -> Option<Record> {
+        let read = self.db.begin_read().ok()?;
+        let table = read.open_table(VECTORS_TABLE).ok()?;
+        let bytes = table.get(id.0).ok()??;
+        bincode::deserialize(bytes.value()).ok()
+    }
+
+    fn put_vector(&mut self, id: RecordID, record: Record) {
+        let Ok(bytes) = bincode::serialize(&record) else { return };
+        if let Ok(write) = self.transaction() {
+            if let Ok(mut table) = write.open_table(VECTORS_TABLE) {
+                let _ = table.insert(id.0, bytes.as_slice());
+            }
+        }
+    }
+
+    fn remove_vector(&mut self, id: &RecordID) {
+        if let Ok(write) = self.transaction() {
+            if let Ok(mut table) = write.open_table(VECTORS_TABLE) {
+                let _ = table.remove(id.0);
+            }
+        }
+    }
+
+    fn iter_vectors(&self) -> Box<dyn Iterator<Item = (RecordID, Record)> + '_> {
+        let records: Vec<(RecordID, Record)> = (|| {
+            let read = self.db.begin_read().ok()?;
+            let table = read.open_table(VECTORS_TABLE).ok()?;
+            let records = table
+                .iter()
+                .ok()?
+                .filter_map(|entry| entry.ok())
+                .filter_map(|(key, value)| {
+                    let record = bincode::deserialize(value.value()).ok()?;
+                    Some((RecordID(key.value()), record))
+                })
+                .collect();
+
+            Some(records)
+        })()
+        .unwrap_or_default();
+
+        Box::new(records.into_iter())
+    }
+
+    fn len(&self) -> usize {
+        self.db
+            .begin_read()
+            .ok()
+            .and_then(|read| read.open_table(VECTORS_TABLE).ok())
+            .and_then(|table| table.len().ok())
+            .unwrap_or(0) as usize
+    }
+
+    fn get_node(&self, id: u32) -> Option<Vec<u8>> {
+        let read = self.db.begin_read().ok()?;
+        let table = read.open_table(NODES_TABLE).ok()?;
+        let bytes = table.get(id).ok()??;
+        Some(bytes.value().to_vec())
+    }
+
+    fn put_node(&mut self, id: u32, bytes: Vec<u8>) {
+        if let Ok(write) = self.transaction() {
+            if let Ok(mut table) = write.open_table(NODES_TABLE) {
+                let _ = table.insert(id, bytes.as_slice());
+            }
+        }
+    }
+
+    fn commit(&mut self) -> Result<(), Error> {
+        if let Some(write) = self.pending.take() {
+            write.commit().map_err(Self::to_error)?;
+        }
+
+        Ok(())
+    }
+}
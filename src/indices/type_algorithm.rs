@@ -6,6 +6,7 @@ use super::*;
 #[derive(Serialize, Deserialize)]
 pub enum IndexAlgorithm {
     BruteForce, // -> IndexBruteForce
+    Hnsw,       // -> IndexHnsw
 }
 
 impl IndexAlgorithm {
@@ -15,11 +16,12 @@ impl IndexAlgorithm {
         config: SourceConfig,
         metric: DistanceMetric,
     ) -> Box<dyn VectorIndex> {
-        let index = match self {
-            IndexAlgorithm::BruteForce => IndexBruteForce::new(config, metric),
-        };
-
-        Box::new(index)
+        match self {
+            IndexAlgorithm::BruteForce => {
+                Box::new(IndexBruteForce::new(config, metric))
+            }
+            IndexAlgorithm::Hnsw => Box::new(IndexHnsw::new(config, metric)),
+        }
     }
 
     /// Persists the index to a file based on the algorithm.
@@ -34,6 +36,7 @@ impl IndexAlgorithm {
             IndexAlgorithm::BruteForce => {
                 Self::_persist_index::<IndexBruteForce>(path, index)
             }
+            IndexAlgorithm::Hnsw => Self::_persist_index::<IndexHnsw>(path, index),
         }
     }
 
@@ -0,0 +1,171 @@
+use super::*;
+use rayon::prelude::*;
+
+/// A forest of independently-built random-projection trees.
+///
+/// A single [`Tree`] is noisy: one unlucky hyperplane split near the query
+/// point hurts every lookup that crosses it. Querying several trees built
+/// from independent random hyperplanes and taking the union of their
+/// candidates smooths this out, at the cost of re-ranking more candidates.
+pub struct Forest<const N: usize> {
+    trees: Vec<Tree<N>>,
+}
+
+impl<const N: usize> Forest<N> {
+    /// Builds a forest of `num_trees` independently-built trees.
+    /// * `keys` - The keys of vectors.
+    /// * `vectors` - Mapping of keys to vectors.
+    /// * `max_leaf_size` - The maximum number of keys in a leaf.
+    /// * `num_trees` - The number of trees to build.
+    pub fn build(
+        keys: &Vec<&'static str>,
+        vectors: &HashMap<&str, Vector<N>>,
+        max_leaf_size: i32,
+        num_trees: usize,
+    ) -> Forest<N> {
+        let trees = (0..num_trees)
+            .into_par_iter()
+            .map(|_| Tree::build(keys, vectors, max_leaf_size))
+            .collect();
+
+        Forest { trees }
+    }
+
+    /// Inserts a key into every tree in the forest with the given vector.
+    /// * `data` - The new key and vector to insert.
+    /// * `vectors` - Mapping of keys to vectors.
+    /// * `max_leaf_size` - The maximum number of keys in a leaf.
+    pub fn insert(
+        &mut self,
+        data: (&'static str, &Vector<N>),
+        vectors: &HashMap<&'static str, Vector<N>>,
+        max_leaf_size: i32,
+    ) {
+        for tree in self.trees.iter_mut() {
+            tree.insert(data, vectors, max_leaf_size);
+        }
+    }
+
+    /// Deletes a key from every tree in the forest.
+    /// * `data` - The key and vector to delete.
+    pub fn delete(&mut self, data: (&'static str, &Vector<N>)) {
+        for tree in self.trees.iter_mut() {
+            tree.delete(data);
+        }
+    }
+
+    /// Rebuilds every tree whose [`Tree::leaf_size_variance`] exceeds
+    /// `variance_threshold`, from the current `keys`/`vectors` snapshot.
+    /// Rebuilding from the live snapshot is also how deleted keys are
+    /// reaped out of a tree's structure, since `Tree::build` only ever
+    /// places the keys it's given.
+    /// * `keys` - The current, live keys to rebuild unbalanced trees from.
+    /// * `vectors` - Mapping of keys to vectors.
+    /// * `max_leaf_size` - The maximum number of keys in a leaf.
+    /// * `variance_threshold` - Leaf-size variance above which a tree is
+    ///   considered lopsided enough to rebuild.
+    pub fn reap_unbalanced(
+        &mut self,
+        keys: &Vec<&'static str>,
+        vectors: &HashMap<&str, Vector<N>>,
+        max_leaf_size: i32,
+        variance_threshold: f32,
+    ) {
+        for tree in self.trees.iter_mut() {
+            if tree.leaf_size_variance() > variance_threshold {
+                *tree = Tree::build(keys, vectors, max_leaf_size);
+            }
+        }
+    }
+
+    /// Queries every tree concurrently for candidate nearest neighbors of a
+    /// vector, deduplicating across trees into a shared set. Each tree
+    /// gathers up to `n` raw candidates, so the union returned is usually
+    /// larger than `n`; the caller re-ranks it exactly to pick the final
+    /// top `n`.
+    /// * `vector` - The vector to query.
+    /// * `n` - The number of raw candidates to gather per tree.
+    pub fn query(&self, vector: &Vector<N>, n: i32) -> DashSet<&str> {
+        let candidates = DashSet::new();
+
+        self.trees.par_iter().for_each(|tree| {
+            tree.query(&candidates, vector, n);
+        });
+
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Vectors = HashMap<&'static str, Vector<2>>;
+
+    fn sample_vectors() -> (Vec<&'static str>, Vectors) {
+        let keys = vec!["a", "b", "c", "d", "e", "f", "g", "h"];
+        let mut vectors = HashMap::new();
+        for (i, key) in keys.iter().enumerate() {
+            vectors.insert(*key, Vector([i as f32, (i * 2) as f32]));
+        }
+
+        (keys, vectors)
+    }
+
+    #[test]
+    fn test_forest_query_finds_itself() {
+        let (keys, vectors) = sample_vectors();
+        let forest = Forest::build(&keys, &vectors, 2, 4);
+
+        let query = vectors["c"].clone();
+        let candidates = forest.query(&query, keys.len() as i32);
+
+        assert!(candidates.contains("c"));
+    }
+
+    #[test]
+    fn test_forest_query_unions_across_trees() {
+        let (keys, vectors) = sample_vectors();
+        let forest = Forest::build(&keys, &vectors, 2, 8);
+
+        let query = vectors["a"].clone();
+        let one_tree = Tree::build(&keys, &vectors, 2);
+        let one_tree_candidates = DashSet::new();
+        one_tree.query(&one_tree_candidates, &query, 1);
+
+        let forest_candidates = forest.query(&query, 1);
+
+        // A forest with several independently-built trees should surface at
+        // least as many distinct keys as any single tree does for the same
+        // per-tree budget, since it takes the union across trees.
+        assert!(forest_candidates.len() >= one_tree_candidates.len());
+    }
+
+    #[test]
+    fn test_forest_delete_removes_key_from_every_tree() {
+        let (keys, vectors) = sample_vectors();
+        let mut forest = Forest::build(&keys, &vectors, 2, 4);
+
+        let target = vectors["d"].clone();
+        forest.delete(("d", &target));
+
+        let candidates = forest.query(&target, keys.len() as i32);
+        assert!(!candidates.contains("d"));
+    }
+
+    #[test]
+    fn test_forest_reap_unbalanced_keeps_live_keys() {
+        let (mut keys, mut vectors) = sample_vectors();
+        let mut forest = Forest::build(&keys, &vectors, 2, 4);
+
+        // Remove a key from the live snapshot so a rebuild reaps it, then
+        // force every tree to rebuild regardless of its variance.
+        vectors.remove("d");
+        keys.retain(|&key| key != "d");
+        forest.reap_unbalanced(&keys, &vectors, 2, -1.0);
+
+        let candidates = forest.query(&vectors["a"].clone(), keys.len() as i32);
+        assert!(!candidates.contains("d"));
+        assert!(candidates.contains("a"));
+    }
+}
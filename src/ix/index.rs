@@ -5,12 +5,17 @@ use super::*;
 /// * `ef_search`: Nodes to consider during search.
 /// * `ml`: Layer multiplier. The optimal value is `1/ln(M)`.
 /// * `seed`: Seed for random number generator.
+/// * `metric`: Distance metric used to compare vectors.
+/// * `heuristic`: Enables Algorithm 4 heuristic neighbor selection
+///   instead of the default plain nearest-`M` cut when set.
 #[derive(Serialize, Deserialize, Clone, Copy)]
 pub struct IndexConfig {
     pub ef_construction: usize,
     pub ef_search: usize,
     pub ml: f32,
     pub seed: u64,
+    pub metric: Metric,
+    pub heuristic: Option<HeuristicConfig>,
 }
 
 impl Default for IndexConfig {
@@ -19,10 +24,20 @@ impl Default for IndexConfig {
     /// * `ef_search`: 15
     /// * `ml`: 0.3
     /// * `seed`: Randomized integer
+    /// * `metric`: Euclidean
+    /// * `heuristic`: Disabled (plain nearest-`M` selection)
     fn default() -> Self {
         let ml = 0.3;
         let seed: u64 = random();
-        Self { ef_construction: 40, ef_search: 15, ml, seed }
+        let metric = Metric::Euclidean;
+        Self {
+            ef_construction: 40,
+            ef_search: 15,
+            ml,
+            seed,
+            metric,
+            heuristic: None,
+        }
     }
 }
 
@@ -43,15 +58,17 @@ impl<'a, const M: usize, const N: usize> IndexConstruction<'a, M, N> {
         &self,
         vector_id: &VectorID,
         layer: &LayerID,
-        layers: &[Vec<UpperNode<M>>],
+        layers: &[FlatLayer<M>],
     ) {
         let vector = &self.vectors[vector_id];
 
         let (mut search, mut insertion) = self.search_pool.pop();
         insertion.ef = self.config.ef_construction;
 
+        let metric = self.config.metric;
+
         search.reset();
-        search.push(&VectorID(0), vector, self.vectors);
+        search.push(&VectorID(0), vector, self.vectors, metric);
 
         for current_layer in self.top_layer.descend() {
             if current_layer <= *layer {
@@ -60,19 +77,31 @@ impl<'a, const M: usize, const N: usize> IndexConstruction<'a, M, N> {
 
             // Find the nearest neighbor candidates.
             if current_layer > *layer {
-                let layer = layers[current_layer.0 - 1].as_slice();
-                search.search(layer, vector, self.vectors, M);
+                let layer = &layers[current_layer.0 - 1];
+                search.search(layer, vector, self.vectors, M, metric);
                 search.cull();
             } else {
-                search.search(self.base_layer, vector, self.vectors, M);
+                search.search(self.base_layer, vector, self.vectors, M, metric);
                 break;
             }
         }
 
-        // Select the neighbors.
-        let candidates = {
-            let candidates = search.select_simple();
-            &candidates[..Ord::min(candidates.len(), M)]
+        // Select the neighbors. The heuristic pass favors diverse
+        // directions over a tight cluster and gives a much better
+        // connected graph than the plain nearest-`M` cut.
+        let candidates = match &self.config.heuristic {
+            Some(heuristic) => search.select_heuristic(
+                self.base_layer,
+                vector,
+                self.vectors,
+                metric,
+                M,
+                heuristic,
+            ),
+            None => {
+                let candidates = search.select_simple();
+                &candidates[..Ord::min(candidates.len(), M)]
+            }
         };
 
         for (i, candidate) in candidates.iter().enumerate() {
@@ -86,7 +115,7 @@ impl<'a, const M: usize, const N: usize> IndexConstruction<'a, M, N> {
                     Ordering::Greater
                 } else {
                     let other = &self.vectors[id];
-                    distance.cmp(&old.distance(other).into())
+                    distance.cmp(&old.distance(other, metric).into())
                 }
             };
 
@@ -113,9 +142,15 @@ pub struct IndexGraph<D, const N: usize, const M: usize = 32> {
     pub config: IndexConfig,
     pub data: HashMap<VectorID, D>,
     vectors: HashMap<VectorID, Vector<N>>,
+    // Original (pre-normalization) norm of each vector, populated when
+    // `config.metric` is `Metric::Cosine`.
+    norms: HashMap<VectorID, f32>,
     slots: Vec<VectorID>,
-    base_layer: Vec<BaseNode<M>>,
-    upper_layers: Vec<Vec<UpperNode<M>>>,
+    // Free-list of deleted ids, popped by `insert` before minting a new
+    // one off the end of `slots`.
+    tombstones: Vec<VectorID>,
+    base_layer: FlatLayer<M>,
+    upper_layers: Vec<FlatLayer<M>>,
 }
 
 impl<D, const N: usize, const M: usize> Index<&VectorID>
@@ -135,8 +170,10 @@ impl<D: Copy, const N: usize, const M: usize> IndexGraph<D, N, M> {
             config: *config,
             data: HashMap::new(),
             vectors: HashMap::new(),
+            norms: HashMap::new(),
             slots: vec![],
-            base_layer: vec![],
+            tombstones: vec![],
+            base_layer: FlatLayer::from_nodes(&[]),
             upper_layers: vec![],
         }
     }
@@ -182,12 +219,21 @@ impl<D: Copy, const N: usize, const M: usize> IndexGraph<D, N, M> {
         // construction progresses, while preserving randomness in
         // each point's layer and insertion order.
 
-        let vectors = records
+        let mut vectors = records
             .into_iter()
             .enumerate()
             .map(|(i, item)| (VectorID(i as u32), item.vector))
             .collect::<HashMap<VectorID, Vector<N>>>();
 
+        // Cosine distance is computed between unit vectors, so normalize
+        // up front and keep the original norms around for later lookup.
+        let mut norms = HashMap::new();
+        if config.metric == Metric::Cosine {
+            for (id, vector) in vectors.iter_mut() {
+                norms.insert(*id, vector.normalize());
+            }
+        }
+
         // Figure out how many nodes will go on each layer.
         // This helps us allocate memory capacity for each
         // layer in advance, and also helps enable batch
@@ -204,7 +250,7 @@ impl<D: Copy, const N: usize, const M: usize> IndexGraph<D, N, M> {
         // Create index constructor.
 
         let search_pool = SearchPool::new(vectors.len());
-        let mut upper_layers = vec![vec![]; top_layer.0];
+        let mut upper_layers = vec![FlatLayer::from_nodes(&[]); top_layer.0];
         let base_layer = vectors
             .par_iter()
             .map(|_| RwLock::new(BaseNode::default()))
@@ -232,10 +278,12 @@ impl<D: Copy, const N: usize, const M: usize> IndexGraph<D, N, M> {
 
             // Copy the base layer state to the upper layer.
             if !layer.is_zero() {
-                (&state.base_layer[..end])
+                let nodes = (&state.base_layer[..end])
                     .into_par_iter()
-                    .map(|zero| UpperNode::from_zero(&zero.read()))
-                    .collect_into_vec(&mut upper_layers[layer.0 - 1]);
+                    .map(|zero| zero.read().clone())
+                    .collect::<Vec<_>>();
+
+                upper_layers[layer.0 - 1] = FlatLayer::from_nodes(&nodes);
             }
         }
 
@@ -247,34 +295,87 @@ impl<D: Copy, const N: usize, const M: usize> IndexGraph<D, N, M> {
 
         // Unwrap the base nodes for the base layer.
         let base_iter = base_layer.into_par_iter();
-        let base_layer = base_iter.map(|node| node.into_inner()).collect();
+        let base_nodes =
+            base_iter.map(|node| node.into_inner()).collect::<Vec<_>>();
+        let base_layer = FlatLayer::from_nodes(&base_nodes);
 
         // Add IDs to the slots.
         let slots = (0..vectors.len()).map(|i| VectorID(i as u32)).collect();
 
         let config = *config;
+        let tombstones = vec![];
 
-        Self { data, vectors, base_layer, upper_layers, slots, config }
+        Self {
+            data,
+            vectors,
+            norms,
+            base_layer,
+            upper_layers,
+            slots,
+            tombstones,
+            config,
+        }
     }
 
     /// Inserts a vector into a built or new index graph.
     /// * `record`: The vector record to insert.
+    ///
+    /// A thin one-element call into [`Self::insert_batch`]; inserting
+    /// records one at a time still pays the batch's base-layer clone for
+    /// each call, so prefer `insert_batch` for bulk loading.
     pub fn insert(&mut self, record: &IndexRecord<D, N>) {
-        // Create a new vector ID using the next available slot.
-        let id = VectorID(self.slots.len() as u32);
+        self.insert_batch(std::slice::from_ref(record));
+    }
 
-        // Insert the new vector and data.
-        self.vectors.insert(id, record.vector);
-        self.data.insert(id, record.data);
+    /// Inserts a batch of vectors into a built or new index graph.
+    /// * `records`: The vector records to insert.
+    ///
+    /// Unlike calling [`Self::insert`] once per record, this clones the
+    /// base layer into per-node `RwLock`s a single time for the whole
+    /// batch, then inserts every record into that shared, fine-grained-
+    /// locked structure with `par_iter`, reusing one [`SearchPool`] across
+    /// the batch. That turns what would be an O(n) base-layer copy per
+    /// point into one O(n) copy per batch.
+    pub fn insert_batch(&mut self, records: &[IndexRecord<D, N>]) {
+        if records.is_empty() {
+            return;
+        }
 
-        // Create index constructor.
+        // Assign every record an id up front, reusing tombstones first
+        // before minting new ones off the end of `slots`.
+        let ids: Vec<VectorID> = records
+            .iter()
+            .map(|_| match self.tombstones.pop() {
+                Some(id) => {
+                    self.slots[id.0 as usize] = id;
+                    id
+                }
+                None => {
+                    let id = VectorID(self.slots.len() as u32);
+                    self.base_layer.push_node();
+                    self.slots.push(id);
+                    id
+                }
+            })
+            .collect();
 
-        self.base_layer.push(BaseNode::default());
+        // Insert the new vectors and data.
+        for (&id, record) in ids.iter().zip(records) {
+            let mut vector = record.vector.clone();
+            if self.config.metric == Metric::Cosine {
+                self.norms.insert(id, vector.normalize());
+            }
 
-        let base_layer = self
-            .base_layer
-            .par_iter()
-            .map(|node| RwLock::new(node.clone()))
+            self.vectors.insert(id, vector);
+            self.data.insert(id, record.data);
+        }
+
+        // Create index constructor, wrapping the base layer in per-node
+        // locks once for the whole batch.
+
+        let base_layer = (0..self.base_layer.len())
+            .into_par_iter()
+            .map(|i| RwLock::new(self.base_layer.to_base_node(i)))
             .collect::<Vec<_>>();
 
         let top_layer = match self.upper_layers.is_empty() {
@@ -290,28 +391,37 @@ impl<D: Copy, const N: usize, const M: usize> IndexGraph<D, N, M> {
             config: &self.config,
         };
 
-        // Insert new vector into the contructor.
-        state.insert(&id, &top_layer, &self.upper_layers);
-
-        // Add new vector id to the slots.
-        self.slots.push(id);
+        // Insert every vector in the batch in parallel. `IndexConstruction::
+        // insert` only locks the neighbor nodes it mutates, so concurrent
+        // inserters don't contend on unrelated parts of the base layer.
+        let inserter =
+            |id: &VectorID| state.insert(id, &top_layer, &self.upper_layers);
+        ids.par_iter().for_each(inserter);
 
-        // Update the index base layer.
-        self.base_layer = state
+        // Update the index base layer once for the whole batch.
+        let base_nodes = state
             .base_layer
             .into_par_iter()
             .map(|node| node.read().clone())
-            .collect();
+            .collect::<Vec<_>>();
+
+        self.base_layer = FlatLayer::from_nodes(&base_nodes);
     }
 
     pub fn delete(&mut self, id: &VectorID) {
         // Remove the vector from the base layer.
-        let base_node = &mut self.base_layer[id.0 as usize];
-        let index = base_node.iter().position(|x| *x == *id);
+        let node = id.0 as usize;
+        let neighbors = self.base_layer.neighbors(node);
+        let index = neighbors.iter().position(|x| x == id);
         if let Some(index) = index {
-            base_node.set(index, &INVALID);
+            self.base_layer.set(node, index, INVALID);
         }
 
+        // Clear the deleted node's own outgoing edges too, so a later
+        // `insert` that reuses this id starts from an empty neighbor
+        // list instead of one still pointing at stale neighbors.
+        self.base_layer.clear_node(node);
+
         // Remove the vector from the upper layers.
         for layer in LayerID(self.upper_layers.len()).descend() {
             let upper_layer = match layer.0 > 0 {
@@ -319,17 +429,58 @@ impl<D: Copy, const N: usize, const M: usize> IndexGraph<D, N, M> {
                 false => break,
             };
 
-            let node = &mut upper_layer[id.0 as usize];
-            let index = node.0.iter().position(|x| *x == *id);
+            let neighbors = upper_layer.neighbors(node);
+            let index = neighbors.iter().position(|x| x == id);
 
             if let Some(index) = index {
-                node.set(index, &INVALID);
+                upper_layer.set(node, index, INVALID);
             }
         }
 
         self.vectors.remove(id).unwrap();
         self.data.remove(id).unwrap();
+        self.norms.remove(id);
         self.slots[id.0 as usize] = INVALID;
+        self.tombstones.push(*id);
+    }
+
+    /// Ratio of tombstoned (deleted, not yet reclaimed) ids to the total
+    /// number of slots ever allocated. Callers can poll this and call
+    /// [`Self::compact`] once it crosses their own threshold.
+    pub fn tombstone_ratio(&self) -> f32 {
+        if self.slots.is_empty() {
+            return 0.0;
+        }
+
+        self.tombstones.len() as f32 / self.slots.len() as f32
+    }
+
+    /// Rebuilds the graph from its still-live vectors, dropping
+    /// tombstoned slots and remapping ids to a dense `0..n` range. This
+    /// is equivalent to building fresh from the surviving records, and
+    /// reclaims whatever the insert/delete free-list churn left behind.
+    pub fn compact(&mut self) {
+        let records: Vec<IndexRecord<D, N>> = self
+            .slots
+            .iter()
+            .filter(|id| id.is_valid())
+            .map(|id| {
+                let mut vector = self.vectors[id].clone();
+
+                // `build` normalizes cosine vectors itself, so undo the
+                // existing normalization first to avoid doing it twice.
+                if let Some(norm) = self.norms.get(id) {
+                    for x in vector.0.iter_mut() {
+                        *x *= norm;
+                    }
+                }
+
+                let data = *self.data.get(id).unwrap();
+                IndexRecord { vector, data }
+            })
+            .collect();
+
+        *self = Self::build(&self.config, &records);
     }
 
     /// Searches the index graph for the nearest neighbors.
@@ -350,18 +501,20 @@ impl<D: Copy, const N: usize, const M: usize> IndexGraph<D, N, M> {
         let slots_iter = self.slots.as_slice().into_par_iter();
         let vector_id = slots_iter.find_first(|id| id.is_valid()).unwrap();
 
+        let metric = self.config.metric;
+
         search.visited.resize_capacity(self.vectors.len());
-        search.push(vector_id, vector, &self.vectors);
+        search.push(vector_id, vector, &self.vectors, metric);
 
         for layer in LayerID(self.upper_layers.len()).descend() {
             search.ef = if layer.is_zero() { self.config.ef_search } else { 5 };
 
             if layer.0 == 0 {
-                let layer = self.base_layer.as_slice();
-                search.search(layer, vector, &self.vectors, M);
+                let layer = &self.base_layer;
+                search.search(layer, vector, &self.vectors, M, metric);
             } else {
-                let layer = self.upper_layers[layer.0 - 1].as_slice();
-                search.search(layer, vector, &self.vectors, M);
+                let layer = &self.upper_layers[layer.0 - 1];
+                search.search(layer, vector, &self.vectors, M, metric);
             }
 
             if !layer.is_zero() {
@@ -0,0 +1,157 @@
+use super::*;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Commands a running [`MaintenanceWorker`] accepts over its channel.
+pub enum MaintenanceCommand {
+    /// Run a maintenance pass immediately instead of waiting for the next
+    /// tick.
+    RunNow,
+    /// Stop the worker thread.
+    Stop,
+}
+
+/// Cadence and thresholds for a [`MaintenanceWorker`].
+/// * `interval` - How often to inspect the forest for imbalance.
+/// * `max_leaf_size` - Passed through to [`Tree::build`] on rebuild.
+/// * `variance_threshold` - Leaf-size variance above which a tree is
+///   considered lopsided enough to rebuild. See
+///   [`Tree::leaf_size_variance`].
+#[derive(Debug, Clone, Copy)]
+pub struct MaintenanceConfig {
+    pub interval: Duration,
+    pub max_leaf_size: i32,
+    pub variance_threshold: f32,
+}
+
+impl Default for MaintenanceConfig {
+    /// Default configuration for the maintenance worker.
+    /// * `interval`: 60 seconds
+    /// * `max_leaf_size`: 32
+    /// * `variance_threshold`: 0.5
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(60),
+            max_leaf_size: 32,
+            variance_threshold: 0.5,
+        }
+    }
+}
+
+/// Start/stop handle for a [`Forest`]'s background maintenance worker.
+///
+/// The worker thread wakes up every [`MaintenanceConfig::interval`], or on
+/// demand via [`Self::trigger`], rebuilds any tree that's grown lopsided,
+/// and keeps running until [`Self::stop`] is called. Dropping the handle
+/// without calling [`Self::stop`] disconnects the command channel, so the
+/// worker thread exits the next time it checks it; nothing waits for the
+/// thread to finish.
+pub struct MaintenanceWorker {
+    sender: mpsc::Sender<MaintenanceCommand>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MaintenanceWorker {
+    /// Spawns a worker thread that periodically inspects `forest` for
+    /// imbalanced trees and rebuilds them from `vectors`.
+    /// * `forest` - The forest to maintain.
+    /// * `vectors` - Live key-to-vector map to rebuild unbalanced trees
+    ///   from. The worker only rebuilds trees with the keys present here,
+    ///   so deleted keys are reaped out on the next pass.
+    /// * `config` - The worker's cadence and imbalance thresholds.
+    pub fn start<const N: usize>(
+        forest: Arc<RwLock<Forest<N>>>,
+        vectors: Arc<RwLock<HashMap<&'static str, Vector<N>>>>,
+        config: MaintenanceConfig,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let run_pass = move || {
+            let vectors = vectors.read();
+            let keys: Vec<&'static str> = vectors.keys().copied().collect();
+
+            forest.write().reap_unbalanced(
+                &keys,
+                &vectors,
+                config.max_leaf_size,
+                config.variance_threshold,
+            );
+        };
+
+        let thread = thread::spawn(move || loop {
+            match receiver.recv_timeout(config.interval) {
+                Ok(MaintenanceCommand::Stop) => break,
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                Ok(MaintenanceCommand::RunNow) => run_pass(),
+                Err(mpsc::RecvTimeoutError::Timeout) => run_pass(),
+            }
+        });
+
+        Self { sender, thread: Some(thread) }
+    }
+
+    /// Triggers an immediate maintenance pass instead of waiting for the
+    /// next tick.
+    pub fn trigger(&self) {
+        let _ = self.sender.send(MaintenanceCommand::RunNow);
+    }
+
+    /// Stops the worker thread and waits for it to finish.
+    pub fn stop(mut self) {
+        let _ = self.sender.send(MaintenanceCommand::Stop);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_vectors() -> HashMap<&'static str, Vector<2>> {
+        let mut vectors = HashMap::new();
+        for (i, key) in ["a", "b", "c", "d"].iter().enumerate() {
+            vectors.insert(*key, Vector([i as f32, (i * 2) as f32]));
+        }
+
+        vectors
+    }
+
+    #[test]
+    fn test_maintenance_worker_reaps_deleted_key_on_trigger() {
+        let vectors = sample_vectors();
+        let keys: Vec<&'static str> = vectors.keys().copied().collect();
+        let forest = Forest::build(&keys, &vectors, 1, 2);
+
+        let forest = Arc::new(RwLock::new(forest));
+        let vectors = Arc::new(RwLock::new(vectors));
+
+        let config = MaintenanceConfig {
+            interval: Duration::from_secs(60),
+            max_leaf_size: 1,
+            variance_threshold: -1.0,
+        };
+        let worker =
+            MaintenanceWorker::start(forest.clone(), vectors.clone(), config);
+
+        vectors.write().remove("d");
+        worker.trigger();
+
+        let target = vectors.read()["a"].clone();
+        let mut reaped = false;
+        for _ in 0..50 {
+            let candidates = forest.read().query(&target, keys.len() as i32);
+            if !candidates.contains("d") {
+                reaped = true;
+                break;
+            }
+
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        worker.stop();
+        assert!(reaped);
+    }
+}
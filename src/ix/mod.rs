@@ -15,7 +15,7 @@ use rayon::iter::*;
 use serde::{Deserialize, Serialize};
 use serde_big_array::BigArray;
 use std::cmp::*;
-use std::collections::{BinaryHeap, HashMap};
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::ops::{Deref, Index};
 
 // This code is inspired by the HNSW implementation in the
@@ -75,6 +75,43 @@ impl<const N: usize> Tree<N> {
         }
     }
 
+    /// Collects the size of every leaf reachable from this node.
+    fn leaf_sizes(&self, sizes: &mut Vec<usize>) {
+        match self {
+            Tree::Leaf(leaf) => sizes.push(leaf.len()),
+            Tree::Branch(branch) => {
+                branch.left_tree.leaf_sizes(sizes);
+                branch.right_tree.leaf_sizes(sizes);
+            }
+        }
+    }
+
+    /// The variance of this tree's leaf sizes, normalized by the mean leaf
+    /// size, used by the maintenance worker to decide whether a tree has
+    /// become lopsided enough to rebuild. Returns `0.0` for a tree with
+    /// fewer than two leaves.
+    pub fn leaf_size_variance(&self) -> f32 {
+        let mut sizes = Vec::new();
+        self.leaf_sizes(&mut sizes);
+
+        if sizes.len() < 2 {
+            return 0.0;
+        }
+
+        let mean = sizes.iter().sum::<usize>() as f32 / sizes.len() as f32;
+        if mean == 0.0 {
+            return 0.0;
+        }
+
+        let variance = sizes
+            .iter()
+            .map(|&size| (size as f32 - mean).powi(2))
+            .sum::<f32>()
+            / sizes.len() as f32;
+
+        variance / mean
+    }
+
     /// Queries the tree for the nearest neighbors of a vector.
     /// * `candidates` - The set of candidates to add to.
     /// * `vector` - The vector to query.
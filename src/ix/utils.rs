@@ -1,4 +1,5 @@
 use super::*;
+use crate::utils::heuristic as heur;
 
 pub const INVALID: VectorID = VectorID(u32::MAX);
 
@@ -130,13 +131,6 @@ impl<const M: usize> Deref for BaseNode<M> {
     }
 }
 
-impl<'a, const M: usize> Layer for &'a [BaseNode<M>] {
-    type Slice = &'a [VectorID];
-    fn nearest_iter(&self, vector_id: &VectorID) -> NearestIter<Self::Slice> {
-        NearestIter::new(&self[vector_id.0 as usize])
-    }
-}
-
 impl<'a, const M: usize> Layer for &'a [RwLock<BaseNode<M>>] {
     type Slice = MappedRwLockReadGuard<'a, [VectorID]>;
     fn nearest_iter(&self, vector_id: &VectorID) -> NearestIter<Self::Slice> {
@@ -147,23 +141,118 @@ impl<'a, const M: usize> Layer for &'a [RwLock<BaseNode<M>>] {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy)]
-pub struct UpperNode<const M: usize>(
-    #[serde(with = "BigArray")] pub [VectorID; M],
-);
+/// A node's `(offset, count)` view into a [`FlatLayer`]'s flat neighbor
+/// buffer. `offset` is always `node_index * M`; `count` is the number of
+/// populated neighbor slots, tracked explicitly instead of scanning for
+/// the first invalid entry.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+pub struct Meta {
+    pub offset: usize,
+    pub count: usize,
+}
+
+/// A full graph layer's neighbor IDs. Every node's (up to `M`) neighbors
+/// live back-to-back in one flat buffer, indexed by `node_index * M +
+/// slot`, instead of one fixed-size array per node (mirroring
+/// instant-distance's single-`Vec` neighbor layout). This keeps the hot
+/// `search.search(layer, ...)` loop's accesses contiguous and replaces
+/// the old "scan until the first invalid slot" node length lookup with
+/// an O(1) read of `Meta::count`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FlatLayer<const M: usize> {
+    neighbors: Vec<VectorID>,
+    meta: Vec<Meta>,
+}
+
+impl<const M: usize> FlatLayer<M> {
+    /// Builds a flat layer from each node's neighbor array, in order.
+    pub fn from_nodes(nodes: &[BaseNode<M>]) -> Self {
+        let mut neighbors = Vec::with_capacity(nodes.len() * M);
+        let mut meta = Vec::with_capacity(nodes.len());
+
+        for (i, node) in nodes.iter().enumerate() {
+            let count = node.0.iter().take_while(|id| id.is_valid()).count();
+            neighbors.extend_from_slice(&node.0);
+            meta.push(Meta { offset: i * M, count });
+        }
 
-impl<const M: usize> UpperNode<M> {
-    pub fn from_zero(node: &BaseNode<M>) -> Self {
-        let mut nearest = [INVALID; M];
-        nearest.copy_from_slice(&node.0[..M]);
-        Self(nearest)
+        Self { neighbors, meta }
+    }
+
+    /// Number of nodes in this layer.
+    pub fn len(&self) -> usize {
+        self.meta.len()
+    }
+
+    /// Appends a new, empty node to the layer.
+    pub fn push_node(&mut self) {
+        let offset = self.neighbors.len();
+        self.neighbors.resize(offset + M, INVALID);
+        self.meta.push(Meta { offset, count: 0 });
+    }
+
+    /// Materializes node `index`'s neighbor slots back into a `BaseNode`,
+    /// e.g. to feed it back into construction.
+    pub fn to_base_node(&self, index: usize) -> BaseNode<M> {
+        let mut array = [INVALID; M];
+        let slice = self.neighbors(index);
+        array[..slice.len()].copy_from_slice(slice);
+        BaseNode(array)
+    }
+
+    /// Returns node `index`'s populated neighbor IDs.
+    pub fn neighbors(&self, index: usize) -> &[VectorID] {
+        let meta = self.meta[index];
+        &self.neighbors[meta.offset..meta.offset + meta.count]
+    }
+
+    /// Inserts a vector ID into the node's neighbor list at `index`,
+    /// shifting later entries and truncating past `M`.
+    pub fn insert(&mut self, node: usize, index: usize, vector_id: VectorID) {
+        if index >= M {
+            return;
+        }
+
+        let offset = self.meta[node].offset;
+        let count = self.meta[node].count;
+
+        if index < count {
+            let end = offset + count.min(M - 1);
+            let range = offset + index..end;
+            self.neighbors.copy_within(range, offset + index + 1);
+        }
+
+        self.neighbors[offset + index] = vector_id;
+        self.meta[node].count = (count + 1).min(M).max(index + 1);
+    }
+
+    /// Sets the vector ID at `index` within the node's neighbor list.
+    pub fn set(&mut self, node: usize, index: usize, vector_id: VectorID) {
+        if index >= M {
+            return;
+        }
+
+        let offset = self.meta[node].offset;
+        self.neighbors[offset + index] = vector_id;
+
+        let meta = &mut self.meta[node];
+        if index >= meta.count {
+            meta.count = index + 1;
+        }
+    }
+
+    /// Drops node `index`'s neighbor list back to empty, without touching
+    /// its backing slots (still reserved, just no longer read by
+    /// `neighbors`/`nearest_iter`), e.g. to recycle a tombstoned id.
+    pub fn clear_node(&mut self, index: usize) {
+        self.meta[index].count = 0;
     }
 }
 
-impl<'a, const M: usize> Layer for &'a [UpperNode<M>] {
+impl<'a, const M: usize> Layer for &'a FlatLayer<M> {
     type Slice = &'a [VectorID];
     fn nearest_iter(&self, vector_id: &VectorID) -> NearestIter<Self::Slice> {
-        NearestIter::new(&self[vector_id.0 as usize].0)
+        NearestIter::new(self.neighbors(vector_id.0 as usize))
     }
 }
 
@@ -233,8 +322,8 @@ pub struct Search<const M: usize, const N: usize> {
     pub visited: Visited,
     candidates: BinaryHeap<Reverse<Candidate>>,
     nearest: Vec<Candidate>,
-    working: Vec<Candidate>,
-    discarded: Vec<Candidate>,
+    working: Vec<heur::Candidate<VectorID>>,
+    discarded: Vec<heur::Candidate<VectorID>>,
 }
 
 impl<const M: usize, const N: usize> Search<M, N> {
@@ -250,6 +339,7 @@ impl<const M: usize, const N: usize> Search<M, N> {
         vector: &Vector<N>,
         vectors: &HashMap<VectorID, Vector<N>>,
         links: usize,
+        metric: Metric,
     ) {
         while let Some(Reverse(candidate)) = self.candidates.pop() {
             // Skip candidates that are too far.
@@ -261,7 +351,7 @@ impl<const M: usize, const N: usize> Search<M, N> {
 
             let layer_iter = layer.nearest_iter(&candidate.vector_id);
             for vector_id in layer_iter.take(links) {
-                self.push(&vector_id, vector, vectors);
+                self.push(&vector_id, vector, vectors, metric);
             }
 
             self.nearest.truncate(self.ef);
@@ -274,14 +364,22 @@ impl<const M: usize, const N: usize> Search<M, N> {
         vector_id: &VectorID,
         vector: &Vector<N>,
         vectors: &HashMap<VectorID, Vector<N>>,
+        metric: Metric,
     ) {
         if !self.visited.insert(vector_id) {
             return;
         }
 
+        // A tombstoned id can still be referenced by another node's
+        // neighbor list until the next `compact`; skip it defensively
+        // instead of indexing into a vector that's no longer there.
+        let other = match vectors.get(vector_id) {
+            Some(other) => other,
+            None => return,
+        };
+
         // Create a new candidate.
-        let other = &vectors[vector_id];
-        let distance = OrderedFloat::from(vector.distance(other));
+        let distance = OrderedFloat::from(vector.distance(other, metric));
         let new = Candidate { distance, vector_id: *vector_id };
 
         // Make sure the index to insert to is within the EF scope.
@@ -322,6 +420,64 @@ impl<const M: usize, const N: usize> Search<M, N> {
         &self.nearest
     }
 
+    /// Selects up to `m` neighbors using the Malkov-Yashunin heuristic
+    /// (Algorithm 4 in the HNSW paper): repeatedly takes the candidate
+    /// nearest the query and keeps it only if it's closer to the query
+    /// than to every neighbor already kept, which favors diverse
+    /// directions over a tight cluster and gives much better graph
+    /// connectivity than [`Self::select_simple`]. The actual pass is
+    /// shared with every other HNSW-style index in this crate; see
+    /// [`heur::extend_candidates`] and [`heur::select_diverse`].
+    ///
+    /// When `heuristic.extend_candidates` is set, the working set is
+    /// first widened with each candidate's own neighbors on `layer`
+    /// before the pass runs, at the cost of extra distance computations.
+    /// When `heuristic.keep_pruned` is set and fewer than `m` candidates
+    /// survive the pass, the discarded candidates backfill the rest in
+    /// distance order rather than leaving the node under-connected.
+    pub fn select_heuristic<L: Layer>(
+        &mut self,
+        layer: L,
+        query: &Vector<N>,
+        vectors: &HashMap<VectorID, Vector<N>>,
+        metric: Metric,
+        m: usize,
+        heuristic: &HeuristicConfig,
+    ) -> &[Candidate] {
+        self.working.clear();
+        self.working.extend(self.nearest.drain(..).map(|c| heur::Candidate {
+            distance: c.distance,
+            id: c.vector_id,
+        }));
+
+        if heuristic.extend_candidates {
+            heur::extend_candidates(
+                &mut self.working,
+                |id| layer.nearest_iter(&id).collect(),
+                |id| vectors.get(&id).map(|v| v.distance(query, metric)),
+            );
+        }
+
+        let accepted = heur::select_diverse(
+            &mut self.working,
+            &mut self.discarded,
+            m,
+            heuristic.keep_pruned,
+            |id| vectors.contains_key(&id),
+            |a, b| {
+                let a = vectors.get(&a)?;
+                let b = vectors.get(&b)?;
+                Some(a.distance(b, metric))
+            },
+        );
+
+        self.nearest = accepted
+            .into_iter()
+            .map(|c| Candidate { distance: c.distance, vector_id: c.id })
+            .collect();
+        &self.nearest
+    }
+
     pub fn iter(
         &self,
     ) -> impl Iterator<Item = Candidate> + ExactSizeIterator + '_ {
@@ -15,12 +15,89 @@ impl VectorID {
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Vector<const N: usize>(#[serde(with = "BigArray")] pub [f32; N]);
 
+/// Distance metric used to compare vectors in the index graph.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum Metric {
+    /// Euclidean distance, with a final square root.
+    Euclidean,
+    /// Cosine distance (1 - cosine similarity).
+    Cosine,
+    /// Negative dot product. Smaller is nearer, matching the other
+    /// variants, but unlike `Cosine` it isn't normalized by magnitude.
+    DotProduct,
+    /// Manhattan distance (L1 norm of the difference).
+    Manhattan,
+}
+
+impl Default for Metric {
+    /// Defaults to `Euclidean`, matching the index graph's prior behavior.
+    fn default() -> Self {
+        Metric::Euclidean
+    }
+}
+
+/// Opts a build into Algorithm 4's heuristic neighbor selection instead of
+/// the default plain nearest-`M` cut, trading extra construction-time work
+/// for a better-connected graph.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct HeuristicConfig {
+    /// Widen the candidate set with each candidate's own neighbors on the
+    /// layer being connected before running the selection pass.
+    pub extend_candidates: bool,
+    /// Backfill from the candidates the pass discarded, in distance
+    /// order, when fewer than `M` neighbors survive it.
+    pub keep_pruned: bool,
+}
+
 impl<const N: usize> Vector<N> {
-    /// Returns the Euclidean distance between two vectors.
-    pub fn distance(&self, other: &Self) -> f32 {
+    /// Returns the distance between two vectors under the given metric.
+    pub fn distance(&self, other: &Self, metric: Metric) -> f32 {
+        match metric {
+            Metric::Euclidean => self.sqeuclidean(other),
+            Metric::Cosine => self.cosine(other),
+            Metric::DotProduct => self.dot(other),
+            Metric::Manhattan => self.manhattan(other),
+        }
+    }
+
+    fn sqeuclidean(&self, other: &Self) -> f32 {
         let iter = self.0.iter().zip(other.0.iter());
         iter.map(|(a, b)| (a - b).powi(2)).sum::<f32>().sqrt()
     }
+
+    fn cosine(&self, other: &Self) -> f32 {
+        let iter = self.0.iter().zip(other.0.iter());
+        let dot = iter.map(|(a, b)| a * b).sum::<f32>();
+        1.0 - dot / (self.norm() * other.norm())
+    }
+
+    fn dot(&self, other: &Self) -> f32 {
+        let iter = self.0.iter().zip(other.0.iter());
+        -iter.map(|(a, b)| a * b).sum::<f32>()
+    }
+
+    fn manhattan(&self, other: &Self) -> f32 {
+        let iter = self.0.iter().zip(other.0.iter());
+        iter.map(|(a, b)| (a - b).abs()).sum()
+    }
+
+    /// Returns the vector's L2 norm (magnitude).
+    pub fn norm(&self) -> f32 {
+        self.0.iter().map(|x| x.powi(2)).sum::<f32>().sqrt()
+    }
+
+    /// Normalizes the vector in place to unit length and returns its
+    /// original norm.
+    pub fn normalize(&mut self) -> f32 {
+        let norm = self.norm();
+        if norm > 0.0 {
+            for x in self.0.iter_mut() {
+                *x /= norm;
+            }
+        }
+
+        norm
+    }
 }
 
 impl<const N: usize> Index<&VectorID> for [Vector<N>] {
@@ -22,11 +22,13 @@ async fn main() {
         .arg_required_else_help(true)
         .subcommand(start())
         .subcommand(configure())
+        .subcommand(upgrade())
         .get_matches();
 
     match command.subcommand() {
         Some(("start", args)) => start_handler(args).await,
         Some(("configure", args)) => configure_handler(args).await,
+        Some(("upgrade", _)) => upgrade_handler().await,
         _ => unreachable!(),
     }
 }
@@ -48,7 +50,8 @@ async fn start_handler(args: &ArgMatches) {
     let port = args.get_one::<u16>("port").unwrap();
     let addr = format!("[::]:{port}").parse().unwrap();
 
-    let database = Database::open().expect("Failed to open the database");
+    let database =
+        Database::open().await.expect("Failed to open the database");
     let service = DatabaseServer::new(Arc::new(database));
 
     tracing::info!("The database server is ready on port {port}");
@@ -76,18 +79,54 @@ fn configure() -> Command {
         .value_parser(clap::value_parser!(usize))
         .allow_negative_numbers(false);
 
+    let arg_compression_level = arg!(
+        --"compression-level" <level>
+        "zstd level to compress snapshot files with, 0 to disable"
+    )
+    .default_value("0")
+    .value_parser(clap::value_parser!(i32))
+    .allow_negative_numbers(false);
+
     Command::new("configure")
         .about("Configure the initial database parameters")
         .arg(arg_dimension)
         .arg(arg_metric)
         .arg(arg_density)
+        .arg(arg_compression_level)
 }
 
 async fn configure_handler(args: &ArgMatches) {
     let dim = *args.get_one::<usize>("dim").unwrap();
     let metric = *args.get_one::<Metric>("metric").unwrap();
     let density = *args.get_one::<usize>("density").unwrap();
+    let compression_level = *args.get_one::<i32>("compression-level").unwrap();
+
+    let params = Parameters {
+        dimension: dim,
+        metric,
+        density,
+        compression_level,
+    };
+
+    Database::configure(&params).await;
+}
+
+fn upgrade() -> Command {
+    Command::new("upgrade").about(
+        "Rewrite the database's snapshot files at the current format version",
+    )
+}
+
+async fn upgrade_handler() {
+    // Opening the database already migrates an older snapshot in memory;
+    // creating a fresh snapshot right after rewrites it atomically at the
+    // current format version.
+    let database =
+        Database::open().await.expect("Failed to open the database");
+    let stats = database
+        .create_snapshot()
+        .await
+        .expect("Failed to rewrite the snapshot");
 
-    let params = Parameters { dimension: dim, metric, density };
-    Database::configure(&params);
+    println!("Snapshot upgraded successfully with {} record(s)", stats.count);
 }
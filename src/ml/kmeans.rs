@@ -26,6 +26,7 @@ pub struct KMeans {
     metric: Metric,
     n_clusters: usize,
     max_iter: usize,
+    balanced: bool,
 }
 
 impl KMeans {
@@ -41,6 +42,7 @@ impl KMeans {
             max_iter: 300,
             assignments: Vec::new(),
             centroids: Vec::with_capacity(n_clusters),
+            balanced: false,
         }
     }
 
@@ -56,6 +58,18 @@ impl KMeans {
         self
     }
 
+    /// Configure whether the two clusters should be kept evenly sized.
+    ///
+    /// Only applies when `n_clusters` is 2. Instead of assigning each point
+    /// to its nearest centroid independently, points are ranked by the
+    /// signed distance difference `d(x, c0) - d(x, c1)` and split down the
+    /// middle, so each cluster ends up with roughly half the points. This
+    /// avoids the lopsided splits plain 2-means can produce.
+    pub fn with_balanced(mut self, balanced: bool) -> Self {
+        self.balanced = balanced;
+        self
+    }
+
     /// Train the K-means algorithm with the given vectors.
     pub fn fit(&mut self, vectors: Vectors) -> Result<()> {
         if self.n_clusters > vectors.len() {
@@ -168,12 +182,41 @@ impl KMeans {
 
     /// Create cluster assignments for the vectors.
     fn assign_clusters(&self, vectors: Vectors) -> Vec<ClusterIndex> {
+        if self.balanced && self.n_clusters == 2 {
+            return self.assign_clusters_balanced(vectors);
+        }
+
         vectors
             .par_iter()
             .map(|vector| self.find_nearest_centroid(vector))
             .collect()
     }
 
+    /// Split the vectors evenly between the two centroids by ranking them
+    /// on the signed distance difference `d(x, c0) - d(x, c1)` and cutting
+    /// the sorted list in half.
+    fn assign_clusters_balanced(&self, vectors: Vectors) -> Vec<ClusterIndex> {
+        let mut diffs: Vec<(usize, f64)> = vectors
+            .par_iter()
+            .enumerate()
+            .map(|(i, vector)| {
+                let d0 = self.metric.distance(vector, &self.centroids[0]);
+                let d1 = self.metric.distance(vector, &self.centroids[1]);
+                (i, d0.unwrap_or(0.0) - d1.unwrap_or(0.0))
+            })
+            .collect();
+
+        diffs.sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap());
+
+        let midpoint = diffs.len() / 2;
+        let mut assignments = vec![0; vectors.len()];
+        for (rank, (i, _)) in diffs.into_iter().enumerate() {
+            assignments[i] = if rank < midpoint { 0 } else { 1 };
+        }
+
+        assignments
+    }
+
     /// Find the index of the nearest centroid from a vector.
     pub fn find_nearest_centroid(&self, vector: &Vector) -> ClusterIndex {
         self.centroids
@@ -228,6 +271,24 @@ mod tests {
         evaluate_kmeans(10, generate_vectors(100));
     }
 
+    #[test]
+    fn test_kmeans_balanced_split() {
+        let vectors = generate_vectors(101);
+        let vectors: Vectors = {
+            let vectors_ref: Vec<&Vector> = vectors.iter().collect();
+            Rc::from(vectors_ref.as_slice())
+        };
+
+        let mut kmeans = KMeans::new(2).with_balanced(true);
+        kmeans.fit(vectors.clone()).unwrap();
+
+        let assignments = kmeans.assignments();
+        let cluster_0 = assignments.iter().filter(|c| **c == 0).count();
+        let cluster_1 = assignments.iter().filter(|c| **c == 1).count();
+
+        assert!(cluster_0.abs_diff(cluster_1) <= 1);
+    }
+
     fn evaluate_kmeans(n_cluster: usize, vectors: Vec<Vector>) {
         let vectors: Vectors = {
             let vectors_ref: Vec<&Vector> = vectors.iter().collect();
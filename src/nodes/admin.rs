@@ -0,0 +1,180 @@
+use super::*;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use axum::routing::get;
+use axum::Router;
+use serde_json::json;
+
+impl CoordinatorNode {
+    /// Serve a read-only JSON admin API on `port`, alongside the gRPC
+    /// server, so operators can inspect cluster topology without querying
+    /// Postgres directly. Exposes `GET /status`, mirroring [`Self::status`],
+    /// and `GET /metrics`, a Prometheus text-exposition scrape target for
+    /// [`NodeMetrics`]; a separate, lower-privilege surface from the gRPC
+    /// API, meant to sit behind an operator-only network boundary rather
+    /// than be exposed to clients.
+    pub fn spawn_admin_server(self: &Arc<Self>, port: u16) {
+        let router = Router::new()
+            .route("/status", get(status_handler))
+            .route("/metrics", get(coordinator_metrics_handler))
+            .with_state(self.clone());
+
+        let addr = SocketAddr::from(([0, 0, 0, 0], port));
+        tokio::spawn(async move {
+            tracing::info!("admin API is running at port {port}");
+            let listener = tokio::net::TcpListener::bind(addr)
+                .await
+                .expect("Failed to bind the admin API port");
+
+            axum::serve(listener, router)
+                .await
+                .expect("Failed to start the admin API server");
+        });
+    }
+}
+
+impl DataNode {
+    /// Serve a read-only `/metrics` Prometheus scrape target on `port`,
+    /// alongside the gRPC server, mirroring
+    /// [`CoordinatorNode::spawn_admin_server`]'s admin API.
+    pub fn spawn_admin_server(self: &Arc<Self>, port: u16) {
+        let router = Router::new()
+            .route("/metrics", get(data_metrics_handler))
+            .with_state(self.clone());
+
+        let addr = SocketAddr::from(([0, 0, 0, 0], port));
+        tokio::spawn(async move {
+            tracing::info!("admin API is running at port {port}");
+            let listener = tokio::net::TcpListener::bind(addr)
+                .await
+                .expect("Failed to bind the admin API port");
+
+            axum::serve(listener, router)
+                .await
+                .expect("Failed to start the admin API server");
+        });
+    }
+}
+
+async fn status_handler(
+    State(node): State<Arc<CoordinatorNode>>,
+) -> impl IntoResponse {
+    match node.status().await {
+        Ok(status) => (StatusCode::OK, Json(status)).into_response(),
+        Err(error) => {
+            let body = Json(json!({ "error": error.message() }));
+            (StatusCode::INTERNAL_SERVER_ERROR, body).into_response()
+        }
+    }
+}
+
+async fn coordinator_metrics_handler(
+    State(node): State<Arc<CoordinatorNode>>,
+) -> impl IntoResponse {
+    (StatusCode::OK, node.metrics.render())
+}
+
+async fn data_metrics_handler(
+    State(node): State<Arc<DataNode>>,
+) -> impl IntoResponse {
+    (StatusCode::OK, node.metrics.render())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::postgres::test_utils;
+
+    #[tokio::test]
+    async fn test_admin_server_status_endpoint() {
+        let params = test_utils::node_parameters();
+        let db = test_utils::database_url();
+
+        let mut conn = PgConnection::connect(&db.to_string()).await.unwrap();
+        test_utils::drop_schema(&mut conn, "odb_coordinator").await;
+
+        let pool_params = PoolParameters::default();
+        let max_elapsed = Duration::from_secs(5);
+        CoordinatorNode::configure(
+            db.to_owned(),
+            params,
+            pool_params,
+            max_elapsed,
+        )
+        .await;
+
+        let node = Arc::new(CoordinatorNode::new(db, max_elapsed).await);
+        node.spawn_admin_server(18085);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let response = reqwest::get("http://0.0.0.0:18085/status")
+            .await
+            .expect("Failed to reach the admin API");
+
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["dimension"], 128);
+    }
+
+    #[tokio::test]
+    async fn test_admin_server_metrics_endpoint() {
+        let params = test_utils::node_parameters();
+        let db = test_utils::database_url();
+
+        let mut conn = PgConnection::connect(&db.to_string()).await.unwrap();
+        test_utils::drop_schema(&mut conn, "odb_coordinator").await;
+
+        let pool_params = PoolParameters::default();
+        let max_elapsed = Duration::from_secs(5);
+        CoordinatorNode::configure(
+            db.to_owned(),
+            params,
+            pool_params,
+            max_elapsed,
+        )
+        .await;
+
+        let node = Arc::new(CoordinatorNode::new(db, max_elapsed).await);
+        node.spawn_admin_server(18086);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let response = reqwest::get("http://0.0.0.0:18086/metrics")
+            .await
+            .expect("Failed to reach the admin API");
+
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+        let body = response.text().await.unwrap();
+        assert!(body.contains("oasysdb_node_queries_total"));
+    }
+
+    #[tokio::test]
+    async fn test_data_admin_server_metrics_endpoint() {
+        let name = "admin_metrics_test";
+        let schema_name = format!("odb_node_{name}");
+
+        let params = test_utils::node_parameters();
+        let db = test_utils::database_url();
+
+        let mut conn = PgConnection::connect(&db.to_string()).await.unwrap();
+        test_utils::drop_schema(&mut conn, &schema_name).await;
+
+        let node = Arc::new(DataNode::new(name, params, db).await);
+        node.spawn_admin_server(18087);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let response = reqwest::get("http://0.0.0.0:18087/metrics")
+            .await
+            .expect("Failed to reach the admin API");
+
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+        let body = response.text().await.unwrap();
+        assert!(body.contains("oasysdb_node_inserts_total"));
+    }
+}
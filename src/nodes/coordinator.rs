@@ -1,7 +1,18 @@
 use super::*;
 use crate::protoc;
+use crate::types::Metric;
+use futures::future::join_all;
 use futures::StreamExt;
 use protoc::coordinator_node_server::CoordinatorNode as ProtoCoordinatorNode;
+use protoc::data_node_client::DataNodeClient;
+use sqlx::pool::PoolConnection;
+use sqlx::postgres::{PgPoolOptions, Postgres};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tonic::transport::Channel;
+
+type Pool = sqlx::PgPool;
 
 /// Coordinator node definition.
 ///
@@ -13,16 +24,34 @@ pub struct CoordinatorNode {
     params: NodeParameters,
     database_url: DatabaseURL,
     schema: CoordinatorSchema,
+    registry: Arc<NodeRegistry>,
+    pool: Pool,
+    /// Cached `DataNode` clients, keyed by address, so a [`Self::query`]
+    /// fan-out reuses an already-connected channel instead of redialing
+    /// the same data node on every request.
+    channels: Mutex<HashMap<Box<str>, DataNodeClient<Channel>>>,
+    /// Prometheus counters, rendered by the admin API's `/metrics` route.
+    pub(crate) metrics: NodeMetrics,
 }
 
 impl CoordinatorNode {
-    /// Create a new coordinator node instance.
+    /// Create a new coordinator node instance, pooling its Postgres
+    /// connections according to the `max_connections`/`acquire_timeout_secs`
+    /// persisted by [`Self::configure`].
     /// - `database_url`: URL to the Postgres database.
-    pub async fn new(database_url: impl Into<DatabaseURL>) -> Self {
+    /// - `max_elapsed`: How long to retry a transient connection failure
+    ///   (e.g. the database isn't accepting connections yet) before giving
+    ///   up. See [`connect_with_retry`].
+    pub async fn new(
+        database_url: impl Into<DatabaseURL>,
+        max_elapsed: Duration,
+    ) -> Self {
         let database_url = database_url.into();
-        let mut connection = PgConnection::connect(database_url.as_ref())
-            .await
-            .expect("Failed to connect to Postgres database");
+        let mut connection = connect_with_retry(max_elapsed, || {
+            PgConnection::connect(database_url.as_ref())
+        })
+        .await
+        .expect("Failed to connect to Postgres database");
 
         let schema = CoordinatorSchema::new();
         let parameter_table = schema.parameter_table();
@@ -35,40 +64,83 @@ impl CoordinatorNode {
         .expect("Configure the coordinator node first");
 
         params.trace();
-        Self { params, database_url, schema }
+
+        let (max_connections, acquire_timeout_secs): (i32, i32) =
+            sqlx::query_as(&format!(
+                "SELECT max_connections, acquire_timeout_secs
+                FROM {parameter_table}"
+            ))
+            .fetch_one(&mut connection)
+            .await
+            .expect("Configure the coordinator node first");
+
+        let pool = connect_with_retry(max_elapsed, || {
+            PgPoolOptions::new()
+                .max_connections(max_connections as u32)
+                .acquire_timeout(Duration::from_secs(
+                    acquire_timeout_secs as u64,
+                ))
+                // Ping a pooled connection with `SELECT 1` before handing it
+                // out, so a connection the database dropped is recycled
+                // instead of failing the request that borrows it.
+                .test_before_acquire(true)
+                .connect(database_url.as_ref())
+        })
+        .await
+        .expect("Failed to connect to Postgres database");
+
+        schema.migrate(&mut connection).await;
+
+        let registry = Arc::new(NodeRegistry::default());
+        let channels = Mutex::new(HashMap::new());
+        let metrics = NodeMetrics::default();
+        Self { params, database_url, schema, registry, pool, channels, metrics }
     }
 
     /// Configure the coordinator node with parameters.
     /// - `database_url`: URL to the Postgres database.
     /// - `params`: Coordinator node parameters.
+    /// - `pool_params`: Sizing and timeout of the pool [`Self::new`] builds
+    ///   once the node starts.
+    /// - `max_elapsed`: How long to retry a transient connection failure
+    ///   before giving up. See [`connect_with_retry`].
     pub async fn configure(
         database_url: impl Into<DatabaseURL>,
         params: impl Into<NodeParameters>,
+        pool_params: PoolParameters,
+        max_elapsed: Duration,
     ) {
         let params = params.into();
         let database_url = database_url.into();
 
-        let mut conn = PgConnection::connect(database_url.as_ref())
-            .await
-            .expect("Failed to connect to Postgres database");
+        let mut conn = connect_with_retry(max_elapsed, || {
+            PgConnection::connect(database_url.as_ref())
+        })
+        .await
+        .expect("Failed to connect to Postgres database");
 
         let schema = CoordinatorSchema::new();
-        if !schema.exists(&mut conn).await {
-            schema.create(&mut conn).await;
-            schema.create_all_tables(&mut conn).await;
+        if !schema.exists(&mut PgEngine(&mut conn)).await {
+            schema.create(&mut PgEngine(&mut conn)).await;
+            schema.create_all_tables(&mut PgEngine(&mut conn)).await;
             tracing::info!("the database is provisioned for the coordinator");
         }
 
         let parameter_table = schema.parameter_table();
         sqlx::query(&format!(
-            "INSERT INTO {parameter_table} (metric, dimension, density)
-            VALUES ($1, $2, $3)
+            "INSERT INTO {parameter_table}
+                (metric, dimension, density,
+                max_connections, acquire_timeout_secs)
+            VALUES ($1, $2, $3, $4, $5)
             ON CONFLICT (singleton)
-            DO UPDATE SET metric = $1, dimension = $2, density = $3"
+            DO UPDATE SET metric = $1, dimension = $2, density = $3,
+                max_connections = $4, acquire_timeout_secs = $5"
         ))
         .bind(params.metric.as_str())
         .bind(params.dimension as i32)
         .bind(params.density as i32)
+        .bind(pool_params.max_size as i32)
+        .bind(pool_params.acquire_timeout.as_secs() as i32)
         .execute(&mut conn)
         .await
         .expect("Failed to configure the node parameters");
@@ -80,6 +152,927 @@ impl CoordinatorNode {
     pub fn params(&self) -> &NodeParameters {
         &self.params
     }
+
+    /// Return the registry tracking registered data nodes' liveness.
+    pub fn registry(&self) -> &NodeRegistry {
+        &self.registry
+    }
+
+    /// Return a pooled connection to the node's Postgres database.
+    ///
+    /// Overrides [`NodeExt::connect`]'s default of opening a brand new
+    /// connection per call: this acquires one from the pool created in
+    /// [`Self::new`] instead, reusing and recycling connections across the
+    /// gRPC requests this node handles.
+    pub async fn connect(&self) -> Result<PoolConnection<Postgres>, Status> {
+        self.pool.acquire().await.map_err(|_| {
+            Status::internal("Failed to acquire a Postgres connection")
+        })
+    }
+
+    /// Poll every registered data node's `heartbeat` RPC once, recording a
+    /// fresh heartbeat in [`Self::registry`] for every node that responds.
+    /// A node that fails to respond simply keeps aging towards
+    /// [`NodeHealth::Suspect`]/[`NodeHealth::Down`] rather than failing
+    /// this call.
+    async fn poll_data_nodes(&self) -> Result<(), Status> {
+        let mut conn = self.connect().await?;
+        let connection_table = self.schema.connection_table();
+        let nodes: Vec<NodeConnection> =
+            sqlx::query_as(&format!("SELECT name, address, count FROM {connection_table}"))
+                .fetch_all(&mut conn)
+                .await
+                .map_err(|_| Status::internal("Failed to list registered data nodes"))?;
+
+        for node in nodes {
+            let name = node.name.clone();
+            let address = format!("http://{}", node.address);
+            let heartbeat = async {
+                let mut client = DataNodeClient::connect(address).await?;
+                let request = Request::new(protoc::HeartbeatRequest::default());
+                client.heartbeat(request).await
+            }
+            .await;
+
+            match heartbeat {
+                Ok(response) => {
+                    let response = response.into_inner();
+                    let recorded = self
+                        .record_node_heartbeat(
+                            &mut conn,
+                            name.clone(),
+                            response.record_count,
+                            response.cluster_count,
+                            response.schema_version,
+                        )
+                        .await;
+
+                    if let Err(error) = recorded {
+                        tracing::warn!(
+                            "failed to persist heartbeat for {name}: {error}"
+                        );
+                    }
+                }
+                Err(error) => {
+                    tracing::warn!(
+                        "data node \"{name}\" missed a heartbeat: {error}"
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Updates [`Self::registry`]'s in-memory health state and persists
+    /// `last_seen` for `name`, so the on-disk audit trail agrees with it.
+    /// Shared by [`Self::poll_data_nodes`], [`Self::status`], and the
+    /// push-based [`ProtoCoordinatorNode::heartbeat`] below, so every path
+    /// that hears from a data node records it the same way.
+    async fn record_node_heartbeat(
+        &self,
+        conn: &mut PgConnection,
+        name: impl Into<Box<str>>,
+        record_count: u64,
+        cluster_count: u64,
+        schema_version: i32,
+    ) -> Result<(), Status> {
+        let name = name.into();
+        self.registry.record_heartbeat(
+            name.clone(),
+            record_count,
+            cluster_count,
+            schema_version,
+        );
+
+        let connection_table = self.schema.connection_table();
+        sqlx::query(&format!(
+            "UPDATE {connection_table} SET last_seen = now() WHERE name = $1"
+        ))
+        .bind(name.as_ref())
+        .execute(conn)
+        .await
+        .map_err(|_| Status::internal("Failed to record node heartbeat"))?;
+
+        Ok(())
+    }
+
+    /// Spawn a background task that polls every registered data node's
+    /// liveness on a fixed `interval`, so [`Self::registry`] stays fresh
+    /// without the caller driving it manually.
+    pub fn spawn_health_monitor(self: &Arc<Self>, interval: Duration) {
+        let node = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if let Err(error) = node.poll_data_nodes().await {
+                    tracing::warn!("failed to poll data node heartbeats: {error}");
+                }
+            }
+        });
+    }
+
+    /// Reports cluster topology and health: the node's configured
+    /// parameters, and every registered data node's address, a
+    /// reachability flag obtained by pinging it just now, and its latest
+    /// known subcluster/record counts. Modeled after Garage's admin
+    /// cluster status API so operators can script health checks against a
+    /// stable shape.
+    pub async fn status(&self) -> Result<ClusterStatus, Status> {
+        let mut conn = self.connect().await?;
+
+        let state_table = self.schema.state_table();
+        let (initialized, layout_version): (bool, i32) = sqlx::query_as(
+            &format!(
+                "SELECT initialized, layout_version
+                FROM {state_table} LIMIT 1"
+            ),
+        )
+        .fetch_optional(&mut conn)
+        .await
+        .map_err(|_| Status::internal("Failed to read the node state"))?
+        .unwrap_or((true, 0));
+
+        let connection_table = self.schema.connection_table();
+        let connections: Vec<(Box<str>, Box<str>)> = sqlx::query_as(
+            &format!("SELECT name, address FROM {connection_table}"),
+        )
+        .fetch_all(&mut conn)
+        .await
+        .map_err(|_| {
+            Status::internal("Failed to list registered data nodes")
+        })?;
+
+        let subcluster_table = self.schema.subcluster_table();
+        let subcluster_counts: Vec<(Box<str>, i64)> = sqlx::query_as(
+            &format!(
+                "SELECT connection_name, COUNT(*) FROM {subcluster_table}
+                GROUP BY connection_name"
+            ),
+        )
+        .fetch_all(&mut conn)
+        .await
+        .map_err(|_| Status::internal("Failed to count subclusters"))?;
+
+        let mut nodes = Vec::with_capacity(connections.len());
+        for (name, address) in connections {
+            let subcluster_count = subcluster_counts
+                .iter()
+                .find(|(node_name, _)| *node_name == name)
+                .map(|(_, count)| *count)
+                .unwrap_or(0);
+
+            let reachable = ping_node(&address).await;
+            if let Ok(response) = &reachable {
+                let recorded = self
+                    .record_node_heartbeat(
+                        &mut conn,
+                        name.clone(),
+                        response.record_count,
+                        response.cluster_count,
+                        response.schema_version,
+                    )
+                    .await;
+
+                if let Err(error) = recorded {
+                    tracing::warn!(
+                        "failed to persist heartbeat for \"{name}\": {error}"
+                    );
+                }
+            }
+
+            let record_count = match &reachable {
+                Ok(response) => response.record_count,
+                Err(_) => self
+                    .registry
+                    .snapshot()
+                    .into_iter()
+                    .find(|(status, _)| status.name == name)
+                    .map(|(status, _)| status.record_count)
+                    .unwrap_or(0),
+            };
+
+            nodes.push(NodeStatusReport {
+                name: name.clone(),
+                address,
+                reachable: reachable.is_ok(),
+                health: self.registry.health(&name),
+                subcluster_count,
+                record_count,
+            });
+        }
+
+        Ok(ClusterStatus {
+            initialized,
+            metric: self.params.metric.as_str().into(),
+            dimension: self.params.dimension,
+            density: self.params.density,
+            layout_version,
+            node_count: nodes.len(),
+            nodes,
+        })
+    }
+
+    /// Plans and persists sub-cluster moves off every registered data node
+    /// that exceeds its capacity-weighted target share, using
+    /// [`plan_subcluster_moves`]'s min-cost max-flow formulation. Every
+    /// sub-cluster of an overloaded node is a candidate, so as many move as
+    /// the other nodes' spare capacity takes, which can leave a donor below
+    /// its target rather than just at it. Each move physically relocates the sub-cluster's
+    /// cluster row and records via [`Self::transfer_subcluster`], not just
+    /// the ownership pointer. Called automatically from
+    /// [`ProtoCoordinatorNode::register_node`] when a new node joins an
+    /// already-initialized cluster, so it doesn't sit idle while older
+    /// nodes stay overloaded.
+    ///
+    /// This is distinct from [`Self::rebalance_clusters`]: that method
+    /// picks replica owners for coordinator-level `clusters` rows via
+    /// weighted reservoir sampling, while this one reassigns rows in the
+    /// `subclusters` table (sub-clusters reported by data nodes), which
+    /// needs an incremental, load-and-distance-aware placement instead of
+    /// a full re-sample on every call.
+    ///
+    /// Incremental: only sub-clusters owned by a node whose current count
+    /// exceeds its target share are considered, so a node already within
+    /// its share keeps its sub-clusters untouched.
+    pub async fn rebalance_subclusters(
+        &self,
+    ) -> Result<Vec<SubclusterMove>, Status> {
+        let mut conn = self.connect().await?;
+
+        let state_table = self.schema.state_table();
+        let initialized: bool = sqlx::query_scalar(&format!(
+            "SELECT initialized FROM {state_table} LIMIT 1"
+        ))
+        .fetch_optional(&mut conn)
+        .await
+        .map_err(|_| Status::internal("Failed to read coordinator state"))?
+        .unwrap_or(true);
+
+        if !initialized {
+            return Ok(Vec::new());
+        }
+
+        let (node_count,): (i32,) =
+            sqlx::query_as(&format!("SELECT node_count FROM {state_table}"))
+                .fetch_one(&mut conn)
+                .await
+                .map_err(|_| {
+                    Status::internal("Failed to read coordinator state")
+                })?;
+
+        let connection_table = self.schema.connection_table();
+        let capacities: Vec<NodeCapacity> = sqlx::query_as(&format!(
+            "SELECT name, capacity FROM {connection_table}"
+        ))
+        .fetch_all(&mut conn)
+        .await
+        .map_err(|_| Status::internal("Failed to list data node capacities"))?;
+
+        let live_nodes = self.registry.live_node_names();
+        let capacities: Vec<NodeCapacity> = capacities
+            .into_iter()
+            .filter(|capacity| {
+                live_nodes.is_empty() || live_nodes.contains(&capacity.name)
+            })
+            .collect();
+
+        if capacities.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        tracing::info!(
+            "rebalancing sub-clusters across {} of {node_count} registered",
+            capacities.len()
+        );
+
+        let subcluster_table = self.schema.subcluster_table();
+        let rows: Vec<(Uuid, String, Vec<u8>)> = sqlx::query_as(&format!(
+            "SELECT id, connection_name, centroid FROM {subcluster_table}"
+        ))
+        .fetch_all(&mut conn)
+        .await
+        .map_err(|_| Status::internal("Failed to list sub-clusters"))?;
+
+        let mut current: HashMap<Box<str>, usize> = HashMap::new();
+        let mut node_centroids: HashMap<Box<str>, Vec<Vector>> = HashMap::new();
+        let mut loads = Vec::with_capacity(rows.len());
+        for (id, connection_name, centroid) in rows {
+            let connection_name: Box<str> = connection_name.into_boxed_str();
+            let centroid: Vector = bincode::deserialize(&centroid).map_err(|_| {
+                Status::internal("Failed to deserialize sub-cluster centroid")
+            })?;
+
+            *current.entry(connection_name.clone()).or_insert(0) += 1;
+            node_centroids
+                .entry(connection_name.clone())
+                .or_default()
+                .push(centroid.clone());
+
+            loads.push(SubclusterLoad { id, connection_name, centroid });
+        }
+
+        let total = loads.len();
+        let total_capacity: f32 = capacities.iter().map(|c| c.capacity).sum();
+        let targets: Vec<NodeShare> = capacities
+            .iter()
+            .map(|capacity| {
+                let share = total as f32 * (capacity.capacity / total_capacity);
+                NodeShare {
+                    name: capacity.name.clone(),
+                    current: *current.get(&capacity.name).unwrap_or(&0),
+                    target: share.round() as usize,
+                }
+            })
+            .collect();
+
+        let mean_centroids: HashMap<Box<str>, Vector> = node_centroids
+            .into_iter()
+            .filter_map(|(name, centroids)| {
+                mean_centroid(&centroids).map(|centroid| (name, centroid))
+            })
+            .collect();
+
+        let overloaded: Vec<SubclusterLoad> = loads
+            .into_iter()
+            .filter(|load| {
+                let node =
+                    targets.iter().find(|n| n.name == load.connection_name);
+                node.is_some_and(|n| n.current > n.target)
+            })
+            .collect();
+
+        let moves = plan_subcluster_moves(
+            &overloaded,
+            &targets,
+            &mean_centroids,
+            self.params.metric,
+        );
+
+        let donors: HashMap<Uuid, Box<str>> = overloaded
+            .iter()
+            .map(|load| (load.id, load.connection_name.clone()))
+            .collect();
+
+        for placement in &moves {
+            let Some(from) = donors.get(&placement.subcluster_id) else {
+                continue;
+            };
+
+            self.transfer_subcluster(
+                &mut conn,
+                placement.subcluster_id,
+                from,
+                &placement.to,
+            )
+            .await?;
+        }
+
+        Ok(moves)
+    }
+
+    /// Physically relocates one sub-cluster's cluster row and all of its
+    /// records from data node `from`'s schema to `to`'s schema, then
+    /// repoints [`Self::schema`]'s `subclusters` ownership row to `to` —
+    /// all inside a single transaction, so a query never sees the
+    /// sub-cluster owned by neither node or both at once.
+    ///
+    /// Idempotent: every statement tolerates re-running a move that
+    /// already completed (the `INSERT`s no-op via `ON CONFLICT`, the
+    /// `DELETE`s no-op once the source rows are already gone), so a crash
+    /// between [`Self::rebalance_subclusters`] planning a move and this
+    /// committing it is safe to retry from scratch on the next rebalance.
+    async fn transfer_subcluster(
+        &self,
+        conn: &mut PgConnection,
+        subcluster_id: Uuid,
+        from: &str,
+        to: &str,
+    ) -> Result<(), Status> {
+        let from_schema = DataSchema::new(from);
+        let to_schema = DataSchema::new(to);
+
+        let from_cluster_table = from_schema.cluster_table();
+        let to_cluster_table = to_schema.cluster_table();
+        let from_record_table = from_schema.record_table();
+        let to_record_table = to_schema.record_table();
+
+        let mut tx = conn.begin().await.map_err(|_| {
+            Status::internal("Failed to start sub-cluster transfer")
+        })?;
+
+        sqlx::query(&format!(
+            "INSERT INTO {to_cluster_table} (id, centroid)
+            SELECT id, centroid FROM {from_cluster_table} WHERE id = $1
+            ON CONFLICT (id) DO NOTHING"
+        ))
+        .bind(subcluster_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| {
+            Status::internal("Failed to copy the sub-cluster to its new node")
+        })?;
+
+        sqlx::query(&format!(
+            "INSERT INTO {to_record_table} (id, cluster_id, vector, data)
+            SELECT id, cluster_id, vector, data
+            FROM {from_record_table} WHERE cluster_id = $1
+            ON CONFLICT (id) DO NOTHING"
+        ))
+        .bind(subcluster_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| {
+            Status::internal("Failed to copy records to the new node")
+        })?;
+
+        sqlx::query(&format!(
+            "DELETE FROM {from_record_table} WHERE cluster_id = $1"
+        ))
+        .bind(subcluster_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| {
+            Status::internal("Failed to remove migrated records from \
+                the old node")
+        })?;
+
+        sqlx::query(&format!("DELETE FROM {from_cluster_table} WHERE id = $1"))
+            .bind(subcluster_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| {
+                Status::internal(
+                    "Failed to remove the migrated sub-cluster from \
+                    the old node",
+                )
+            })?;
+
+        let subcluster_table = self.schema.subcluster_table();
+        sqlx::query(&format!(
+            "UPDATE {subcluster_table}
+            SET connection_name = $1 WHERE id = $2"
+        ))
+        .bind(to)
+        .bind(subcluster_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| Status::internal("Failed to persist sub-cluster move"))?;
+
+        tx.commit().await.map_err(|_| {
+            Status::internal("Failed to commit sub-cluster transfer")
+        })?;
+
+        Ok(())
+    }
+
+    /// Recomputes the partition ring's target assignment from every live
+    /// data node's capacity (excluding `draining`, if given), persists
+    /// only the partitions whose owner actually changed, bumps
+    /// [`Self::schema`]'s layout version, and physically migrates each
+    /// moved partition's sub-clusters via [`Self::transfer_subcluster`].
+    ///
+    /// Distinct from [`Self::rebalance_subclusters`]: that method
+    /// incrementally reassigns sub-clusters that have drifted over an
+    /// already-settled target share, while this one recomputes the whole
+    /// partition ring from scratch via [`assign_partitions`] and diffs it
+    /// against the last persisted layout via [`diff_partitions`], so it's
+    /// the one that needs to run whenever the candidate set itself changes
+    /// (a node joins or is draining).
+    async fn rebalance_partitions(
+        &self,
+        draining: Option<&str>,
+    ) -> Result<Vec<PartitionMove>, Status> {
+        let mut conn = self.connect().await?;
+
+        let connection_table = self.schema.connection_table();
+        let capacities: Vec<NodeCapacity> = sqlx::query_as(&format!(
+            "SELECT name, capacity FROM {connection_table}"
+        ))
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|_| Status::internal("Failed to list data node capacities"))?;
+
+        let live_nodes = self.registry.live_node_names();
+        let candidates: Vec<PlacementCandidate> = capacities
+            .into_iter()
+            .filter(|capacity| {
+                Some(capacity.name.as_ref()) != draining
+                    && (live_nodes.is_empty()
+                        || live_nodes.contains(&capacity.name))
+            })
+            .map(PlacementCandidate::from)
+            .collect();
+
+        if candidates.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let partition_table = self.schema.partition_table();
+        let current: Vec<PartitionAssignment> = sqlx::query_as(&format!(
+            "SELECT partition, connection_name FROM {partition_table}"
+        ))
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|_| Status::internal("Failed to read the partition layout"))?;
+
+        let target = assign_partitions(&candidates);
+        let moves = diff_partitions(&current, &target);
+        if moves.is_empty() {
+            return Ok(moves);
+        }
+
+        tracing::info!(
+            "partition layout changed, migrating {} of {} partitions",
+            moves.len(),
+            target.len()
+        );
+
+        for placement in &moves {
+            sqlx::query(&format!(
+                "INSERT INTO {partition_table} (partition, connection_name)
+                VALUES ($1, $2)
+                ON CONFLICT (partition) DO UPDATE SET connection_name = $2"
+            ))
+            .bind(placement.partition as i32)
+            .bind(placement.to.as_ref())
+            .execute(&mut *conn)
+            .await
+            .map_err(|_| {
+                Status::internal("Failed to persist the partition layout")
+            })?;
+        }
+
+        let state_table = self.schema.state_table();
+        sqlx::query(&format!(
+            "UPDATE {state_table} SET layout_version = layout_version + 1"
+        ))
+        .execute(&mut *conn)
+        .await
+        .map_err(|_| Status::internal("Failed to bump the layout version"))?;
+
+        let subcluster_table = self.schema.subcluster_table();
+        for placement in &moves {
+            let Some(from) = &placement.from else { continue };
+
+            let subclusters: Vec<(Uuid,)> = sqlx::query_as(&format!(
+                "SELECT id FROM {subcluster_table} WHERE connection_name = $1"
+            ))
+            .bind(from.as_ref())
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|_| {
+                Status::internal("Failed to list sub-clusters to migrate")
+            })?;
+
+            for (subcluster_id,) in subclusters {
+                if partition_of(subcluster_id) != placement.partition {
+                    continue;
+                }
+
+                self.transfer_subcluster(
+                    &mut conn,
+                    subcluster_id,
+                    from,
+                    &placement.to,
+                )
+                .await?;
+            }
+        }
+
+        Ok(moves)
+    }
+
+    /// Migrates every partition owned by `name` onto the remaining live
+    /// data nodes by excluding it from [`Self::rebalance_partitions`]'s
+    /// placement candidates, so its share of the ring is reassigned and
+    /// physically moved before it disconnects instead of after, avoiding a
+    /// window where in-flight queries route to a node that's already gone.
+    pub async fn drain_node(
+        &self,
+        name: &str,
+    ) -> Result<Vec<PartitionMove>, Status> {
+        self.rebalance_partitions(Some(name)).await
+    }
+
+    /// Scatter-gather the `count` nearest records to `vector` across the
+    /// data nodes that own the `probes` nearest sub-clusters, recomputing
+    /// distances on merge via [`merge_candidates`] rather than trusting
+    /// any one node's partial order, then drops any candidate past
+    /// `radius`.
+    ///
+    /// Concurrent and partial-failure tolerant: an unreachable data node
+    /// is logged and recorded in [`QueryResult::errors`] instead of
+    /// failing the whole query, since the remaining nodes can still
+    /// return a usable result. Each data node's `DataNodeClient` channel
+    /// is cached in `self.channels` and reused across calls instead of
+    /// being redialed per query.
+    pub async fn query(
+        &self,
+        vector: &Vector,
+        count: usize,
+        probes: usize,
+        radius: f32,
+    ) -> Result<QueryResult, Status> {
+        self.metrics.record_query();
+        let mut conn = self.connect().await?;
+
+        let subcluster_table = self.schema.subcluster_table();
+        let rows: Vec<(Uuid, String, Vec<u8>)> = sqlx::query_as(&format!(
+            "SELECT id, connection_name, centroid FROM {subcluster_table}"
+        ))
+        .fetch_all(&mut conn)
+        .await
+        .map_err(|_| Status::internal("Failed to list sub-clusters"))?;
+
+        let mut subclusters = Vec::with_capacity(rows.len());
+        for (id, connection_name, centroid) in rows {
+            let connection_name = connection_name.into_boxed_str();
+            let centroid: Vector = bincode::deserialize(&centroid).map_err(|_| {
+                Status::internal("Failed to deserialize sub-cluster centroid")
+            })?;
+
+            subclusters.push(SubclusterLoad { id, connection_name, centroid });
+        }
+
+        let metric = self.params.metric;
+        let probed = rank_subclusters(vector, &subclusters, metric, probes);
+
+        let mut by_node: HashMap<Box<str>, Vec<String>> = HashMap::new();
+        for subcluster in probed {
+            by_node
+                .entry(subcluster.connection_name.clone())
+                .or_default()
+                .push(subcluster.id.to_string());
+        }
+
+        if by_node.is_empty() {
+            return Ok(QueryResult::default());
+        }
+
+        let names: Vec<String> =
+            by_node.keys().map(ToString::to_string).collect();
+
+        let connection_table = self.schema.connection_table();
+        let query = format!(
+            "SELECT name, address FROM {connection_table} WHERE name = ANY($1)"
+        );
+
+        let addresses: HashMap<Box<str>, String> =
+            sqlx::query_as::<_, (String, String)>(&query)
+                .bind(&names)
+                .fetch_all(&mut conn)
+                .await
+                .map_err(|_| {
+                    Status::internal("Failed to list data node addresses")
+                })?
+                .into_iter()
+                .map(|(name, address)| (name.into_boxed_str(), address))
+                .collect();
+
+        let fanout = by_node.into_iter().map(|(name, cluster_ids)| {
+            self.metrics.record_fanout(&name);
+            let address = addresses.get(&name).cloned();
+            let vector = vector.as_slice().to_vec();
+
+            async move {
+                let result = match address {
+                    Some(address) => {
+                        self.search_node(&address, vector, cluster_ids, count)
+                            .await
+                    }
+                    None => Err("data node has no registered address".into()),
+                };
+
+                (name, result)
+            }
+        });
+
+        let partials = join_all(fanout).await;
+        for (name, result) in &partials {
+            if let Err(message) = result {
+                tracing::warn!(
+                    "data node {name} unreachable during query, returning \
+                    partial results: {message}"
+                );
+            }
+        }
+
+        let mut result = merge_candidates(partials, vector, metric, count);
+        result.candidates.retain(|c| c.distance <= radius as f64);
+        Ok(result)
+    }
+
+    /// Scatter-gather the `count` nearest candidates for `vector` across
+    /// every registered data node, each searching the sub-clusters it
+    /// owns. Unlike [`Self::query`], which narrows the fan-out to the
+    /// nodes owning the `probes` nearest sub-clusters, this broadcasts to
+    /// the whole cluster membership.
+    ///
+    /// Nodes are contacted in tiers of `fanout` at a time, most-reliable
+    /// first by [`NodeRegistry::rank_by_reliability`] (recent success rate,
+    /// then round-trip time), and a tier only runs if the previous one
+    /// didn't return at least `count` candidates. Each attempt is bounded
+    /// by `deadline`, so one slow or dead node can't stall the whole
+    /// query; a timeout or connection failure is recorded as a per-node
+    /// error in the returned [`QueryResult`] (see
+    /// [`QueryResult::degraded`]) rather than failing the call, and also
+    /// feeds back into the node's reliability ranking for the next call.
+    pub async fn search_cluster(
+        &self,
+        vector: &Vector,
+        count: usize,
+        fanout: usize,
+        deadline: Duration,
+    ) -> Result<QueryResult, Status> {
+        self.metrics.record_query();
+        let mut conn = self.connect().await?;
+
+        let subcluster_table = self.schema.subcluster_table();
+        let rows: Vec<(String, Uuid)> = sqlx::query_as(&format!(
+            "SELECT connection_name, id FROM {subcluster_table}"
+        ))
+        .fetch_all(&mut conn)
+        .await
+        .map_err(|_| Status::internal("Failed to list sub-clusters"))?;
+
+        let mut by_node: HashMap<Box<str>, Vec<String>> = HashMap::new();
+        for (connection_name, id) in rows {
+            by_node
+                .entry(connection_name.into_boxed_str())
+                .or_default()
+                .push(id.to_string());
+        }
+
+        if by_node.is_empty() {
+            return Ok(QueryResult::default());
+        }
+
+        let names: Vec<String> =
+            by_node.keys().map(ToString::to_string).collect();
+
+        let connection_table = self.schema.connection_table();
+        let query = format!(
+            "SELECT name, address FROM {connection_table} WHERE name = ANY($1)"
+        );
+
+        let addresses: HashMap<Box<str>, String> =
+            sqlx::query_as::<_, (String, String)>(&query)
+                .bind(&names)
+                .fetch_all(&mut conn)
+                .await
+                .map_err(|_| {
+                    Status::internal("Failed to list data node addresses")
+                })?
+                .into_iter()
+                .map(|(name, address)| (name.into_boxed_str(), address))
+                .collect();
+
+        let all_names: Vec<Box<str>> = by_node.keys().cloned().collect();
+        let ranked = self.registry.rank_by_reliability(&all_names);
+
+        let mut partials = Vec::with_capacity(ranked.len());
+        for tier in ranked.chunks(fanout.max(1)) {
+            let attempts = tier.iter().map(|name| {
+                self.metrics.record_fanout(name);
+                let cluster_ids =
+                    by_node.get(name).cloned().unwrap_or_default();
+                let address = addresses.get(name).cloned();
+                let vector = vector.as_slice().to_vec();
+
+                async move {
+                    let started = Instant::now();
+                    let result = match address {
+                        Some(address) => {
+                            let attempt = self.search_node(
+                                &address,
+                                vector,
+                                cluster_ids,
+                                count,
+                            );
+
+                            let attempt =
+                                tokio::time::timeout(deadline, attempt);
+                            match attempt.await {
+                                Ok(result) => result,
+                                Err(_) => Err("data node timed out".into()),
+                            }
+                        }
+                        None => {
+                            Err("data node has no registered address".into())
+                        }
+                    };
+
+                    self.registry.record_latency(
+                        name.clone(),
+                        result.is_ok(),
+                        started.elapsed(),
+                    );
+
+                    (name.clone(), result)
+                }
+            });
+
+            let tier_results = join_all(attempts).await;
+            let contributed: usize = tier_results
+                .iter()
+                .map(|(_, result)| result.as_ref().map(Vec::len).unwrap_or(0))
+                .sum();
+
+            partials.extend(tier_results);
+            if contributed >= count {
+                break;
+            }
+        }
+
+        for (name, result) in &partials {
+            if let Err(message) = result {
+                tracing::warn!(
+                    "data node {name} unreachable during cluster-wide \
+                    search, returning partial results: {message}"
+                );
+            }
+        }
+
+        Ok(merge_candidates(partials, vector, self.params.metric, count))
+    }
+
+    /// Returns a cached `DataNodeClient` for `address`, connecting and
+    /// caching a fresh one on first use. `DataNodeClient<Channel>` clones
+    /// cheaply (the underlying channel is reference-counted), so every
+    /// caller shares the same connection instead of dialing a new one.
+    async fn data_node_client(
+        &self,
+        address: &str,
+    ) -> Result<DataNodeClient<Channel>, Box<str>> {
+        let mut channels = self.channels.lock().await;
+        if let Some(client) = channels.get(address) {
+            return Ok(client.clone());
+        }
+
+        let url = format!("http://{address}");
+        let client = DataNodeClient::connect(url)
+            .await
+            .map_err(|e| format!("failed to connect: {e}").into_boxed_str())?;
+
+        channels.insert(address.into(), client.clone());
+        Ok(client)
+    }
+
+    /// Query one data node's `search` RPC for its locally top-`count`
+    /// records among `cluster_ids`, returning `(id, vector)` pairs for
+    /// [`merge_candidates`] to re-rank, or an error message if the node
+    /// couldn't be reached. Drops the cached channel for `address` on
+    /// failure, so a node that comes back under a new connection isn't
+    /// stuck behind a dead one.
+    async fn search_node(
+        &self,
+        address: &str,
+        vector: Vec<f32>,
+        cluster_ids: Vec<String>,
+        count: usize,
+    ) -> Result<Vec<(Uuid, Vector)>, Box<str>> {
+        let mut client = self.data_node_client(address).await?;
+
+        let request = Request::new(protoc::SearchRequest {
+            vector,
+            cluster_ids,
+            count: count as u32,
+        });
+
+        let response = client.search(request).await.map_err(|e| {
+            self.channels.try_lock().map(|mut c| c.remove(address));
+            format!("search request failed: {e}").into_boxed_str()
+        })?;
+
+        Ok(response
+            .into_inner()
+            .candidates
+            .into_iter()
+            .filter_map(|candidate| {
+                let id = candidate.id.parse().ok()?;
+                Some((id, candidate.vector.into()))
+            })
+            .collect())
+    }
+}
+
+/// Pings one data node's `heartbeat` RPC, returning its reported load on
+/// success or an error message if it couldn't be reached.
+async fn ping_node(
+    address: &str,
+) -> Result<protoc::HeartbeatResponse, Box<str>> {
+    let url = format!("http://{address}");
+    let mut client = DataNodeClient::connect(url)
+        .await
+        .map_err(|e| format!("failed to connect: {e}").into_boxed_str())?;
+
+    let request = Request::new(protoc::HeartbeatRequest::default());
+    let response = client.heartbeat(request).await.map_err(|e| {
+        format!("heartbeat request failed: {e}").into_boxed_str()
+    })?;
+
+    Ok(response.into_inner())
 }
 
 impl NodeExt for CoordinatorNode {
@@ -87,19 +1080,39 @@ impl NodeExt for CoordinatorNode {
         &self.database_url
     }
 
-    fn schema(&self) -> &impl NodeSchema {
+    fn schema(&self) -> &impl NodeSchemaNames {
         &self.schema
     }
 }
 
 #[async_trait]
 impl ProtoCoordinatorNode for Arc<CoordinatorNode> {
+    /// Accepts a data node's self-reported heartbeat, pushed on a fixed
+    /// interval by its own background ticker (see
+    /// [`crate::nodes::DataNode::spawn_heartbeat_loop`]). This complements
+    /// [`Self::poll_data_nodes`]'s pull-based polling: together they keep
+    /// [`Self::registry`] fresh whichever direction last succeeded.
     async fn heartbeat(
         &self,
-        _request: Request<protoc::HeartbeatRequest>,
+        request: Request<protoc::HeartbeatRequest>,
     ) -> ServerResult<protoc::HeartbeatResponse> {
-        // TODO: Check the heartbeat of all the data nodes in the cluster.
-        Ok(Response::new(protoc::HeartbeatResponse {}))
+        let report = request.into_inner();
+        if report.name.is_empty() {
+            let message = "Heartbeat is missing the reporting node's name";
+            return Err(Status::invalid_argument(message));
+        }
+
+        let mut conn = self.connect().await?;
+        self.record_node_heartbeat(
+            &mut conn,
+            report.name,
+            report.record_count,
+            report.cluster_count,
+            report.schema_version,
+        )
+        .await?;
+
+        Ok(Response::new(protoc::HeartbeatResponse::default()))
     }
 
     async fn register_node(
@@ -137,8 +1150,24 @@ impl ProtoCoordinatorNode for Arc<CoordinatorNode> {
             None => {
                 self.register_new_node(&mut conn, &node).await?;
 
-                // TODO: If the cluster is initialized transfer some subcluster
-                // and records to the new node to balance the load.
+                // Shed load onto the node it just joined with, rather
+                // than leaving it idle until someone calls the `rebalance`
+                // RPC. That RPC still exists for recomputing coordinator-
+                // level `clusters` ownership explicitly (e.g. after a
+                // capacity change), which this doesn't touch.
+                if let Err(error) = self.rebalance_subclusters().await {
+                    tracing::warn!(
+                        "failed to rebalance sub-clusters for the new \
+                        node: {error}"
+                    );
+                }
+
+                if let Err(error) = self.rebalance_partitions(None).await {
+                    tracing::warn!(
+                        "failed to rebalance the partition layout for the \
+                        new node: {error}"
+                    );
+                }
             }
         };
 
@@ -147,6 +1176,76 @@ impl ProtoCoordinatorNode for Arc<CoordinatorNode> {
         }))
     }
 
+    /// Report cluster membership and per-node load, as tracked by
+    /// [`Self::registry`] from polled heartbeats.
+    async fn list_nodes(
+        &self,
+        _request: Request<protoc::ListNodesRequest>,
+    ) -> ServerResult<protoc::ListNodesResponse> {
+        let nodes = self
+            .registry
+            .snapshot()
+            .into_iter()
+            .map(|(status, health)| protoc::NodeStatus {
+                name: status.name.to_string(),
+                healthy: health != NodeHealth::Down,
+                suspect: health == NodeHealth::Suspect,
+                record_count: status.record_count,
+                cluster_count: status.cluster_count,
+                schema_version: status.schema_version,
+            })
+            .collect();
+
+        Ok(Response::new(protoc::ListNodesResponse { nodes }))
+    }
+
+    async fn rebalance(
+        &self,
+        request: Request<protoc::RebalanceRequest>,
+    ) -> ServerResult<protoc::RebalanceResponse> {
+        let replicas = request.into_inner().replicas.max(1) as usize;
+        let mut conn = self.connect().await?;
+        let reassigned = self.rebalance_clusters(&mut conn, replicas).await?;
+
+        Ok(Response::new(protoc::RebalanceResponse {
+            reassigned: reassigned as u32,
+        }))
+    }
+
+    /// Cluster-wide top-`k` search, exposing [`Self::search_cluster`] as an
+    /// RPC so a client can query the whole cluster directly instead of
+    /// only through another data node's proxy.
+    async fn search(
+        &self,
+        request: Request<protoc::ClusterSearchRequest>,
+    ) -> ServerResult<protoc::ClusterSearchResponse> {
+        let request = request.into_inner();
+        let vector: Vector = request.vector.into();
+        let count = request.k.max(1) as usize;
+        let fanout = request.fanout.max(1) as usize;
+        let deadline_ms = request.deadline_ms.max(1) as u64;
+        let deadline = Duration::from_millis(deadline_ms);
+
+        let result =
+            self.search_cluster(&vector, count, fanout, deadline).await?;
+
+        let degraded = result.degraded();
+        let candidates = result
+            .candidates
+            .into_iter()
+            .map(|candidate| protoc::ClusterSearchCandidate {
+                id: candidate.id.to_string(),
+                connection_name: candidate.connection_name.to_string(),
+                distance: candidate.distance,
+            })
+            .collect();
+
+        Ok(Response::new(protoc::ClusterSearchResponse {
+            candidates,
+            degraded,
+        }))
+    }
+
     async fn insert(
         &self,
         request: Request<protoc::InsertRequest>,
@@ -194,14 +1293,17 @@ impl CoordinatorNode {
 
         sqlx::query(&format!(
             "UPDATE {connection_table}
-            SET address = $1
-            WHERE name = $2"
+            SET address = $1, capacity = $2, last_seen = now()
+            WHERE name = $3"
         ))
         .bind(&address)
+        .bind(node.capacity)
         .bind(&node.name)
         .execute(conn)
         .await
-        .map_err(|_| Status::internal("Failed to update existing node"))?;
+        .map_err(|e| {
+            status_from_db_error(e, "Failed to update existing node")
+        })?;
 
         tracing::info!("data node \"{}\" rejoins the cluster", &node.name);
         Ok(())
@@ -216,19 +1318,82 @@ impl CoordinatorNode {
         let address = format!("{}:{}", node.host, node.port);
 
         sqlx::query(&format!(
-            "INSERT INTO {connection_table} (name, address)
-            VALUES ($1, $2)"
+            "INSERT INTO {connection_table} (name, address, capacity)
+            VALUES ($1, $2, $3)"
         ))
         .bind(&node.name)
         .bind(address)
+        .bind(node.capacity)
         .execute(conn)
         .await
-        .map_err(|_| Status::internal("Failed to register new node"))?;
+        .map_err(|e| status_from_db_error(e, "Failed to register new node"))?;
 
         tracing::info!("registered a new data node: {}", &node.name);
         Ok(())
     }
 
+    /// Recomputes which data node(s) own each cluster, proportional to
+    /// every registered node's capacity, and persists the mapping via
+    /// [`NodeExt::_assign_cluster`]. Returns the number of clusters
+    /// reassigned to a different primary owner than before.
+    async fn rebalance_clusters(
+        &self,
+        conn: &mut PgConnection,
+        replicas: usize,
+    ) -> Result<usize, Status> {
+        let cluster_table = self.schema.cluster_table();
+        let clusters: Vec<(Uuid,)> =
+            sqlx::query_as(&format!("SELECT id FROM {cluster_table}"))
+                .fetch_all(&mut *conn)
+                .await
+                .map_err(|_| Status::internal("Failed to list clusters"))?;
+
+        let connection_table = self.schema.connection_table();
+        let capacities: Vec<NodeCapacity> = sqlx::query_as(&format!(
+            "SELECT name, capacity FROM {connection_table}"
+        ))
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|_| Status::internal("Failed to list data node capacities"))?;
+
+        // Exclude nodes the registry considers `Down` from placement so
+        // clusters aren't assigned to a node that's stopped heartbeating.
+        let live_nodes = self.registry.live_node_names();
+        let candidates: Vec<PlacementCandidate> = capacities
+            .into_iter()
+            .filter(|capacity| {
+                live_nodes.is_empty() || live_nodes.contains(&capacity.name)
+            })
+            .map(PlacementCandidate::from)
+            .collect();
+
+        let assignment_table = self.schema.assignment_table();
+        let mut reassigned = 0;
+        for (cluster_id,) in clusters {
+            let previous_primary: Option<(String,)> = sqlx::query_as(&format!(
+                "SELECT connection_name FROM {assignment_table}
+                WHERE cluster_id = $1 AND rank = 0"
+            ))
+            .bind(cluster_id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|_| Status::internal("Failed to read cluster assignment"))?;
+
+            let owners = select_replicas(cluster_id, &candidates, replicas);
+            for (rank, name) in owners.iter().enumerate() {
+                self._assign_cluster(&mut *conn, cluster_id, name, rank as i32)
+                    .await?;
+            }
+
+            let new_primary = owners.first().map(|name| name.as_ref());
+            if previous_primary.map(|(name,)| name).as_deref() != new_primary {
+                reassigned += 1;
+            }
+        }
+
+        Ok(reassigned)
+    }
+
     async fn find_nearest_cluster(
         &self,
         conn: &mut PgConnection,
@@ -246,8 +1411,7 @@ impl CoordinatorNode {
 
         while let Some(row) = rows.next().await {
             let row = row.map_err(|e| {
-                let message = format!("Failed to retrieve cluster: {e}");
-                Status::internal(message)
+                status_from_db_error(e, "Failed to retrieve cluster")
             })?;
 
             let cluster = Cluster::from_row(&row).map_err(|e| {
@@ -256,6 +1420,17 @@ impl CoordinatorNode {
             })?;
 
             let metric = self.params().metric;
+            if matches!(metric, Metric::Hamming | Metric::Jaccard)
+                && vector.len() != cluster.centroid.len()
+            {
+                return Err(Status::invalid_argument(format!(
+                    "Vector has {} dimensions, but clusters were built \
+                    with {}",
+                    vector.len(),
+                    cluster.centroid.len()
+                )));
+            }
+
             let distance = metric.distance(vector, &cluster.centroid);
             if distance < min_distance {
                 min_distance = distance;
@@ -287,6 +1462,7 @@ mod tests {
                 name: "c12eb363".to_string(),
                 host: "0.0.0.0".to_string(),
                 port: 2510,
+                capacity: 1.0,
             }),
         });
 
@@ -295,6 +1471,42 @@ mod tests {
         assert_eq!(params.dimension, 128);
     }
 
+    #[tokio::test]
+    async fn test_coordinator_node_heartbeat_updates_registry() {
+        let coordinator = coordinator_node_mock_server().await;
+        let request = Request::new(protoc::RegisterNodeRequest {
+            connection: Some(protoc::NodeConnection {
+                name: "88a4f0d1".to_string(),
+                host: "0.0.0.0".to_string(),
+                port: 2511,
+                capacity: 1.0,
+            }),
+        });
+        coordinator.register_node(request).await.unwrap();
+
+        assert!(coordinator.registry().health("88a4f0d1").is_none());
+
+        let request = Request::new(protoc::HeartbeatRequest {
+            name: "88a4f0d1".to_string(),
+            record_count: 10,
+            cluster_count: 2,
+            schema_version: 1,
+        });
+
+        coordinator.heartbeat(request).await.unwrap();
+        assert_eq!(
+            coordinator.registry().health("88a4f0d1"),
+            Some(NodeHealth::Healthy)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_coordinator_node_heartbeat_requires_name() {
+        let coordinator = coordinator_node_mock_server().await;
+        let request = Request::new(protoc::HeartbeatRequest::default());
+        assert!(coordinator.heartbeat(request).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_coordinator_node_find_nearest_cluster() {
         let coordinator = coordinator_node_mock_server().await;
@@ -323,15 +1535,316 @@ mod tests {
         assert_eq!(cluster.unwrap().id, ids[0]);
     }
 
+    #[tokio::test]
+    async fn test_coordinator_node_rebalance_subclusters() {
+        let coordinator = coordinator_node_mock_server().await;
+        let dimension = coordinator.params().dimension;
+
+        let db = test_utils::database_url();
+        let mut conn = PgConnection::connect(&db.to_string()).await.unwrap();
+
+        let connection_table = coordinator.schema.connection_table();
+        sqlx::query(&format!(
+            "INSERT INTO {connection_table} (name, address, capacity)
+            VALUES ('node-a', '0.0.0.0:1', 1.0), ('node-b', '0.0.0.0:2', 1.0)"
+        ))
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let centroid = bincode::serialize(&Vector::from(vec![0.0; dimension]))
+            .unwrap();
+
+        let cluster_table = coordinator.schema.cluster_table();
+        let (cluster_id,): (Uuid,) = sqlx::query_as(&format!(
+            "INSERT INTO {cluster_table} (centroid)
+            VALUES ($1) RETURNING id"
+        ))
+        .bind(&centroid)
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+
+        let node_a = DataSchema::new("node-a");
+        let node_b = DataSchema::new("node-b");
+        test_utils::drop_schema(&mut conn, node_a.name()).await;
+        test_utils::drop_schema(&mut conn, node_b.name()).await;
+        node_a.create(&mut PgEngine(&mut conn)).await;
+        node_a.create_all_tables(&mut PgEngine(&mut conn)).await;
+        node_b.create(&mut PgEngine(&mut conn)).await;
+        node_b.create_all_tables(&mut PgEngine(&mut conn)).await;
+
+        let subcluster_table = coordinator.schema.subcluster_table();
+        for _ in 0..3 {
+            let subcluster_id = Uuid::new_v4();
+
+            sqlx::query(&format!(
+                "INSERT INTO {subcluster_table}
+                (id, connection_name, cluster_id, centroid)
+                VALUES ($1, 'node-a', $2, $3)"
+            ))
+            .bind(subcluster_id)
+            .bind(cluster_id)
+            .bind(&centroid)
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+            // node-a's own local cluster row for this sub-cluster, so the
+            // move below has an actual cluster/record to relocate.
+            sqlx::query(&format!(
+                "INSERT INTO {} (id, centroid) VALUES ($1, $2)",
+                node_a.cluster_table()
+            ))
+            .bind(subcluster_id)
+            .bind(&centroid)
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+            sqlx::query(&format!(
+                "INSERT INTO {} (cluster_id, vector) VALUES ($1, $2)",
+                node_a.record_table()
+            ))
+            .bind(subcluster_id)
+            .bind(&centroid)
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        }
+
+        // node-a (3 sub-clusters) and node-b (0) have equal capacity, so
+        // each targets round(3 * 0.5) = 2; node-a is over its target, so all
+        // three of its sub-clusters are candidates and node-b's spare
+        // capacity of 2 takes two of them.
+        let moves = coordinator.rebalance_subclusters().await.unwrap();
+        assert_eq!(moves.len(), 2);
+        assert!(moves.iter().all(|m| m.to.as_ref() == "node-b"));
+
+        for placement in &moves {
+            let moved_cluster: Option<(Uuid,)> = sqlx::query_as(&format!(
+                "SELECT id FROM {} WHERE id = $1",
+                node_b.cluster_table()
+            ))
+            .bind(placement.subcluster_id)
+            .fetch_optional(&mut conn)
+            .await
+            .unwrap();
+            assert!(moved_cluster.is_some());
+
+            let moved_record: Option<(Uuid,)> = sqlx::query_as(&format!(
+                "SELECT id FROM {} WHERE cluster_id = $1",
+                node_b.record_table()
+            ))
+            .bind(placement.subcluster_id)
+            .fetch_optional(&mut conn)
+            .await
+            .unwrap();
+            assert!(moved_record.is_some());
+
+            let stale_cluster: Option<(Uuid,)> = sqlx::query_as(&format!(
+                "SELECT id FROM {} WHERE id = $1",
+                node_a.cluster_table()
+            ))
+            .bind(placement.subcluster_id)
+            .fetch_optional(&mut conn)
+            .await
+            .unwrap();
+            assert!(stale_cluster.is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_coordinator_node_query_reports_unreachable_node() {
+        let coordinator = coordinator_node_mock_server().await;
+        let dimension = coordinator.params().dimension;
+
+        let db = test_utils::database_url();
+        let mut conn = PgConnection::connect(&db.to_string()).await.unwrap();
+
+        let connection_table = coordinator.schema.connection_table();
+        sqlx::query(&format!(
+            "INSERT INTO {connection_table} (name, address, capacity)
+            VALUES ('node-a', '0.0.0.0:1', 1.0)"
+        ))
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let centroid = bincode::serialize(&Vector::from(vec![0.0; dimension]))
+            .unwrap();
+
+        let cluster_table = coordinator.schema.cluster_table();
+        let (cluster_id,): (Uuid,) = sqlx::query_as(&format!(
+            "INSERT INTO {cluster_table} (centroid)
+            VALUES ($1) RETURNING id"
+        ))
+        .bind(&centroid)
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+
+        let subcluster_table = coordinator.schema.subcluster_table();
+        sqlx::query(&format!(
+            "INSERT INTO {subcluster_table}
+            (id, connection_name, cluster_id, centroid)
+            VALUES ($1, 'node-a', $2, $3)"
+        ))
+        .bind(Uuid::new_v4())
+        .bind(cluster_id)
+        .bind(&centroid)
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        // "node-a" owns the only sub-cluster but was never registered with
+        // a listening server, so the fan-out fails and is reported as a
+        // per-node error instead of failing the whole query.
+        let query = vec![0.0; dimension].into();
+        let radius = f32::INFINITY;
+        let result =
+            coordinator.query(&query, 5, 1, radius).await.unwrap();
+
+        assert!(result.candidates.is_empty());
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].0.as_ref(), "node-a");
+    }
+
+    #[tokio::test]
+    async fn test_coordinator_node_search_cluster_reports_degraded() {
+        let coordinator = coordinator_node_mock_server().await;
+        let dimension = coordinator.params().dimension;
+
+        let db = test_utils::database_url();
+        let mut conn = PgConnection::connect(&db.to_string()).await.unwrap();
+
+        let connection_table = coordinator.schema.connection_table();
+        sqlx::query(&format!(
+            "INSERT INTO {connection_table} (name, address, capacity)
+            VALUES ('node-a', '0.0.0.0:1', 1.0)"
+        ))
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let centroid = bincode::serialize(&Vector::from(vec![0.0; dimension]))
+            .unwrap();
+
+        let cluster_table = coordinator.schema.cluster_table();
+        let (cluster_id,): (Uuid,) = sqlx::query_as(&format!(
+            "INSERT INTO {cluster_table} (centroid)
+            VALUES ($1) RETURNING id"
+        ))
+        .bind(&centroid)
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+
+        let subcluster_table = coordinator.schema.subcluster_table();
+        sqlx::query(&format!(
+            "INSERT INTO {subcluster_table}
+            (id, connection_name, cluster_id, centroid)
+            VALUES ($1, 'node-a', $2, $3)"
+        ))
+        .bind(Uuid::new_v4())
+        .bind(cluster_id)
+        .bind(&centroid)
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        // "node-a" owns the only sub-cluster but was never registered
+        // with a listening server, so the whole-cluster fan-out still
+        // reports it as a per-node error rather than failing the call.
+        let query = vec![0.0; dimension].into();
+        let deadline = Duration::from_millis(50);
+        let result = coordinator
+            .search_cluster(&query, 5, 1, deadline)
+            .await
+            .unwrap();
+
+        assert!(result.candidates.is_empty());
+        assert!(result.degraded());
+        assert_eq!(result.errors[0].0.as_ref(), "node-a");
+    }
+
+    #[tokio::test]
+    async fn test_coordinator_node_register_node_lays_out_partitions() {
+        let coordinator = coordinator_node_mock_server().await;
+        let request = Request::new(protoc::RegisterNodeRequest {
+            connection: Some(protoc::NodeConnection {
+                name: "partition-a".to_string(),
+                host: "0.0.0.0".to_string(),
+                port: 2512,
+                capacity: 1.0,
+            }),
+        });
+        coordinator.register_node(request).await.unwrap();
+
+        let status = coordinator.status().await.unwrap();
+        assert!(status.layout_version > 0);
+
+        let db = test_utils::database_url();
+        let mut conn = PgConnection::connect(&db.to_string()).await.unwrap();
+        let partition_table = coordinator.schema.partition_table();
+        let (owners,): (i64,) = sqlx::query_as(&format!(
+            "SELECT COUNT(DISTINCT connection_name) FROM {partition_table}"
+        ))
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+
+        assert_eq!(owners, 1);
+    }
+
+    #[tokio::test]
+    async fn test_coordinator_node_drain_node_excludes_it_from_placement() {
+        let coordinator = coordinator_node_mock_server().await;
+        for (name, port) in [("node-a", 2513), ("node-b", 2514)] {
+            let request = Request::new(protoc::RegisterNodeRequest {
+                connection: Some(protoc::NodeConnection {
+                    name: name.to_string(),
+                    host: "0.0.0.0".to_string(),
+                    port,
+                    capacity: 1.0,
+                }),
+            });
+            coordinator.register_node(request).await.unwrap();
+        }
+
+        coordinator.drain_node("node-a").await.unwrap();
+
+        let db = test_utils::database_url();
+        let mut conn = PgConnection::connect(&db.to_string()).await.unwrap();
+        let partition_table = coordinator.schema.partition_table();
+        let (draining_owned,): (i64,) = sqlx::query_as(&format!(
+            "SELECT COUNT(*) FROM {partition_table}
+            WHERE connection_name = 'node-a'"
+        ))
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+
+        assert_eq!(draining_owned, 0);
+    }
+
     async fn coordinator_node_mock_server() -> Arc<CoordinatorNode> {
         let params = test_utils::node_parameters();
         let db = test_utils::database_url();
 
         let mut conn = PgConnection::connect(&db.to_string()).await.unwrap();
         test_utils::drop_schema(&mut conn, COORDINATOR_SCHEMA).await;
-        CoordinatorNode::configure(db.to_owned(), params).await;
+        let pool_params = PoolParameters::default();
+        let max_elapsed = Duration::from_secs(5);
+        CoordinatorNode::configure(
+            db.to_owned(),
+            params,
+            pool_params,
+            max_elapsed,
+        )
+        .await;
 
-        let coordinator = CoordinatorNode::new(db).await;
+        let coordinator = CoordinatorNode::new(db, max_elapsed).await;
         test_utils::assert_table_count(&mut conn, COORDINATOR_SCHEMA, 4).await;
 
         Arc::new(coordinator)
@@ -1,9 +1,15 @@
 use super::*;
 use crate::protod;
+use protod::coordinator_node_client::CoordinatorNodeClient;
 use protod::data_node_server::DataNode as ProtoDataNode;
 use regex::Regex;
+use sqlx::pool::PoolConnection;
+use sqlx::postgres::{PgPoolOptions, PgRow};
+use sqlx::{Postgres, Row};
+use std::cmp::Ordering;
 
 type NodeName = Box<str>;
+type Pool = sqlx::PgPool;
 
 /// Data node server definition.
 ///
@@ -16,14 +22,41 @@ pub struct DataNode {
     params: NodeParameters,
     database_url: DatabaseURL,
     schema: DataSchema,
+    pool: Pool,
+    /// Prometheus counters, rendered by the admin API's `/metrics` route.
+    pub(crate) metrics: NodeMetrics,
 }
 
 impl DataNode {
-    /// Create a new data node instance.
+    /// Create a new data node instance with the default pool sizing.
+    /// See [`Self::new_with_pool`] to configure it explicitly.
     pub async fn new(
         name: impl Into<NodeName>,
         params: impl Into<NodeParameters>,
         database_url: impl Into<DatabaseURL>,
+    ) -> Self {
+        Self::new_with_pool(
+            name,
+            params,
+            database_url,
+            PoolParameters::default(),
+            DEFAULT_CONNECT_MAX_ELAPSED,
+        )
+        .await
+    }
+
+    /// Create a new data node instance, managing its Postgres connections
+    /// through a pool sized and timed out according to `pool_params`
+    /// instead of opening a fresh connection per request.
+    /// - `max_elapsed`: How long to retry a transient connection failure
+    ///   (e.g. the database isn't accepting connections yet) before giving
+    ///   up. See [`connect_with_retry`].
+    pub async fn new_with_pool(
+        name: impl Into<NodeName>,
+        params: impl Into<NodeParameters>,
+        database_url: impl Into<DatabaseURL>,
+        pool_params: PoolParameters,
+        max_elapsed: Duration,
     ) -> Self {
         let name = name.into();
         let params = params.into();
@@ -35,18 +68,37 @@ impl DataNode {
             panic!("Invalid node name: {action}");
         }
 
-        let mut connection = PgConnection::connect(database_url.as_ref())
+        let pool = connect_with_retry(max_elapsed, || {
+            PgPoolOptions::new()
+                .max_connections(pool_params.max_size)
+                .acquire_timeout(pool_params.acquire_timeout)
+                // Ping a pooled connection with `SELECT 1` before handing
+                // it out, so a connection the database dropped is recycled
+                // instead of failing the request that borrows it.
+                .test_before_acquire(true)
+                .connect(database_url.as_ref())
+        })
+        .await
+        .expect("Failed to connect to Postgres database");
+
+        let mut connection = pool
+            .acquire()
             .await
-            .expect("Failed to connect to Postgres database");
+            .expect("Failed to acquire a connection from the pool");
 
         let schema = DataSchema::new(name.as_ref());
-        if !schema.exists(&mut connection).await {
-            schema.create(&mut connection).await;
-            schema.create_all_tables(&mut connection).await;
+        if !schema.exists(&mut PgEngine(&mut connection)).await {
+            schema.create(&mut PgEngine(&mut connection)).await;
+            schema
+                .create_all_tables(&mut PgEngine(&mut connection))
+                .await;
             tracing::info!("database is provisioned for data node: {name}");
         }
 
-        Self { name, params, database_url, schema }
+        schema.migrate(&mut connection).await;
+
+        let metrics = NodeMetrics::default();
+        Self { name, params, database_url, schema, pool, metrics }
     }
 
     /// Return the name configured for this data node.
@@ -58,6 +110,70 @@ impl DataNode {
     pub fn params(&self) -> &NodeParameters {
         &self.params
     }
+
+    /// Return a pooled connection to the node's Postgres database.
+    ///
+    /// Overrides [`NodeExt::connect`]'s default of opening a brand new
+    /// connection per call: this acquires one from the pool created in
+    /// [`Self::new`]/[`Self::new_with_pool`] instead, reusing and
+    /// recycling connections across requests.
+    pub async fn connect(&self) -> Result<PoolConnection<Postgres>, Status> {
+        self.pool
+            .acquire()
+            .await
+            .map_err(|_| Status::internal("Failed to acquire a Postgres connection"))
+    }
+
+    /// Spawn a background task that pushes this node's liveness and load to
+    /// the coordinator at `coordinator_addr` every `interval`, so the
+    /// coordinator's registry stays fresh between its own poll-based
+    /// heartbeat checks.
+    pub fn spawn_heartbeat_loop(
+        self: &Arc<Self>,
+        coordinator_addr: SocketAddr,
+        interval: Duration,
+    ) {
+        let node = self.clone();
+        let addr = format!("http://{coordinator_addr}");
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if let Err(error) = node.report_heartbeat(&addr).await {
+                    tracing::warn!("failed to report heartbeat: {error}");
+                }
+            }
+        });
+    }
+
+    /// Reports this node's current load to the coordinator at `addr`
+    /// (`http://host:port`), as a single push for
+    /// [`Self::spawn_heartbeat_loop`].
+    async fn report_heartbeat(&self, addr: &str) -> Result<(), Status> {
+        let mut conn = self.connect().await?;
+        let record_table = self.schema.record_table();
+        let cluster_table = self.schema.cluster_table();
+
+        let record_count = count_rows(&mut conn, &record_table).await?;
+        let cluster_count = count_rows(&mut conn, &cluster_table).await?;
+        let schema_version = self.schema.current_version(&mut conn).await;
+
+        let mut client = CoordinatorNodeClient::connect(addr.to_string())
+            .await
+            .map_err(|_| {
+                Status::internal("Failed to connect to coordinator node")
+            })?;
+
+        let request = Request::new(protod::HeartbeatRequest {
+            name: self.name.to_string(),
+            record_count,
+            cluster_count,
+            schema_version,
+        });
+
+        client.heartbeat(request).await?;
+        Ok(())
+    }
 }
 
 impl NodeExt for DataNode {
@@ -65,24 +181,38 @@ impl NodeExt for DataNode {
         &self.database_url
     }
 
-    fn schema(&self) -> &impl NodeSchema {
+    fn schema(&self) -> &impl NodeSchemaNames {
         &self.schema
     }
 }
 
 #[async_trait]
 impl ProtoDataNode for Arc<DataNode> {
+    /// Report this node's liveness and current load, so a coordinator
+    /// polling this RPC can track the node's health and schema version.
     async fn heartbeat(
         &self,
         _request: Request<protod::HeartbeatRequest>,
     ) -> ServerResult<protod::HeartbeatResponse> {
-        Ok(Response::new(protod::HeartbeatResponse {}))
+        let mut conn = self.connect().await?;
+
+        let record_count = count_rows(&mut conn, &self.schema.record_table()).await?;
+        let cluster_count = count_rows(&mut conn, &self.schema.cluster_table()).await?;
+        let schema_version = self.schema.current_version(&mut conn).await;
+
+        Ok(Response::new(protod::HeartbeatResponse {
+            name: self.name.to_string(),
+            record_count,
+            cluster_count,
+            schema_version,
+        }))
     }
 
     async fn insert_cluster(
         &self,
         request: Request<protod::InsertClusterRequest>,
     ) -> ServerResult<protod::InsertClusterResponse> {
+        self.metrics.record_insert();
         let request = request.into_inner();
         let centroid: Vector = request.centroid.into();
 
@@ -91,6 +221,124 @@ impl ProtoDataNode for Arc<DataNode> {
 
         Ok(Response::new(protod::InsertClusterResponse { id: id.to_string() }))
     }
+
+    /// Insert a batch of clusters in one round trip and one transaction,
+    /// instead of one `insert_cluster` call per centroid.
+    async fn insert_clusters(
+        &self,
+        request: Request<protod::InsertClustersRequest>,
+    ) -> ServerResult<protod::InsertClustersResponse> {
+        self.metrics.record_insert();
+        let request = request.into_inner();
+        let dimension = self.params().dimension;
+
+        let mut centroids = Vec::with_capacity(request.centroids.len());
+        for (index, centroid) in request.centroids.into_iter().enumerate() {
+            if centroid.len() != dimension {
+                return Err(Status::invalid_argument(format!(
+                    "Invalid dimension for centroid at index {index}: \
+                    expected {dimension}, found {}",
+                    centroid.len()
+                )));
+            }
+
+            centroids.push(centroid.into());
+        }
+
+        let mut conn = self.connect().await?;
+        let ids = self._insert_clusters(&mut conn, &centroids).await?;
+
+        Ok(Response::new(protod::InsertClustersResponse {
+            ids: ids.iter().map(ToString::to_string).collect(),
+        }))
+    }
+
+    /// Return this node's locally top-`count` nearest records to `vector`,
+    /// restricted to `cluster_ids`, so a coordinator's scatter-gather query
+    /// only scans the rows its sub-cluster routing decided are relevant
+    /// instead of the whole table.
+    async fn search(
+        &self,
+        request: Request<protod::SearchRequest>,
+    ) -> ServerResult<protod::SearchResponse> {
+        self.metrics.record_query();
+        let request = request.into_inner();
+        let vector: Vector = request.vector.into();
+        let count = request.count.max(1) as usize;
+
+        let cluster_ids: Vec<Uuid> = request
+            .cluster_ids
+            .iter()
+            .filter_map(|id| id.parse().ok())
+            .collect();
+
+        let mut conn = self.connect().await?;
+        let record_table = self.schema.record_table();
+        let rows: Vec<RecordCandidate> = sqlx::query_as(&format!(
+            "SELECT id, vector FROM {record_table} WHERE cluster_id = ANY($1)"
+        ))
+        .bind(&cluster_ids)
+        .fetch_all(&mut conn)
+        .await
+        .map_err(|_| Status::internal("Failed to scan records for search"))?;
+
+        let metric = self.params().metric;
+        let mut ranked: Vec<(f64, RecordCandidate)> = rows
+            .into_iter()
+            .map(|record| {
+                let distance = metric
+                    .distance(&vector, &record.vector)
+                    .unwrap_or(f64::MAX);
+
+                (distance, record)
+            })
+            .collect();
+
+        ranked.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        ranked.truncate(count);
+
+        let candidates = ranked
+            .into_iter()
+            .map(|(_, record)| protod::Candidate {
+                id: record.id.to_string(),
+                vector: record.vector.as_slice().to_vec(),
+            })
+            .collect();
+
+        Ok(Response::new(protod::SearchResponse { candidates }))
+    }
+}
+
+/// Row shape for a single candidate scanned by `search`'s records query,
+/// carrying just enough to let the coordinator recompute its distance
+/// after merging candidates from other data nodes.
+struct RecordCandidate {
+    id: Uuid,
+    vector: Vector,
+}
+
+impl FromRow<'_, PgRow> for RecordCandidate {
+    fn from_row(row: &PgRow) -> sqlx::Result<Self> {
+        let id = row.try_get("id")?;
+        let bytea: Vec<u8> = row.try_get("vector")?;
+        let vector = bincode::deserialize(&bytea)
+            .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+
+        Ok(Self { id, vector })
+    }
+}
+
+/// Count the rows currently in `table`, for reporting load in a heartbeat.
+async fn count_rows(
+    connection: &mut PgConnection,
+    table: &TableName,
+) -> Result<u64, Status> {
+    let (count,): (i64,) = sqlx::query_as(&format!("SELECT COUNT(*) FROM {table}"))
+        .fetch_one(connection)
+        .await
+        .map_err(|_| Status::internal(format!("Failed to count rows in {table}")))?;
+
+    Ok(count as u64)
 }
 
 #[cfg(test)]
@@ -131,6 +379,51 @@ mod tests {
         assert_eq!(count, 10);
     }
 
+    #[tokio::test]
+    async fn test_data_node_search_ranks_and_truncates() {
+        let node_name = "7a913cf2";
+        let node = data_node_mock_server(node_name).await;
+
+        let dimension = node.params().dimension;
+        let mut conn = node.connect().await.unwrap();
+
+        let cluster_table = node.schema().cluster_table();
+        let centroid = bincode::serialize(&vec![0.0; dimension]).unwrap();
+        let (cluster_id,): (Uuid,) = sqlx::query_as(&format!(
+            "INSERT INTO {cluster_table} (centroid) VALUES ($1) RETURNING id"
+        ))
+        .bind(&centroid)
+        .fetch_one(&mut conn)
+        .await
+        .unwrap();
+
+        let record_table = node.schema().record_table();
+        for value in [5.0, 0.1] {
+            let vector = bincode::serialize(&vec![value; dimension]).unwrap();
+            sqlx::query(&format!(
+                "INSERT INTO {record_table} (cluster_id, vector)
+                VALUES ($1, $2)"
+            ))
+            .bind(cluster_id)
+            .bind(&vector)
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        }
+
+        let request = protod::SearchRequest {
+            vector: vec![0.0; dimension],
+            cluster_ids: vec![cluster_id.to_string()],
+            count: 1,
+        };
+
+        let response =
+            node.search(Request::new(request)).await.unwrap().into_inner();
+
+        assert_eq!(response.candidates.len(), 1);
+        assert_eq!(response.candidates[0].vector, vec![0.1; dimension]);
+    }
+
     async fn data_node_mock_server(name: impl Into<NodeName>) -> Arc<DataNode> {
         let name = name.into();
         let schema_name = format!("{DATA_SCHEMA}{name}");
@@ -144,7 +437,10 @@ mod tests {
 
         test_utils::drop_schema(&mut conn, &schema_name).await;
         let node = DataNode::new(name, params, db).await;
-        test_utils::assert_table_count(&mut conn, &schema_name, 2).await;
+
+        // Clusters and records tables, plus the migrations table that
+        // `DataSchema::migrate` provisions and records applied migrations in.
+        test_utils::assert_table_count(&mut conn, &schema_name, 3).await;
 
         Arc::new(node)
     }
@@ -0,0 +1,340 @@
+use super::*;
+use crate::types::Metric;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+/// A directed edge in a [`FlowNetwork`]'s residual graph.
+#[derive(Debug, Clone, Copy)]
+struct FlowEdge {
+    to: usize,
+    capacity: i64,
+    cost: i64,
+}
+
+/// Minimum-cost maximum-flow network, solved via successive shortest
+/// augmenting paths: repeatedly find the cheapest source-to-sink path in
+/// the residual graph and push as much flow through it as its bottleneck
+/// edge allows, until no path remains.
+///
+/// Shortest paths are found with SPFA (a queue-based Bellman-Ford), not
+/// Dijkstra, since pushing flow along an edge leaves behind a
+/// negative-cost reverse edge in the residual graph.
+#[derive(Debug)]
+pub struct FlowNetwork {
+    edges: Vec<FlowEdge>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl FlowNetwork {
+    /// Create a network with `nodes` vertices and no edges yet.
+    pub fn new(nodes: usize) -> Self {
+        Self { edges: Vec::new(), adjacency: vec![Vec::new(); nodes] }
+    }
+
+    /// Add a directed edge `from -> to` with `capacity` and `cost` per unit
+    /// of flow, plus its zero-capacity residual reverse edge. Returns the
+    /// index of the forward edge, to later read its flow via
+    /// [`Self::flow_on`].
+    pub fn add_edge(
+        &mut self,
+        from: usize,
+        to: usize,
+        capacity: i64,
+        cost: i64,
+    ) -> usize {
+        let forward = self.edges.len();
+        self.edges.push(FlowEdge { to, capacity, cost });
+        self.adjacency[from].push(forward);
+
+        let backward = self.edges.len();
+        self.edges.push(FlowEdge { to: from, capacity: 0, cost: -cost });
+        self.adjacency[to].push(backward);
+
+        forward
+    }
+
+    /// Flow currently pushed through `edge`, i.e. the capacity accumulated
+    /// on its paired reverse edge.
+    pub fn flow_on(&self, edge: usize) -> i64 {
+        self.edges[edge ^ 1].capacity
+    }
+
+    /// Push flow from `source` to `sink` until no augmenting path remains,
+    /// returning the total flow pushed and its total cost.
+    pub fn solve(&mut self, source: usize, sink: usize) -> (i64, i64) {
+        let mut total_flow = 0;
+        let mut total_cost = 0;
+
+        loop {
+            let (distance, parent_edge) = self.shortest_path(source, sink);
+            let Some(parent_edge) = parent_edge else { break };
+
+            let mut bottleneck = i64::MAX;
+            let mut node = sink;
+            while node != source {
+                let edge =
+                    parent_edge[node].expect("path has no predecessor edge");
+                bottleneck = bottleneck.min(self.edges[edge].capacity);
+                node = self.edge_from(edge);
+            }
+
+            let mut node = sink;
+            while node != source {
+                let edge =
+                    parent_edge[node].expect("path has no predecessor edge");
+                self.edges[edge].capacity -= bottleneck;
+                self.edges[edge ^ 1].capacity += bottleneck;
+                node = self.edge_from(edge);
+            }
+
+            total_flow += bottleneck;
+            total_cost += bottleneck * distance[sink];
+        }
+
+        (total_flow, total_cost)
+    }
+
+    /// SPFA shortest path by cost from `source`, returning the distance
+    /// table alongside the predecessor edge of `sink` (`None` if `sink`
+    /// isn't reachable through an edge with spare capacity).
+    fn shortest_path(
+        &self,
+        source: usize,
+        sink: usize,
+    ) -> (Vec<i64>, Option<Vec<Option<usize>>>) {
+        let n = self.adjacency.len();
+        let mut distance = vec![i64::MAX; n];
+        let mut parent_edge: Vec<Option<usize>> = vec![None; n];
+        let mut in_queue = vec![false; n];
+
+        distance[source] = 0;
+        let mut queue = VecDeque::from([source]);
+        in_queue[source] = true;
+
+        while let Some(node) = queue.pop_front() {
+            in_queue[node] = false;
+            for &edge_index in &self.adjacency[node] {
+                let edge = self.edges[edge_index];
+                if edge.capacity <= 0 {
+                    continue;
+                }
+
+                let candidate = distance[node].saturating_add(edge.cost);
+                if candidate < distance[edge.to] {
+                    distance[edge.to] = candidate;
+                    parent_edge[edge.to] = Some(edge_index);
+
+                    if !in_queue[edge.to] {
+                        queue.push_back(edge.to);
+                        in_queue[edge.to] = true;
+                    }
+                }
+            }
+        }
+
+        if distance[sink] == i64::MAX {
+            (distance, None)
+        } else {
+            (distance, Some(parent_edge))
+        }
+    }
+
+    /// The tail node of `edge`, recovered from its paired reverse edge.
+    fn edge_from(&self, edge: usize) -> usize {
+        self.edges[edge ^ 1].to
+    }
+}
+
+/// A registered data node's current sub-cluster count and target share,
+/// used to build the node side of [`plan_subcluster_moves`]'s network.
+#[derive(Debug, Clone)]
+pub struct NodeShare {
+    pub name: Box<str>,
+    pub current: usize,
+    pub target: usize,
+}
+
+/// A sub-cluster eligible for reassignment, currently owned by a node
+/// whose load exceeds its target share.
+#[derive(Debug, Clone)]
+pub struct SubclusterLoad {
+    pub id: Uuid,
+    pub connection_name: Box<str>,
+    pub centroid: Vector,
+}
+
+/// A planned reassignment of `subcluster_id` onto data node `to`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubclusterMove {
+    pub subcluster_id: Uuid,
+    pub to: Box<str>,
+}
+
+/// Scales a [`Metric::distance`] (a float) and a node's integer load into
+/// comparable integer cost units, since [`FlowNetwork`] costs must be
+/// whole numbers.
+const COST_SCALE: i64 = 1_000;
+
+/// Plans moves of `overloaded` sub-clusters onto nodes with spare
+/// capacity, as a min-cost max-flow problem. Every entry in `overloaded`
+/// can move, so as many move as the receiving nodes' spare capacity
+/// allows, not just each donor's excess over its target share:
+/// - `source` connects to each entry in `overloaded`, capacity 1, cost 0.
+/// - Each overloaded sub-cluster connects to every node that isn't its
+///   current owner and still has spare capacity, capacity 1, cost growing
+///   with the node's current load and with the [`Metric`] distance from
+///   the sub-cluster's centroid to that node's mean existing centroid.
+/// - Each node connects to `sink` with capacity equal to its remaining
+///   spare capacity (`target - current`).
+///
+/// Solved with [`FlowNetwork::solve`]. Only sub-clusters already
+/// identified as overloaded are considered, so the caller controls
+/// incrementality by only passing sub-clusters whose owner exceeds its
+/// target share.
+pub fn plan_subcluster_moves(
+    overloaded: &[SubclusterLoad],
+    nodes: &[NodeShare],
+    node_centroids: &HashMap<Box<str>, Vector>,
+    metric: Metric,
+) -> Vec<SubclusterMove> {
+    if overloaded.is_empty() || nodes.is_empty() {
+        return Vec::new();
+    }
+
+    let source = 0;
+    let subcluster_base = 1;
+    let node_base = subcluster_base + overloaded.len();
+    let sink = node_base + nodes.len();
+
+    let mut network = FlowNetwork::new(sink + 1);
+    for (i, _) in overloaded.iter().enumerate() {
+        network.add_edge(source, subcluster_base + i, 1, 0);
+    }
+
+    let mut candidate_edges = Vec::new();
+    for (i, subcluster) in overloaded.iter().enumerate() {
+        for (j, node) in nodes.iter().enumerate() {
+            let no_spare = node.target <= node.current;
+            if node.name == subcluster.connection_name || no_spare {
+                continue;
+            }
+
+            let load_penalty = node.current as i64 * COST_SCALE;
+            let distance_penalty = node_centroids
+                .get(&node.name)
+                .and_then(|centroid| {
+                    metric.distance(&subcluster.centroid, centroid)
+                })
+                .unwrap_or(0.0);
+
+            let cost =
+                load_penalty + (distance_penalty * COST_SCALE as f64) as i64;
+            let edge =
+                network.add_edge(subcluster_base + i, node_base + j, 1, cost);
+            candidate_edges.push((edge, subcluster.id, node.name.clone()));
+        }
+    }
+
+    for (j, node) in nodes.iter().enumerate() {
+        let spare = node.target.saturating_sub(node.current) as i64;
+        network.add_edge(node_base + j, sink, spare, 0);
+    }
+
+    network.solve(source, sink);
+    candidate_edges
+        .into_iter()
+        .filter(|(edge, ..)| network.flow_on(*edge) > 0)
+        .map(|(_, subcluster_id, to)| SubclusterMove { subcluster_id, to })
+        .collect()
+}
+
+/// The element-wise mean of `centroids`, or `None` if the slice is empty.
+pub fn mean_centroid(centroids: &[Vector]) -> Option<Vector> {
+    let dimension = centroids.first()?.len();
+    let mut sum = vec![0.0; dimension];
+    for centroid in centroids {
+        for (total, value) in sum.iter_mut().zip(centroid.as_slice()) {
+            *total += value;
+        }
+    }
+
+    for total in &mut sum {
+        *total /= centroids.len() as f32;
+    }
+
+    Some(sum.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flow_network_respects_capacity() {
+        // source -> a (cap 2) -> sink (cap 1), so only 1 unit can flow.
+        let mut network = FlowNetwork::new(3);
+        let source_to_a = network.add_edge(0, 1, 2, 1);
+        network.add_edge(1, 2, 1, 1);
+
+        let (flow, cost) = network.solve(0, 2);
+        assert_eq!(flow, 1);
+        assert_eq!(cost, 2);
+        assert_eq!(network.flow_on(source_to_a), 1);
+    }
+
+    #[test]
+    fn test_flow_network_saturates_parallel_paths() {
+        // Two parallel paths of capacity 1 each, costs 1 and 5: both are
+        // saturated, for a total cost of 6.
+        let mut network = FlowNetwork::new(4);
+        let cheap = network.add_edge(0, 1, 1, 1);
+        let expensive = network.add_edge(0, 2, 1, 5);
+        network.add_edge(1, 3, 1, 0);
+        network.add_edge(2, 3, 1, 0);
+
+        let (flow, cost) = network.solve(0, 3);
+        assert_eq!(flow, 2);
+        assert_eq!(cost, 6);
+        assert_eq!(network.flow_on(cheap), 1);
+        assert_eq!(network.flow_on(expensive), 1);
+    }
+
+    fn load(connection_name: &str) -> SubclusterLoad {
+        SubclusterLoad {
+            id: Uuid::new_v4(),
+            connection_name: connection_name.into(),
+            centroid: vec![0.0; 4].into(),
+        }
+    }
+
+    #[test]
+    fn test_plan_subcluster_moves_balances_overloaded_node() {
+        let overloaded = vec![load("a"), load("a")];
+        let nodes = vec![
+            NodeShare { name: "a".into(), current: 3, target: 1 },
+            NodeShare { name: "b".into(), current: 0, target: 2 },
+        ];
+
+        let empty = HashMap::new();
+        let metric = Metric::Euclidean;
+        let moves = plan_subcluster_moves(&overloaded, &nodes, &empty, metric);
+
+        assert_eq!(moves.len(), 2);
+        assert!(moves.iter().all(|m| m.to.as_ref() == "b"));
+    }
+
+    #[test]
+    fn test_plan_subcluster_moves_is_noop_without_spare_capacity() {
+        let overloaded = vec![load("a")];
+        let nodes = vec![
+            NodeShare { name: "a".into(), current: 2, target: 1 },
+            NodeShare { name: "b".into(), current: 2, target: 2 },
+        ];
+
+        let empty = HashMap::new();
+        let metric = Metric::Euclidean;
+        let moves = plan_subcluster_moves(&overloaded, &nodes, &empty, metric);
+
+        assert!(moves.is_empty());
+    }
+}
@@ -0,0 +1,68 @@
+use super::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex as StdMutex;
+
+/// Prometheus counters for one [`CoordinatorNode`]/[`DataNode`] instance.
+/// Unlike [`crate::func::metrics`], which is process-wide since `Search`
+/// has no owning instance to hang state off of, a node already has `self`
+/// to hold this on, so each node tracks its own counters independently.
+#[derive(Debug, Default)]
+pub struct NodeMetrics {
+    queries: AtomicU64,
+    inserts: AtomicU64,
+    fanout: StdMutex<HashMap<Box<str>, u64>>,
+}
+
+impl NodeMetrics {
+    /// Records one top-level query/search request handled by this node.
+    pub fn record_query(&self) {
+        self.queries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records one insert request handled by this node.
+    pub fn record_insert(&self) {
+        self.inserts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records that `connection_name` was contacted as part of a
+    /// scatter-gather fan-out, so an operator can spot a data node that's
+    /// hit disproportionately often (a hot sub-cluster owner) or one
+    /// that's rarely reached.
+    pub fn record_fanout(&self, connection_name: &str) {
+        let mut fanout = self.fanout.lock().unwrap();
+        *fanout.entry(connection_name.into()).or_insert(0) += 1;
+    }
+
+    /// Renders the counters as Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut body = String::new();
+
+        body.push_str("# HELP oasysdb_node_queries_total Queries handled.\n");
+        body.push_str("# TYPE oasysdb_node_queries_total counter\n");
+        body.push_str(&format!(
+            "oasysdb_node_queries_total {}\n",
+            self.queries.load(Ordering::Relaxed)
+        ));
+
+        body.push_str("# HELP oasysdb_node_inserts_total Inserts handled.\n");
+        body.push_str("# TYPE oasysdb_node_inserts_total counter\n");
+        body.push_str(&format!(
+            "oasysdb_node_inserts_total {}\n",
+            self.inserts.load(Ordering::Relaxed)
+        ));
+
+        body.push_str(
+            "# HELP oasysdb_node_fanout_total \
+            Scatter-gather requests sent to each data node.\n",
+        );
+        body.push_str("# TYPE oasysdb_node_fanout_total counter\n");
+        for (name, count) in self.fanout.lock().unwrap().iter() {
+            body.push_str(&format!(
+                "oasysdb_node_fanout_total{{node=\"{name}\"}} {count}\n"
+            ));
+        }
+
+        body
+    }
+}
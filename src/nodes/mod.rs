@@ -1,9 +1,25 @@
+mod admin;
 mod coordinator;
 mod data;
+mod flow;
+mod metrics;
+mod partitions;
+mod placement;
+mod query;
+mod registry;
+mod status;
 
 // Re-export types from submodules.
+pub use admin::*;
 pub use coordinator::*;
 pub use data::*;
+pub use flow::*;
+pub use metrics::*;
+pub use partitions::*;
+pub use placement::*;
+pub use query::*;
+pub use registry::*;
+pub use status::*;
 
 type DatabaseURL = Url;
 type ServerResult<T> = StandardResult<Response<T>, Status>;
@@ -16,10 +32,33 @@ use sqlx::{Connection, PgConnection};
 use std::net::SocketAddr;
 use std::result::Result as StandardResult;
 use std::sync::Arc;
+use std::time::Duration;
 use tonic::{async_trait, Request, Response, Status};
 use url::Url;
 use uuid::Uuid;
 
+/// Sizing and timeout configuration for a node's Postgres connection pool.
+///
+/// Fields:
+/// - max_size: Maximum number of pooled connections kept open at once.
+/// - acquire_timeout: How long to wait for a connection before erroring.
+#[derive(Debug, Clone, Copy)]
+pub struct PoolParameters {
+    pub max_size: u32,
+    pub acquire_timeout: Duration,
+}
+
+impl Default for PoolParameters {
+    fn default() -> Self {
+        Self { max_size: 10, acquire_timeout: Duration::from_secs(30) }
+    }
+}
+
+/// Default budget for [`connect_with_retry`] to keep retrying a transient
+/// Postgres connection failure before giving up, used where a caller
+/// doesn't have a more specific value (e.g. from a CLI flag) to pass.
+pub const DEFAULT_CONNECT_MAX_ELAPSED: Duration = Duration::from_secs(30);
+
 /// Node server trait for common functionality.
 ///
 /// This trait provides common functionality for both coordinator and data
@@ -30,7 +69,7 @@ trait NodeExt {
     fn database_url(&self) -> &DatabaseURL;
 
     /// Return the schema configuration of the node.
-    fn schema(&self) -> &impl NodeSchema;
+    fn schema(&self) -> &impl NodeSchemaNames;
 
     /// Return connection to the node's Postgres database.
     async fn connect(&self) -> Result<PgConnection, Status> {
@@ -63,4 +102,113 @@ trait NodeExt {
 
         Ok(id)
     }
+
+    /// Insert multiple clusters in a single transaction, returning their
+    /// generated ids in the same order as `centroids`. The whole batch is
+    /// rolled back if any row fails to insert.
+    async fn _insert_clusters(
+        &self,
+        conn: &mut PgConnection,
+        centroids: &[Vector],
+    ) -> Result<Vec<Uuid>, Status> {
+        let cluster_table = self.schema().cluster_table();
+
+        let placeholders: Vec<String> = (0..centroids.len())
+            .map(|i| format!("(gen_random_uuid(), ${})", i + 1))
+            .collect();
+
+        let query_str = format!(
+            "INSERT INTO {cluster_table} (id, centroid)
+            VALUES {}
+            RETURNING id",
+            placeholders.join(", ")
+        );
+
+        let mut query = sqlx::query_as::<_, (Uuid,)>(&query_str);
+        for centroid in centroids {
+            let byte = bincode::serialize(centroid).map_err(|_| {
+                Status::internal("Failed to serialize centroid vector")
+            })?;
+
+            query = query.bind(byte);
+        }
+
+        let mut tx = conn
+            .begin()
+            .await
+            .map_err(|_| Status::internal("Failed to start a transaction"))?;
+
+        let rows = query.fetch_all(&mut *tx).await.map_err(|_| {
+            Status::internal("Failed to insert a batch of clusters")
+        })?;
+
+        tx.commit()
+            .await
+            .map_err(|_| Status::internal("Failed to commit cluster batch insert"))?;
+
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Record that `connection_name` owns `cluster_id` at replica `rank`
+    /// (0 for the primary owner, 1+ for replicas), overwriting any prior
+    /// rank recorded for the same pair.
+    async fn _assign_cluster(
+        &self,
+        conn: &mut PgConnection,
+        cluster_id: Uuid,
+        connection_name: &str,
+        rank: i32,
+    ) -> Result<(), Status> {
+        let assignment_table = self.schema().assignment_table();
+        sqlx::query(&format!(
+            "INSERT INTO {assignment_table} (cluster_id, connection_name, rank)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (cluster_id, connection_name)
+            DO UPDATE SET rank = $3"
+        ))
+        .bind(cluster_id)
+        .bind(connection_name)
+        .bind(rank)
+        .execute(conn)
+        .await
+        .map_err(|_| Status::internal("Failed to assign cluster to node"))?;
+
+        Ok(())
+    }
+}
+
+/// Translates a Postgres error into a gRPC [`Status`] a caller can act on,
+/// instead of collapsing every failure into [`Status::internal`]. `fallback`
+/// is used as the message whenever `error` isn't a recognized SQLSTATE, so
+/// callers keep a useful message for the unclassified case.
+///
+/// - unique violation (`23505`) -> [`Status::already_exists`]
+/// - other integrity constraint violations (SQLSTATE class `23`) ->
+///   [`Status::failed_precondition`]
+/// - serialization failure (`40001`) -> [`Status::aborted`], safe to retry
+/// - connection loss -> [`Status::unavailable`], safe to retry
+/// - everything else -> [`Status::internal`]
+pub(crate) fn status_from_db_error(
+    error: sqlx::Error,
+    fallback: &str,
+) -> Status {
+    if let sqlx::Error::Database(ref db_error) = error {
+        if let Some(code) = db_error.code() {
+            return match code.as_ref() {
+                "23505" => Status::already_exists(db_error.message()),
+                "40001" => Status::aborted(db_error.message()),
+                code if code.starts_with("23") => {
+                    Status::failed_precondition(db_error.message())
+                }
+                _ => Status::internal(fallback),
+            };
+        }
+    }
+
+    match error {
+        sqlx::Error::Io(_)
+        | sqlx::Error::PoolClosed
+        | sqlx::Error::PoolTimedOut => Status::unavailable(fallback),
+        _ => Status::internal(fallback),
+    }
 }
@@ -0,0 +1,162 @@
+use super::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Number of virtual partitions the coordinator's partition ring is split
+/// into. Fixed rather than configurable: large enough that
+/// [`assign_partitions`] spreads vectors evenly across a modest number of
+/// data nodes, small enough that the ring fits in one Postgres table and
+/// one full scan per [`CoordinatorNode::rebalance_partitions`] call.
+pub const PARTITION_COUNT: u32 = 256;
+
+/// A partition's current owner in the ring, as persisted by
+/// [`CoordinatorNode::rebalance_partitions`] and read back on the next
+/// call to compute a diff against.
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct PartitionAssignment {
+    #[sqlx(try_from = "i32")]
+    pub partition: u32,
+    pub connection_name: Box<str>,
+}
+
+/// A planned repoint of `partition` onto data node `to`, from whichever
+/// node owned it before (`None` if the partition had no owner yet).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionMove {
+    pub partition: u32,
+    pub from: Option<Box<str>>,
+    pub to: Box<str>,
+}
+
+/// Assigns every partition in the ring to one of `candidates`, weighted by
+/// capacity, reusing [`select_replicas`]'s deterministic weighted-reservoir
+/// selection by treating each partition index as a synthetic cluster id.
+/// Because that selection only depends on a candidate's own key, adding or
+/// removing a data node only reshuffles the partitions it's actually
+/// involved in rather than the whole ring, keeping
+/// [`diff_partitions`] minimal.
+pub fn assign_partitions(
+    candidates: &[PlacementCandidate],
+) -> Vec<PartitionAssignment> {
+    (0..PARTITION_COUNT)
+        .filter_map(|partition| {
+            let id = partition_uuid(partition);
+            let owner = select_replicas(id, candidates, 1).into_iter().next()?;
+            Some(PartitionAssignment { partition, connection_name: owner })
+        })
+        .collect()
+}
+
+/// Which partition a sub-cluster (or other ring-addressed id) falls into,
+/// used to tell which of its partition's move, if any, should carry it
+/// along.
+pub fn partition_of(id: Uuid) -> u32 {
+    let mut hasher = DefaultHasher::new();
+    id.hash(&mut hasher);
+    (hasher.finish() % PARTITION_COUNT as u64) as u32
+}
+
+/// Deterministically maps a partition index to a synthetic [`Uuid`], so
+/// [`select_replicas`] can be reused unmodified to place ring partitions
+/// the same way it places coordinator-level clusters.
+fn partition_uuid(partition: u32) -> Uuid {
+    Uuid::from_u128(partition as u128)
+}
+
+/// The minimal set of moves that takes the ring from `old` to `new`: only
+/// partitions whose owner actually changed, so a partition that didn't
+/// move keeps its data in place.
+pub fn diff_partitions(
+    old: &[PartitionAssignment],
+    new: &[PartitionAssignment],
+) -> Vec<PartitionMove> {
+    let previous: HashMap<u32, &Box<str>> = old
+        .iter()
+        .map(|assignment| (assignment.partition, &assignment.connection_name))
+        .collect();
+
+    new.iter()
+        .filter_map(|assignment| {
+            let from = previous.get(&assignment.partition).copied().cloned();
+            if from.as_ref() == Some(&assignment.connection_name) {
+                return None;
+            }
+
+            Some(PartitionMove {
+                partition: assignment.partition,
+                from,
+                to: assignment.connection_name.clone(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assign_partitions_respects_capacity() {
+        let candidates = vec![
+            PlacementCandidate { name: "small".into(), capacity: 1.0 },
+            PlacementCandidate { name: "large".into(), capacity: 100.0 },
+        ];
+
+        let assignments = assign_partitions(&candidates);
+        assert_eq!(assignments.len(), PARTITION_COUNT as usize);
+
+        let large_share = assignments
+            .iter()
+            .filter(|a| a.connection_name.as_ref() == "large")
+            .count();
+
+        // With a dramatically larger capacity, "large" should own almost
+        // every partition in the ring.
+        assert!(large_share > assignments.len() / 2);
+    }
+
+    #[test]
+    fn test_assign_partitions_is_deterministic() {
+        let candidates = vec![
+            PlacementCandidate { name: "a".into(), capacity: 1.0 },
+            PlacementCandidate { name: "b".into(), capacity: 1.0 },
+        ];
+
+        let first = assign_partitions(&candidates);
+        let second = assign_partitions(&candidates);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_diff_partitions_only_reports_changed_owners() {
+        let old = vec![
+            PartitionAssignment { partition: 0, connection_name: "a".into() },
+            PartitionAssignment { partition: 1, connection_name: "b".into() },
+        ];
+
+        let new = vec![
+            PartitionAssignment { partition: 0, connection_name: "a".into() },
+            PartitionAssignment { partition: 1, connection_name: "c".into() },
+        ];
+
+        let moves = diff_partitions(&old, &new);
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].partition, 1);
+        assert_eq!(moves[0].from.as_deref(), Some("b"));
+        assert_eq!(moves[0].to.as_ref(), "c");
+    }
+
+    #[test]
+    fn test_diff_partitions_reports_new_partitions_with_no_prior_owner() {
+        let old: Vec<PartitionAssignment> = Vec::new();
+        let new = vec![PartitionAssignment {
+            partition: 0,
+            connection_name: "a".into(),
+        }];
+
+        let moves = diff_partitions(&old, &new);
+        assert_eq!(moves.len(), 1);
+        assert!(moves[0].from.is_none());
+    }
+}
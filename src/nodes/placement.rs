@@ -0,0 +1,120 @@
+use super::*;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
+
+/// A data node competing to own a cluster, weighted by its registered
+/// [`NodeCapacity`].
+#[derive(Debug, Clone)]
+pub struct PlacementCandidate {
+    pub name: Box<str>,
+    pub capacity: f32,
+}
+
+impl From<NodeCapacity> for PlacementCandidate {
+    fn from(value: NodeCapacity) -> Self {
+        Self { name: value.name, capacity: value.capacity }
+    }
+}
+
+/// Selects which data node(s) should own a cluster using weighted
+/// reservoir sampling ([Efraimidis-Spirakis](https://doi.org/10.1016/j.ipl.2005.11.003)).
+///
+/// For each candidate `i` with capacity `w_i`, a key `k_i = u_i^(1/w_i)` is
+/// derived, where `u_i` is deterministically drawn from `(cluster_id, name)`
+/// rather than sampled fresh each call. The `replicas` candidates with the
+/// largest keys are returned, primary owner first. Because every key only
+/// depends on the candidate itself (not on who else is competing), adding
+/// or removing a node only perturbs the clusters that node is actually
+/// involved in, instead of reshuffling the whole placement.
+pub fn select_replicas(
+    cluster_id: Uuid,
+    candidates: &[PlacementCandidate],
+    replicas: usize,
+) -> Vec<Box<str>> {
+    let mut keyed: Vec<(f32, &Box<str>)> = candidates
+        .iter()
+        .filter(|candidate| candidate.capacity > 0.0)
+        .map(|candidate| {
+            let unit = stable_unit_interval(cluster_id, &candidate.name);
+            let key = unit.powf(1.0 / candidate.capacity);
+            (key, &candidate.name)
+        })
+        .collect();
+
+    keyed.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal));
+    keyed.into_iter().take(replicas).map(|(_, name)| name.clone()).collect()
+}
+
+/// Deterministically maps `(cluster_id, name)` to a pseudo-random value in
+/// `(0, 1]`, used as the uniform draw `u_i` in the placement key formula.
+fn stable_unit_interval(cluster_id: Uuid, name: &str) -> f32 {
+    let mut hasher = DefaultHasher::new();
+    cluster_id.hash(&mut hasher);
+    name.hash(&mut hasher);
+
+    // Keep the top 24 bits so the result fits losslessly in an f32, and
+    // avoid 0 since it's the base of a power in the caller.
+    let bits = (hasher.finish() >> 40) as u32;
+    (bits.max(1) as f32) / (((1u32 << 24) - 1) as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_replicas_respects_capacity() {
+        let candidates = vec![
+            PlacementCandidate { name: "small".into(), capacity: 1.0 },
+            PlacementCandidate { name: "large".into(), capacity: 100.0 },
+        ];
+
+        // With a dramatically larger capacity, "large" should almost always
+        // win the single replica slot across many distinct clusters.
+        let mut large_wins = 0;
+        for _ in 0..100 {
+            let cluster_id = Uuid::new_v4();
+            let winner = select_replicas(cluster_id, &candidates, 1);
+            if winner[0].as_ref() == "large" {
+                large_wins += 1;
+            }
+        }
+
+        assert!(large_wins > 80, "expected the higher-capacity node to dominate placement");
+    }
+
+    #[test]
+    fn test_select_replicas_is_deterministic() {
+        let cluster_id = Uuid::new_v4();
+        let candidates = vec![
+            PlacementCandidate { name: "a".into(), capacity: 1.0 },
+            PlacementCandidate { name: "b".into(), capacity: 2.0 },
+            PlacementCandidate { name: "c".into(), capacity: 3.0 },
+        ];
+
+        let first = select_replicas(cluster_id, &candidates, 2);
+        let second = select_replicas(cluster_id, &candidates, 2);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_select_replicas_minimal_disruption() {
+        let cluster_id = Uuid::new_v4();
+        let candidates = vec![
+            PlacementCandidate { name: "a".into(), capacity: 1.0 },
+            PlacementCandidate { name: "b".into(), capacity: 1.0 },
+            PlacementCandidate { name: "c".into(), capacity: 1.0 },
+        ];
+
+        let before = select_replicas(cluster_id, &candidates, 1);
+
+        // Adding a fourth node should not change the winner unless the new
+        // node's own key happens to beat it.
+        let mut grown = candidates.clone();
+        grown.push(PlacementCandidate { name: "d".into(), capacity: 1.0 });
+        let after = select_replicas(cluster_id, &grown, 1);
+
+        assert!(before[0].as_ref() == after[0].as_ref() || after[0].as_ref() == "d");
+    }
+}
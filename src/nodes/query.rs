@@ -0,0 +1,159 @@
+use super::*;
+use crate::types::Metric;
+use std::cmp::Ordering;
+
+/// A single candidate surfaced by a [`CoordinatorNode::query`]
+/// scatter-gather, from whichever data node currently owns its
+/// sub-cluster.
+#[derive(Debug, Clone)]
+pub struct QueryCandidate {
+    pub id: Uuid,
+    pub connection_name: Box<str>,
+    pub distance: f64,
+}
+
+/// The outcome of a [`CoordinatorNode::query`] scatter-gather: the merged
+/// top-k candidates, plus one `(connection_name, message)` entry per data
+/// node that couldn't be reached, so a partial outage doesn't fail the
+/// whole query.
+#[derive(Debug, Clone, Default)]
+pub struct QueryResult {
+    pub candidates: Vec<QueryCandidate>,
+    pub errors: Vec<(Box<str>, Box<str>)>,
+}
+
+impl QueryResult {
+    /// Whether one or more data nodes failed to contribute to this result,
+    /// so a caller can tell a complete result from a partial one.
+    pub fn degraded(&self) -> bool {
+        !self.errors.is_empty()
+    }
+}
+
+/// The `probes` sub-clusters whose centroid is nearest `query`, used to
+/// decide which data nodes a [`CoordinatorNode::query`] fans out to
+/// instead of every registered node.
+pub fn rank_subclusters<'a>(
+    query: &Vector,
+    subclusters: &'a [SubclusterLoad],
+    metric: Metric,
+    probes: usize,
+) -> Vec<&'a SubclusterLoad> {
+    let mut ranked: Vec<(f64, &SubclusterLoad)> = subclusters
+        .iter()
+        .map(|subcluster| {
+            let distance = metric
+                .distance(query, &subcluster.centroid)
+                .unwrap_or(f64::MAX);
+
+            (distance, subcluster)
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+    ranked.into_iter().take(probes).map(|(_, s)| s).collect()
+}
+
+/// Merges every data node's partial results into a single globally-ordered
+/// top-`count` list, recomputing each candidate's distance against `query`
+/// rather than trusting a node's own ordering, since each node only
+/// compares within its own rows. An entry with an `Err` partial is
+/// recorded in [`QueryResult::errors`] instead of failing the whole query.
+pub fn merge_candidates(
+    partials: Vec<(Box<str>, Result<Vec<(Uuid, Vector)>, Box<str>>)>,
+    query: &Vector,
+    metric: Metric,
+    count: usize,
+) -> QueryResult {
+    let mut result = QueryResult::default();
+    for (connection_name, partial) in partials {
+        match partial {
+            Ok(rows) => {
+                for (id, vector) in rows {
+                    let distance =
+                        metric.distance(query, &vector).unwrap_or(f64::MAX);
+
+                    result.candidates.push(QueryCandidate {
+                        id,
+                        connection_name: connection_name.clone(),
+                        distance,
+                    });
+                }
+            }
+            Err(message) => result.errors.push((connection_name, message)),
+        }
+    }
+
+    result.candidates.sort_by(|a, b| {
+        a.distance.partial_cmp(&b.distance).unwrap_or(Ordering::Equal)
+    });
+
+    result.candidates.truncate(count);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subcluster(connection_name: &str, centroid: Vec<f32>) -> SubclusterLoad {
+        SubclusterLoad {
+            id: Uuid::new_v4(),
+            connection_name: connection_name.into(),
+            centroid: centroid.into(),
+        }
+    }
+
+    #[test]
+    fn test_rank_subclusters_orders_by_distance_and_caps_at_probes() {
+        let subclusters = vec![
+            subcluster("far", vec![10.0, 10.0]),
+            subcluster("near", vec![0.1, 0.1]),
+            subcluster("mid", vec![1.0, 1.0]),
+        ];
+
+        let query: Vector = vec![0.0, 0.0].into();
+        let metric = Metric::Euclidean;
+        let ranked = rank_subclusters(&query, &subclusters, metric, 2);
+
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].connection_name.as_ref(), "near");
+        assert_eq!(ranked[1].connection_name.as_ref(), "mid");
+    }
+
+    #[test]
+    fn test_merge_candidates_reorders_across_nodes_and_truncates() {
+        let query: Vector = vec![0.0, 0.0].into();
+        let near_id = Uuid::new_v4();
+        let far_id = Uuid::new_v4();
+
+        // "node-a" thinks its only candidate is the closest, but "node-b"
+        // holds a candidate that's actually nearer to the query.
+        let partials = vec![
+            (
+                "node-a".into(),
+                Ok(vec![(far_id, vec![5.0, 5.0].into())]),
+            ),
+            (
+                "node-b".into(),
+                Ok(vec![(near_id, vec![0.1, 0.1].into())]),
+            ),
+            ("node-c".into(), Err("unreachable".into())),
+        ];
+
+        let result = merge_candidates(partials, &query, Metric::Euclidean, 1);
+
+        assert_eq!(result.candidates.len(), 1);
+        assert_eq!(result.candidates[0].id, near_id);
+
+        let errors = vec![("node-c".into(), "unreachable".into())];
+        assert_eq!(result.errors, errors);
+        assert!(result.degraded());
+    }
+
+    #[test]
+    fn test_query_result_is_not_degraded_without_errors() {
+        let result = QueryResult::default();
+        assert!(!result.degraded());
+    }
+}
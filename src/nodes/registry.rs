@@ -0,0 +1,286 @@
+use super::*;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Instant;
+
+/// Liveness of a registered data node, derived from how long it's been
+/// since the coordinator last heard from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeHealth {
+    /// Heartbeat received within [`NodeRegistry`]'s `suspect_after` window.
+    Healthy,
+    /// No heartbeat for at least `suspect_after`, but less than `down_after`.
+    /// Still eligible for cluster placement.
+    Suspect,
+    /// No heartbeat for at least `down_after`. Excluded from placement.
+    Down,
+}
+
+/// A data node's last reported heartbeat.
+#[derive(Debug, Clone)]
+pub struct NodeStatus {
+    pub name: Box<str>,
+    pub record_count: u64,
+    pub cluster_count: u64,
+    pub schema_version: i32,
+    last_seen: Instant,
+}
+
+/// A node's rolling request outcome, used to order a scatter-gather
+/// fan-out by how reliable a node has recently been rather than fanning
+/// out in arbitrary order.
+#[derive(Debug, Clone, Copy)]
+struct NodeReliability {
+    attempts: u32,
+    successes: u32,
+    /// Exponentially-weighted moving average of observed round-trip time,
+    /// so one slow outlier doesn't dominate the ranking the way a plain
+    /// average would.
+    recent_rtt: Duration,
+}
+
+impl NodeReliability {
+    fn success_rate(&self) -> f32 {
+        if self.attempts == 0 {
+            1.0
+        } else {
+            self.successes as f32 / self.attempts as f32
+        }
+    }
+}
+
+/// Tracks data node liveness from heartbeats, computing [`NodeHealth`]
+/// lazily from elapsed time rather than needing a write on every tick.
+///
+/// A node becomes [`NodeHealth::Suspect`] after `suspect_after` elapses
+/// with no heartbeat, and [`NodeHealth::Down`] after `down_after`. This is
+/// process-local membership state, rebuilt from scratch on coordinator
+/// restart as nodes heartbeat back in.
+#[derive(Debug)]
+pub struct NodeRegistry {
+    nodes: RwLock<HashMap<Box<str>, NodeStatus>>,
+    reliability: RwLock<HashMap<Box<str>, NodeReliability>>,
+    suspect_after: Duration,
+    down_after: Duration,
+}
+
+impl NodeRegistry {
+    pub fn new(suspect_after: Duration, down_after: Duration) -> Self {
+        Self {
+            nodes: RwLock::new(HashMap::new()),
+            reliability: RwLock::new(HashMap::new()),
+            suspect_after,
+            down_after,
+        }
+    }
+
+    /// Record a fresh heartbeat from `name`, overwriting its prior status.
+    pub fn record_heartbeat(
+        &self,
+        name: impl Into<Box<str>>,
+        record_count: u64,
+        cluster_count: u64,
+        schema_version: i32,
+    ) {
+        let name = name.into();
+        let status = NodeStatus {
+            name: name.clone(),
+            record_count,
+            cluster_count,
+            schema_version,
+            last_seen: Instant::now(),
+        };
+
+        let mut nodes = self.nodes.write().expect("node registry lock was poisoned");
+        nodes.insert(name, status);
+    }
+
+    /// Every registered node's last known status paired with its current
+    /// health, for operators to inspect cluster membership and load.
+    pub fn snapshot(&self) -> Vec<(NodeStatus, NodeHealth)> {
+        let nodes = self.nodes.read().expect("node registry lock was poisoned");
+        nodes.values().map(|status| (status.clone(), self.health_of(status))).collect()
+    }
+
+    /// Names of every node that isn't [`NodeHealth::Down`], i.e. still
+    /// eligible to be assigned clusters.
+    pub fn live_node_names(&self) -> Vec<Box<str>> {
+        self.snapshot()
+            .into_iter()
+            .filter(|(_, health)| *health != NodeHealth::Down)
+            .map(|(status, _)| status.name)
+            .collect()
+    }
+
+    /// The current health of `name`, or `None` if it has never heartbeated.
+    pub fn health(&self, name: &str) -> Option<NodeHealth> {
+        let nodes = self.nodes.read().expect("node registry lock was poisoned");
+        nodes.get(name).map(|status| self.health_of(status))
+    }
+
+    /// Records the outcome and round-trip time of one request to `name`,
+    /// folding it into its rolling success rate and EWMA latency used by
+    /// [`Self::rank_by_reliability`]. A node seen for the first time starts
+    /// from `rtt` rather than an arbitrary default, so a single sample is
+    /// enough to place it sensibly in the ranking.
+    pub fn record_latency(
+        &self,
+        name: impl Into<Box<str>>,
+        success: bool,
+        rtt: Duration,
+    ) {
+        const SMOOTHING: f32 = 0.3;
+
+        let name = name.into();
+        let mut reliability = self
+            .reliability
+            .write()
+            .expect("node reliability lock was poisoned");
+
+        let entry = reliability.entry(name).or_insert(NodeReliability {
+            attempts: 0,
+            successes: 0,
+            recent_rtt: rtt,
+        });
+
+        entry.attempts += 1;
+        if success {
+            entry.successes += 1;
+        }
+
+        let previous = entry.recent_rtt.as_secs_f32();
+        let sample = rtt.as_secs_f32();
+        let smoothed = previous * (1.0 - SMOOTHING) + sample * SMOOTHING;
+        entry.recent_rtt = Duration::from_secs_f32(smoothed.max(0.0));
+    }
+
+    /// Orders `names` most-reliable-first: higher recent success rate
+    /// wins, ties broken by lower recent round-trip time. A name with no
+    /// recorded attempts yet is treated as perfectly reliable, so a newly
+    /// joined node gets a fair first try instead of being starved to the
+    /// back of the fan-out order.
+    pub fn rank_by_reliability(&self, names: &[Box<str>]) -> Vec<Box<str>> {
+        let reliability = self
+            .reliability
+            .read()
+            .expect("node reliability lock was poisoned");
+
+        let mut ranked: Vec<(Box<str>, f32, Duration)> = names
+            .iter()
+            .map(|name| match reliability.get(name) {
+                Some(r) => (name.clone(), r.success_rate(), r.recent_rtt),
+                None => (name.clone(), 1.0, Duration::ZERO),
+            })
+            .collect();
+
+        ranked.sort_by(|a, b| {
+            let by_rate = b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal);
+            by_rate.then_with(|| a.2.cmp(&b.2))
+        });
+
+        ranked.into_iter().map(|(name, ..)| name).collect()
+    }
+
+    fn health_of(&self, status: &NodeStatus) -> NodeHealth {
+        let elapsed = status.last_seen.elapsed();
+        if elapsed >= self.down_after {
+            NodeHealth::Down
+        } else if elapsed >= self.suspect_after {
+            NodeHealth::Suspect
+        } else {
+            NodeHealth::Healthy
+        }
+    }
+}
+
+impl Default for NodeRegistry {
+    /// Suspects a node after 15s without a heartbeat and marks it down
+    /// after 60s, assuming heartbeats are polled every few seconds.
+    fn default() -> Self {
+        Self::new(Duration::from_secs(15), Duration::from_secs(60))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::sleep;
+
+    #[test]
+    fn test_unseen_node_is_not_in_snapshot() {
+        let registry = NodeRegistry::default();
+        assert!(registry.snapshot().is_empty());
+    }
+
+    #[test]
+    fn test_fresh_heartbeat_is_healthy() {
+        let registry = NodeRegistry::default();
+        registry.record_heartbeat("node-a", 10, 2, 1);
+
+        let snapshot = registry.snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].1, NodeHealth::Healthy);
+    }
+
+    #[test]
+    fn test_health_of_unseen_node_is_none() {
+        let registry = NodeRegistry::default();
+        registry.record_heartbeat("node-a", 0, 0, 1);
+
+        assert_eq!(registry.health("node-a"), Some(NodeHealth::Healthy));
+        assert_eq!(registry.health("node-b"), None);
+    }
+
+    #[test]
+    fn test_stale_heartbeat_becomes_suspect_then_down() {
+        let registry = NodeRegistry::new(
+            Duration::from_millis(10),
+            Duration::from_millis(30),
+        );
+
+        registry.record_heartbeat("node-a", 0, 0, 1);
+
+        sleep(Duration::from_millis(15));
+        assert_eq!(registry.snapshot()[0].1, NodeHealth::Suspect);
+        assert_eq!(registry.live_node_names().len(), 1);
+
+        sleep(Duration::from_millis(20));
+        assert_eq!(registry.snapshot()[0].1, NodeHealth::Down);
+        assert!(registry.live_node_names().is_empty());
+    }
+
+    #[test]
+    fn test_unseen_nodes_rank_as_equally_reliable() {
+        let registry = NodeRegistry::default();
+        let names: Vec<Box<str>> = vec!["node-b".into(), "node-a".into()];
+
+        // No recorded attempts for either, so the original order is kept.
+        let ranked = registry.rank_by_reliability(&names);
+        assert_eq!(ranked, names);
+    }
+
+    #[test]
+    fn test_rank_by_reliability_prefers_higher_success_rate() {
+        let registry = NodeRegistry::default();
+        registry.record_latency("node-a", true, Duration::from_millis(5));
+        registry.record_latency("node-b", false, Duration::from_millis(1));
+
+        let names: Vec<Box<str>> = vec!["node-b".into(), "node-a".into()];
+        let ranked = registry.rank_by_reliability(&names);
+        assert_eq!(ranked, vec!["node-a".into(), "node-b".into()]);
+    }
+
+    #[test]
+    fn test_rank_by_reliability_breaks_ties_with_lower_rtt() {
+        let registry = NodeRegistry::default();
+        registry.record_latency("node-a", true, Duration::from_millis(50));
+        registry.record_latency("node-b", true, Duration::from_millis(5));
+
+        let names: Vec<Box<str>> = vec!["node-a".into(), "node-b".into()];
+        let ranked = registry.rank_by_reliability(&names);
+        assert_eq!(ranked, vec!["node-b".into(), "node-a".into()]);
+    }
+}
@@ -0,0 +1,38 @@
+use super::*;
+use serde::Serialize;
+
+/// A registered data node's connectivity and load, as reported by
+/// [`CoordinatorNode::status`].
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeStatusReport {
+    pub name: Box<str>,
+    pub address: Box<str>,
+    /// Whether the node responded to a `heartbeat` RPC just now, as
+    /// opposed to [`NodeHealth`] which is derived from how long ago it
+    /// last responded.
+    pub reachable: bool,
+    /// The node's [`NodeRegistry`]-derived health, or `None` if it has
+    /// never heartbeated (e.g. it just registered and hasn't been polled
+    /// or pushed a heartbeat yet).
+    pub health: Option<NodeHealth>,
+    pub subcluster_count: i64,
+    pub record_count: u64,
+}
+
+/// Coordinator cluster topology and health, modeled after Garage's admin
+/// cluster status API: the node's configured parameters plus every
+/// registered data node's current reachability and load.
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterStatus {
+    pub initialized: bool,
+    pub metric: Box<str>,
+    pub dimension: usize,
+    pub density: usize,
+    /// Version of the partition ring's current layout, bumped by
+    /// [`CoordinatorNode::rebalance_partitions`] every time it moves a
+    /// partition. Lets a caller holding an older value detect that its
+    /// view of which data node owns a partition may be stale and retry.
+    pub layout_version: i32,
+    pub node_count: usize,
+    pub nodes: Vec<NodeStatusReport>,
+}
@@ -0,0 +1,664 @@
+use super::*;
+use sqlx::sqlite::SqliteConnection;
+use sqlx::Row;
+use std::collections::HashMap as Map;
+use uuid::Uuid;
+
+/// A portable column type for [`TableSpec`], translated into each engine's
+/// own type names, e.g. [`ColumnKind::Blob`] becomes `BYTEA` in Postgres and
+/// `BLOB` in SQLite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Uuid,
+    Text,
+    Integer,
+    Real,
+    Boolean,
+    Blob,
+    Json,
+}
+
+/// A single column in a [`TableSpec`].
+#[derive(Debug, Clone)]
+pub struct ColumnSpec {
+    pub name: &'static str,
+    pub kind: ColumnKind,
+    pub primary_key: bool,
+    pub not_null: bool,
+    pub generated: bool,
+    pub references: Option<(TableName, &'static str)>,
+}
+
+impl ColumnSpec {
+    fn new(name: &'static str, kind: ColumnKind) -> Self {
+        Self {
+            name,
+            kind,
+            primary_key: false,
+            not_null: false,
+            generated: false,
+            references: None,
+        }
+    }
+
+    pub fn uuid(name: &'static str) -> Self {
+        Self::new(name, ColumnKind::Uuid)
+    }
+
+    pub fn text(name: &'static str) -> Self {
+        Self::new(name, ColumnKind::Text)
+    }
+
+    pub fn blob(name: &'static str) -> Self {
+        Self::new(name, ColumnKind::Blob)
+    }
+
+    pub fn json(name: &'static str) -> Self {
+        Self::new(name, ColumnKind::Json)
+    }
+
+    pub fn primary_key(mut self) -> Self {
+        self.primary_key = true;
+        self
+    }
+
+    pub fn not_null(mut self) -> Self {
+        self.not_null = true;
+        self
+    }
+
+    /// Mark this column as server-generated, e.g. a UUID primary key
+    /// defaulted by the engine rather than supplied by the caller.
+    pub fn generated(mut self) -> Self {
+        self.generated = true;
+        self
+    }
+
+    pub fn references(
+        mut self,
+        table: TableName,
+        column: &'static str,
+    ) -> Self {
+        self.references = Some((table, column));
+        self
+    }
+}
+
+/// Declarative description of a table to create via
+/// [`StorageEngine::create_table`].
+///
+/// A table can be described two ways:
+/// - `columns`: a portable column list, used to generate DDL on SQL-backed
+///   engines. [`LmdbEngine`] doesn't use it: it only opens a named
+///   sub-database for the table.
+/// - `raw_sql`: an escape hatch for tables whose constraints (composite
+///   checks, foreign keys enforcing business rules) aren't worth modeling
+///   portably yet. SQL-backed engines run it verbatim; [`LmdbEngine`]
+///   ignores it and just opens a named sub-database, since enforcing those
+///   constraints becomes the caller's responsibility under a plain KV store.
+#[derive(Debug, Clone, Default)]
+pub struct TableSpec {
+    pub name: TableName,
+    pub columns: Vec<ColumnSpec>,
+    pub raw_sql: Option<String>,
+}
+
+impl TableSpec {
+    pub fn new(name: impl Into<TableName>) -> Self {
+        Self { name: name.into(), columns: Vec::new(), raw_sql: None }
+    }
+
+    pub fn column(mut self, column: ColumnSpec) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    /// Run `sql` verbatim on SQL-backed engines instead of generating DDL
+    /// from `columns`.
+    pub fn raw_sql(mut self, sql: impl Into<String>) -> Self {
+        self.raw_sql = Some(sql.into());
+        self
+    }
+}
+
+/// A stored IVF cluster centroid, as the storage layer sees it: a bag of
+/// bytes rather than a deserialized [`crate::types::Vector`].
+///
+/// `id` is `None` when inserting a new cluster and `Some` when updating an
+/// existing one; see [`StorageEngine::upsert_cluster`].
+#[derive(Debug, Clone)]
+pub struct ClusterRow {
+    pub id: Option<Uuid>,
+    pub centroid: Vec<u8>,
+}
+
+/// A stored vector record, as the storage layer sees it.
+///
+/// `id` is `None` when inserting a new record and `Some` when updating an
+/// existing one; see [`StorageEngine::upsert_record`].
+#[derive(Debug, Clone)]
+pub struct RecordRow {
+    pub id: Option<Uuid>,
+    pub cluster_id: Uuid,
+    pub vector: Vec<u8>,
+    pub data: Option<serde_json::Value>,
+}
+
+/// The storage operations a [`NodeSchema`] needs, kept generic so
+/// `CoordinatorSchema`/`DataSchema` aren't hardwired to Postgres.
+///
+/// [`PgEngine`] is the default, backed by `sqlx::PgConnection`.
+/// [`SqliteEngine`] and [`LmdbEngine`] let a node run embedded, without a
+/// Postgres server.
+#[async_trait]
+pub trait StorageEngine: Send {
+    /// Create a namespace grouping this engine's tables, if it doesn't
+    /// already exist.
+    async fn create_schema(&mut self, schema: &str);
+
+    /// Return true if `schema` has already been provisioned.
+    async fn schema_exists(&mut self, schema: &str) -> bool;
+
+    /// Create a table per `spec`, if it doesn't already exist.
+    async fn create_table(&mut self, spec: &TableSpec);
+
+    /// Insert a new cluster, or update an existing one when `cluster.id` is
+    /// set. Returns the cluster's id either way.
+    async fn upsert_cluster(
+        &mut self,
+        table: &TableName,
+        cluster: ClusterRow,
+    ) -> Uuid;
+
+    /// Insert a new record, or update an existing one when `record.id` is
+    /// set. Returns the record's id either way.
+    async fn upsert_record(
+        &mut self,
+        table: &TableName,
+        record: RecordRow,
+    ) -> Uuid;
+
+    /// Return every cluster stored in `table`.
+    async fn scan_clusters(&mut self, table: &TableName) -> Vec<ClusterRow>;
+
+    /// Return every record in `table` assigned to `cluster_id`.
+    async fn scan_records_in_cluster(
+        &mut self,
+        table: &TableName,
+        cluster_id: Uuid,
+    ) -> Vec<RecordRow>;
+}
+
+// Render a column's engine-specific type name and constraints.
+fn render_column(column: &ColumnSpec, dialect: Dialect) -> String {
+    let kind = match (dialect, column.kind) {
+        (Dialect::Postgres, ColumnKind::Uuid) => "UUID",
+        (Dialect::Postgres, ColumnKind::Text) => "TEXT",
+        (Dialect::Postgres, ColumnKind::Integer) => "INTEGER",
+        (Dialect::Postgres, ColumnKind::Real) => "REAL",
+        (Dialect::Postgres, ColumnKind::Boolean) => "BOOLEAN",
+        (Dialect::Postgres, ColumnKind::Blob) => "BYTEA",
+        (Dialect::Postgres, ColumnKind::Json) => "JSONB",
+        (Dialect::Sqlite, ColumnKind::Uuid) => "TEXT",
+        (Dialect::Sqlite, ColumnKind::Text) => "TEXT",
+        (Dialect::Sqlite, ColumnKind::Integer) => "INTEGER",
+        (Dialect::Sqlite, ColumnKind::Real) => "REAL",
+        (Dialect::Sqlite, ColumnKind::Boolean) => "INTEGER",
+        (Dialect::Sqlite, ColumnKind::Blob) => "BLOB",
+        (Dialect::Sqlite, ColumnKind::Json) => "TEXT",
+    };
+
+    let mut ddl = format!("{} {kind}", column.name);
+    if column.generated && column.kind == ColumnKind::Uuid {
+        ddl.push_str(match dialect {
+            Dialect::Postgres => " DEFAULT gen_random_uuid()",
+            Dialect::Sqlite => " DEFAULT (lower(hex(randomblob(16))))",
+        });
+    }
+
+    if column.primary_key {
+        ddl.push_str(" PRIMARY KEY");
+    }
+
+    if column.not_null {
+        ddl.push_str(" NOT NULL");
+    }
+
+    if let Some((table, reference)) = &column.references {
+        ddl.push_str(&format!(" REFERENCES {table} ({reference})"));
+    }
+
+    ddl
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Dialect {
+    Postgres,
+    Sqlite,
+}
+
+fn render_create_table(spec: &TableSpec, dialect: Dialect) -> String {
+    let columns: Vec<String> =
+        spec.columns.iter().map(|c| render_column(c, dialect)).collect();
+
+    format!(
+        "CREATE TABLE IF NOT EXISTS {} ({})",
+        spec.name,
+        columns.join(", ")
+    )
+}
+
+/// [`StorageEngine`] backed by a live `sqlx::PgConnection`.
+///
+/// Borrows the connection rather than owning it, so a node can keep using
+/// its existing connection/pool for the ad-hoc queries that sit outside the
+/// schema's table-creation and row-storage concerns.
+pub struct PgEngine<'a>(pub &'a mut PgConnection);
+
+#[async_trait]
+impl StorageEngine for PgEngine<'_> {
+    async fn create_schema(&mut self, schema: &str) {
+        sqlx::query(&format!("CREATE SCHEMA IF NOT EXISTS {schema}"))
+            .execute(&mut *self.0)
+            .await
+            .expect("Failed to create the schema");
+    }
+
+    async fn schema_exists(&mut self, schema: &str) -> bool {
+        let row = sqlx::query(
+            "SELECT schema_name FROM information_schema.schemata
+            WHERE schema_name = $1",
+        )
+        .bind(schema)
+        .fetch_optional(&mut *self.0)
+        .await
+        .expect("Failed to check if schema exists");
+
+        row.is_some()
+    }
+
+    async fn create_table(&mut self, spec: &TableSpec) {
+        let sql = match &spec.raw_sql {
+            Some(sql) => sql.to_owned(),
+            None => render_create_table(spec, Dialect::Postgres),
+        };
+
+        sqlx::query(&sql)
+            .execute(&mut *self.0)
+            .await
+            .unwrap_or_else(|_| panic!("Failed to create table {}", spec.name));
+    }
+
+    async fn upsert_cluster(
+        &mut self,
+        table: &TableName,
+        cluster: ClusterRow,
+    ) -> Uuid {
+        if let Some(id) = cluster.id {
+            sqlx::query(&format!(
+                "UPDATE {table} SET centroid = $2 WHERE id = $1"
+            ))
+            .bind(id)
+            .bind(&cluster.centroid)
+            .execute(&mut *self.0)
+            .await
+            .expect("Failed to update the cluster");
+
+            return id;
+        }
+
+        let row: (Uuid,) = sqlx::query_as(&format!(
+            "INSERT INTO {table} (id, centroid)
+            VALUES (gen_random_uuid(), $1)
+            RETURNING id"
+        ))
+        .bind(&cluster.centroid)
+        .fetch_one(&mut *self.0)
+        .await
+        .expect("Failed to insert the cluster");
+
+        row.0
+    }
+
+    async fn upsert_record(
+        &mut self,
+        table: &TableName,
+        record: RecordRow,
+    ) -> Uuid {
+        if let Some(id) = record.id {
+            sqlx::query(&format!(
+                "UPDATE {table}
+                SET cluster_id = $2, vector = $3, data = $4
+                WHERE id = $1"
+            ))
+            .bind(id)
+            .bind(record.cluster_id)
+            .bind(&record.vector)
+            .bind(&record.data)
+            .execute(&mut *self.0)
+            .await
+            .expect("Failed to update the record");
+
+            return id;
+        }
+
+        let row: (Uuid,) = sqlx::query_as(&format!(
+            "INSERT INTO {table} (id, cluster_id, vector, data)
+            VALUES (gen_random_uuid(), $1, $2, $3)
+            RETURNING id"
+        ))
+        .bind(record.cluster_id)
+        .bind(&record.vector)
+        .bind(&record.data)
+        .fetch_one(&mut *self.0)
+        .await
+        .expect("Failed to insert the record");
+
+        row.0
+    }
+
+    async fn scan_clusters(&mut self, table: &TableName) -> Vec<ClusterRow> {
+        sqlx::query(&format!("SELECT id, centroid FROM {table}"))
+            .fetch_all(&mut *self.0)
+            .await
+            .expect("Failed to scan clusters")
+            .into_iter()
+            .map(|row| ClusterRow {
+                id: Some(row.get("id")),
+                centroid: row.get("centroid"),
+            })
+            .collect()
+    }
+
+    async fn scan_records_in_cluster(
+        &mut self,
+        table: &TableName,
+        cluster_id: Uuid,
+    ) -> Vec<RecordRow> {
+        sqlx::query(&format!(
+            "SELECT id, cluster_id, vector, data
+            FROM {table}
+            WHERE cluster_id = $1"
+        ))
+        .bind(cluster_id)
+        .fetch_all(&mut *self.0)
+        .await
+        .expect("Failed to scan records in cluster")
+        .into_iter()
+        .map(|row| RecordRow {
+            id: Some(row.get("id")),
+            cluster_id: row.get("cluster_id"),
+            vector: row.get("vector"),
+            data: row.get("data"),
+        })
+        .collect()
+    }
+}
+
+/// [`StorageEngine`] backed by a live `sqlx::SqliteConnection`, for running
+/// a node embedded without a Postgres server.
+///
+/// SQLite has no schema/namespace concept, so [`Self::create_schema`] and
+/// [`Self::schema_exists`] are no-ops/always-true: table names are already
+/// namespaced by the `schema.table` dotted name baked into [`TableName`].
+pub struct SqliteEngine<'a>(pub &'a mut SqliteConnection);
+
+#[async_trait]
+impl StorageEngine for SqliteEngine<'_> {
+    async fn create_schema(&mut self, _schema: &str) {}
+
+    async fn schema_exists(&mut self, _schema: &str) -> bool {
+        true
+    }
+
+    async fn create_table(&mut self, spec: &TableSpec) {
+        let sql = match &spec.raw_sql {
+            Some(sql) => sql.to_owned(),
+            None => render_create_table(spec, Dialect::Sqlite),
+        };
+
+        sqlx::query(&sql)
+            .execute(&mut *self.0)
+            .await
+            .unwrap_or_else(|_| panic!("Failed to create table {}", spec.name));
+    }
+
+    async fn upsert_cluster(
+        &mut self,
+        table: &TableName,
+        cluster: ClusterRow,
+    ) -> Uuid {
+        let id = cluster.id.unwrap_or_else(Uuid::new_v4);
+        sqlx::query(&format!(
+            "INSERT OR REPLACE INTO {table} (id, centroid) VALUES (?, ?)"
+        ))
+        .bind(id.to_string())
+        .bind(&cluster.centroid)
+        .execute(&mut *self.0)
+        .await
+        .expect("Failed to upsert the cluster");
+
+        id
+    }
+
+    async fn upsert_record(
+        &mut self,
+        table: &TableName,
+        record: RecordRow,
+    ) -> Uuid {
+        let id = record.id.unwrap_or_else(Uuid::new_v4);
+        sqlx::query(&format!(
+            "INSERT OR REPLACE INTO {table} (id, cluster_id, vector, data)
+            VALUES (?, ?, ?, ?)"
+        ))
+        .bind(id.to_string())
+        .bind(record.cluster_id.to_string())
+        .bind(&record.vector)
+        .bind(record.data.map(|data| data.to_string()))
+        .execute(&mut *self.0)
+        .await
+        .expect("Failed to upsert the record");
+
+        id
+    }
+
+    async fn scan_clusters(&mut self, table: &TableName) -> Vec<ClusterRow> {
+        sqlx::query(&format!("SELECT id, centroid FROM {table}"))
+            .fetch_all(&mut *self.0)
+            .await
+            .expect("Failed to scan clusters")
+            .into_iter()
+            .map(|row| {
+                let id: String = row.get("id");
+                ClusterRow {
+                    id: Uuid::parse_str(&id).ok(),
+                    centroid: row.get("centroid"),
+                }
+            })
+            .collect()
+    }
+
+    async fn scan_records_in_cluster(
+        &mut self,
+        table: &TableName,
+        cluster_id: Uuid,
+    ) -> Vec<RecordRow> {
+        sqlx::query(&format!(
+            "SELECT id, cluster_id, vector, data
+            FROM {table}
+            WHERE cluster_id = ?"
+        ))
+        .bind(cluster_id.to_string())
+        .fetch_all(&mut *self.0)
+        .await
+        .expect("Failed to scan records in cluster")
+        .into_iter()
+        .map(|row| {
+            let id: String = row.get("id");
+            let cluster_id: String = row.get("cluster_id");
+            let data: Option<String> = row.get("data");
+
+            RecordRow {
+                id: Uuid::parse_str(&id).ok(),
+                cluster_id: Uuid::parse_str(&cluster_id)
+                    .expect("Stored cluster_id is not a valid UUID"),
+                vector: row.get("vector"),
+                data: data.and_then(|data| serde_json::from_str(&data).ok()),
+            }
+        })
+        .collect()
+    }
+}
+
+type LmdbTable = heed::Database<heed::types::Bytes, heed::types::Bytes>;
+
+/// [`StorageEngine`] backed by an LMDB environment via `heed`, for running
+/// a node fully embedded with no external database process at all.
+///
+/// LMDB is a plain key-value store, so each table becomes a named
+/// sub-database keyed by the row id's bytes. A cluster entry holds its
+/// bincode-encoded centroid; a record entry holds the bincode-encoded
+/// `(cluster_id, vector, data)` tuple, with `data` as a JSON string.
+/// [`Self::scan_records_in_cluster`] has no secondary
+/// index to filter by `cluster_id`, so it scans the whole sub-database;
+/// fine for the embedded/single-node use case this unlocks, but not a
+/// substitute for the indexed lookup Postgres gives us for free.
+pub struct LmdbEngine {
+    pub env: heed::Env,
+    tables: Map<TableName, LmdbTable>,
+}
+
+impl LmdbEngine {
+    pub fn new(env: heed::Env) -> Self {
+        Self { env, tables: Map::new() }
+    }
+
+    fn database(&mut self, table: &TableName) -> LmdbTable {
+        if let Some(db) = self.tables.get(table) {
+            return *db;
+        }
+
+        let mut tx =
+            self.env.write_txn().expect("Failed to open LMDB write txn");
+        let db = self
+            .env
+            .create_database(&mut tx, Some(table.as_ref()))
+            .expect("Failed to open/create an LMDB sub-database");
+        tx.commit().expect("Failed to commit LMDB sub-database creation");
+
+        self.tables.insert(table.clone(), db);
+        db
+    }
+}
+
+#[async_trait]
+impl StorageEngine for LmdbEngine {
+    // LMDB environments are already a single namespace on disk; the
+    // "schema" is just a naming prefix baked into each table's name.
+    async fn create_schema(&mut self, _schema: &str) {}
+
+    async fn schema_exists(&mut self, _schema: &str) -> bool {
+        true
+    }
+
+    async fn create_table(&mut self, spec: &TableSpec) {
+        self.database(&spec.name);
+    }
+
+    async fn upsert_cluster(
+        &mut self,
+        table: &TableName,
+        mut cluster: ClusterRow,
+    ) -> Uuid {
+        let id = cluster.id.unwrap_or_else(Uuid::new_v4);
+        cluster.id = Some(id);
+
+        let db = self.database(table);
+        let mut tx =
+            self.env.write_txn().expect("Failed to open LMDB write txn");
+        let bytes = bincode::serialize(&cluster.centroid)
+            .expect("Failed to encode cluster");
+        db.put(&mut tx, id.as_bytes(), &bytes)
+            .expect("Failed to upsert the cluster");
+        tx.commit().expect("Failed to commit cluster upsert");
+
+        id
+    }
+
+    async fn upsert_record(
+        &mut self,
+        table: &TableName,
+        mut record: RecordRow,
+    ) -> Uuid {
+        let id = record.id.unwrap_or_else(Uuid::new_v4);
+        record.id = Some(id);
+
+        let db = self.database(table);
+        let mut tx =
+            self.env.write_txn().expect("Failed to open LMDB write txn");
+        let bytes = bincode::serialize(&(
+            record.cluster_id,
+            &record.vector,
+            record.data.as_ref().map(|data| data.to_string()),
+        ))
+        .expect("Failed to encode record");
+        db.put(&mut tx, id.as_bytes(), &bytes)
+            .expect("Failed to upsert the record");
+        tx.commit().expect("Failed to commit record upsert");
+
+        id
+    }
+
+    async fn scan_clusters(&mut self, table: &TableName) -> Vec<ClusterRow> {
+        let db = self.database(table);
+        let tx = self.env.read_txn().expect("Failed to open LMDB read txn");
+
+        db.iter(&tx)
+            .expect("Failed to iterate clusters")
+            .map(|entry| {
+                let (key, value) =
+                    entry.expect("Failed to read cluster entry");
+                let centroid: Vec<u8> = bincode::deserialize(value)
+                    .expect("Failed to decode cluster");
+
+                ClusterRow {
+                    id: Uuid::from_slice(key).ok(),
+                    centroid,
+                }
+            })
+            .collect()
+    }
+
+    async fn scan_records_in_cluster(
+        &mut self,
+        table: &TableName,
+        cluster_id: Uuid,
+    ) -> Vec<RecordRow> {
+        let db = self.database(table);
+        let tx = self.env.read_txn().expect("Failed to open LMDB read txn");
+
+        db.iter(&tx)
+            .expect("Failed to iterate records")
+            .filter_map(|entry| {
+                let (key, value) = entry.expect("Failed to read record entry");
+                let (row_cluster_id, vector, data): (
+                    Uuid,
+                    Vec<u8>,
+                    Option<String>,
+                ) = bincode::deserialize(value)
+                    .expect("Failed to decode record");
+
+                if row_cluster_id != cluster_id {
+                    return None;
+                }
+
+                Some(RecordRow {
+                    id: Uuid::from_slice(key).ok(),
+                    cluster_id: row_cluster_id,
+                    vector,
+                    data: data.and_then(|data| serde_json::from_str(&data).ok()),
+                })
+            })
+            .collect()
+    }
+}
@@ -1,7 +1,9 @@
+mod engine;
 mod rows;
 mod schemas;
 
 // Re-export types from submodules.
+pub use engine::*;
 pub use rows::*;
 pub use schemas::*;
 
@@ -9,10 +11,72 @@ pub use schemas::*;
 use crate::types::Metric;
 use async_trait::async_trait;
 use sqlx::PgConnection;
+use std::future::Future;
+use std::io::ErrorKind;
+use std::time::Duration;
 
 type SchemaName = Box<str>;
 type TableName = Box<str>;
 
+/// Retries `attempt` with exponential backoff (base 100ms, factor 2, capped
+/// at 30s) as long as it keeps failing with a [`is_transient_error`] error
+/// and `max_elapsed` hasn't passed yet. A permanent error (e.g. bad
+/// credentials) or a transient error past `max_elapsed` is returned as-is.
+///
+/// Shared by [`crate::nodes::CoordinatorNode`] and [`crate::nodes::DataNode`]
+/// so both fail fast on a misconfigured database but tolerate one that's
+/// still starting up, as is common in container orchestration.
+pub async fn connect_with_retry<T, F, Fut>(
+    max_elapsed: Duration,
+    mut attempt: F,
+) -> Result<T, sqlx::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, sqlx::Error>>,
+{
+    const BASE_BACKOFF: Duration = Duration::from_millis(100);
+    const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+    let start = std::time::Instant::now();
+    let mut backoff = BASE_BACKOFF;
+
+    loop {
+        match attempt().await {
+            Ok(value) => return Ok(value),
+            Err(error) if is_transient_error(&error) => {
+                let elapsed = start.elapsed();
+                if elapsed >= max_elapsed {
+                    return Err(error);
+                }
+
+                tracing::warn!(
+                    "transient Postgres connection error, retrying in \
+                    {backoff:?}: {error}"
+                );
+
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// True for connection-level I/O errors worth retrying (the database isn't
+/// accepting connections yet), false for permanent errors like
+/// authentication or configuration failures, which should fail fast.
+fn is_transient_error(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Io(io_error) => matches!(
+            io_error.kind(),
+            ErrorKind::ConnectionRefused
+                | ErrorKind::ConnectionReset
+                | ErrorKind::ConnectionAborted
+        ),
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 pub mod test_utils {
     use super::*;
@@ -52,6 +52,7 @@ impl From<NodeParameters> for protoc::NodeParameters {
         let metric = match value.metric {
             Metric::Cosine => protoc::Metric::Cosine,
             Metric::Euclidean => protoc::Metric::Euclidean,
+            Metric::DotProduct => protoc::Metric::DotProduct,
         };
 
         Self {
@@ -75,6 +76,17 @@ pub struct NodeConnection {
     pub count: usize,
 }
 
+/// A registered data node's placement weight.
+///
+/// Fields:
+/// - name: Unique data node name.
+/// - capacity: Relative placement weight of the node.
+#[derive(Debug, Clone, FromRow)]
+pub struct NodeCapacity {
+    pub name: NodeName,
+    pub capacity: f32,
+}
+
 impl FromRow<'_, PgRow> for NodeConnection {
     fn from_row(row: &PgRow) -> DatabaseResult<Self> {
         let name = row.try_get("name")?;
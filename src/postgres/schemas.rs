@@ -1,11 +1,11 @@
 use super::*;
+use sqlx::Connection;
+use std::collections::HashMap;
 
-/// Trait of a node schema in Postgres database.
-///
-/// The schema of a coordinator node and a data node are mostly different. This
-/// trait defines the common methods for both types of nodes.
-#[async_trait]
-pub trait NodeSchema {
+/// The engine-agnostic naming half of a node schema, split out from
+/// [`NodeSchema`] so callers that only need table names (not table
+/// creation) aren't forced to pick a [`StorageEngine`].
+pub trait NodeSchemaNames {
     /// Return the schema name of the node.
     fn name(&self) -> SchemaName;
 
@@ -14,48 +14,170 @@ pub trait NodeSchema {
         format!("{}.clusters", self.name()).into_boxed_str()
     }
 
+    /// Return the table name storing cluster-to-node placement.
+    fn assignment_table(&self) -> TableName {
+        format!("{}.cluster_assignments", self.name()).into_boxed_str()
+    }
+}
+
+/// Trait of a node schema, generic over the [`StorageEngine`] that actually
+/// runs its table-creation and row operations.
+///
+/// The schema of a coordinator node and a data node are mostly different. This
+/// trait defines the common methods for both types of nodes. [`PgEngine`] is
+/// the default engine; see [`StorageEngine`] for running a node embedded
+/// against SQLite or LMDB instead.
+#[async_trait]
+pub trait NodeSchema<E: StorageEngine>: NodeSchemaNames {
     /// Create a new schema belonging to a node in the database.
-    async fn create(&self, connection: &mut PgConnection) {
+    async fn create(&self, engine: &mut E) {
         tracing::info!("creating a database schema: {}", self.name());
-        sqlx::query(&format!("CREATE SCHEMA IF NOT EXISTS {}", self.name()))
-            .execute(connection)
-            .await
-            .expect("Failed to create the schema");
+        engine.create_schema(&self.name()).await;
     }
 
     /// Create all tables required by the node.
-    async fn create_all_tables(&self, connection: &mut PgConnection);
+    async fn create_all_tables(&self, engine: &mut E);
 
     /// Create a table to store cluster data.
     ///
     /// Columns:
     /// - id: Cluster ID.
     /// - centroid: Centroid vector of the cluster.
-    async fn create_cluster_table(&self, connection: &mut PgConnection) {
-        let table = self.cluster_table();
+    async fn create_cluster_table(&self, engine: &mut E) {
+        let spec = TableSpec::new(self.cluster_table())
+            .column(ColumnSpec::uuid("id").primary_key().generated())
+            .column(ColumnSpec::blob("centroid").not_null());
+
+        engine.create_table(&spec).await;
+    }
+
+    /// Return true if the schema exists in the database.
+    async fn exists(&self, engine: &mut E) -> bool {
+        engine.schema_exists(&self.name()).await
+    }
+}
+
+/// Adds versioned schema migrations to a [`NodeSchemaNames`] implementor,
+/// so evolving an already-provisioned schema doesn't need to recreate it
+/// from scratch. [`CoordinatorSchema`] and [`DataSchema`] each provide
+/// their own ordered [`Self::migrations`]; the rest of the machinery here
+/// is shared.
+///
+/// Stays Postgres-specific: migrations are raw, versioned SQL, which isn't
+/// something [`StorageEngine`] abstracts. The engine-generic parts of a
+/// schema are its table bootstrapping, via [`NodeSchema`].
+#[async_trait]
+pub trait Migratable: NodeSchemaNames {
+    /// Ordered list of this schema's migrations, applied by [`Self::migrate`].
+    ///
+    /// Add new entries here to evolve the schema after it has already been
+    /// provisioned; never edit or remove an entry that may have already
+    /// run, since [`Self::migrate`] detects and refuses drift.
+    fn migrations(&self) -> Vec<Migration>;
+
+    /// Table tracking which numbered migrations have been applied.
+    fn migrations_table(&self) -> TableName {
+        format!("{}.__migrations", self.name()).into_boxed_str()
+    }
+
+    /// Return the highest migration version applied so far, or 0 if the
+    /// migrations table doesn't exist yet or has no rows.
+    async fn current_version(&self, connection: &mut PgConnection) -> i32 {
+        let table = self.migrations_table();
+        sqlx::query_as::<_, (i32,)>(&format!(
+            "SELECT COALESCE(MAX(version), 0) FROM {table}"
+        ))
+        .fetch_one(&mut *connection)
+        .await
+        .map(|(version,)| version)
+        .unwrap_or(0)
+    }
+
+    /// Create the table tracking applied migrations, if it's missing.
+    async fn create_migrations_table(&self, connection: &mut PgConnection) {
+        let table = self.migrations_table();
         sqlx::query(&format!(
             "CREATE TABLE IF NOT EXISTS {table} (
-                id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
-                centroid BYTEA NOT NULL
+                version INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                checksum BIGINT NOT NULL,
+                applied_at TIMESTAMPTZ NOT NULL DEFAULT now()
             )"
         ))
         .execute(connection)
         .await
-        .expect("Failed to create cluster table");
+        .expect("Failed to create the migrations table");
     }
 
-    /// Return true if the schema exists in the database.
-    async fn exists(&self, connection: &mut PgConnection) -> bool {
-        let schema_name = self.name();
-        let row = sqlx::query(&format!(
-            "SELECT schema_name FROM information_schema.schemata
-            WHERE schema_name = '{schema_name}'"
-        ))
-        .fetch_optional(connection)
-        .await
-        .expect("Failed to check if schema exists");
-
-        row.is_some()
+    /// Applies every pending migration in order, each inside its own
+    /// transaction, and records it in [`Self::migrations_table`].
+    ///
+    /// Refuses to run (panics) if an already-applied migration's checksum
+    /// no longer matches its current definition, since that means the
+    /// schema has drifted from what was actually applied.
+    async fn migrate(&self, connection: &mut PgConnection) {
+        self.create_migrations_table(connection).await;
+
+        let table = self.migrations_table();
+        let applied: HashMap<i32, (String, i64)> =
+            sqlx::query_as::<_, (i32, String, i64)>(&format!(
+                "SELECT version, name, checksum FROM {table}"
+            ))
+            .fetch_all(&mut *connection)
+            .await
+            .expect("Failed to read applied migrations")
+            .into_iter()
+            .map(|(version, name, checksum)| (version, (name, checksum)))
+            .collect();
+
+        for migration in self.migrations() {
+            let checksum = migration.checksum();
+
+            if let Some((name, applied_checksum)) =
+                applied.get(&migration.version)
+            {
+                assert_eq!(
+                    *applied_checksum, checksum,
+                    "Migration {} ({name}) has drifted: its checksum no \
+                    longer matches what is currently defined.",
+                    migration.version,
+                );
+
+                continue;
+            }
+
+            let mut tx = connection
+                .begin()
+                .await
+                .expect("Failed to start migration transaction");
+
+            sqlx::query(&migration.sql)
+                .execute(&mut *tx)
+                .await
+                .unwrap_or_else(|_| {
+                    panic!("Failed to apply migration {}", migration.version)
+                });
+
+            sqlx::query(&format!(
+                "INSERT INTO {table} (version, name, checksum)
+                VALUES ($1, $2, $3)"
+            ))
+            .bind(migration.version)
+            .bind(migration.name)
+            .bind(checksum)
+            .execute(&mut *tx)
+            .await
+            .unwrap_or_else(|_| {
+                panic!("Failed to record migration {}", migration.version)
+            });
+
+            tx.commit().await.expect("Failed to commit migration");
+            tracing::info!(
+                "applied migration {}: {}",
+                migration.version,
+                migration.name
+            );
+        }
     }
 }
 
@@ -65,11 +187,16 @@ pub trait NodeSchema {
 /// to the coordinator node. By default, the schema name is coordinator.
 ///
 /// The schema contains the following tables:
-/// - states: Storing coordinator node states.
+/// - states: Storing coordinator node states, including the partition
+///   ring's layout version.
 /// - parameters: Storing node parameters.
 /// - clusters: Storing cluster information.
 /// - connections: Storing data node connections.
 /// - subclusters: Storing sub-cluster information.
+/// - cluster_assignments: Storing cluster-to-node placement.
+/// - partitions: Storing the partition ring's current owner per partition,
+///   see [`crate::nodes::assign_partitions`].
+/// - __migrations: Tracking applied schema migrations, see [`Migratable`].
 ///
 /// P.S. Sub-clusters are clusters from the data nodes.
 #[derive(Debug)]
@@ -83,21 +210,24 @@ impl Default for CoordinatorSchema {
     }
 }
 
-#[async_trait]
-impl NodeSchema for CoordinatorSchema {
+impl NodeSchemaNames for CoordinatorSchema {
     fn name(&self) -> SchemaName {
         self.name.to_owned()
     }
+}
 
-    async fn create_all_tables(&self, connection: &mut PgConnection) {
+#[async_trait]
+impl<E: StorageEngine> NodeSchema<E> for CoordinatorSchema {
+    async fn create_all_tables(&self, engine: &mut E) {
         tracing::info!("creating tables for the coordinator node");
 
-        self.create_state_table(connection).await;
-        self.create_parameter_table(connection).await;
+        self.create_state_table(engine).await;
+        self.create_parameter_table(engine).await;
 
-        self.create_cluster_table(connection).await;
-        self.create_connection_table(connection).await;
-        self.create_subcluster_table(connection).await;
+        self.create_cluster_table(engine).await;
+        self.create_connection_table(engine).await;
+        self.create_subcluster_table(engine).await;
+        self.create_assignment_table(engine).await;
     }
 }
 
@@ -127,13 +257,23 @@ impl CoordinatorSchema {
         format!("{}.subclusters", self.name()).into_boxed_str()
     }
 
+    /// Return the name of the table storing the partition ring's current
+    /// owner per partition.
+    pub fn partition_table(&self) -> TableName {
+        format!("{}.partitions", self.name()).into_boxed_str()
+    }
+
     /// Create a table to store node states.
     ///
     /// Columns:
     /// - initialized: Whether the node is initialized.
-    pub async fn create_state_table(&self, connection: &mut PgConnection) {
+    ///
+    /// Uses [`TableSpec::raw_sql`]: the singleton-row constraint isn't worth
+    /// modeling portably yet, so non-SQL engines like [`LmdbEngine`] just
+    /// open the table without enforcing it.
+    pub async fn create_state_table<E: StorageEngine>(&self, engine: &mut E) {
         let table = self.state_table();
-        sqlx::query(&format!(
+        let spec = TableSpec::new(table.clone()).raw_sql(format!(
             "CREATE TABLE IF NOT EXISTS {table} (
                 singleton BOOLEAN PRIMARY KEY DEFAULT true,
                 initialized BOOLEAN NOT NULL,
@@ -141,10 +281,9 @@ impl CoordinatorSchema {
 
                 CONSTRAINT single_row CHECK (singleton)
             )"
-        ))
-        .execute(connection)
-        .await
-        .expect("Failed to create the state table");
+        ));
+
+        engine.create_table(&spec).await;
     }
 
     /// Create a table to store node parameters.
@@ -153,29 +292,41 @@ impl CoordinatorSchema {
     /// - metric: Metric used to calculate distance.
     /// - dimension: Vector dimension.
     /// - density: Number of records in each cluster.
-    pub async fn create_parameter_table(&self, connection: &mut PgConnection) {
+    /// - max_connections: Coordinator's Postgres pool size.
+    /// - acquire_timeout_secs: Coordinator's pool connection acquire timeout.
+    pub async fn create_parameter_table<E: StorageEngine>(
+        &self,
+        engine: &mut E,
+    ) {
         let table = self.parameter_table();
-        sqlx::query(&format!(
+        let spec = TableSpec::new(table.clone()).raw_sql(format!(
             "CREATE TABLE IF NOT EXISTS {table} (
                 singleton BOOLEAN PRIMARY KEY DEFAULT true,
                 metric TEXT NOT NULL,
                 dimension INTEGER NOT NULL,
                 density INTEGER NOT NULL,
+                max_connections INTEGER NOT NULL DEFAULT 10,
+                acquire_timeout_secs INTEGER NOT NULL DEFAULT 30,
 
                 CONSTRAINT single_row CHECK (singleton),
                 CONSTRAINT valid_dimension CHECK (dimension > 0),
                 CONSTRAINT valid_density CHECK (density > 0),
+                CONSTRAINT valid_max_connections CHECK (max_connections > 0),
+                CONSTRAINT valid_acquire_timeout
+                    CHECK (acquire_timeout_secs > 0),
                 CONSTRAINT valid_metric CHECK (
                     metric IN (
                         'euclidean',
-                        'cosine'
+                        'cosine',
+                        'dot',
+                        'hamming',
+                        'jaccard'
                     )
                 )
             )"
-        ))
-        .execute(connection)
-        .await
-        .expect("Failed to create the parameter table");
+        ));
+
+        engine.create_table(&spec).await;
     }
 
     /// Create a table to track data node connections.
@@ -183,17 +334,59 @@ impl CoordinatorSchema {
     /// Columns:
     /// - name: Unique name of the data node.
     /// - address: Network address to connect to the data node.
-    pub async fn create_connection_table(&self, connection: &mut PgConnection) {
+    /// - capacity: Relative placement weight of the node, e.g. proportional
+    ///   to its disk or memory budget. Higher capacity nodes are favored
+    ///   when clusters are placed across the registered data nodes.
+    /// - last_seen: When the node was last heard from, via either a polled
+    ///   or a self-reported heartbeat. An audit trail only: liveness for
+    ///   routing decisions is derived from [`crate::nodes::NodeRegistry`]'s
+    ///   in-memory state, not this column.
+    pub async fn create_connection_table<E: StorageEngine>(
+        &self,
+        engine: &mut E,
+    ) {
         let table = self.connection_table();
-        sqlx::query(&format!(
+        let spec = TableSpec::new(table.clone()).raw_sql(format!(
             "CREATE TABLE IF NOT EXISTS {table} (
                 name TEXT PRIMARY KEY,
-                address TEXT NOT NULL
+                address TEXT NOT NULL,
+                capacity REAL NOT NULL DEFAULT 1.0,
+                last_seen TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+                CONSTRAINT valid_capacity CHECK (capacity > 0)
             )"
-        ))
-        .execute(connection)
-        .await
-        .expect("Failed to create the connection table");
+        ));
+
+        engine.create_table(&spec).await;
+    }
+
+    /// Create a table mapping each cluster to the data node(s) that own it.
+    ///
+    /// Columns:
+    /// - cluster_id: Cluster being placed.
+    /// - connection_name: Data node the cluster is assigned to.
+    /// - rank: Replica rank, 0 for the primary owner, 1+ for replicas.
+    pub async fn create_assignment_table<E: StorageEngine>(
+        &self,
+        engine: &mut E,
+    ) {
+        let assignment_table = self.assignment_table();
+        let connection_table = self.connection_table();
+        let cluster_table = self.cluster_table();
+
+        let spec =
+            TableSpec::new(assignment_table.clone()).raw_sql(format!(
+                "CREATE TABLE IF NOT EXISTS {assignment_table} (
+                cluster_id UUID NOT NULL REFERENCES {cluster_table} (id),
+                connection_name TEXT NOT NULL
+                    REFERENCES {connection_table} (name),
+                rank INTEGER NOT NULL,
+
+                PRIMARY KEY (cluster_id, connection_name)
+            )"
+            ));
+
+        engine.create_table(&spec).await;
     }
 
     /// Create a table to store clusters from data nodes.
@@ -203,22 +396,65 @@ impl CoordinatorSchema {
     /// - connection_name: Data node name of the sub-cluster.
     /// - cluster_id: Cluster ID assigned for the sub-cluster.
     /// - centroid: Centroid vector as a byte array.
-    pub async fn create_subcluster_table(&self, connection: &mut PgConnection) {
+    pub async fn create_subcluster_table<E: StorageEngine>(
+        &self,
+        engine: &mut E,
+    ) {
         let subcluster_table = self.subcluster_table();
         let connection_table = self.connection_table();
         let cluster_table = self.cluster_table();
 
-        sqlx::query(&format!(
+        let spec = TableSpec::new(subcluster_table.clone()).raw_sql(format!(
             "CREATE TABLE IF NOT EXISTS {subcluster_table} (
                 id UUID PRIMARY KEY,
-                connection_name TEXT NOT NULL REFERENCES {connection_table} (name),
+                connection_name TEXT NOT NULL
+                    REFERENCES {connection_table} (name),
                 cluster_id UUID NOT NULL REFERENCES {cluster_table} (id),
                 centroid BYTEA NOT NULL
             )"
-        ))
-        .execute(connection)
-        .await
-        .expect("Failed to create the subcluster table");
+        ));
+
+        engine.create_table(&spec).await;
+    }
+}
+
+impl Migratable for CoordinatorSchema {
+    fn migrations(&self) -> Vec<Migration> {
+        vec![
+            Migration {
+                version: 1,
+                name: "index subclusters by connection_name",
+                sql: format!(
+                    "CREATE INDEX IF NOT EXISTS \
+                    idx_subclusters_connection_name
+                    ON {} (connection_name)",
+                    self.subcluster_table()
+                ),
+            },
+            Migration {
+                version: 2,
+                name: "add the partition ring table",
+                sql: format!(
+                    "CREATE TABLE IF NOT EXISTS {} (
+                        partition INTEGER PRIMARY KEY,
+                        connection_name TEXT NOT NULL
+                            REFERENCES {} (name)
+                    )",
+                    self.partition_table(),
+                    self.connection_table(),
+                ),
+            },
+            Migration {
+                version: 3,
+                name: "track the partition ring's layout version",
+                sql: format!(
+                    "ALTER TABLE {}
+                    ADD COLUMN IF NOT EXISTS layout_version
+                        INTEGER NOT NULL DEFAULT 0",
+                    self.state_table(),
+                ),
+            },
+        ]
     }
 }
 
@@ -230,21 +466,24 @@ impl CoordinatorSchema {
 /// Tables:
 /// - clusters: Storing cluster information.
 /// - records: Storing vector records.
+/// - __migrations: Tracking applied schema migrations, see [`Migratable`].
 #[derive(Debug)]
 pub struct DataSchema {
     name: SchemaName, // Full schema name of data node: odb_node_{node_name}
 }
 
-#[async_trait]
-impl NodeSchema for DataSchema {
+impl NodeSchemaNames for DataSchema {
     fn name(&self) -> SchemaName {
         self.name.to_owned()
     }
+}
 
-    async fn create_all_tables(&self, connection: &mut PgConnection) {
+#[async_trait]
+impl<E: StorageEngine> NodeSchema<E> for DataSchema {
+    async fn create_all_tables(&self, engine: &mut E) {
         tracing::info!("creating tables for the data node.");
-        self.create_cluster_table(connection).await;
-        self.create_record_table(connection).await;
+        self.create_cluster_table(engine).await;
+        self.create_record_table(engine).await;
     }
 }
 
@@ -267,20 +506,62 @@ impl DataSchema {
     /// - cluster_id: Cluster ID assigned for the record.
     /// - vector: Record vector as a byte array.
     /// - data: Additional metadata as a JSON object.
-    pub async fn create_record_table(&self, connection: &mut PgConnection) {
-        let record_table = self.record_table();
-        let cluster_table = self.cluster_table();
+    pub async fn create_record_table<E: StorageEngine>(&self, engine: &mut E) {
+        let spec = TableSpec::new(self.record_table())
+            .column(ColumnSpec::uuid("id").primary_key().generated())
+            .column(
+                ColumnSpec::uuid("cluster_id")
+                    .not_null()
+                    .references(self.cluster_table(), "id"),
+            )
+            .column(ColumnSpec::blob("vector").not_null())
+            .column(ColumnSpec::json("data"));
+
+        engine.create_table(&spec).await;
+    }
+}
 
-        sqlx::query(&format!(
-            "CREATE TABLE IF NOT EXISTS {record_table} (
-                id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
-                cluster_id UUID NOT NULL REFERENCES {cluster_table} (id),
-                vector BYTEA NOT NULL,
-                data JSONB
-            )"
-        ))
-        .execute(connection)
-        .await
-        .expect("Failed to create the data record table");
+impl Migratable for DataSchema {
+    /// Add new entries here to evolve the schema after it has already been
+    /// provisioned; never edit or remove an entry that may have already
+    /// run, since [`Migratable::migrate`] detects and refuses drift.
+    fn migrations(&self) -> Vec<Migration> {
+        vec![Migration {
+            version: 1,
+            name: "index records by cluster_id",
+            sql: format!(
+                "CREATE INDEX IF NOT EXISTS idx_records_cluster_id
+                ON {} (cluster_id)",
+                self.record_table()
+            ),
+        }]
+    }
+}
+
+/// A single numbered schema migration, applied by [`Migratable::migrate`].
+///
+/// Fields:
+/// - version: Order the migration is applied in, starting at 1.
+/// - name: Human-readable label recorded alongside the version.
+/// - sql: Statement executed to bring the schema to this version.
+#[derive(Debug, Clone)]
+pub(crate) struct Migration {
+    version: i32,
+    name: &'static str,
+    sql: String,
+}
+
+impl Migration {
+    // A simple FNV-1a hash over the migration's resolved SQL, stored so a
+    // later run can detect if an already-applied migration's definition
+    // has since changed.
+    fn checksum(&self) -> i64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in self.sql.as_bytes() {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+
+        hash as i64
     }
 }
@@ -1,4 +1,4 @@
-pub use crate::db::Database;
+pub use crate::db::{Database, SnapshotWorker};
 pub use crate::indices::*;
 pub use crate::types::distance::DistanceMetric;
 pub use crate::types::err::{Error, ErrorCode};
@@ -88,14 +88,51 @@ fn object_as_value_filter() {
 #[test]
 fn and_filters() {
     let filters = Filters::AND(vec![
-        Filter::new("text", &json!("value").into(), &FilterOperator::Equal),
-        Filter::new("integer", &json!(10).into(), &FilterOperator::GreaterThan),
+        Filters::Leaf(Filter::new(
+            "text",
+            &json!("value").into(),
+            &FilterOperator::Equal,
+        )),
+        Filters::Leaf(Filter::new(
+            "integer",
+            &json!(10).into(),
+            &FilterOperator::GreaterThan,
+        )),
     ]);
 
     let filters_from_str = Filters::from("text = value AND integer > 10");
     assert_eq!(filters, filters_from_str);
 }
 
+#[test]
+fn nested_mixed_and_or_filters() {
+    let filters = Filters::from(
+        "text = value AND (integer > 10 OR boolean = true)",
+    );
+
+    let expected = Filters::AND(vec![
+        Filters::Leaf(Filter::new(
+            "text",
+            &json!("value").into(),
+            &FilterOperator::Equal,
+        )),
+        Filters::OR(vec![
+            Filters::Leaf(Filter::new(
+                "integer",
+                &json!(10).into(),
+                &FilterOperator::GreaterThan,
+            )),
+            Filters::Leaf(Filter::new(
+                "boolean",
+                &json!(true).into(),
+                &FilterOperator::Equal,
+            )),
+        ]),
+    ]);
+
+    assert_eq!(filters, expected);
+}
+
 #[test]
 fn collection_text_integer_or_filters() {
     let collection = create_collection_multitype_metadata();
@@ -28,3 +28,17 @@ fn openai_create_vector() {
     let vector = model.create_vector(content).unwrap();
     assert_eq!(vector.len(), 1536);
 }
+
+#[test]
+fn openai_create_vectors() {
+    setup_environment();
+    let model = model_openai();
+
+    let contents = ["OasysDB is awesome!", "Vector search made easy."];
+    let vectors = model.create_vectors(&contents).unwrap();
+
+    assert_eq!(vectors.len(), contents.len());
+    for vector in vectors {
+        assert_eq!(vector.len(), 1536);
+    }
+}
@@ -19,17 +19,38 @@ pub enum DistanceMetric {
     /// Cosine distance (1 - cosine similarity):
     /// [Cosine similarity](https://www.geeksforgeeks.org/cosine-similarity/)
     Cosine,
+    /// Negative dot product (inner product). Smaller is nearer, which
+    /// matches the other variants, but unlike [`DistanceMetric::Cosine`]
+    /// it isn't normalized by magnitude, so it ranks correctly for models
+    /// trained with a maximum-inner-product objective.
+    DotProduct,
+    /// [Manhattan distance](https://en.wikipedia.org/wiki/Taxicab_geometry)
+    /// (L1 norm of the difference), i.e. the sum of the absolute
+    /// differences between each dimension.
+    Manhattan,
+    /// [Hamming distance](https://en.wikipedia.org/wiki/Hamming_distance):
+    /// the number of dimensions that disagree once each vector is reduced
+    /// to a sign bit per dimension. Meant for binary-quantized embeddings.
+    Hamming,
 }
 
 impl DistanceMetric {
     /// Computes the distance between two vectors.
+    ///
+    /// Panics if `a` and `b` don't have the same length, since none of
+    /// these metrics are meaningful across mismatched dimensions.
     pub fn distance(&self, a: &Vector, b: &Vector) -> f32 {
         let a = &a.to_vec();
         let b = &b.to_vec();
 
+        assert_eq!(a.len(), b.len(), "Vectors must have the same dimension.");
+
         let dist = match self {
             DistanceMetric::Euclidean => Self::sqeuclidean(a, b),
             DistanceMetric::Cosine => Self::cosine(a, b),
+            DistanceMetric::DotProduct => Self::dot(a, b),
+            DistanceMetric::Manhattan => Self::manhattan(a, b),
+            DistanceMetric::Hamming => Self::hamming(a, b),
         };
 
         dist.unwrap() as f32
@@ -59,6 +80,38 @@ impl DistanceMetric {
         let dist = 1.0 - dot / (norm_a * norm_b);
         Some(dist as f64)
     }
+
+    fn dot(a: &[f32], b: &[f32]) -> Option<f64> {
+        #[cfg(feature = "simd")]
+        return f32::dot(a, b).map(|dot| -dot);
+
+        let dot = a.iter().zip(b.iter()).map(|(a, b)| a * b).sum::<f32>();
+        Some(-dot as f64)
+    }
+
+    fn manhattan(a: &[f32], b: &[f32]) -> Option<f64> {
+        #[cfg(feature = "simd")]
+        return f32::l1(a, b);
+
+        let dist = a.iter().zip(b.iter()).map(|(a, b)| (a - b).abs() as f64).sum::<f64>();
+
+        Some(dist)
+    }
+
+    fn hamming(a: &[f32], b: &[f32]) -> Option<f64> {
+        // Reduce each dimension to a sign bit so the two vectors can be
+        // compared bit-for-bit, then count the disagreeing bits.
+        let to_bits = |v: &[f32]| -> Vec<u8> {
+            v.iter().map(|x| if *x >= 0.0 { 1 } else { 0 }).collect()
+        };
+        let (a, b) = (to_bits(a), to_bits(b));
+
+        #[cfg(feature = "simd")]
+        return u8::hamming(&a, &b);
+
+        let dist = a.iter().zip(b.iter()).filter(|(a, b)| a != b).count();
+        Some(dist as f64)
+    }
 }
 
 #[cfg(test)]
@@ -78,4 +131,29 @@ mod tests {
         let dist = metric.distance(&a, &b);
         assert!(dist <= 0.01);
     }
+
+    #[test]
+    fn test_distance_metric_dot_manhattan_hamming() {
+        let a = Vector::from(vec![1.0, 3.0, 5.0]);
+        let b = Vector::from(vec![2.0, 4.0, 6.0]);
+
+        let dot = DistanceMetric::DotProduct.distance(&a, &b);
+        assert_eq!(dot, -(1.0 * 2.0 + 3.0 * 4.0 + 5.0 * 6.0));
+
+        let manhattan = DistanceMetric::Manhattan.distance(&a, &b);
+        assert_eq!(manhattan, 3.0);
+
+        let c = Vector::from(vec![1.0, -3.0, 5.0]);
+        let d = Vector::from(vec![2.0, 4.0, -6.0]);
+        let hamming = DistanceMetric::Hamming.distance(&c, &d);
+        assert_eq!(hamming, 2.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "same dimension")]
+    fn test_distance_metric_dimension_mismatch() {
+        let a = Vector::from(vec![1.0, 2.0]);
+        let b = Vector::from(vec![1.0, 2.0, 3.0]);
+        DistanceMetric::Euclidean.distance(&a, &b);
+    }
 }
@@ -40,6 +40,11 @@ pub struct Error {
     pub code: ErrorCode,
     /// Details about the error and why it occurred.
     pub message: String,
+    /// The underlying error this one was constructed from, if any. Kept
+    /// so the original cause (and its own source chain) stays inspectable
+    /// via [`StandardError::source`] instead of being flattened into
+    /// `message` and discarded, as the `From` impls below used to do.
+    source: Option<Box<dyn StandardError + Send + Sync>>,
 }
 
 impl Error {
@@ -47,7 +52,30 @@ impl Error {
     /// - `code`: Error code.
     /// - `message`: Details why the error occurred.
     pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
-        Self { code, message: message.into() }
+        Self { code, message: message.into(), source: None }
+    }
+
+    /// Creates a new error instance wrapping `source`, keeping it
+    /// inspectable via [`StandardError::source`] instead of flattening it
+    /// into the message.
+    /// - `code`: Error code.
+    /// - `source`: Underlying error that caused this one.
+    pub fn wrap(
+        code: ErrorCode,
+        source: impl StandardError + Send + Sync + 'static,
+    ) -> Self {
+        let message = source.to_string();
+        Self { code, message, source: Some(Box::new(source)) }
+    }
+
+    /// Prepends `msg` to the error message, to annotate an error with the
+    /// context of the operation that produced it (e.g. which query or
+    /// table was involved) while keeping the underlying cause unchanged
+    /// and still inspectable via [`StandardError::source`].
+    /// - `msg`: Context to prepend to the existing message.
+    pub fn with_context(mut self, msg: impl Into<String>) -> Self {
+        self.message = format!("{}: {}", msg.into(), self.message);
+        self
     }
 }
 
@@ -59,33 +87,34 @@ impl Display for Error {
 
 // Implement interoperability with other error types.
 
-impl StandardError for Error {}
+impl StandardError for Error {
+    fn source(&self) -> Option<&(dyn StandardError + 'static)> {
+        let source = self.source.as_ref()?;
+        Some(source.as_ref() as &(dyn StandardError + 'static))
+    }
+}
 
 impl From<IOError> for Error {
     fn from(err: IOError) -> Self {
-        let code = ErrorCode::FileError;
-        Error::new(code, err.to_string())
+        Error::wrap(ErrorCode::FileError, err)
     }
 }
 
 impl From<BincodeError> for Error {
     fn from(err: BincodeError) -> Self {
-        let code = ErrorCode::SerializationError;
-        Error::new(code, err.to_string())
+        Error::wrap(ErrorCode::SerializationError, err)
     }
 }
 
 impl From<SQLError> for Error {
     fn from(err: SQLError) -> Self {
-        let code = ErrorCode::SQLError;
-        Error::new(code, err.to_string())
+        Error::wrap(ErrorCode::SQLError, err)
     }
 }
 
 impl From<JSONError> for Error {
     fn from(err: JSONError) -> Self {
-        let code = ErrorCode::SerializationError;
-        Error::new(code, err.to_string())
+        Error::wrap(ErrorCode::SerializationError, err)
     }
 }
 
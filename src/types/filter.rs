@@ -1,27 +1,83 @@
 use super::*;
 
-/// Joined multiple filters operation with either AND or OR.
+/// Boolean expression tree used to filter records by their metadata.
 ///
-/// At the moment, OasysDB only supports single-type join operations. This
-/// means that we can't use both AND and OR operations in the same filter.
+/// Unlike the previous single-level AND/OR design, `Filters` can now nest
+/// arbitrarily, which lets expressions like `(age >= 20 AND gpa < 4.0) OR
+/// active = true` parse and evaluate as expected.
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub enum Filters {
+    /// No filter. Always passes, used when no expression is provided.
     NONE,
-    AND(Vec<Filter>),
-    OR(Vec<Filter>),
+    /// A single filter predicate.
+    Leaf(Filter),
+    /// Both sides must pass.
+    And(Box<Filters>, Box<Filters>),
+    /// Either side must pass.
+    Or(Box<Filters>, Box<Filters>),
+    /// The inner expression must not pass.
+    Not(Box<Filters>),
 }
 
 impl Filters {
     /// Returns true if the record passes the filters.
     /// - metadata: Record metadata to check against the filters.
     ///
-    /// Filters of NONE type will always return true. This is useful when
+    /// Filters of `NONE` will always return true. This is useful when
     /// no filters are provided and we want to include all records.
     pub fn apply(&self, metadata: &HashMap<String, Value>) -> bool {
         match self {
             Filters::NONE => true,
-            Filters::AND(filters) => filters.iter().all(|f| f.apply(metadata)),
-            Filters::OR(filters) => filters.iter().any(|f| f.apply(metadata)),
+            Filters::Leaf(filter) => filter.apply(metadata),
+            Filters::And(a, b) => a.apply(metadata) && b.apply(metadata),
+            Filters::Or(a, b) => a.apply(metadata) || b.apply(metadata),
+            Filters::Not(filter) => !filter.apply(metadata),
+        }
+    }
+}
+
+/// A [`Filters`] tree compiled once into a reusable predicate.
+///
+/// `Filters::apply` re-walks the expression tree and looks up each
+/// leaf's key on every call, which is wasted work when the same
+/// expression is evaluated against many records in a search loop.
+/// [`FilterPredicate::compile`] resolves that tree into a closure once,
+/// so evaluating it against a record is a single direct call instead of
+/// a repeated tree traversal.
+pub struct FilterPredicate(Box<dyn Fn(&HashMap<String, Value>) -> bool + Send + Sync>);
+
+impl FilterPredicate {
+    /// Compiles `filters` into a reusable predicate.
+    pub fn compile(filters: &Filters) -> Self {
+        Self(Self::compile_node(filters))
+    }
+
+    /// Returns true if `metadata` passes the compiled filters.
+    pub fn test(&self, metadata: &HashMap<String, Value>) -> bool {
+        (self.0)(metadata)
+    }
+
+    fn compile_node(
+        filters: &Filters,
+    ) -> Box<dyn Fn(&HashMap<String, Value>) -> bool + Send + Sync> {
+        match filters {
+            Filters::NONE => Box::new(|_| true),
+            Filters::Leaf(filter) => {
+                let filter = filter.clone();
+                Box::new(move |metadata| filter.apply(metadata))
+            }
+            Filters::And(a, b) => {
+                let (a, b) = (Self::compile_node(a), Self::compile_node(b));
+                Box::new(move |metadata| a(metadata) && b(metadata))
+            }
+            Filters::Or(a, b) => {
+                let (a, b) = (Self::compile_node(a), Self::compile_node(b));
+                Box::new(move |metadata| a(metadata) || b(metadata))
+            }
+            Filters::Not(filter) => {
+                let filter = Self::compile_node(filter);
+                Box::new(move |metadata| !filter(metadata))
+            }
         }
     }
 }
@@ -29,34 +85,200 @@ impl Filters {
 impl TryFrom<&str> for Filters {
     type Error = Status;
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        if value.is_empty() {
+        if value.trim().is_empty() {
             return Ok(Filters::NONE);
         }
 
-        const OR: &str = " OR ";
-        const AND: &str = " AND ";
+        let tokens = tokenize(value);
+        let mut parser = Parser::new(&tokens);
+        let filters = parser.parse_or()?;
 
-        // Check which join operator is used.
-        let or_count = value.matches(OR).count();
-        let and_count = value.matches(AND).count();
-
-        if or_count > 0 && and_count > 0 {
-            let message = "Mixing AND and OR join operators is not supported";
+        if parser.peek().is_some() {
+            let message = "Unexpected trailing tokens in filter expression";
             return Err(Status::invalid_argument(message));
         }
 
-        let join = if or_count > 0 { OR } else { AND };
-        let filters = value
-            .split(join)
-            .map(TryInto::try_into)
-            .collect::<Result<_, _>>()?;
+        Ok(filters)
+    }
+}
+
+// Splits a filter expression into tokens, treating parentheses as their
+// own tokens so `(age >= 20 AND gpa < 4.0) OR active = true` tokenizes as
+// `["(", "age", ">=", "20", "AND", "gpa", "<", "4.0", ")", "OR", "active",
+// "=", "true"]`.
+fn tokenize(value: &str) -> Vec<String> {
+    value
+        .replace('(', " ( ")
+        .replace(')', " ) ")
+        .replace('[', " [ ")
+        .replace(']', " ] ")
+        .replace(',', " , ")
+        .split_whitespace()
+        .map(String::from)
+        .collect()
+}
 
-        let filters = match join {
-            OR => Filters::OR(filters),
-            _ => Filters::AND(filters),
-        };
+// Recursive-descent parser respecting `NOT` > `AND` > `OR` precedence and
+// parenthesized groups.
+struct Parser<'a> {
+    tokens: &'a [String],
+    position: usize,
+}
 
-        Ok(filters)
+impl<'a> Parser<'a> {
+    fn new(tokens: &'a [String]) -> Self {
+        Parser { tokens, position: 0 }
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&str> {
+        self.tokens.get(self.position + offset).map(String::as_str)
+    }
+
+    fn advance(&mut self) -> Option<&str> {
+        let token = self.peek();
+        self.position += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Filters, Status> {
+        let mut left = self.parse_and()?;
+        while self.peek() == Some("OR") {
+            self.advance();
+            let right = self.parse_and()?;
+            left = Filters::Or(Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Filters, Status> {
+        let mut left = self.parse_not()?;
+        while self.peek() == Some("AND") {
+            self.advance();
+            let right = self.parse_not()?;
+            left = Filters::And(Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Filters, Status> {
+        if self.peek() == Some("NOT") {
+            self.advance();
+            let inner = self.parse_not()?;
+            return Ok(Filters::Not(Box::new(inner)));
+        }
+
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Filters, Status> {
+        match self.advance() {
+            Some("(") => {
+                let inner = self.parse_or()?;
+                match self.advance() {
+                    Some(")") => Ok(inner),
+                    _ => {
+                        let message = "Expected closing parenthesis";
+                        Err(Status::invalid_argument(message))
+                    }
+                }
+            }
+            Some(key) => {
+                let key = key.to_string();
+                match (self.peek(), self.peek_at(1)) {
+                    (Some("IN"), _) => {
+                        self.advance();
+                        self.parse_in(key, false)
+                    }
+                    (Some("NOT"), Some("IN")) => {
+                        self.advance();
+                        self.advance();
+                        self.parse_in(key, true)
+                    }
+                    (Some("BETWEEN"), _) => {
+                        self.advance();
+                        self.parse_between(key)
+                    }
+                    _ => {
+                        let operator = self.advance().ok_or_else(|| {
+                            Status::invalid_argument("Expected a filter operator")
+                        })?;
+
+                        let value = self.advance().ok_or_else(|| {
+                            Status::invalid_argument("Expected a filter value")
+                        })?;
+
+                        let operator = Operator::try_from(operator)?;
+                        let value = Value::from(value);
+                        Ok(Filters::Leaf(Filter { key, value, operator }))
+                    }
+                }
+            }
+            None => {
+                let message = "Unexpected end of filter expression";
+                Err(Status::invalid_argument(message))
+            }
+        }
+    }
+
+    // Parses the `[value, value, ...]` list following an `IN`/`NOT IN`
+    // operator into a `Filter` whose value is a `Value::Array`.
+    fn parse_in(&mut self, key: String, negated: bool) -> Result<Filters, Status> {
+        match self.advance() {
+            Some("[") => {}
+            _ => {
+                let message = "Expected '[' to start an IN value list";
+                return Err(Status::invalid_argument(message));
+            }
+        }
+
+        let mut values = Vec::new();
+        loop {
+            match self.advance() {
+                Some("]") => break,
+                Some(",") => continue,
+                Some(token) => values.push(Value::from(token)),
+                None => {
+                    let message = "Unterminated IN value list";
+                    return Err(Status::invalid_argument(message));
+                }
+            }
+        }
+
+        let operator = if negated { Operator::NotIn } else { Operator::In };
+        let filter = Filter { key, value: Value::Array(values), operator };
+        Ok(Filters::Leaf(filter))
+    }
+
+    // Parses the `low AND high` range following a `BETWEEN` operator into a
+    // `Filter` whose value is the 2-element `Value::Array` of bounds.
+    fn parse_between(&mut self, key: String) -> Result<Filters, Status> {
+        let low = self.advance().ok_or_else(|| {
+            Status::invalid_argument("Expected a BETWEEN lower bound")
+        })?;
+        let low = Value::from(low);
+
+        match self.advance() {
+            Some("AND") => {}
+            _ => {
+                let message = "Expected AND between BETWEEN bounds";
+                return Err(Status::invalid_argument(message));
+            }
+        }
+
+        let high = self.advance().ok_or_else(|| {
+            Status::invalid_argument("Expected a BETWEEN upper bound")
+        })?;
+        let high = Value::from(high);
+
+        let value = Value::Array(vec![low, high]);
+        let filter = Filter { key, value, operator: Operator::Between };
+        Ok(Filters::Leaf(filter))
     }
 }
 
@@ -78,10 +300,50 @@ impl Filter {
             None => return false,
         };
 
+        if let Value::Array(operand) = &self.value {
+            return match self.operator {
+                Operator::In => operand.contains(value),
+                Operator::NotIn => !operand.contains(value),
+                Operator::Between => self.filter_between(value, operand),
+                _ => false,
+            };
+        }
+
         match (value, &self.value) {
+            // `Null` is never equal to or ordered against anything,
+            // including another `Null`, so it never passes a filter.
+            (Value::Null, _) | (_, Value::Null) => false,
             (Value::Text(a), Value::Text(b)) => self.filter_text(a, b),
             (Value::Number(a), Value::Number(b)) => self.filter_number(a, b),
+            (Value::Int(a), Value::Int(b)) => self.filter_int(a, b),
+            (Value::Timestamp(a), Value::Timestamp(b)) => {
+                self.filter_int(a, b)
+            }
             (Value::Boolean(a), Value::Boolean(b)) => self.filter_boolean(a, b),
+            (Value::NumberArray(a), Value::NumberArray(b)) => {
+                self.filter_number_array(a, b)
+            }
+            _ => false,
+        }
+    }
+
+    // Checks that a value falls within the inclusive `[low, high]` bounds
+    // produced by the `BETWEEN` operator.
+    fn filter_between(&self, value: &Value, bounds: &[Value]) -> bool {
+        match (bounds.first(), bounds.get(1), value) {
+            (
+                Some(Value::Number(low)),
+                Some(Value::Number(high)),
+                Value::Number(n),
+            ) => n >= low && n <= high,
+            (Some(Value::Int(low)), Some(Value::Int(high)), Value::Int(n)) => {
+                n >= low && n <= high
+            }
+            (
+                Some(Value::Timestamp(low)),
+                Some(Value::Timestamp(high)),
+                Value::Timestamp(n),
+            ) => n >= low && n <= high,
             _ => false,
         }
     }
@@ -115,6 +377,26 @@ impl Filter {
             _ => false,
         }
     }
+
+    fn filter_int(&self, a: &i64, b: &i64) -> bool {
+        match self.operator {
+            Operator::Equal => a == b,
+            Operator::NotEqual => a != b,
+            Operator::GreaterThan => a > b,
+            Operator::GreaterThanOrEqual => a >= b,
+            Operator::LessThan => a < b,
+            Operator::LessThanOrEqual => a <= b,
+            _ => false,
+        }
+    }
+
+    fn filter_number_array(&self, a: &[f64], b: &[f64]) -> bool {
+        match self.operator {
+            Operator::Equal => a == b,
+            Operator::NotEqual => a != b,
+            _ => false,
+        }
+    }
 }
 
 impl TryFrom<&str> for Filter {
@@ -149,6 +431,15 @@ pub enum Operator {
     LessThan,
     LessThanOrEqual,
     Contains,
+    /// Matches when the metadata value equals any element of the filter's
+    /// `Value::Array` operand.
+    In,
+    /// Matches when the metadata value equals none of the filter's
+    /// `Value::Array` operand's elements.
+    NotIn,
+    /// Matches when the metadata value falls within the inclusive range of
+    /// the filter's 2-element `Value::Array` operand.
+    Between,
 }
 
 impl TryFrom<&str> for Operator {
@@ -180,29 +471,29 @@ mod tests {
     #[test]
     fn test_filters_from_string() {
         let filters = Filters::try_from("name CONTAINS Ada").unwrap();
-        let expected = Filters::AND(vec![Filter {
+        let expected = Filters::Leaf(Filter {
             key: "name".into(),
             value: "Ada".into(),
             operator: Operator::Contains,
-        }]);
+        });
 
         assert_eq!(filters, expected);
 
         let filters = Filters::try_from("gpa >= 3.0 OR age < 21").unwrap();
         let expected = {
-            let filter_gpa = Filter {
+            let filter_gpa = Filters::Leaf(Filter {
                 key: "gpa".into(),
                 value: Value::Number(3.0),
                 operator: Operator::GreaterThanOrEqual,
-            };
+            });
 
-            let filter_age = Filter {
+            let filter_age = Filters::Leaf(Filter {
                 key: "age".into(),
                 value: Value::Number(21.0),
                 operator: Operator::LessThan,
-            };
+            });
 
-            Filters::OR(vec![filter_gpa, filter_age])
+            Filters::Or(Box::new(filter_gpa), Box::new(filter_age))
         };
 
         assert_eq!(filters, expected);
@@ -230,6 +521,50 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_filters_nested_parentheses() -> Result<(), Box<dyn Error>> {
+        let data = setup_metadata();
+
+        let filters =
+            Filters::try_from("(age >= 20 AND gpa < 3.0) OR active = true")?;
+        assert!(filters.apply(&data));
+
+        let filters = Filters::try_from("NOT (age < 20)")?;
+        assert!(filters.apply(&data));
+
+        let filters =
+            Filters::try_from("age >= 20 AND (gpa < 3.0 OR active = true)")?;
+        assert!(filters.apply(&data));
+
+        let filters =
+            Filters::try_from("age >= 20 AND (gpa < 3.0 OR active = false)")?;
+        assert!(!filters.apply(&data));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_filters_in_not_in_between() -> Result<(), Box<dyn Error>> {
+        let data = setup_metadata();
+
+        let filters = Filters::try_from("name IN [Bob, Alice, Carol]")?;
+        assert!(filters.apply(&data));
+
+        let filters = Filters::try_from("name IN [Bob, Carol]")?;
+        assert!(!filters.apply(&data));
+
+        let filters = Filters::try_from("name NOT IN [Bob, Carol]")?;
+        assert!(filters.apply(&data));
+
+        let filters = Filters::try_from("gpa BETWEEN 3.0 AND 4.0")?;
+        assert!(filters.apply(&data));
+
+        let filters = Filters::try_from("gpa BETWEEN 3.6 AND 4.0")?;
+        assert!(!filters.apply(&data));
+
+        Ok(())
+    }
+
     fn setup_metadata() -> HashMap<String, Value> {
         let keys = vec!["name", "age", "gpa", "active"];
         let values: Vec<Value> = vec![
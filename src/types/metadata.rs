@@ -1,5 +1,6 @@
 use super::*;
 use crate::protos;
+use serde_json::Value as Json;
 use std::collections::HashMap;
 
 /// Metadata of a vector record.
@@ -9,6 +10,14 @@ use std::collections::HashMap;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Metadata(HashMap<String, Option<Value>>);
 
+impl Metadata {
+    /// Return the field's value, distinguishing a missing key (`None`) from a
+    /// key present with a null value (`Some(None)`).
+    fn field(&self, key: &str) -> Option<&Option<Value>> {
+        self.0.get(key)
+    }
+}
+
 impl From<HashMap<String, protos::Value>> for Metadata {
     fn from(metadata: HashMap<String, protos::Value>) -> Self {
         let mut map = HashMap::new();
@@ -46,3 +55,310 @@ impl From<protos::Value> for Option<Value> {
         }
     }
 }
+
+impl Value {
+    // Coerces `Integer`/`Float` to a common `f64` so the 2 numeric variants
+    // can be compared against each other, e.g. `Integer(3)` against the
+    // `Float(3.5)` of an incoming filter.
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            Value::Integer(i) => Some(*i as f64),
+            Value::Float(f) => Some(*f as f64),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<&Json> for Value {
+    type Error = Status;
+    fn try_from(value: &Json) -> Result<Self, Self::Error> {
+        match value {
+            Json::String(s) => Ok(Value::Text(s.clone())),
+            Json::Bool(b) => Ok(Value::Boolean(*b)),
+            Json::Number(n) if n.is_i64() || n.is_u64() => {
+                let message = "Integer filter value out of range";
+                let int = n.as_i64().ok_or_else(|| Status::invalid_argument(message))?;
+                let int = i32::try_from(int).map_err(|_| Status::invalid_argument(message))?;
+                Ok(Value::Integer(int))
+            }
+            Json::Number(n) => {
+                let message = "Invalid numeric filter value";
+                let float = n.as_f64().ok_or_else(|| Status::invalid_argument(message))?;
+                Ok(Value::Float(float as f32))
+            }
+            _ => {
+                let message = "Filter value must be a string, number, or boolean";
+                Err(Status::invalid_argument(message))
+            }
+        }
+    }
+}
+
+/// Boolean expression tree used to filter vector records by their
+/// [`Metadata`], deserialized from a JSON request body.
+///
+/// Unlike `types::filter::Filters`, which parses a query string against a
+/// flat, non-optional metadata map, `MetadataFilters` parses a JSON AST
+/// against the richer [`Metadata`] map, where a field may be absent,
+/// present but null, or present with a typed [`Value`]. Composite nodes take
+/// a list of children, e.g.:
+///
+/// ```json
+/// { "and": [ { "gte": { "year": 2020 } }, { "in": { "lang": ["en", "de"] } } ] }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetadataFilters {
+    And(Vec<MetadataFilters>),
+    Or(Vec<MetadataFilters>),
+    Not(Box<MetadataFilters>),
+    Leaf(MetadataFilter),
+}
+
+impl MetadataFilters {
+    /// Returns true if the metadata passes the filter expression.
+    pub fn apply(&self, metadata: &Metadata) -> bool {
+        match self {
+            MetadataFilters::And(children) => {
+                children.iter().all(|child| child.apply(metadata))
+            }
+            MetadataFilters::Or(children) => {
+                children.iter().any(|child| child.apply(metadata))
+            }
+            MetadataFilters::Not(inner) => !inner.apply(metadata),
+            MetadataFilters::Leaf(filter) => filter.apply(metadata),
+        }
+    }
+}
+
+impl TryFrom<&Json> for MetadataFilters {
+    type Error = Status;
+    fn try_from(value: &Json) -> Result<Self, Self::Error> {
+        let object = value.as_object().ok_or_else(|| {
+            Status::invalid_argument("Filter expression must be a JSON object")
+        })?;
+
+        let (operator, body) = object.iter().next().ok_or_else(|| {
+            Status::invalid_argument("Filter expression cannot be empty")
+        })?;
+
+        match operator.as_str() {
+            "and" => Ok(MetadataFilters::And(parse_children(body)?)),
+            "or" => Ok(MetadataFilters::Or(parse_children(body)?)),
+            "not" => Ok(MetadataFilters::Not(Box::new(body.try_into()?))),
+            _ => Ok(MetadataFilters::Leaf(MetadataFilter::parse(operator, body)?)),
+        }
+    }
+}
+
+// Parses the array of child expressions under an `and`/`or` operator.
+fn parse_children(value: &Json) -> Result<Vec<MetadataFilters>, Status> {
+    value
+        .as_array()
+        .ok_or_else(|| Status::invalid_argument("Expected an array of filters"))?
+        .iter()
+        .map(MetadataFilters::try_from)
+        .collect()
+}
+
+/// A single metadata field predicate, e.g. `{"gte": {"year": 2020}}`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataFilter {
+    key: String,
+    operator: MetadataOperator,
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetadataOperator {
+    Eq(Value),
+    Ne(Value),
+    Gt(Value),
+    Gte(Value),
+    Lt(Value),
+    Lte(Value),
+    In(Vec<Value>),
+    /// True when the key is present, regardless of whether its value is null.
+    Exists,
+    /// True when the key is present and its value is null.
+    IsNull,
+}
+
+impl MetadataFilter {
+    // Parses a single `{operator: body}` predicate. `exists`/`is_null` take
+    // the field name directly, e.g. `{"exists": "year"}`; the remaining
+    // operators take a single-entry `{field: value}` object.
+    fn parse(operator: &str, body: &Json) -> Result<Self, Status> {
+        if operator == "exists" || operator == "is_null" {
+            let key = body.as_str().ok_or_else(|| {
+                Status::invalid_argument("Expected a field name string")
+            })?;
+
+            let operator = match operator {
+                "exists" => MetadataOperator::Exists,
+                _ => MetadataOperator::IsNull,
+            };
+
+            return Ok(MetadataFilter { key: key.to_string(), operator });
+        }
+
+        let object = body.as_object().ok_or_else(|| {
+            Status::invalid_argument("Expected a single-field filter object")
+        })?;
+
+        let (key, operand) = object.iter().next().ok_or_else(|| {
+            Status::invalid_argument("Filter operator is missing a field")
+        })?;
+
+        let operator = match operator {
+            "eq" => MetadataOperator::Eq(Value::try_from(operand)?),
+            "ne" => MetadataOperator::Ne(Value::try_from(operand)?),
+            "gt" => MetadataOperator::Gt(Value::try_from(operand)?),
+            "gte" => MetadataOperator::Gte(Value::try_from(operand)?),
+            "lt" => MetadataOperator::Lt(Value::try_from(operand)?),
+            "lte" => MetadataOperator::Lte(Value::try_from(operand)?),
+            "in" => {
+                let values = operand
+                    .as_array()
+                    .ok_or_else(|| Status::invalid_argument("Expected an array for IN"))?
+                    .iter()
+                    .map(Value::try_from)
+                    .collect::<Result<Vec<Value>, Status>>()?;
+
+                MetadataOperator::In(values)
+            }
+            _ => {
+                let message = format!("Unknown filter operator: {operator}");
+                return Err(Status::invalid_argument(message));
+            }
+        };
+
+        Ok(MetadataFilter { key: key.clone(), operator })
+    }
+
+    fn apply(&self, metadata: &Metadata) -> bool {
+        let field = metadata.field(&self.key);
+
+        match &self.operator {
+            MetadataOperator::Exists => field.is_some(),
+            MetadataOperator::IsNull => matches!(field, Some(None)),
+            operator => match field {
+                Some(Some(value)) => operator.compare(value),
+                _ => false,
+            },
+        }
+    }
+}
+
+impl MetadataOperator {
+    // Compares a present, non-null metadata value against the operator's
+    // operand, coercing `Integer`/`Float` to a common number where needed.
+    fn compare(&self, value: &Value) -> bool {
+        match self {
+            MetadataOperator::Eq(operand) => values_eq(value, operand),
+            MetadataOperator::Ne(operand) => !values_eq(value, operand),
+            MetadataOperator::In(operands) => {
+                operands.iter().any(|operand| values_eq(value, operand))
+            }
+            MetadataOperator::Gt(operand) => compare_numbers(value, operand)
+                .map(|ordering| ordering.is_gt())
+                .unwrap_or_else(|| compare_text(value, operand, |a, b| a > b)),
+            MetadataOperator::Gte(operand) => compare_numbers(value, operand)
+                .map(|ordering| ordering.is_ge())
+                .unwrap_or_else(|| compare_text(value, operand, |a, b| a >= b)),
+            MetadataOperator::Lt(operand) => compare_numbers(value, operand)
+                .map(|ordering| ordering.is_lt())
+                .unwrap_or_else(|| compare_text(value, operand, |a, b| a < b)),
+            MetadataOperator::Lte(operand) => compare_numbers(value, operand)
+                .map(|ordering| ordering.is_le())
+                .unwrap_or_else(|| compare_text(value, operand, |a, b| a <= b)),
+            MetadataOperator::Exists | MetadataOperator::IsNull => unreachable!(
+                "Exists/IsNull are handled in MetadataFilter::apply before reaching here"
+            ),
+        }
+    }
+}
+
+fn values_eq(a: &Value, b: &Value) -> bool {
+    if let (Some(a), Some(b)) = (a.as_number(), b.as_number()) {
+        return a == b;
+    }
+
+    match (a, b) {
+        (Value::Text(a), Value::Text(b)) => a == b,
+        (Value::Boolean(a), Value::Boolean(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn compare_numbers(a: &Value, b: &Value) -> Option<std::cmp::Ordering> {
+    let (a, b) = (a.as_number()?, b.as_number()?);
+    a.partial_cmp(&b)
+}
+
+fn compare_text(a: &Value, b: &Value, compare: impl Fn(&str, &str) -> bool) -> bool {
+    match (a, b) {
+        (Value::Text(a), Value::Text(b)) => compare(a, b),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> MetadataFilters {
+        let value: Json = serde_json::from_str(json).unwrap();
+        MetadataFilters::try_from(&value).unwrap()
+    }
+
+    fn setup_metadata() -> Metadata {
+        let mut map = HashMap::new();
+        map.insert("year".to_string(), Some(Value::Integer(2022)));
+        map.insert("lang".to_string(), Some(Value::Text("en".to_string())));
+        map.insert("featured".to_string(), Some(Value::Boolean(true)));
+        map.insert("archived_at".to_string(), None);
+
+        Metadata(map)
+    }
+
+    #[test]
+    fn test_leaf_operators() {
+        let metadata = setup_metadata();
+
+        assert!(parse(r#"{"eq": {"lang": "en"}}"#).apply(&metadata));
+        assert!(!parse(r#"{"eq": {"lang": "de"}}"#).apply(&metadata));
+        assert!(parse(r#"{"ne": {"lang": "de"}}"#).apply(&metadata));
+        assert!(parse(r#"{"gte": {"year": 2020}}"#).apply(&metadata));
+        assert!(!parse(r#"{"gt": {"year": 2022}}"#).apply(&metadata));
+        assert!(parse(r#"{"lte": {"year": 2022.5}}"#).apply(&metadata));
+        assert!(parse(r#"{"in": {"lang": ["en", "de"]}}"#).apply(&metadata));
+        assert!(!parse(r#"{"in": {"lang": ["de", "fr"]}}"#).apply(&metadata));
+    }
+
+    #[test]
+    fn test_exists_and_is_null() {
+        let metadata = setup_metadata();
+
+        assert!(parse(r#"{"exists": "year"}"#).apply(&metadata));
+        assert!(!parse(r#"{"exists": "missing"}"#).apply(&metadata));
+        assert!(parse(r#"{"is_null": "archived_at"}"#).apply(&metadata));
+        assert!(!parse(r#"{"is_null": "year"}"#).apply(&metadata));
+    }
+
+    #[test]
+    fn test_composite_expressions() {
+        let metadata = setup_metadata();
+
+        let filters = parse(
+            r#"{ "and": [ {"gte": {"year": 2020}}, {"in": {"lang": ["en", "de"]}} ] }"#,
+        );
+        assert!(filters.apply(&metadata));
+
+        let filters = parse(r#"{"not": {"eq": {"lang": "en"}}}"#);
+        assert!(!filters.apply(&metadata));
+
+        let filters =
+            parse(r#"{ "or": [ {"eq": {"lang": "fr"}}, {"exists": "featured"} ] }"#);
+        assert!(filters.apply(&metadata));
+    }
+}
@@ -4,6 +4,9 @@ use simsimd::SpatialSimilarity;
 // Distance name constants.
 const EUCLIDEAN: &str = "euclidean";
 const COSINE: &str = "cosine";
+const DOT: &str = "dot";
+const HAMMING: &str = "hamming";
+const JACCARD: &str = "jaccard";
 
 /// Distance formula for vector similarity calculations.
 ///
@@ -14,11 +17,23 @@ const COSINE: &str = "cosine";
 /// ### Cosine
 /// We use cosine distance instead of cosine similarity to be consistent with
 /// other distance metrics where a lower value indicates a closer match.
+///
+/// ### Dot
+/// We negate the dot product so that, like the other metrics, a lower value
+/// indicates a closer match.
+///
+/// ### Hamming / Jaccard
+/// Both are meant for binary (bit) vectors: each dimension is reduced to a
+/// sign bit (negative is 0, non-negative is 1) before comparison, so they
+/// also work on regular float vectors that were never explicitly quantized.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy)]
 pub enum Metric {
     Euclidean,
     Cosine,
+    DotProduct,
+    Hamming,
+    Jaccard,
 }
 
 impl Metric {
@@ -28,14 +43,32 @@ impl Metric {
         match self {
             Metric::Euclidean => f32::sqeuclidean(a, b),
             Metric::Cosine => f32::cosine(a, b),
+            Metric::DotProduct => f32::dot(a, b).map(|dot| -dot),
+            Metric::Hamming => {
+                let (a, b) = (Self::to_bits(a), Self::to_bits(b));
+                u8::hamming(&a, &b)
+            }
+            Metric::Jaccard => {
+                let (a, b) = (Self::to_bits(a), Self::to_bits(b));
+                u8::jaccard(&a, &b)
+            }
         }
     }
 
+    /// Reduce a float vector to one sign bit per dimension, for
+    /// [`Metric::Hamming`] and [`Metric::Jaccard`].
+    fn to_bits(v: &[f32]) -> Vec<u8> {
+        v.iter().map(|x| if *x >= 0.0 { 1 } else { 0 }).collect()
+    }
+
     /// Return the metric name as a string slice.
     pub fn as_str(&self) -> &str {
         match self {
             Metric::Euclidean => EUCLIDEAN,
             Metric::Cosine => COSINE,
+            Metric::DotProduct => DOT,
+            Metric::Hamming => HAMMING,
+            Metric::Jaccard => JACCARD,
         }
     }
 }
@@ -46,7 +79,12 @@ impl From<&str> for Metric {
         match value.as_str() {
             COSINE => Metric::Cosine,
             EUCLIDEAN => Metric::Euclidean,
-            _ => panic!("Metric should be cosine or euclidean"),
+            DOT => Metric::DotProduct,
+            HAMMING => Metric::Hamming,
+            JACCARD => Metric::Jaccard,
+            _ => panic!(
+                "Metric should be euclidean, cosine, dot, hamming, or jaccard"
+            ),
         }
     }
 }
@@ -68,8 +106,33 @@ mod tests {
 
         let euclidean = Metric::Euclidean.distance(&a, &b).unwrap();
         let cosine = Metric::Cosine.distance(&a, &b).unwrap();
+        let dot = Metric::DotProduct.distance(&a, &b).unwrap();
 
         assert_eq!(euclidean, 27.0);
         assert_eq!(cosine.round(), 0.0);
+        assert_eq!(dot, -32.0);
+    }
+
+    #[test]
+    fn test_distance_hamming_jaccard() {
+        let a = Vector::from(vec![1.0, -3.0, 5.0]);
+        let b = Vector::from(vec![2.0, 4.0, -6.0]);
+        let hamming = Metric::Hamming.distance(&a, &b).unwrap();
+        assert_eq!(hamming, 2.0);
+
+        // Bits: [1, 1, 0] vs [1, 0, 0] -> intersection 1, union 2.
+        let c = Vector::from(vec![1.0, 1.0, -1.0]);
+        let d = Vector::from(vec![1.0, -1.0, -1.0]);
+        let jaccard = Metric::Jaccard.distance(&c, &d).unwrap();
+        assert_eq!(jaccard, 0.5);
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert!(matches!(Metric::from("euclidean"), Metric::Euclidean));
+        assert!(matches!(Metric::from("COSINE"), Metric::Cosine));
+        assert!(matches!(Metric::from("dot"), Metric::DotProduct));
+        assert!(matches!(Metric::from("hamming"), Metric::Hamming));
+        assert!(matches!(Metric::from("JACCARD"), Metric::Jaccard));
     }
 }
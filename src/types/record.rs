@@ -12,10 +12,41 @@ use uuid::Uuid;
 pub struct RecordID(Uuid);
 
 impl RecordID {
+    /// Namespace used to derive content-addressed IDs in
+    /// [`Self::from_vector`], distinguishing OasysDB's vector hashes from
+    /// other uses of UUID v5.
+    const NAMESPACE: Uuid = Uuid::from_bytes([
+        0x6f, 0x61, 0x73, 0x79, 0x73, 0x64, 0x62, 0x00, 0x76, 0x65, 0x63,
+        0x74, 0x6f, 0x72, 0x00, 0x00,
+    ]);
+
     /// Generate a new random record ID using UUID v4.
     pub fn new() -> Self {
         RecordID(Uuid::new_v4())
     }
+
+    /// Generates a new, time-ordered record ID using UUID v7.
+    ///
+    /// Unlike [`Self::new`]'s fully random v4 IDs, v7 IDs are monotonically
+    /// increasing (a millisecond timestamp prefix followed by random bits),
+    /// so using them as the source table's primary key keeps bulk inserts
+    /// appending near the tail of the B-tree instead of scattering writes
+    /// across it, while remaining just as collision-resistant.
+    pub fn new_sortable() -> Self {
+        RecordID(Uuid::now_v7())
+    }
+
+    /// Generates a content-addressed record ID by hashing `vector` into
+    /// the [`Self::NAMESPACE`] with UUID v5, so identical vectors always
+    /// map to the same ID. Useful for idempotent re-ingestion, where
+    /// re-inserting the same vector should resolve to the same record
+    /// instead of creating a duplicate.
+    pub fn from_vector(vector: &Vector) -> Self {
+        let bytes: Vec<u8> =
+            vector.as_slice().iter().flat_map(|v| v.to_le_bytes()).collect();
+
+        RecordID(Uuid::new_v5(&Self::NAMESPACE, &bytes))
+    }
 }
 
 impl fmt::Display for RecordID {
@@ -42,7 +73,21 @@ impl FromStr for RecordID {
 pub enum Value {
     Text(String),
     Number(f64),
+    /// A whole number, distinct from the lossy, float-backed [`Self::Number`]
+    /// so values like external IDs round-trip exactly.
+    Int(i64),
     Boolean(bool),
+    /// An explicit absence of a value, distinct from a missing metadata key.
+    /// Never equal to or ordered against any other value when filtering,
+    /// including another `Null`.
+    Null,
+    /// A point in time, stored as microseconds since the Unix epoch.
+    Timestamp(i64),
+    /// A fixed-length list of numbers, e.g. a bounding box or embedding tag.
+    NumberArray(Vec<f64>),
+    /// A list of values. Only used as a filter operand, e.g. for the `IN`
+    /// and `BETWEEN` operators. Not stored as record metadata.
+    Array(Vec<Value>),
 }
 
 impl From<Value> for protos::Value {
@@ -51,7 +96,19 @@ impl From<Value> for protos::Value {
         let value = match value {
             Value::Text(text) => ProtoValue::Text(text),
             Value::Number(number) => ProtoValue::Number(number),
+            Value::Int(int) => ProtoValue::Int(int),
             Value::Boolean(boolean) => ProtoValue::Boolean(boolean),
+            // Represented as its own oneof arm, distinct from the oneof
+            // being unset, so an explicit null round-trips instead of
+            // being indistinguishable from "value is required" below.
+            Value::Null => ProtoValue::Null(0),
+            Value::Timestamp(micros) => ProtoValue::Timestamp(micros),
+            Value::NumberArray(values) => {
+                ProtoValue::NumberArray(protos::NumberArray { values })
+            }
+            Value::Array(_) => {
+                panic!("Array values are not supported as record metadata")
+            }
         };
 
         protos::Value { value: Some(value) }
@@ -65,7 +122,13 @@ impl TryFrom<protos::Value> for Value {
         match value.value {
             Some(ProtoValue::Text(text)) => Ok(Value::Text(text)),
             Some(ProtoValue::Number(number)) => Ok(Value::Number(number)),
+            Some(ProtoValue::Int(int)) => Ok(Value::Int(int)),
             Some(ProtoValue::Boolean(boolean)) => Ok(Value::Boolean(boolean)),
+            Some(ProtoValue::Null(_)) => Ok(Value::Null),
+            Some(ProtoValue::Timestamp(micros)) => Ok(Value::Timestamp(micros)),
+            Some(ProtoValue::NumberArray(array)) => {
+                Ok(Value::NumberArray(array.values))
+            }
             None => Err(Status::invalid_argument("Metadata value is required")),
         }
     }
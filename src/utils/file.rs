@@ -3,7 +3,7 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::fs::{self, OpenOptions};
 use std::io::{BufReader, BufWriter};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Reads a binary file and deserializes its contents to a type.
 /// - `path`: Path to the binary file.
@@ -17,20 +17,18 @@ pub fn read_binary_file<T: DeserializeOwned>(
 }
 
 /// Serializes the data and writes it to a binary file.
-/// - `tmp_dir`: Temporary directory path.
 /// - `path`: Path to the binary file.
 /// - `data`: Data to write.
 ///
-/// This function writes the data to a temporary file first and then renames
-/// the temporary file to the target file. This ensures that the target file
-/// is not corrupted if the operation is interrupted or fails.
+/// This function writes the data to a `.tmp` sibling of `path` first and
+/// then renames it over `path`. This ensures that readers never observe a
+/// partially written file, even if the process is interrupted mid-write.
 pub fn write_binary_file<T: Serialize>(
-    tmp_dir: impl AsRef<Path>,
     path: impl AsRef<Path>,
     data: &T,
 ) -> Result<(), Error> {
-    let file_name = parse_file_name(&path)?;
-    let tmp_file = tmp_dir.as_ref().join(file_name);
+    let path = path.as_ref();
+    let tmp_file = tmp_sibling(path)?;
     let file = OpenOptions::new()
         .write(true)
         .create(true)
@@ -40,10 +38,21 @@ pub fn write_binary_file<T: Serialize>(
     let writer = BufWriter::new(file);
     bincode::serialize_into(writer, data)?;
 
-    fs::rename(&tmp_file, &path)?;
+    fs::rename(&tmp_file, path)?;
     Ok(())
 }
 
+/// Returns a `.tmp`-suffixed sibling of `path`, to write to before the
+/// atomic rename in [`write_binary_file`].
+fn tmp_sibling(path: &Path) -> Result<PathBuf, Error> {
+    let file_name = parse_file_name(path)?;
+    let tmp_name = format!("{file_name}.tmp");
+    match path.parent() {
+        Some(parent) => Ok(parent.join(tmp_name)),
+        None => Ok(PathBuf::from(tmp_name)),
+    }
+}
+
 /// Parses the file name from a path.
 /// - `path`: Path to a file.
 pub fn parse_file_name(path: impl AsRef<Path>) -> Result<String, Error> {
@@ -0,0 +1,229 @@
+use ordered_float::OrderedFloat;
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// A candidate neighbor for [`select_diverse`]/[`extend_candidates`],
+/// decoupled from whichever vector/metric types a specific HNSW subsystem
+/// in this crate uses, so the Malkov-Yashunin selection pass below only
+/// has to be written (and fixed) once instead of once per subsystem.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Candidate<Id> {
+    pub distance: OrderedFloat<f32>,
+    pub id: Id,
+}
+
+/// Widens `working` with each of its candidates' own neighbors on the
+/// caller's graph layer, for `HeuristicConfig::extend_candidates`.
+/// - `neighbors_of`: `id`'s neighbor ids on the layer being connected.
+/// - `distance_to_query`: distance from `id` to the query vector, or
+///   `None` if `id` no longer has a vector (e.g. a stale tombstoned
+///   neighbor), in which case it's skipped instead of widening `working`
+///   with it.
+///
+/// Ids already in `working`, or pulled in earlier in this same pass, are
+/// never added twice. `working` ends up sorted ascending by distance.
+pub fn extend_candidates<Id: Copy + Eq + Hash>(
+    working: &mut Vec<Candidate<Id>>,
+    mut neighbors_of: impl FnMut(Id) -> Vec<Id>,
+    mut distance_to_query: impl FnMut(Id) -> Option<f32>,
+) {
+    let mut seen: HashSet<Id> = working.iter().map(|c| c.id).collect();
+
+    for id in seen.clone().into_iter() {
+        for neighbor_id in neighbors_of(id) {
+            if !seen.insert(neighbor_id) {
+                continue;
+            }
+
+            if let Some(distance) = distance_to_query(neighbor_id) {
+                let distance = OrderedFloat(distance);
+                working.push(Candidate { distance, id: neighbor_id });
+            }
+        }
+    }
+
+    working.sort();
+}
+
+/// Selects up to `m` neighbors from `working` using the Malkov-Yashunin
+/// heuristic (Algorithm 4 in the HNSW paper): repeatedly takes the
+/// candidate nearest the query and keeps it only if it's closer to the
+/// query than to every neighbor already kept, which favors diverse
+/// directions over a tight cluster and gives much better graph
+/// connectivity than a plain nearest-`m` cut.
+///
+/// `working` must already be sorted ascending by distance to the query
+/// and is fully drained. `discarded` is a caller-owned scratch buffer,
+/// reused across calls to avoid reallocating it on every insertion,
+/// filled with the pass's rejects so `keep_pruned` can backfill from it
+/// in distance order when fewer than `m` candidates turn out diverse.
+/// - `has_vector`: whether `id` still has a vector to compare against; a
+///   candidate without one can never be diverse and is discarded outright.
+/// - `distance`: distance between two ids that both have vectors, or
+///   `None` if `kept` no longer does, in which case that comparison is
+///   skipped rather than failing the whole candidate.
+pub fn select_diverse<Id: Copy>(
+    working: &mut Vec<Candidate<Id>>,
+    discarded: &mut Vec<Candidate<Id>>,
+    m: usize,
+    keep_pruned: bool,
+    has_vector: impl Fn(Id) -> bool,
+    distance: impl Fn(Id, Id) -> Option<f32>,
+) -> Vec<Candidate<Id>> {
+    discarded.clear();
+
+    let mut accepted: Vec<Candidate<Id>> = Vec::with_capacity(m);
+    for candidate in working.drain(..) {
+        if accepted.len() >= m {
+            break;
+        }
+
+        let is_diverse = has_vector(candidate.id)
+            && accepted.iter().all(|kept| {
+                match distance(candidate.id, kept.id) {
+                    Some(to_kept) => candidate.distance.0 < to_kept,
+                    None => true,
+                }
+            });
+
+        if is_diverse {
+            accepted.push(candidate);
+        } else {
+            discarded.push(candidate);
+        }
+    }
+
+    if keep_pruned && accepted.len() < m {
+        discarded.sort();
+        for candidate in discarded.drain(..) {
+            if accepted.len() >= m {
+                break;
+            }
+
+            accepted.push(candidate);
+        }
+    }
+
+    accepted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A 1-D "vector" space, query fixed at 0.0, so distance is just the
+    // absolute difference. Two points cluster tightly just left of the
+    // query (ids 0, 1), three cluster tightly on the far side (ids 2, 3, 4),
+    // and one sits alone on the near side (id 5) behind id 0.
+    fn points() -> Vec<f32> {
+        vec![-0.1, -0.2, 5.0, 5.1, 5.0, -4.9]
+    }
+
+    fn distance(points: &[f32], a: usize, b: usize) -> Option<f32> {
+        Some((points[a] - points[b]).abs())
+    }
+
+    fn working_from(points: &[f32]) -> Vec<Candidate<usize>> {
+        let mut working: Vec<Candidate<usize>> = points
+            .iter()
+            .enumerate()
+            .map(|(id, p)| Candidate { distance: OrderedFloat(p.abs()), id })
+            .collect();
+        working.sort();
+        working
+    }
+
+    #[test]
+    fn test_select_diverse_favors_spread_over_a_tight_cluster() {
+        let points = points();
+        let mut working = working_from(&points);
+        let mut discarded = Vec::new();
+
+        let accepted = select_diverse(
+            &mut working,
+            &mut discarded,
+            2,
+            false,
+            |_| true,
+            |a, b| distance(&points, a, b),
+        );
+
+        // id 1 is a near-duplicate of the already-kept id 0 and id 5 sits
+        // behind id 0 on the same side of the query, so neither is diverse
+        // enough to keep; id 2, on the far side, is.
+        let ids: Vec<usize> = accepted.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![0, 2]);
+    }
+
+    #[test]
+    fn test_select_diverse_keep_pruned_backfills_to_m() {
+        let points = points();
+        let mut working = working_from(&points);
+        let mut discarded = Vec::new();
+
+        let accepted = select_diverse(
+            &mut working,
+            &mut discarded,
+            4,
+            true,
+            |_| true,
+            |a, b| distance(&points, a, b),
+        );
+
+        assert_eq!(accepted.len(), 4);
+    }
+
+    #[test]
+    fn test_select_diverse_without_keep_pruned_can_return_fewer_than_m() {
+        let points = points();
+        let mut working = working_from(&points);
+        let mut discarded = Vec::new();
+
+        let accepted = select_diverse(
+            &mut working,
+            &mut discarded,
+            4,
+            false,
+            |_| true,
+            |a, b| distance(&points, a, b),
+        );
+
+        assert!(accepted.len() < 4);
+        assert!(!discarded.is_empty());
+    }
+
+    #[test]
+    fn test_select_diverse_discards_candidate_missing_a_vector() {
+        let points = points();
+        let mut working = working_from(&points);
+        let mut discarded = Vec::new();
+
+        let accepted = select_diverse(
+            &mut working,
+            &mut discarded,
+            2,
+            false,
+            |id| id != 0,
+            |a, b| distance(&points, a, b),
+        );
+
+        assert!(!accepted.iter().any(|c| c.id == 0));
+    }
+
+    #[test]
+    fn test_extend_candidates_widens_with_neighbors_and_sorts() {
+        let points = points();
+        let seed = Candidate { distance: OrderedFloat(0.0), id: 0usize };
+        let mut working = vec![seed];
+
+        extend_candidates(
+            &mut working,
+            |id| if id == 0 { vec![1, 2] } else { vec![] },
+            |id| Some(points[id]),
+        );
+
+        let ids: Vec<usize> = working.iter().map(|c| c.id).collect();
+        assert!(ids.contains(&1) && ids.contains(&2));
+        assert!(working.windows(2).all(|w| w[0].distance <= w[1].distance));
+    }
+}
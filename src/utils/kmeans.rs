@@ -1,6 +1,7 @@
 use crate::types::distance::DistanceMetric;
 use crate::types::record::Vector;
 use rand::seq::SliceRandom;
+use rand::Rng;
 use rayon::prelude::*;
 use std::rc::Rc;
 
@@ -21,6 +22,42 @@ impl ClusterID {
     }
 }
 
+/// Centroid initialization strategy for [`KMeans`].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Init {
+    /// Spread centroids out via squared-distance-weighted sampling.
+    /// Cheap, and good enough for most datasets.
+    #[default]
+    KMeansPlusPlus,
+    /// Cluster several random subsamples, pool their centroids into one
+    /// candidate set, then re-cluster that candidate set from each of
+    /// its own subsets and keep the lowest-inertia result. Costs more
+    /// upfront but is noticeably more stable on clustered data, where
+    /// k-means++ can still land in a bad local optimum.
+    Refined {
+        /// Number of subsamples to draw, and number of times the
+        /// candidate set is re-clustered.
+        samplings: usize,
+        /// Fraction of `vectors` in each subsample, in `(0.0, 1.0]`.
+        fraction: f32,
+    },
+}
+
+/// What to do when an iteration leaves a cluster with no points assigned
+/// to it.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum EmptyClusterPolicy {
+    /// Reinitialize the empty cluster to a uniformly random vector.
+    /// Cheap, but can re-empty the cluster on the very next iteration.
+    #[default]
+    RandomReinit,
+    /// Take the point that contributes the most to the within-cluster
+    /// variance of the worst-offending non-empty cluster and make it the
+    /// empty cluster's new singleton centroid. Avoids wasting iterations
+    /// on a degenerate random restart.
+    MaxVariance,
+}
+
 /// KMeans clustering model.
 ///
 /// KMeans is a simple unsupervised learning algorithm that groups similar
@@ -34,6 +71,16 @@ pub struct KMeans {
     metric: DistanceMetric,
     assignment: Vec<ClusterID>, // Cluster assignment for each vector.
     centroids: Vec<Vector>,     // Centroids of each cluster.
+    init: Init,                 // Centroid initialization strategy.
+    empty_cluster_policy: EmptyClusterPolicy,
+    n_init: usize, // Number of independent restarts `fit` runs.
+
+    // Mini-batch fitting state. `centroid_counts` tracks how many points
+    // have ever been assigned to each centroid across every `fit_minibatch`
+    // call, so the per-centroid learning rate keeps decaying instead of
+    // resetting every time more batches are fed in.
+    batch_size: Option<usize>,
+    centroid_counts: Vec<usize>,
 }
 
 impl KMeans {
@@ -52,12 +99,96 @@ impl KMeans {
             metric,
             assignment: vec![],
             centroids: vec![],
+            init: Init::default(),
+            empty_cluster_policy: EmptyClusterPolicy::default(),
+            n_init: 1,
+            batch_size: None,
+            centroid_counts: vec![],
         }
     }
 
+    /// Configures the centroid initialization strategy. Defaults to
+    /// [`Init::KMeansPlusPlus`].
+    pub fn with_init(mut self, init: Init) -> Self {
+        self.init = init;
+        self
+    }
+
+    /// Configures how an empty cluster is handled after an iteration.
+    /// Defaults to [`EmptyClusterPolicy::RandomReinit`].
+    pub fn with_empty_cluster_policy(
+        mut self,
+        policy: EmptyClusterPolicy,
+    ) -> Self {
+        self.empty_cluster_policy = policy;
+        self
+    }
+
+    /// Configures how many independent k-means++ restarts [`Self::fit`]
+    /// runs, keeping whichever one reaches the lowest [`Self::inertia`].
+    /// Defaults to 1 (no restarts).
+    pub fn with_n_init(mut self, n_init: usize) -> Self {
+        self.n_init = n_init;
+        self
+    }
+
+    /// Configures the mini-batch size used by [`Self::fit_minibatch`].
+    /// Calling this before [`Self::fit_minibatch`] is optional since that
+    /// method also takes an explicit `batch_size`; it mainly lets callers
+    /// read the configured value back via [`Self::batch_size`].
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = Some(batch_size);
+        self
+    }
+
+    /// Returns the batch size configured by [`Self::with_batch_size`] or
+    /// last used by [`Self::fit_minibatch`], if any.
+    pub fn batch_size(&self) -> Option<usize> {
+        self.batch_size
+    }
+
     /// Fits the KMeans model to the given vectors.
     /// - `vectors`: Array of vectors to cluster.
     pub fn fit(&mut self, vectors: Vectors) {
+        let mut best: Option<(Vec<Vector>, Vec<ClusterID>, f32)> = None;
+
+        for _ in 0..self.n_init.max(1) {
+            self.run_fit(vectors.clone());
+            let inertia = self.inertia(vectors.clone());
+
+            let improved = match &best {
+                Some((_, _, best_inertia)) => inertia < *best_inertia,
+                None => true,
+            };
+
+            if improved {
+                let centroids = self.centroids.clone();
+                let assignment = self.assignment.clone();
+                best = Some((centroids, assignment, inertia));
+            }
+        }
+
+        // `best` is always populated since `n_init` runs at least once.
+        if let Some((centroids, assignment, _)) = best {
+            self.centroids = centroids;
+            self.assignment = assignment;
+        }
+    }
+
+    /// Runs one full Lloyd's-algorithm fit, overwriting `self.centroids`
+    /// and `self.assignment`. Factored out of [`Self::fit`] so it can be
+    /// repeated `n_init` times from independent seeds.
+    fn run_fit(&mut self, vectors: Vectors) {
+        // Elkan's bounds only hold under the triangle inequality, which
+        // only squared-Euclidean (and plain Euclidean) distance satisfies
+        // here; every other metric keeps the plain assign/update loop.
+        match self.metric {
+            DistanceMetric::Euclidean => self.run_fit_elkan(vectors),
+            _ => self.run_fit_standard(vectors),
+        }
+    }
+
+    fn run_fit_standard(&mut self, vectors: Vectors) {
         // Cloning the vectors is acceptable because with Rc, we are
         // only cloning the references, not the actual data.
         self.centroids = self.initialize_centroids(vectors.clone());
@@ -83,13 +214,408 @@ impl KMeans {
         }
     }
 
+    /// True (non-squared) Euclidean distance, recovered from `self.metric`'s
+    /// squared-distance output. Elkan's bounds are only valid in a metric
+    /// that satisfies the triangle inequality, which the squared distance
+    /// itself does not, so every bound comparison in [`Self::run_fit_elkan`]
+    /// works in this space instead.
+    fn true_distance(&self, a: &Vector, b: &Vector) -> f32 {
+        self.metric.distance(a, b).sqrt()
+    }
+
+    /// Same Lloyd's-algorithm loop as [`Self::run_fit_standard`], but with
+    /// the per-point assignment step accelerated using Elkan's algorithm
+    /// (Elkan, 2003). Instead of comparing every point against every
+    /// centroid each iteration, it maintains a lower bound `lower[x][j]`
+    /// from point `x` to centroid `j` and an upper bound `upper[x]` to
+    /// `x`'s currently assigned centroid:
+    ///
+    /// - A point is skipped entirely once `upper[x] <= s(assigned)`, where
+    ///   `s(j)` is half the distance from centroid `j` to its nearest
+    ///   other centroid: no other centroid can possibly be closer.
+    /// - Otherwise, centroid `j` is only checked if both
+    ///   `upper[x] > lower[x][j]` and `upper[x] > d(assigned, j) / 2`;
+    ///   the upper bound is tightened with one real distance computation
+    ///   the first time a point needs it.
+    /// - After centroids move by `delta[j]`, bounds are relaxed instead of
+    ///   recomputed: `lower[x][j] -= delta[j]`, `upper[x] += delta[assigned]`.
+    ///
+    /// This gives the same result as [`Self::run_fit_standard`] with far
+    /// fewer distance computations once `num_centroids` is large.
+    fn run_fit_elkan(&mut self, vectors: Vectors) {
+        self.centroids = self.initialize_centroids(vectors.clone());
+
+        let n = vectors.len();
+        let k = self.num_centroids;
+
+        self.assignment = vectors
+            .par_iter()
+            .map(|vector| self.find_nearest_centroid(vector))
+            .collect();
+
+        let mut lower: Vec<Vec<f32>> = vectors
+            .par_iter()
+            .map(|vector| {
+                self.centroids
+                    .iter()
+                    .map(|centroid| self.true_distance(vector, centroid))
+                    .collect()
+            })
+            .collect();
+
+        let mut upper: Vec<f32> = (0..n)
+            .map(|i| lower[i][self.assignment[i].to_usize()])
+            .collect();
+
+        let mut repeat_count = 0;
+        for _ in 0..self.num_iterations {
+            if repeat_count > 3 {
+                break;
+            }
+
+            let centroid_distances: Vec<Vec<f32>> = (0..k)
+                .map(|i| {
+                    (0..k)
+                        .map(|j| {
+                            let a = &self.centroids[i];
+                            let b = &self.centroids[j];
+                            self.true_distance(a, b)
+                        })
+                        .collect()
+                })
+                .collect();
+
+            let half_nearest: Vec<f32> = (0..k)
+                .map(|j| {
+                    let nearest = (0..k)
+                        .filter(|&i| i != j)
+                        .map(|i| centroid_distances[j][i])
+                        .fold(f32::INFINITY, f32::min);
+                    nearest * 0.5
+                })
+                .collect();
+
+            for i in 0..n {
+                let mut assigned = self.assignment[i].to_usize();
+                if upper[i] <= half_nearest[assigned] {
+                    continue;
+                }
+
+                let vector = vectors[i];
+                let mut upper_is_exact = false;
+
+                for j in 0..k {
+                    let skip = j == assigned
+                        || upper[i] <= lower[i][j]
+                        || upper[i] <= 0.5 * centroid_distances[assigned][j];
+                    if skip {
+                        continue;
+                    }
+
+                    if !upper_is_exact {
+                        let centroid = &self.centroids[assigned];
+                        let d = self.true_distance(vector, centroid);
+                        upper[i] = d;
+                        lower[i][assigned] = d;
+                        upper_is_exact = true;
+
+                        let half_sep = 0.5 * centroid_distances[assigned][j];
+                        if upper[i] <= lower[i][j] || upper[i] <= half_sep {
+                            continue;
+                        }
+                    }
+
+                    let d = self.true_distance(vector, &self.centroids[j]);
+                    lower[i][j] = d;
+                    if d < upper[i] {
+                        upper[i] = d;
+                        self.assignment[i] = ClusterID(j as u16);
+
+                        // `d` is the exact distance to the new assigned
+                        // center, so the remaining checks this sweep must
+                        // prune against it instead of the stale center
+                        // `assigned` pointed to before this reassignment.
+                        assigned = j;
+                        upper_is_exact = true;
+                    }
+                }
+            }
+
+            let centroids = self.update_centroids(vectors.clone());
+            let deltas: Vec<f32> = (0..k)
+                .map(|j| self.true_distance(&self.centroids[j], &centroids[j]))
+                .collect();
+
+            for i in 0..n {
+                for j in 0..k {
+                    lower[i][j] = (lower[i][j] - deltas[j]).max(0.0);
+                }
+                upper[i] += deltas[self.assignment[i].to_usize()];
+            }
+
+            match self.centroids == centroids {
+                true => repeat_count += 1,
+                false => {
+                    self.centroids = centroids;
+                    repeat_count = 0;
+                }
+            }
+        }
+    }
+
+    /// Total within-cluster sum of squared distances of `vectors` to their
+    /// currently assigned centroid. Lower is better; useful for comparing
+    /// [`Self::with_n_init`] restarts or picking `num_centroids` via an
+    /// elbow/inertia curve.
+    pub fn inertia(&self, vectors: Vectors) -> f32 {
+        self.inertia_of(vectors, &self.centroids)
+    }
+
+    /// Seeds the initial centroids according to `self.init`.
     fn initialize_centroids(&self, vectors: Vectors) -> Vec<Vector> {
+        match self.init {
+            Init::KMeansPlusPlus => self.kmeans_plus_plus(vectors),
+            Init::Refined { samplings, fraction } => {
+                self.refined_start(vectors, samplings, fraction)
+            }
+        }
+    }
+
+    /// Seeds the initial centroids with k-means++: the first centroid is
+    /// picked uniformly at random, and each subsequent one is picked with
+    /// probability proportional to its squared distance from the nearest
+    /// centroid chosen so far. This spreads the starting centroids out
+    /// across the dataset, which converges faster and more reliably than
+    /// plain uniform sampling.
+    fn kmeans_plus_plus(&self, vectors: Vectors) -> Vec<Vector> {
         let mut rng = rand::thread_rng();
+        let mut centroids = Vec::with_capacity(self.num_centroids);
+
+        let first = vectors.choose(&mut rng).unwrap();
+        centroids.push((*first).to_owned());
+
+        for _ in 1..self.num_centroids {
+            let distances: Vec<f32> = vectors
+                .par_iter()
+                .map(|vector| {
+                    centroids
+                        .iter()
+                        .map(|centroid| self.metric.distance(vector, centroid))
+                        .fold(f32::INFINITY, f32::min)
+                })
+                .collect();
+
+            let total: f32 = distances.iter().sum();
+            if total == 0.0 {
+                // Every remaining vector coincides with a chosen centroid;
+                // fall back to uniform sampling for the rest.
+                centroids.push((*vectors.choose(&mut rng).unwrap()).to_owned());
+                continue;
+            }
+
+            let threshold = rng.gen::<f32>() * total;
+            let mut cumulative = 0.0;
+            for (i, distance) in distances.iter().enumerate() {
+                cumulative += distance;
+                if cumulative >= threshold {
+                    centroids.push(vectors[i].to_owned());
+                    break;
+                }
+            }
+        }
+
+        centroids
+    }
+
+    /// Refined-start initialization (Bradley & Fayyad, 1998): k-means++
+    /// alone can still settle on a poor local optimum on clustered data,
+    /// so this runs several independent trial clusterings first and
+    /// distills their centroids down to one well-spread starting set.
+    ///
+    /// Draws `samplings` random subsamples (each `fraction * vectors.len()`
+    /// points), runs a short k-means on each to get `samplings` sets of
+    /// centroids, and pools all of them into one candidate set. That
+    /// candidate set is then re-clustered `samplings` times, once per
+    /// candidate subset used as its initial centroids, keeping whichever
+    /// run reaches the lowest inertia on the candidate set.
+    fn refined_start(
+        &self,
+        vectors: Vectors,
+        samplings: usize,
+        fraction: f32,
+    ) -> Vec<Vector> {
+        let mut rng = rand::thread_rng();
+        let raw_size = (vectors.len() as f32 * fraction) as usize;
+        let subsample_size = raw_size.max(self.num_centroids);
+
+        // Short k-means (few iterations) on each subsample.
+        let candidate_sets: Vec<Vec<Vector>> = (0..samplings)
+            .map(|_| {
+                let subsample: Vec<&Vector> = vectors
+                    .choose_multiple(&mut rng, subsample_size)
+                    .cloned()
+                    .collect();
+                let subsample: Vectors = Rc::from(subsample.as_slice());
+
+                let seed = self.kmeans_plus_plus(subsample.clone());
+                self.run_lloyd(subsample, seed, 5)
+            })
+            .collect();
+
+        // Pool every candidate centroid into one set, then re-cluster that
+        // set once per candidate subset, keeping the lowest-inertia run.
+        // The pool is built from its own owned copy of the candidates
+        // (rather than borrowing from `candidate_sets` directly) so
+        // `candidate_sets` is still free to be consumed by value below.
+        let pooled_candidates: Vec<Vector> =
+            candidate_sets.iter().flatten().cloned().collect();
+        let pool: Vec<&Vector> = pooled_candidates.iter().collect();
+        let pool: Vectors = Rc::from(pool.as_slice());
+
+        candidate_sets
+            .into_iter()
+            .map(|seed| self.run_lloyd(pool.clone(), seed, 5))
+            .min_by(|a, b| {
+                let ia = self.inertia_of(pool.clone(), a);
+                let ib = self.inertia_of(pool.clone(), b);
+                ia.partial_cmp(&ib).unwrap()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Runs `iterations` rounds of Lloyd's algorithm from `centroids`
+    /// without touching `self.assignment`/`self.centroids`, so it can be
+    /// used as scratch space by initialization strategies that need to
+    /// try out several candidate starting points before committing to one.
+    fn run_lloyd(
+        &self,
+        vectors: Vectors,
+        mut centroids: Vec<Vector>,
+        iterations: usize,
+    ) -> Vec<Vector> {
+        let k = centroids.len();
+
+        for _ in 0..iterations {
+            let assignment: Vec<ClusterID> = vectors
+                .par_iter()
+                .map(|vector| {
+                    centroids
+                        .iter()
+                        .enumerate()
+                        .map(|(i, c)| (i, self.metric.distance(vector, c)))
+                        .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+                        .map(|(i, _)| ClusterID(i as u16))
+                        .unwrap_or_default()
+                })
+                .collect();
+
+            let dimension = vectors[0].len();
+            let mut sums = vec![vec![0.0; dimension]; k];
+            let mut counts = vec![0usize; k];
+
+            for (i, vector) in vectors.iter().enumerate() {
+                let j = assignment[i].to_usize();
+                counts[j] += 1;
+                sums[j]
+                    .iter_mut()
+                    .zip(vector.as_slice().iter())
+                    .for_each(|(sum, x)| *sum += x);
+            }
+
+            let mut rng = rand::thread_rng();
+            for j in 0..k {
+                if counts[j] == 0 {
+                    let vector = vectors.choose(&mut rng).unwrap();
+                    sums[j] = vector.as_slice().to_vec();
+                    continue;
+                }
+                sums[j].iter_mut().for_each(|sum| *sum /= counts[j] as f32);
+            }
+
+            centroids = sums.into_iter().map(|v| v.into()).collect();
+        }
+
+        centroids
+    }
+
+    /// Total within-cluster sum of squared distances of `vectors` to their
+    /// nearest centroid in `centroids`.
+    fn inertia_of(&self, vectors: Vectors, centroids: &[Vector]) -> f32 {
         vectors
-            .choose_multiple(&mut rng, self.num_centroids)
-            .cloned()
-            .map(|vector| vector.to_owned())
-            .collect()
+            .par_iter()
+            .map(|vector| {
+                centroids
+                    .iter()
+                    .map(|centroid| self.metric.distance(vector, centroid))
+                    .fold(f32::INFINITY, f32::min)
+            })
+            .sum()
+    }
+
+    /// Fits the KMeans model incrementally with mini-batch updates
+    /// (Sculley, 2010), instead of scanning the entire dataset every
+    /// iteration like [`Self::fit`]. Each iteration samples `batch_size`
+    /// vectors without replacement, assigns each to its nearest centroid,
+    /// and nudges that centroid towards the point with a decaying
+    /// learning rate `1 / n_j`, where `n_j` is the running count of points
+    /// ever assigned to centroid `j`. This converges far faster than
+    /// full-batch Lloyd's on large datasets, at the cost of a noisier path.
+    ///
+    /// Calling this repeatedly on new batches keeps refining the same
+    /// model, since `centroid_counts` is never reset outside of the first
+    /// call. `assignments()`/`centroids()` stay valid throughout: points
+    /// outside the current batch simply keep their last-known assignment.
+    pub fn fit_minibatch(&mut self, vectors: Vectors, batch_size: usize) {
+        self.batch_size = Some(batch_size);
+
+        if self.centroids.is_empty() {
+            // Seed k-means++ from a single sampled batch rather than the
+            // whole set, so seeding stays cheap on huge datasets too.
+            let mut rng = rand::thread_rng();
+            let seed: Vec<&Vector> = vectors
+                .choose_multiple(&mut rng, batch_size)
+                .cloned()
+                .collect();
+            let seed: Vectors = Rc::from(seed.as_slice());
+
+            self.centroids = self.initialize_centroids(seed);
+            self.centroid_counts = vec![0; self.num_centroids];
+            self.assignment = vec![ClusterID::default(); vectors.len()];
+        }
+
+        // A later call may pass a larger batch than the one that sized
+        // `assignment` originally; grow it so indexing it below never
+        // panics, leaving the new slots at their default cluster.
+        if vectors.len() > self.assignment.len() {
+            self.assignment.resize(vectors.len(), ClusterID::default());
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut indices: Vec<usize> = (0..vectors.len()).collect();
+
+        for _ in 0..self.num_iterations {
+            let batch = indices.choose_multiple(&mut rng, batch_size);
+
+            for &index in batch {
+                let vector = vectors[index];
+                let cluster = self.find_nearest_centroid(vector);
+                self.assignment[index] = cluster;
+
+                let j = cluster.to_usize();
+                self.centroid_counts[j] += 1;
+                let rate = 1.0 / self.centroid_counts[j] as f32;
+
+                let centroid = &mut self.centroids[j];
+                let updated: Vec<f32> = centroid
+                    .as_slice()
+                    .iter()
+                    .zip(vector.as_slice().iter())
+                    .map(|(c, x)| c + rate * (x - c))
+                    .collect();
+
+                *centroid = updated.into();
+            }
+        }
     }
 
     fn assign_clusters(&self, vectors: Vectors) -> Vec<ClusterID> {
@@ -121,6 +647,18 @@ impl KMeans {
                 });
         }
 
+        let empty: Vec<usize> = (0..k).filter(|&i| counts[i] == 0).collect();
+        if !empty.is_empty()
+            && self.empty_cluster_policy == EmptyClusterPolicy::MaxVariance
+        {
+            self.reinit_empty_clusters(
+                vectors.clone(),
+                &empty,
+                &mut centroids,
+                &mut counts,
+            );
+        }
+
         for i in 0..k {
             if counts[i] == 0 {
                 let mut rng = rand::thread_rng();
@@ -136,6 +674,71 @@ impl KMeans {
         centroids.into_iter().map(|v| v.into()).collect()
     }
 
+    /// Replaces each cluster in `empty` with a singleton centroid taken
+    /// from the worst-offending non-empty cluster, instead of leaving it
+    /// for the random-reinit fallback in [`Self::update_centroids`].
+    ///
+    /// For every empty cluster, finds the non-empty cluster with the
+    /// largest within-cluster sum of squared distances to its *previous*
+    /// centroid (`self.centroids`, not yet overwritten by this update),
+    /// takes the single point in that cluster farthest from it, and hands
+    /// that point to the empty cluster as a new singleton. The donor's
+    /// sum and count are adjusted so the final averaging pass in
+    /// `update_centroids` still produces a correct mean for it.
+    fn reinit_empty_clusters(
+        &self,
+        vectors: Vectors,
+        empty: &[usize],
+        sums: &mut [Vec<f32>],
+        counts: &mut [usize],
+    ) {
+        for &empty_id in empty {
+            let donor = (0..counts.len())
+                .filter(|&j| counts[j] > 0)
+                .max_by(|&a, &b| {
+                    let sse_a = self.cluster_sse(vectors.clone(), a);
+                    let sse_b = self.cluster_sse(vectors.clone(), b);
+                    sse_a.partial_cmp(&sse_b).unwrap()
+                });
+            let Some(donor_id) = donor else { continue };
+
+            let farthest = vectors
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| self.assignment[*i].to_usize() == donor_id)
+                .map(|(i, vector)| {
+                    let distance =
+                        self.metric.distance(vector, &self.centroids[donor_id]);
+                    (i, distance)
+                })
+                .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap());
+            let Some((point_index, _)) = farthest else { continue };
+            let point = vectors[point_index];
+
+            sums[donor_id]
+                .iter_mut()
+                .zip(point.as_slice().iter())
+                .for_each(|(sum, v)| *sum -= v);
+            counts[donor_id] -= 1;
+
+            sums[empty_id] = point.as_slice().to_vec();
+            counts[empty_id] = 1;
+        }
+    }
+
+    /// Within-cluster sum of squared distances of every vector assigned
+    /// to cluster `cluster_id` against its previous centroid.
+    fn cluster_sse(&self, vectors: Vectors, cluster_id: usize) -> f32 {
+        vectors
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.assignment[*i].to_usize() == cluster_id)
+            .map(|(_, vector)| {
+                self.metric.distance(vector, &self.centroids[cluster_id])
+            })
+            .sum()
+    }
+
     /// Finds the nearest centroid to a given vector.
     /// - `vector`: Vector to compare with the centroids.
     pub fn find_nearest_centroid(&self, vector: &Vector) -> ClusterID {
@@ -206,4 +809,155 @@ mod tests {
         let accuracy = correct_count as f32 / vectors.len() as f32;
         assert!(accuracy > 0.95);
     }
+
+    #[test]
+    fn test_kmeans_fit_minibatch() {
+        let mut vectors = vec![];
+        for i in 0..100 {
+            let vector = Vector::from(vec![i as f32; 2]);
+            vectors.push(vector);
+        }
+
+        let vectors: Vectors = {
+            let vectors_ref: Vec<&Vector> = vectors.iter().collect();
+            Rc::from(vectors_ref.as_slice())
+        };
+
+        let mut kmeans = KMeans::new(5, 50, DistanceMetric::Euclidean)
+            .with_batch_size(20);
+        kmeans.fit_minibatch(vectors.clone(), 20);
+
+        assert_eq!(kmeans.batch_size(), Some(20));
+        assert_eq!(kmeans.centroids().len(), 5);
+
+        let mut correct_count = 0;
+        for (i, clusted_id) in kmeans.assignments().iter().enumerate() {
+            let vector = vectors[i];
+            let nearest_centroid = kmeans.find_nearest_centroid(vector);
+            if clusted_id == &nearest_centroid {
+                correct_count += 1;
+            }
+        }
+
+        let accuracy = correct_count as f32 / vectors.len() as f32;
+        assert!(accuracy > 0.9);
+    }
+
+    #[test]
+    fn test_kmeans_refined_start() {
+        let mut vectors = vec![];
+        for i in 0..100 {
+            let vector = Vector::from(vec![i as f32; 2]);
+            vectors.push(vector);
+        }
+
+        let vectors: Vectors = {
+            let vectors_ref: Vec<&Vector> = vectors.iter().collect();
+            Rc::from(vectors_ref.as_slice())
+        };
+
+        let init = Init::Refined { samplings: 4, fraction: 0.5 };
+        let mut kmeans =
+            KMeans::new(5, 20, DistanceMetric::Euclidean).with_init(init);
+        kmeans.fit(vectors.clone());
+        assert_eq!(kmeans.centroids().len(), 5);
+
+        let mut correct_count = 0;
+        for (i, clusted_id) in kmeans.assignments().iter().enumerate() {
+            let vector = vectors[i];
+            let nearest_centroid = kmeans.find_nearest_centroid(vector);
+            if clusted_id == &nearest_centroid {
+                correct_count += 1;
+            }
+        }
+
+        let accuracy = correct_count as f32 / vectors.len() as f32;
+        assert!(accuracy > 0.95);
+    }
+
+    #[test]
+    fn test_kmeans_max_variance_empty_cluster() {
+        // Two tight, well-separated groups with more centroids than
+        // groups, so at least one centroid is very likely to start out
+        // (or become) empty during fitting.
+        let mut vectors = vec![];
+        for _ in 0..20 {
+            vectors.push(Vector::from(vec![0.0, 0.0]));
+        }
+        for _ in 0..20 {
+            vectors.push(Vector::from(vec![100.0, 100.0]));
+        }
+
+        let vectors: Vectors = {
+            let vectors_ref: Vec<&Vector> = vectors.iter().collect();
+            Rc::from(vectors_ref.as_slice())
+        };
+
+        let mut kmeans = KMeans::new(4, 10, DistanceMetric::Euclidean)
+            .with_empty_cluster_policy(EmptyClusterPolicy::MaxVariance);
+        kmeans.fit(vectors.clone());
+
+        assert_eq!(kmeans.centroids().len(), 4);
+        for centroid in kmeans.centroids() {
+            assert_eq!(centroid.len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_kmeans_inertia_and_n_init() {
+        let mut vectors = vec![];
+        for i in 0..100 {
+            let vector = Vector::from(vec![i as f32; 2]);
+            vectors.push(vector);
+        }
+
+        let vectors: Vectors = {
+            let vectors_ref: Vec<&Vector> = vectors.iter().collect();
+            Rc::from(vectors_ref.as_slice())
+        };
+
+        let mut kmeans =
+            KMeans::new(5, 20, DistanceMetric::Euclidean).with_n_init(5);
+        kmeans.fit(vectors.clone());
+
+        let inertia = kmeans.inertia(vectors.clone());
+        assert!(inertia.is_finite());
+        assert!(inertia >= 0.0);
+    }
+
+    #[test]
+    fn test_kmeans_elkan_matches_standard_quality() {
+        let mut vectors = vec![];
+        for i in 0..100 {
+            let vector = Vector::from(vec![i as f32; 2]);
+            vectors.push(vector);
+        }
+
+        let vectors: Vectors = {
+            let vectors_ref: Vec<&Vector> = vectors.iter().collect();
+            Rc::from(vectors_ref.as_slice())
+        };
+
+        // Euclidean uses the Elkan-accelerated path internally.
+        let mut euclidean = KMeans::new(5, 20, DistanceMetric::Euclidean);
+        euclidean.fit(vectors.clone());
+
+        let mut correct_count = 0;
+        for (i, clusted_id) in euclidean.assignments().iter().enumerate() {
+            let vector = vectors[i];
+            let nearest_centroid = euclidean.find_nearest_centroid(vector);
+            if clusted_id == &nearest_centroid {
+                correct_count += 1;
+            }
+        }
+
+        let accuracy = correct_count as f32 / vectors.len() as f32;
+        assert!(accuracy > 0.95);
+
+        // Cosine distance doesn't satisfy the triangle inequality, so it
+        // must keep using the plain (non-Elkan) assignment path.
+        let mut cosine = KMeans::new(5, 20, DistanceMetric::Cosine);
+        cosine.fit(vectors.clone());
+        assert_eq!(cosine.centroids().len(), 5);
+    }
 }
@@ -1,3 +1,4 @@
+pub mod heuristic;
 pub mod kmeans;
 
 // Import common dependencies below.
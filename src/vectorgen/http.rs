@@ -0,0 +1,173 @@
+use super::*;
+
+/// Generic HTTP embedding provider, for OpenAI-compatible or self-hosted
+/// inference servers (local embedding services, Cohere, etc.) that don't
+/// warrant their own [`EmbeddingModel`] implementation.
+pub struct HttpEmbedding {
+    id: String,
+    model: String,
+    endpoint: String,
+    auth_header: String,
+    /// Request body template. Every string value equal to `"$input"` or
+    /// `"$model"` is substituted with the request's content (a string, or
+    /// an array of strings for `create_vectors`) or the configured model
+    /// name, so providers with a different body shape than OpenAI's
+    /// `{"input": ..., "model": ...}` (e.g. Cohere's `"texts": [...]`) can
+    /// still be described as plain data instead of new code.
+    body_template: Value,
+    /// JSON pointer ([RFC 6901](https://www.rfc-editor.org/rfc/rfc6901))
+    /// to the array of per-item response objects, e.g. `/data` for
+    /// OpenAI's `{"data": [{"embedding": [...]}, ...]}`.
+    items_pointer: String,
+    /// JSON pointer, relative to each item in `items_pointer`, to that
+    /// item's embedding array, e.g. `/embedding`.
+    embedding_pointer: String,
+}
+
+impl HttpEmbedding {
+    /// Creates a new generic HTTP embedding provider.
+    /// - `provider`: Provider name used in `id()`, e.g. `openai`, `cohere`.
+    /// - `model`: Model name used in `id()` and substituted for `"$model"`
+    ///   in `body_template`.
+    /// - `endpoint`: URL the request is POSTed to.
+    /// - `auth_header`: Value sent as the request's `authorization` header.
+    /// - `body_template`: Request body, with `"$input"`/`"$model"` standing
+    ///   in for the content and model name.
+    /// - `items_pointer`: JSON pointer to the response's array of per-item
+    ///   objects.
+    /// - `embedding_pointer`: JSON pointer, relative to each item, to its
+    ///   embedding array.
+    pub fn new(
+        provider: &str,
+        model: &str,
+        endpoint: &str,
+        auth_header: &str,
+        body_template: Value,
+        items_pointer: &str,
+        embedding_pointer: &str,
+    ) -> Self {
+        Self {
+            id: format!("{provider}/{model}"),
+            model: model.to_string(),
+            endpoint: endpoint.to_string(),
+            auth_header: auth_header.to_string(),
+            body_template,
+            items_pointer: items_pointer.to_string(),
+            embedding_pointer: embedding_pointer.to_string(),
+        }
+    }
+
+    /// Set a custom endpoint, e.g. to point at a self-hosted server.
+    pub fn with_endpoint(&mut self, endpoint: &str) -> &mut Self {
+        self.endpoint = endpoint.to_string();
+        self
+    }
+
+    // Substitutes `"$input"`/`"$model"` literals anywhere in `template`
+    // with `input`/the configured model name, recursing into arrays and
+    // objects so the template can place them at any depth.
+    fn render_body(&self, template: &Value, input: &Value) -> Value {
+        match template {
+            Value::String(s) if s == "$input" => input.clone(),
+            Value::String(s) if s == "$model" => {
+                Value::String(self.model.clone())
+            }
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| self.render_body(item, input))
+                    .collect(),
+            ),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), self.render_body(v, input)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    // Sends a single request with `input` substituted into the body
+    // template and returns each response item's embedding, in order.
+    fn request(
+        &self,
+        input: Value,
+        expected: usize,
+    ) -> Result<Vec<Vector>, Error> {
+        let body = self.render_body(&self.body_template, &input);
+
+        let client = Client::new();
+        let response = client
+            .post(&self.endpoint)
+            .header("authorization", &self.auth_header)
+            .json(&body)
+            .send()?;
+
+        let json: Value = response.json()?;
+        let items =
+            json.pointer(&self.items_pointer).and_then(Value::as_array);
+        let items = items.ok_or_else(|| {
+            let code = ErrorCode::RequestError;
+            let message = format!(
+                "The API response is missing the `{}` field.",
+                self.items_pointer
+            );
+
+            Error::new(code, message)
+        })?;
+
+        if items.len() != expected {
+            let code = ErrorCode::RequestError;
+            let message = format!(
+                "Expected {expected} embeddings, got {}.",
+                items.len()
+            );
+
+            return Err(Error::new(code, message));
+        }
+
+        items
+            .iter()
+            .map(|item| {
+                let embedding = item.pointer(&self.embedding_pointer);
+                let embedding = embedding.ok_or_else(|| {
+                    let code = ErrorCode::RequestError;
+                    let message = format!(
+                        "An embedding item is missing the `{}` field.",
+                        self.embedding_pointer
+                    );
+
+                    Error::new(code, message)
+                })?;
+
+                let vector: Vec<f32> =
+                    serde_json::from_value(embedding.clone())?;
+                Ok(Vector::from(vector))
+            })
+            .collect()
+    }
+}
+
+impl EmbeddingModel for HttpEmbedding {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn create_vector(&self, content: &str) -> Result<Vector, Error> {
+        let mut vectors = self.request(json!(content), 1)?;
+        Ok(vectors.remove(0))
+    }
+
+    fn create_record(
+        &self,
+        content: &str,
+        data: &Metadata,
+    ) -> Result<Record, Error> {
+        let vector = self.create_vector(content)?;
+        Ok(Record::new(&vector, data))
+    }
+
+    fn create_vectors(&self, contents: &[&str]) -> Result<Vec<Vector>, Error> {
+        self.request(json!(contents), contents.len())
+    }
+}
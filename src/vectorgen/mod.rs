@@ -2,9 +2,11 @@ use crate::prelude::*;
 use reqwest::blocking::Client;
 use serde_json::{json, Value};
 
+mod http;
 mod openai;
 
 // Re-export the model implementations below.
+pub use http::HttpEmbedding;
 pub use openai::OpenAI;
 
 /// Trait for embedding models to easily generate vectors.
@@ -26,4 +28,34 @@ pub trait EmbeddingModel {
         content: &str,
         data: &Metadata,
     ) -> Result<Record, Error>;
+
+    /// Creates a vector embedding for each item in `contents`, in order.
+    /// - `contents`: Text or content URLs to generate vectors for.
+    ///
+    /// The default implementation calls `create_vector` once per item, so
+    /// it costs one request per item. Implementations backed by a batch
+    /// embeddings endpoint should override this to send everything in a
+    /// single request instead.
+    fn create_vectors(&self, contents: &[&str]) -> Result<Vec<Vector>, Error> {
+        contents.iter().map(|content| self.create_vector(content)).collect()
+    }
+
+    /// Creates a vector record for each content/data pair, in order.
+    /// - `contents`: Text or content URLs to generate vectors for.
+    /// - `data`: Metadata to associate with each vector, same length and
+    ///   order as `contents`.
+    fn create_records(
+        &self,
+        contents: &[&str],
+        data: &[Metadata],
+    ) -> Result<Vec<Record>, Error> {
+        let vectors = self.create_vectors(contents)?;
+        let records = vectors
+            .iter()
+            .zip(data)
+            .map(|(vector, data)| Record::new(vector, data))
+            .collect();
+
+        Ok(records)
+    }
 }
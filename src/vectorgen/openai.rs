@@ -1,34 +1,11 @@
 use super::*;
 
 /// Embedding models provided by OpenAI.
-pub struct OpenAI {
-    /// OpenAI API key.
-    pub api_key: String,
-    /// Embedding model name.
-    pub model: String,
-    endpoint: String,
-}
-
-impl EmbeddingModel for OpenAI {
-    fn id(&self) -> &str {
-        let id = format!("openai/{}", self.model);
-        Box::leak(id.into_boxed_str())
-    }
-
-    fn create_vector(&self, content: &str) -> Result<Vector, Error> {
-        self.create_vector(content)
-    }
-
-    fn create_record(
-        &self,
-        content: &str,
-        data: &Metadata,
-    ) -> Result<Record, Error> {
-        let vector = self.create_vector(content)?;
-        let record = Record::new(&vector, data);
-        Ok(record)
-    }
-}
+///
+/// A thin preset over [`HttpEmbedding`] pointed at OpenAI's embeddings
+/// endpoint, for the common case where no custom provider wiring is
+/// needed.
+pub struct OpenAI(HttpEmbedding);
 
 impl OpenAI {
     /// Creates a new OpenAI embedding model instance.
@@ -44,38 +21,18 @@ impl OpenAI {
             panic!("Unsupported embedding model: {model}");
         }
 
-        let endpoint = "https://api.openai.com/v1/embeddings";
-
-        Self {
-            api_key: api_key.to_string(),
-            model: model.to_string(),
-            endpoint: endpoint.to_string(),
-        }
-    }
-
-    fn create_vector(&self, content: &str) -> Result<Vector, Error> {
-        let bearer = format!("Bearer {}", self.api_key);
-
-        // Create the request body for the API.
         // https://platform.openai.com/docs/api-reference/embeddings/create
-        let body = json!({
-            "input": content,
-            "model": self.model,
-        });
-
-        let client = Client::new();
-        let response = client
-            .post(&self.endpoint)
-            .header("authorization", bearer)
-            .json(&body)
-            .send()?;
-
-        // Get the JSON response from the API.
-        let json: Value = response.json()?;
-        let embedding = &json["data"][0]["embedding"];
-        let vector: Vec<f32> = serde_json::from_value(embedding.clone())?;
+        let inner = HttpEmbedding::new(
+            "openai",
+            model,
+            "https://api.openai.com/v1/embeddings",
+            &format!("Bearer {api_key}"),
+            json!({ "input": "$input", "model": "$model" }),
+            "/data",
+            "/embedding",
+        );
 
-        Ok(Vector::from(vector))
+        Self(inner)
     }
 
     /// Set custom endpoint for the OpenAI API.
@@ -85,7 +42,29 @@ impl OpenAI {
             panic!("Invalid OpenAI API endpoint: {endpoint}");
         }
 
-        self.endpoint = endpoint.to_string();
+        self.0.with_endpoint(endpoint);
         self
     }
 }
+
+impl EmbeddingModel for OpenAI {
+    fn id(&self) -> &str {
+        self.0.id()
+    }
+
+    fn create_vector(&self, content: &str) -> Result<Vector, Error> {
+        self.0.create_vector(content)
+    }
+
+    fn create_record(
+        &self,
+        content: &str,
+        data: &Metadata,
+    ) -> Result<Record, Error> {
+        self.0.create_record(content, data)
+    }
+
+    fn create_vectors(&self, contents: &[&str]) -> Result<Vec<Vector>, Error> {
+        self.0.create_vectors(contents)
+    }
+}
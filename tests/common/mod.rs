@@ -1,15 +1,19 @@
-use byteorder::{LittleEndian, ReadBytesExt};
 use curl::easy::Easy;
 use flate2::read::GzDecoder;
+use oasysdb::prelude::{Error as DbError, ErrorCode};
 use sqlx::any::install_default_drivers;
 use sqlx::{AnyConnection, Connection, Executor, Row};
 use std::env;
 use std::error::Error;
 use std::fs::{self, OpenOptions};
-use std::io::{BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::io::{self, BufWriter, Write};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use tar::Archive;
 
+mod vecs;
+pub use vecs::{VecsFormat, VectorReader};
+
 /// Type of benchmark dataset to use.
 /// - `SIFTSMALL`: SIFT small dataset (10k vectors of 128D).
 /// - `SIFT`: SIFT dataset (1000k vectors of 128D).
@@ -23,6 +27,65 @@ pub enum Dataset {
     GIST,
 }
 
+/// Connects to `db_url`, retrying with exponential backoff when the
+/// failure looks transient instead of failing hard on the first attempt.
+/// - `db_url`: Database URL to connect to.
+/// - `initial_interval`: Delay before the first retry.
+/// - `multiplier`: Factor the delay is multiplied by after each retry.
+/// - `max_elapsed`: Total time to keep retrying before giving up.
+///
+/// Against server-mode backends, a connection attempt can spuriously fail
+/// with `ConnectionRefused`/`ConnectionReset`/`ConnectionAborted` while the
+/// server is still starting up, so `sqlx::Error::Io` with one of those
+/// `io::ErrorKind`s is classified as transient and retried; every other
+/// error is treated as permanent and returned immediately, matching the
+/// transient/permanent split sqlx's own tooling uses.
+async fn connect_with_retry(
+    db_url: &str,
+    initial_interval: Duration,
+    multiplier: f64,
+    max_elapsed: Duration,
+) -> Result<AnyConnection, DbError> {
+    let started_at = Instant::now();
+    let mut interval = initial_interval;
+
+    loop {
+        let err = match AnyConnection::connect(db_url).await {
+            Ok(conn) => return Ok(conn),
+            Err(err) => err,
+        };
+
+        if !is_transient(&err) {
+            return Err(DbError::wrap(ErrorCode::SQLError, err));
+        }
+
+        if started_at.elapsed() >= max_elapsed {
+            let code = ErrorCode::ConcurrencyError;
+            let message = format!(
+                "Gave up connecting to the database after {:?}: {err}",
+                started_at.elapsed()
+            );
+
+            return Err(DbError::new(code, message));
+        }
+
+        std::thread::sleep(interval);
+        interval = interval.mul_f64(multiplier);
+    }
+}
+
+/// Returns whether `err` looks like a transient connection failure that's
+/// worth retrying, rather than a permanent one.
+fn is_transient(err: &sqlx::Error) -> bool {
+    let sqlx::Error::Io(io_err) = err else { return false };
+    matches!(
+        io_err.kind(),
+        io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+    )
+}
+
 impl Dataset {
     /// Returns the name of the dataset in lowercase.
     pub fn name(&self) -> &str {
@@ -54,7 +117,11 @@ impl Dataset {
         self.setup().await?;
 
         let db_url = self.database_url();
-        let mut conn = AnyConnection::connect(&db_url).await?;
+        let initial_interval = Duration::from_millis(50);
+        let max_elapsed = Duration::from_secs(10);
+        let mut conn =
+            connect_with_retry(&db_url, initial_interval, 2.0, max_elapsed)
+                .await?;
 
         let table_name = self.name();
         let tables = {
@@ -78,19 +145,8 @@ impl Dataset {
         conn.execute(create_table.as_ref()).await?;
 
         let dataset = self.base_dataset_file();
-        let vectors = self.read_vectors(dataset)?;
-        let mut insert_vector = format!(
-            "INSERT INTO {table_name} (vector)
-            VALUES"
-        );
-
-        for vector in vectors.iter() {
-            let value = serde_json::to_string(vector)?;
-            insert_vector.push_str(&format!("\n({value:?}),"));
-        }
-
-        insert_vector = insert_vector.trim_end_matches(',').to_string();
-        conn.execute(insert_vector.as_ref()).await?;
+        let vectors = VectorReader::open(dataset, VecsFormat::Fvecs)?;
+        Self::bulk_insert(&mut conn, table_name, vectors, 5_000).await?;
 
         // Verify that the vectors were inserted correctly.
         let count = {
@@ -102,6 +158,62 @@ impl Dataset {
         Ok(())
     }
 
+    /// Inserts `vectors` into `table_name` in a single transaction, flushing
+    /// a bound, prepared `INSERT` statement every `batch_size` rows instead
+    /// of building one multi-hundred-MB VALUES string up front.
+    /// - `conn`: Connection to insert into.
+    /// - `table_name`: Name of the table to insert the vectors into.
+    /// - `vectors`: Vectors to insert, streamed in and never fully
+    ///   materialized, so the caller can feed this straight from a
+    ///   [`VectorReader`] without holding the whole dataset in memory.
+    /// - `batch_size`: Number of rows to bind per flushed statement.
+    async fn bulk_insert(
+        conn: &mut AnyConnection,
+        table_name: &str,
+        vectors: impl Iterator<Item = Result<Vec<f32>, Box<dyn Error>>>,
+        batch_size: usize,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut tx = conn.begin().await?;
+        let mut batch = Vec::with_capacity(batch_size.max(1));
+
+        for vector in vectors {
+            batch.push(vector?);
+            if batch.len() == batch_size.max(1) {
+                Self::flush_batch(&mut tx, table_name, &batch).await?;
+                batch.clear();
+            }
+        }
+
+        if !batch.is_empty() {
+            Self::flush_batch(&mut tx, table_name, &batch).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Binds and executes a single chunked `INSERT` statement for `batch`.
+    /// - `tx`: Transaction to execute the statement on.
+    /// - `table_name`: Name of the table to insert the vectors into.
+    /// - `batch`: Vectors to bind into this statement, in order.
+    async fn flush_batch(
+        tx: &mut sqlx::Transaction<'_, sqlx::Any>,
+        table_name: &str,
+        batch: &[Vec<f32>],
+    ) -> Result<(), Box<dyn Error>> {
+        let placeholders = vec!["(?)"; batch.len()].join(", ");
+        let query =
+            format!("INSERT INTO {table_name} (vector) VALUES {placeholders}");
+
+        let mut built = sqlx::query(&query);
+        for vector in batch {
+            built = built.bind(serde_json::to_string(vector)?);
+        }
+
+        built.execute(&mut **tx).await?;
+        Ok(())
+    }
+
     /// Downloads and extracts the dataset to a directory.
     async fn setup(&self) -> Result<(), Box<dyn Error>> {
         if !self.compressed_file().try_exists()? {
@@ -145,36 +257,17 @@ impl Dataset {
         Ok(())
     }
 
-    /// Reads the vectors from the dataset file.
+    /// Reads the vectors from an fvecs dataset file.
     /// - `path`: Path to the fvecs file.
+    ///
+    /// Collects a [`VectorReader`] into a `Vec`. Prefer streaming from
+    /// [`VectorReader`] directly when reading a large dataset such as GIST,
+    /// since this materializes every vector in memory at once.
     pub fn read_vectors(
         &self,
         path: impl AsRef<Path>,
     ) -> Result<Vec<Vec<f32>>, Box<dyn Error>> {
-        let file = OpenOptions::new().read(true).open(path)?;
-        let mut reader = BufReader::new(file);
-
-        let dimension = reader.read_i32::<LittleEndian>()? as usize;
-        let vector_size = 4 + dimension * 4;
-
-        let n = reader.seek(SeekFrom::End(0))? as usize / vector_size;
-        reader.seek(SeekFrom::Start(((0) * vector_size) as u64))?;
-
-        let mut vectors = vec![vec![0f32; n]; dimension];
-        for i in 0..n {
-            for j in 0..dimension {
-                vectors[j][i] = reader.read_f32::<LittleEndian>()?;
-            }
-        }
-
-        // Transpose the vector.
-        let rows = vectors.len();
-        let cols = vectors[0].len();
-        let vectors = (0..cols)
-            .map(|col| (0..rows).map(|row| vectors[row][col]).collect())
-            .collect();
-
-        Ok(vectors)
+        VectorReader::open(path, VecsFormat::Fvecs)?.collect()
     }
 
     /// Returns the URL to download the dataset.
@@ -203,6 +296,17 @@ impl Dataset {
             .join(format!("{}_query.fvecs", self.name()))
     }
 
+    /// Returns the path to the ground-truth neighbors file.
+    ///
+    /// Each entry is an `ivecs` frame: a count followed by that many
+    /// base-vector indices, sorted nearest-first by true distance to the
+    /// query at the same position in [`Self::query_dataset_file`].
+    pub fn groundtruth_file(&self) -> PathBuf {
+        self.tmp_dir()
+            .join(self.name())
+            .join(format!("{}_groundtruth.ivecs", self.name()))
+    }
+
     /// Returns the temporary directory path for testing OasysDB.
     fn tmp_dir(&self) -> PathBuf {
         let dir = env::temp_dir().join("oasysdb");
@@ -0,0 +1,72 @@
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::error::Error;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader};
+use std::path::Path;
+
+/// Component type of a `.*vecs` file, as used by the texmex corpus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VecsFormat {
+    /// `f32` components. Used by the base and query dataset files.
+    Fvecs,
+    /// `u8` components. Used by the SIFT1B base files.
+    Bvecs,
+    /// `i32` components. Used by the ground-truth neighbor files.
+    Ivecs,
+}
+
+/// Streams vectors out of a `.*vecs` file one at a time instead of
+/// materializing the whole dataset in memory.
+///
+/// Each file is a sequence of `[i32 dim][dim components]` frames read back
+/// to back until EOF; components are widened to `f32` as they're read,
+/// regardless of `format`.
+pub struct VectorReader {
+    reader: BufReader<File>,
+    format: VecsFormat,
+}
+
+impl VectorReader {
+    /// Opens `path` for streaming reads.
+    /// - `path`: Path to the `.*vecs` file.
+    /// - `format`: Component format of the file.
+    pub fn open(
+        path: impl AsRef<Path>,
+        format: VecsFormat,
+    ) -> Result<Self, Box<dyn Error>> {
+        let file = OpenOptions::new().read(true).open(path)?;
+        Ok(VectorReader { reader: BufReader::new(file), format })
+    }
+}
+
+impl Iterator for VectorReader {
+    type Item = Result<Vec<f32>, Box<dyn Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let dimension = match self.reader.read_i32::<LittleEndian>() {
+            Ok(dimension) => dimension as usize,
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                return None;
+            }
+            Err(err) => return Some(Err(err.into())),
+        };
+
+        let mut vector = Vec::with_capacity(dimension);
+        for _ in 0..dimension {
+            let component = match self.format {
+                VecsFormat::Fvecs => self.reader.read_f32::<LittleEndian>(),
+                VecsFormat::Bvecs => self.reader.read_u8().map(|v| v as f32),
+                VecsFormat::Ivecs => {
+                    self.reader.read_i32::<LittleEndian>().map(|v| v as f32)
+                }
+            };
+
+            match component {
+                Ok(component) => vector.push(component),
+                Err(err) => return Some(Err(err.into())),
+            }
+        }
+
+        Some(Ok(vector))
+    }
+}
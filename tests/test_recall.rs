@@ -0,0 +1,77 @@
+use common::{Dataset, VecsFormat, VectorReader};
+use futures::executor;
+use oasysdb::prelude::*;
+use std::error::Error;
+
+mod common;
+
+/// Computes recall@k for an ef sweep against the HNSW index, using the
+/// dataset's ground-truth neighbors instead of a brute-force baseline.
+///
+/// Mirrors the Criterion suite's query-latency benchmarks, but reports
+/// accuracy instead of speed, so regressions in index quality (e.g. from
+/// an `ef_search` change) are caught the same way latency regressions are.
+#[test]
+fn test_recall_at_k_sweep() -> Result<(), Box<dyn Error>> {
+    let dataset = Dataset::SIFTSMALL;
+    let db_url = dataset.database_url();
+    let config = SourceConfig::new(dataset.name(), "id", "vector");
+
+    executor::block_on(dataset.populate_database())?;
+
+    let db = Database::open("odb_itest_recall", Some(db_url))?;
+
+    let k = 10;
+    for ef_search in [16, 64, 128] {
+        let params = ParamsHNSW { ef_search, ..Default::default() };
+        let algorithm = IndexAlgorithm::HNSW(params);
+        let index_name = format!("hnsw_ef{ef_search}");
+        db.create_index(&index_name, algorithm, config.clone())?;
+
+        let recall = recall_at_k(&db, &index_name, &dataset, k)?;
+        assert!(recall > 0.0, "recall@{k} was zero at ef_search={ef_search}");
+
+        // println!("ef_search={ef_search}: recall@{k}={recall}");
+    }
+
+    Ok(())
+}
+
+/// Computes the mean recall@k over every query in `dataset`'s query file,
+/// comparing `index`'s results against the ground-truth neighbors file.
+fn recall_at_k(
+    db: &Database,
+    index_name: &str,
+    dataset: &Dataset,
+    k: usize,
+) -> Result<f32, Box<dyn Error>> {
+    let queries = dataset.read_vectors(dataset.query_dataset_file())?;
+    let groundtruth = dataset.groundtruth_file();
+    let truth = VectorReader::open(groundtruth, VecsFormat::Ivecs)?;
+
+    let mut total = 0;
+    let mut hits = 0;
+
+    for (query, truth) in queries.into_iter().zip(truth) {
+        let truth = truth?;
+        let retrieved: Vec<RecordID> = db
+            .search_index(index_name, Vector::from(query), k, "")?
+            .into_iter()
+            .map(|result| result.id)
+            .collect();
+
+        // Ground-truth entries are base-vector positions, i.e. the source
+        // table's `id` primary key, which is exactly what `RecordID` is
+        // built from for this index's source configuration.
+        let top_k_truth = &truth[..k.min(truth.len())];
+        hits += retrieved
+            .iter()
+            .filter_map(|id| id.to_string().parse::<f32>().ok())
+            .filter(|id| top_k_truth.contains(id))
+            .count();
+
+        total += k;
+    }
+
+    Ok(hits as f32 / total as f32)
+}